@@ -0,0 +1,91 @@
+use crate::{align_ptr_up, distance, grow_heap};
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ptr::null_mut;
+use libtock_platform::Syscalls;
+
+/// An allocator that hands out memory in order from the end of the heap. Freed
+/// memory is only reclaimed if it is the most recent allocation.
+pub struct BumpAllocator<S: Syscalls> {
+    // The next free byte, and the end of the heap. Both are null before the
+    // first allocation.
+    next: Cell<*mut u8>,
+    end: Cell<*mut u8>,
+    // Set by `init`, after which the heap never grows.
+    fixed: Cell<bool>,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> BumpAllocator<S> {
+    pub const fn new() -> Self {
+        BumpAllocator {
+            next: Cell::new(null_mut()),
+            end: Cell::new(null_mut()),
+            fixed: Cell::new(false),
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Gives the allocator a fixed heap of `size` bytes at `start`. After this,
+    /// allocations are only served from that memory, and allocations that do
+    /// not fit fail rather than growing the heap. Must be called before the
+    /// first allocation.
+    ///
+    /// # Safety
+    /// The memory must be valid for reads and writes, and not used for anything
+    /// else, for as long as the allocator is in use.
+    pub unsafe fn init(&self, start: *mut u8, size: usize) {
+        self.next.set(start);
+        self.end.set(start.wrapping_add(size));
+        self.fixed.set(true);
+    }
+
+    // Allocates from the current heap, without growing it.
+    fn bump(&self, layout: Layout) -> Option<*mut u8> {
+        let start = align_ptr_up(self.next.get(), layout.align())?;
+        if distance(start, self.end.get())? < layout.size() {
+            return None;
+        }
+        self.next.set(start.wrapping_add(layout.size()));
+        Some(start)
+    }
+}
+
+impl<S: Syscalls> Default for BumpAllocator<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<S: Syscalls> GlobalAlloc for BumpAllocator<S> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if let Some(ptr) = self.bump(layout) {
+            return ptr;
+        }
+        if self.fixed.get() {
+            return null_mut();
+        }
+        let (start, end) = match layout
+            .size()
+            .checked_add(layout.align())
+            .and_then(grow_heap::<S>)
+        {
+            Some(memory) => memory,
+            None => return null_mut(),
+        };
+        // If something else moved the break, the new memory does not follow
+        // the current heap, so the rest of the current heap is abandoned.
+        if start != self.end.get() {
+            self.next.set(start);
+        }
+        self.end.set(end);
+        self.bump(layout).unwrap_or(null_mut())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ptr.wrapping_add(layout.size()) == self.next.get() {
+            self.next.set(ptr);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use core::alloc::{GlobalAlloc, Layout};
+use libtock_unittest::fake;
+
+type BumpAllocator = crate::BumpAllocator<fake::Syscalls>;
+
+fn heap(kernel: &fake::Kernel, len: usize) -> usize {
+    let memory = Box::leak(vec![0u8; len].into_boxed_slice());
+    let start = memory.as_mut_ptr() as usize;
+    kernel.set_memory_region(memory);
+    start
+}
+
+#[test]
+fn sequential() {
+    let kernel = fake::Kernel::new();
+    let start = heap(&kernel, 1024);
+    let allocator = BumpAllocator::new();
+    unsafe {
+        let a = allocator.alloc(Layout::from_size_align(3, 1).unwrap());
+        let b = allocator.alloc(Layout::from_size_align(8, 8).unwrap());
+        assert_eq!(a as usize, start);
+        assert_eq!(b as usize % 8, 0);
+        assert!(b as usize >= start + 3);
+        b.write_bytes(0xff, 8);
+    }
+}
+
+#[test]
+fn grows_heap() {
+    let kernel = fake::Kernel::new();
+    let start = heap(&kernel, 2048);
+    let allocator = BumpAllocator::new();
+    let layout = Layout::from_size_align(200, 1).unwrap();
+    unsafe {
+        let blocks = [(); 4].map(|_| allocator.alloc(layout) as usize);
+        assert_eq!(blocks, [start, start + 200, start + 400, start + 600]);
+        // The heap cannot grow past the region.
+        assert!(allocator
+            .alloc(Layout::from_size_align(2048, 1).unwrap())
+            .is_null());
+    }
+}
+
+#[test]
+fn reclaims_last_allocation() {
+    let kernel = fake::Kernel::new();
+    heap(&kernel, 1024);
+    let allocator = BumpAllocator::new();
+    let layout = Layout::from_size_align(16, 1).unwrap();
+    unsafe {
+        let a = allocator.alloc(layout);
+        let b = allocator.alloc(layout);
+        // Freeing a is not possible while b follows it.
+        allocator.dealloc(a, layout);
+        assert_eq!(allocator.alloc(layout), b.add(16));
+        allocator.dealloc(b.add(16), layout);
+        assert_eq!(allocator.alloc(layout), b.add(16));
+    }
+}
+
+#[test]
+fn fixed_heap() {
+    let kernel = fake::Kernel::new();
+    heap(&kernel, 1024);
+    let memory = Box::leak(Box::new([0u8; 64]));
+    let start = memory.as_mut_ptr();
+    let allocator = BumpAllocator::new();
+    let layout = Layout::from_size_align(32, 1).unwrap();
+    unsafe {
+        allocator.init(start, 64);
+        assert_eq!(allocator.alloc(layout), start);
+        assert_eq!(allocator.alloc(layout), start.add(32));
+        // The heap is full, and does not grow even though the kernel has memory.
+        kernel.take_syscall_log();
+        assert!(allocator.alloc(layout).is_null());
+        assert!(kernel.take_syscall_log().is_empty());
+    }
+}
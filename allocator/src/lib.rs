@@ -0,0 +1,77 @@
+//! Heap allocators for Tock processes. Both allocators start with an empty heap
+//! at the process' memory break, and grow it as needed using Memop's `sbrk`.
+//!
+//! * [`BumpAllocator`] is the smallest and fastest, but only reclaims memory
+//!   when the most recent allocation is freed.
+//! * [`LinkedListAllocator`] keeps a list of freed blocks, so memory is reused
+//!   by later allocations, at the cost of searching that list.
+//!
+//! Neither allocator is `Sync`, as they use no locking, so code that uses one
+//! outside the global allocator must keep it on a single thread.
+//!
+//! When building for Tock, this crate also provides the global allocator,
+//! selected by a feature: `bump-alloc` (the default) or `linked-list-alloc`.
+//! By default the global allocator grows the heap on demand, until the kernel
+//! refuses to move the process break. An app can instead reserve a fixed heap
+//! with `libtock_runtime::heap_size!`, in which case the global allocator only
+//! uses that memory, and allocations that do not fit in it fail.
+//! Using `alloc` additionally requires an allocation error handler, such as the
+//! one provided by `libtock_led_panic`'s `alloc` feature.
+#![cfg_attr(not(test), no_std)]
+#![warn(unsafe_op_in_unsafe_fn)]
+
+mod bump;
+mod linked_list;
+
+pub use bump::BumpAllocator;
+pub use linked_list::LinkedListAllocator;
+
+use libtock_platform::Syscalls;
+
+#[cfg(all(
+    target_os = "none",
+    any(feature = "bump-alloc", feature = "linked-list-alloc")
+))]
+mod tock;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// The minimum number of bytes the heap is grown by, to limit the number of
+// sbrk calls.
+const HEAP_GROWTH: usize = 256;
+
+// Grows the heap by at least `min_increment` bytes. Returns the start and end
+// of the new memory, or None if the kernel refused.
+fn grow_heap<S: Syscalls>(min_increment: usize) -> Option<(*mut u8, *mut u8)> {
+    let increment = align_up(min_increment.max(HEAP_GROWTH), HEAP_GROWTH)?;
+    let signed_increment = i32::try_from(increment).ok()?;
+    // Safety: growing the break does not revoke access to any memory.
+    let start = unsafe { S::memop_sbrk(signed_increment) }.ok()?;
+    Some((start, start.wrapping_add(increment)))
+}
+
+// Rounds `value` up to a multiple of `align`, which must be a power of two.
+fn align_up(value: usize, align: usize) -> Option<usize> {
+    Some(value.checked_add(align - 1)? & !(align - 1))
+}
+
+// Rounds `ptr` up to a multiple of `align`, which must be a power of two. The
+// heap's pointers are only ever offset like this, never rebuilt from
+// addresses, so they keep their provenance.
+fn align_ptr_up(ptr: *mut u8, align: usize) -> Option<*mut u8> {
+    let address = ptr as usize;
+    Some(ptr.wrapping_add(align_up(address, align)? - address))
+}
+
+// Returns the number of bytes from `start` to `end`, or None if `end` is below
+// `start`.
+fn distance(start: *mut u8, end: *mut u8) -> Option<usize> {
+    (end as usize).checked_sub(start as usize)
+}
+
+#[cfg(test)]
+mod bump_tests;
+#[cfg(test)]
+mod linked_list_tests;
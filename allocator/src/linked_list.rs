@@ -0,0 +1,188 @@
+use crate::{align_ptr_up, align_up, distance, grow_heap};
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+use core::ptr::null_mut;
+use libtock_platform::Syscalls;
+
+/// A first-fit allocator that keeps freed memory in a list, sorted by address.
+/// Adjacent free blocks are merged, so memory freed in any order can be reused
+/// by larger allocations.
+///
+/// Blocks are a multiple of the word size, and at least two words long. If an
+/// allocation leaves a single word of a free block unused, that word is lost.
+pub struct LinkedListAllocator<S: Syscalls> {
+    head: Cell<*mut FreeBlock>,
+    // Set by `init`, after which the heap never grows.
+    fixed: Cell<bool>,
+    _syscalls: PhantomData<S>,
+}
+
+// A free block, stored in the free memory itself.
+struct FreeBlock {
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+const BLOCK_ALIGN: usize = align_of::<FreeBlock>();
+const MIN_BLOCK: usize = size_of::<FreeBlock>();
+
+impl<S: Syscalls> LinkedListAllocator<S> {
+    pub const fn new() -> Self {
+        LinkedListAllocator {
+            head: Cell::new(null_mut()),
+            fixed: Cell::new(false),
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Gives the allocator a fixed heap of `size` bytes at `start`. After this,
+    /// allocations are only served from that memory, and allocations that do
+    /// not fit fail rather than growing the heap. Must be called before the
+    /// first allocation.
+    ///
+    /// # Safety
+    /// The memory must be valid for reads and writes, and not used for anything
+    /// else, for as long as the allocator is in use.
+    pub unsafe fn init(&self, start: *mut u8, size: usize) {
+        self.fixed.set(true);
+        unsafe { self.add_region(start, start.wrapping_add(size)) };
+    }
+
+    // Adds the memory `start..end` to the list, trimmed to BLOCK_ALIGN.
+    unsafe fn add_region(&self, start: *mut u8, end: *mut u8) {
+        let start = align_ptr_up(start, BLOCK_ALIGN).unwrap_or(end);
+        let end = end.wrapping_sub(end as usize & (BLOCK_ALIGN - 1));
+        match distance(start, end) {
+            Some(size) if size >= MIN_BLOCK => unsafe { self.free(start, size) },
+            _ => {}
+        }
+    }
+
+    // Takes memory for `size` bytes aligned to `align` out of the free list.
+    unsafe fn take(&self, size: usize, align: usize) -> Option<*mut u8> {
+        let mut prev: *mut FreeBlock = null_mut();
+        let mut block = self.head.get();
+        while !block.is_null() {
+            let (block_start, block_size, next) =
+                unsafe { (block as *mut u8, (*block).size, (*block).next) };
+            let block_end = block_start.wrapping_add(block_size);
+            if let Some(start) = Self::fit(block_start, block_end, size, align) {
+                unsafe { self.set_next(prev, next) };
+                let end = start.wrapping_add(size);
+                // Return the unused memory on either side to the list.
+                if start != block_start {
+                    unsafe { self.free(block_start, start as usize - block_start as usize) };
+                }
+                let unused = block_end as usize - end as usize;
+                if unused >= MIN_BLOCK {
+                    unsafe { self.free(end, unused) };
+                }
+                return Some(start);
+            }
+            prev = block;
+            block = next;
+        }
+        None
+    }
+
+    // Returns where an allocation would start in the free block spanning
+    // `block_start..block_end`, if it fits. Memory skipped for alignment must
+    // be large enough to remain a free block.
+    fn fit(block_start: *mut u8, block_end: *mut u8, size: usize, align: usize) -> Option<*mut u8> {
+        let mut start = align_ptr_up(block_start, align)?;
+        if start != block_start && (start as usize - block_start as usize) < MIN_BLOCK {
+            start = align_ptr_up(block_start.wrapping_add(MIN_BLOCK), align)?;
+        }
+        match distance(start, block_end) {
+            Some(space) if space >= size => Some(start),
+            _ => None,
+        }
+    }
+
+    // Makes `next` follow `prev` in the list, or the head of the list if
+    // `prev` is null.
+    unsafe fn set_next(&self, prev: *mut FreeBlock, next: *mut FreeBlock) {
+        if prev.is_null() {
+            self.head.set(next);
+        } else {
+            unsafe { (*prev).next = next };
+        }
+    }
+
+    // Adds `size` bytes at `start` to the list, merging them with adjacent
+    // free blocks. `start` and `size` must be multiples of BLOCK_ALIGN.
+    unsafe fn free(&self, start: *mut u8, size: usize) {
+        let mut prev: *mut FreeBlock = null_mut();
+        let mut next = self.head.get();
+        while !next.is_null() && (next as usize) < start as usize {
+            prev = next;
+            next = unsafe { (*next).next };
+        }
+
+        let mut block = start as *mut FreeBlock;
+        unsafe { block.write(FreeBlock { size, next }) };
+        if !next.is_null() && start as usize + size == next as usize {
+            unsafe {
+                (*block).size += (*next).size;
+                (*block).next = (*next).next;
+            }
+        }
+        if !prev.is_null() && prev as usize + unsafe { (*prev).size } == start as usize {
+            unsafe {
+                (*prev).size += (*block).size;
+                (*prev).next = (*block).next;
+            }
+            block = prev;
+        }
+        if block != prev {
+            unsafe { self.set_next(prev, block) };
+        }
+    }
+}
+
+// Returns the size and alignment of the block used for `layout`.
+fn block_layout(layout: Layout) -> Option<(usize, usize)> {
+    let size = align_up(layout.size().max(MIN_BLOCK), BLOCK_ALIGN)?;
+    Some((size, layout.align().max(BLOCK_ALIGN)))
+}
+
+impl<S: Syscalls> Default for LinkedListAllocator<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<S: Syscalls> GlobalAlloc for LinkedListAllocator<S> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let (size, align) = match block_layout(layout) {
+            Some(block) => block,
+            None => return null_mut(),
+        };
+        if let Some(ptr) = unsafe { self.take(size, align) } {
+            return ptr;
+        }
+        if self.fixed.get() {
+            return null_mut();
+        }
+        // Grow the heap by enough for the allocation, its alignment, and the
+        // alignment of the new memory itself.
+        let (start, end) = match align
+            .checked_add(MIN_BLOCK + BLOCK_ALIGN)
+            .and_then(|extra| size.checked_add(extra))
+            .and_then(grow_heap::<S>)
+        {
+            Some(memory) => memory,
+            None => return null_mut(),
+        };
+        unsafe { self.add_region(start, end) };
+        unsafe { self.take(size, align) }.unwrap_or(null_mut())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some((size, _)) = block_layout(layout) {
+            unsafe { self.free(ptr, size) };
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use core::alloc::{GlobalAlloc, Layout};
+use libtock_unittest::fake;
+
+type LinkedListAllocator = crate::LinkedListAllocator<fake::Syscalls>;
+
+// Gives the process `len` bytes of memory, aligned so that the alignment
+// padding in the tests is the same on every run.
+fn heap(kernel: &fake::Kernel, len: usize) {
+    let layout = Layout::from_size_align(len, 256).unwrap();
+    // Safety: the memory is never freed, so it is valid for 'static.
+    let memory = unsafe {
+        let start = std::alloc::alloc_zeroed(layout);
+        assert!(!start.is_null());
+        core::slice::from_raw_parts_mut(start, len)
+    };
+    kernel.set_memory_region(memory);
+}
+
+#[test]
+fn reuses_freed_blocks() {
+    let kernel = fake::Kernel::new();
+    heap(&kernel, 4096);
+    let allocator = LinkedListAllocator::new();
+    let small = Layout::from_size_align(32, 8).unwrap();
+    let large = Layout::from_size_align(64, 8).unwrap();
+    unsafe {
+        let a = allocator.alloc(small);
+        let b = allocator.alloc(large);
+        let c = allocator.alloc(small);
+        assert!(!a.is_null() && !b.is_null() && !c.is_null());
+        a.write_bytes(1, 32);
+        b.write_bytes(2, 64);
+        c.write_bytes(3, 32);
+
+        // The freed block is reused by an allocation that fits in it.
+        allocator.dealloc(b, large);
+        let d = allocator.alloc(small);
+        assert_eq!(d, b);
+        let e = allocator.alloc(small);
+        assert_eq!(e, b.add(32));
+        assert_eq!(*a, 1);
+        assert_eq!(*c, 3);
+
+        // Freeing blocks in any order merges them again: a, d, and e together
+        // span the memory before c.
+        allocator.dealloc(e, small);
+        allocator.dealloc(a, small);
+        allocator.dealloc(d, small);
+        let f = allocator.alloc(Layout::from_size_align(96, 8).unwrap());
+        assert_eq!(f, a);
+    }
+}
+
+#[test]
+fn alignment() {
+    let kernel = fake::Kernel::new();
+    heap(&kernel, 4096);
+    let allocator = LinkedListAllocator::new();
+    unsafe {
+        let a = allocator.alloc(Layout::from_size_align(1, 1).unwrap());
+        let b = allocator.alloc(Layout::from_size_align(16, 64).unwrap());
+        assert!(!a.is_null());
+        assert_eq!(b as usize % 64, 0);
+        // The padding before b is returned to the list.
+        let c = allocator.alloc(Layout::from_size_align(16, 8).unwrap());
+        assert!((c as usize) < b as usize);
+    }
+}
+
+#[test]
+fn out_of_memory() {
+    let kernel = fake::Kernel::new();
+    heap(&kernel, 1024);
+    let allocator = LinkedListAllocator::new();
+    unsafe {
+        assert!(allocator
+            .alloc(Layout::from_size_align(2048, 8).unwrap())
+            .is_null());
+        assert!(!allocator
+            .alloc(Layout::from_size_align(256, 8).unwrap())
+            .is_null());
+    }
+}
+
+#[test]
+fn fixed_heap() {
+    let kernel = fake::Kernel::new();
+    heap(&kernel, 1024);
+    let memory = Box::leak(Box::new([0u64; 8]));
+    let start = memory.as_mut_ptr() as *mut u8;
+    let allocator = LinkedListAllocator::new();
+    let layout = Layout::from_size_align(32, 8).unwrap();
+    unsafe {
+        allocator.init(start, 64);
+        let a = allocator.alloc(layout);
+        assert_eq!(a, start);
+        assert_eq!(allocator.alloc(layout), start.add(32));
+        // The heap is full, and does not grow even though the kernel has memory.
+        kernel.take_syscall_log();
+        assert!(allocator.alloc(layout).is_null());
+        assert!(kernel.take_syscall_log().is_empty());
+        // Freed memory is still reused.
+        allocator.dealloc(a, layout);
+        assert_eq!(allocator.alloc(layout), a);
+    }
+}
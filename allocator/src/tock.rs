@@ -0,0 +1,65 @@
+//! The global allocator used when building for Tock.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::ptr::addr_of_mut;
+use libtock_runtime::TockSyscalls;
+
+#[cfg(feature = "linked-list-alloc")]
+type Allocator = crate::LinkedListAllocator<TockSyscalls>;
+#[cfg(not(feature = "linked-list-alloc"))]
+type Allocator = crate::BumpAllocator<TockSyscalls>;
+
+#[global_allocator]
+static ALLOCATOR: TockAllocator = TockAllocator {
+    allocator: Allocator::new(),
+    initialized: Cell::new(false),
+};
+
+struct TockAllocator {
+    allocator: Allocator,
+    initialized: Cell<bool>,
+}
+
+impl TockAllocator {
+    // Gives the allocator the heap reserved by `heap_size!`, if there is one.
+    // The linker script places that heap between `_heap_start` and
+    // `_heap_end`, which are equal if `heap_size!` is not used.
+    fn initialize(&self) {
+        if self.initialized.replace(true) {
+            return;
+        }
+        // The allocator writes the heap through pointers to these symbols, so
+        // they are declared mutable.
+        extern "C" {
+            static mut _heap_start: u8;
+            static mut _heap_end: u8;
+        }
+        // Safety: addr_of_mut! does not create references to the symbols, and
+        // nothing else accesses the memory behind them, which is only written
+        // by the allocator after init below.
+        let (start, end) = unsafe { (addr_of_mut!(_heap_start), addr_of_mut!(_heap_end)) };
+        let size = end as usize - start as usize;
+        if size > 0 {
+            // Safety: the linker script reserves this memory for the heap, and
+            // initialized is set so this only happens once.
+            unsafe { self.allocator.init(start, size) };
+        }
+    }
+}
+
+// Safety: this is only compiled for Tock, where a process has a single thread
+// and upcalls only run inside Yield, which the allocator never calls. So
+// ALLOCATOR is never accessed from two places at once, despite its Cells.
+unsafe impl Sync for TockAllocator {}
+
+unsafe impl GlobalAlloc for TockAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.initialize();
+        unsafe { self.allocator.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.allocator.dealloc(ptr, layout) }
+    }
+}
@@ -0,0 +1,267 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::ops::ControlFlow;
+use core::task::Poll;
+use libtock_platform::{
+    share, subscribe::OneId, DefaultConfig, DoubleBuffer, DriverTransaction, ErrorCode, Subscribe,
+    Syscalls, Upcall,
+};
+
+/// The ADC driver
+///
+/// # Example
+/// ```ignore
+/// use libtock::adc::Adc;
+///
+/// // Read a single sample from channel 0.
+/// let sample = Adc::sample(0)?;
+/// ```
+pub struct Adc<S: Syscalls>(S);
+
+impl<S: Syscalls> Adc<S> {
+    /// Run a check against the ADC capsule to ensure it is present.
+    ///
+    /// Returns `Ok(())` if the driver was present. This does not necessarily
+    /// mean that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result::<u32, _>()?;
+        Ok(())
+    }
+
+    /// Returns the number of ADC channels available to the process.
+    pub fn count() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Returns the number of significant bits in each sample.
+    pub fn resolution_bits() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, GET_RESOLUTION_BITS, 0, 0).to_result()
+    }
+
+    /// Returns the reference voltage of the ADC, in millivolts. Fails with
+    /// `ErrorCode::NoSupport` if the board does not know its reference.
+    pub fn reference_voltage_mv() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, GET_REFERENCE_VOLTAGE_MV, 0, 0).to_result()
+    }
+
+    /// Takes a single sample from `channel`, like [`sample`](Self::sample),
+    /// and converts it to millivolts using the ADC's reference voltage and
+    /// resolution. The result is rounded down.
+    pub fn sample_millivolts(channel: u32) -> Result<u32, ErrorCode> {
+        let reference_mv = Self::reference_voltage_mv()?;
+        let bits = Self::resolution_bits()?;
+        let sample = Self::sample(channel)?;
+        Ok(to_millivolts(sample, reference_mv, bits))
+    }
+
+    /// Initiate a single conversion on `channel`. The result is delivered to
+    /// the registered listener.
+    pub fn start_sample(channel: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SINGLE_SAMPLE, channel, 0).to_result()
+    }
+
+    /// Register an events listener
+    pub fn register_listener<'share, F: Fn(u32, u16)>(
+        listener: &'share AdcListener<F>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the events listener
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, 0)
+    }
+
+    /// Take a single sample from `channel`, blocking until the conversion
+    /// completes. Returns the raw value reported by the capsule.
+    ///
+    /// If the capsule is already sampling, `ErrorCode::Busy` is returned
+    /// immediately.
+    pub fn sample(channel: u32) -> Result<u16, ErrorCode> {
+        let sample_cell: Cell<Option<u16>> = Cell::new(None);
+        let listener = AdcListener(|sample_channel, sample| {
+            if sample_channel == channel {
+                sample_cell.set(Some(sample));
+            }
+        });
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::start_sample(channel)?;
+            loop {
+                if let Some(sample) = sample_cell.get() {
+                    return Ok(sample);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+
+    /// Samples each of `channels` in turn, blocking until all conversions
+    /// complete, and stores the sample from `channels[i]` in `out[i]`. Returns
+    /// `ErrorCode::Size` without sampling if the slices differ in length.
+    ///
+    /// The capsule has no multi-channel command, so the channels are sampled
+    /// one after the other rather than simultaneously. Stops at, and returns,
+    /// the first error; earlier entries of `out` are already written.
+    pub fn sample_channels(channels: &[u32], out: &mut [u16]) -> Result<(), ErrorCode> {
+        if channels.len() != out.len() {
+            return Err(ErrorCode::Size);
+        }
+        let sample_cell: Cell<Option<(u32, u16)>> = Cell::new(None);
+        let listener = AdcListener(|channel, sample| sample_cell.set(Some((channel, sample))));
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            for (&channel, out) in channels.iter().zip(out) {
+                Self::start_sample(channel)?;
+                *out = loop {
+                    match sample_cell.take() {
+                        Some((sample_channel, sample)) if sample_channel == channel => {
+                            break sample
+                        }
+                        _ => S::yield_wait(),
+                    }
+                };
+            }
+            Ok(())
+        })
+    }
+
+    /// Non-blocking counterpart of `sample`, for apps that poll from their own
+    /// main loop. Before polling, an `AdcListener` storing into `reading` must
+    /// be registered and a sample started with `start_sample`. Each call
+    /// handles at most one pending upcall, and returns `Poll::Pending` until
+    /// the conversion completes.
+    pub fn try_sample(reading: &Cell<Option<u16>>) -> Poll<u16> {
+        S::yield_no_wait();
+        match reading.take() {
+            Some(sample) => Poll::Ready(sample),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Fill `buffer` with samples taken from `channel` at `frequency_hz`,
+    /// blocking until the buffer is full.
+    ///
+    /// `buffer` is only shared with the kernel for the duration of this call:
+    /// the allow is revoked before returning, whether sampling succeeded or
+    /// not.
+    ///
+    /// The highest supported frequency depends on the ADC hardware. The
+    /// capsule rejects frequencies it cannot sustain (as well as a frequency
+    /// of 0) with `ErrorCode::Invalid` rather than silently sampling slower.
+    /// If the capsule is already sampling, `ErrorCode::Busy` is returned
+    /// immediately.
+    pub fn sample_continuous(
+        channel: u32,
+        buffer: &mut [u16],
+        frequency_hz: u32,
+    ) -> Result<(), ErrorCode> {
+        let bytes = as_bytes(buffer);
+        DriverTransaction::<S, DRIVER_NUM, 0>::new(SINGLE_BUFFERED_SAMPLE, channel, frequency_hz)
+            .run_with_allow_rw::<ALLOW_BUFFER>(bytes)
+            .and_then(|(mode, _, _)| match mode {
+                MODE_SINGLE_BUFFER => Ok(()),
+                _ => Err(ErrorCode::Fail),
+            })
+    }
+
+    /// Samples `channel` at `frequency_hz` without gaps, filling `a` and `b`
+    /// in turn. Each full buffer is passed to `process` while the capsule
+    /// fills the other one, until `process` returns `ControlFlow::Break`.
+    ///
+    /// Samples are lost if `process` takes longer than the capsule takes to
+    /// fill a buffer. Frequencies are validated as for
+    /// [`sample_continuous`](Self::sample_continuous).
+    pub fn sample_stream<F: FnMut(&[u16]) -> ControlFlow<()>>(
+        channel: u32,
+        frequency_hz: u32,
+        a: &mut [u16],
+        b: &mut [u16],
+        mut process: F,
+    ) -> Result<(), ErrorCode> {
+        let (a, b) = (as_bytes(a), as_bytes(b));
+        DoubleBuffer::<S, DRIVER_NUM, 0, ALLOW_BUFFER, ALLOW_BUFFER_2>::new(
+            CONTINUOUS_BUFFERED_SAMPLE,
+            channel,
+            frequency_hz,
+            STOP,
+        )
+        .run(a, b, |bytes| {
+            // Safety: `bytes` is one of the u16 buffers viewed as bytes, so it
+            // is aligned for u16.
+            let samples = unsafe {
+                core::slice::from_raw_parts(bytes.as_ptr().cast::<u16>(), bytes.len() / 2)
+            };
+            process(samples)
+        })
+    }
+}
+
+// Views `samples` as bytes, to share it with the kernel. The kernel stores
+// each sample in native byte order, so the u16 view is valid again once the
+// allow is revoked.
+fn as_bytes(samples: &mut [u16]) -> &mut [u8] {
+    // Safety: u8 has no alignment requirement and every bit pattern is valid
+    // for both u8 and u16, so viewing the samples as bytes is sound.
+    unsafe {
+        core::slice::from_raw_parts_mut(
+            samples.as_mut_ptr().cast::<u8>(),
+            core::mem::size_of_val(samples),
+        )
+    }
+}
+
+/// A wrapper around a closure to be registered and called when a single
+/// sample completes. The closure receives the channel and the sample value.
+///
+/// ```ignore
+/// let listener = AdcListener(|channel, sample| {
+///     // make use of the sample
+/// });
+/// ```
+pub struct AdcListener<F: Fn(u32, u16)>(pub F);
+impl<F: Fn(u32, u16)> Upcall<OneId<DRIVER_NUM, 0>> for AdcListener<F> {
+    fn upcall(&self, mode: u32, channel: u32, sample: u32) {
+        if mode == MODE_SINGLE_SAMPLE {
+            self.0(channel, sample as u16)
+        }
+    }
+}
+
+// Converts a raw sample to millivolts. A sample of 2^bits would be the
+// reference voltage. Samples out of range for the resolution saturate.
+fn to_millivolts(sample: u16, reference_mv: u32, bits: u32) -> u32 {
+    let millivolts = (sample as u64 * reference_mv as u64) >> bits.min(63);
+    u32::try_from(millivolts).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x5;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SINGLE_SAMPLE: u32 = 1;
+const SINGLE_BUFFERED_SAMPLE: u32 = 3;
+const CONTINUOUS_BUFFERED_SAMPLE: u32 = 4;
+const STOP: u32 = 5;
+const GET_RESOLUTION_BITS: u32 = 101;
+const GET_REFERENCE_VOLTAGE_MV: u32 = 102;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 0;
+const ALLOW_BUFFER_2: u32 = 1;
+
+// Upcall modes (first upcall argument)
+
+const MODE_SINGLE_SAMPLE: u32 = 0;
+const MODE_SINGLE_BUFFER: u32 = 2;
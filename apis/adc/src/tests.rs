@@ -0,0 +1,288 @@
+use core::cell::Cell;
+use core::task::Poll;
+use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type Adc = super::Adc<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Adc::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Adc::count(), Err(ErrorCode::NoDevice));
+    assert_eq!(Adc::sample(0), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Adc::exists(), Ok(()));
+    assert_eq!(Adc::count(), Ok(4));
+}
+
+#[test]
+fn start_sample() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Adc::start_sample(4), Err(ErrorCode::Invalid));
+    assert_eq!(Adc::start_sample(0), Ok(()));
+    assert!(driver.is_busy());
+
+    assert_eq!(Adc::start_sample(0), Err(ErrorCode::Busy));
+    assert_eq!(Adc::sample(0), Err(ErrorCode::Busy));
+}
+
+#[test]
+fn register_unregister_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    let sample_cell: Cell<Option<(u32, u16)>> = Cell::new(None);
+    let listener = crate::AdcListener(|channel, sample| {
+        sample_cell.set(Some((channel, sample)));
+    });
+    share::scope(|subscribe| {
+        assert_eq!(Adc::start_sample(1), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert_eq!(Adc::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Adc::start_sample(1), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sample_cell.get(), Some((1, 100)));
+
+        Adc::unregister_listener();
+        assert_eq!(Adc::start_sample(1), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn sample() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync(0xBEEF);
+    assert_eq!(Adc::sample(2), Ok(0xBEEF));
+    assert!(!driver.is_busy());
+}
+
+#[test]
+fn reference_and_resolution() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Adc::resolution_bits(), Ok(12));
+    assert_eq!(Adc::reference_voltage_mv(), Ok(3300));
+}
+
+#[test]
+fn sample_millivolts() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    // Mid-scale is half the reference voltage.
+    driver.set_value_sync(2048);
+    assert_eq!(Adc::sample_millivolts(1), Ok(1650));
+    driver.set_value_sync(4095);
+    assert_eq!(Adc::sample_millivolts(1), Ok(3299));
+}
+
+#[test]
+fn to_millivolts() {
+    assert_eq!(super::to_millivolts(0, 3300, 12), 0);
+    assert_eq!(super::to_millivolts(0x8000, 1800, 16), 900);
+    assert_eq!(super::to_millivolts(u16::MAX, u32::MAX, 0), u32::MAX);
+}
+
+#[test]
+fn sample_channels() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    for value in [30, 10, 20, 0] {
+        driver.set_value_sync(value);
+    }
+    let mut out = [0xFFFF; 4];
+    assert_eq!(Adc::sample_channels(&[3, 1, 2, 0], &mut out), Ok(()));
+    assert_eq!(out, [30, 10, 20, 0]);
+    assert!(!driver.is_busy());
+
+    assert_eq!(
+        Adc::sample_channels(&[0, 1], &mut out),
+        Err(ErrorCode::Size)
+    );
+    // The first sample is stored even if a later channel fails.
+    driver.set_value_sync(7);
+    let mut out = [0; 2];
+    assert_eq!(
+        Adc::sample_channels(&[1, 4], &mut out),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(out, [7, 0]);
+}
+
+#[test]
+fn sample_continuous() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    let mut buffer = [0u16; 4];
+    driver.fill_buffer_sync(&[10, 20, 30, 40, 50]);
+    assert_eq!(Adc::sample_continuous(1, &mut buffer, 1000), Ok(()));
+    assert_eq!(buffer, [10, 20, 30, 40]);
+    assert!(!driver.is_busy());
+    assert_eq!(driver.buffer_len(), 0);
+}
+
+#[test]
+fn sample_continuous_error() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    let mut buffer = [0u16; 4];
+    assert_eq!(
+        Adc::sample_continuous(1, &mut buffer, 0),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(driver.buffer_len(), 0);
+
+    assert_eq!(Adc::start_sample(0), Ok(()));
+    assert_eq!(
+        Adc::sample_continuous(1, &mut buffer, 1000),
+        Err(ErrorCode::Busy)
+    );
+    assert_eq!(driver.buffer_len(), 0);
+    assert_eq!(buffer, [0; 4]);
+}
+
+#[test]
+fn sample_stream() {
+    use core::ops::ControlFlow;
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    let mut a = [0u16; 2];
+    let mut b = [0u16; 2];
+    let mut seen = [[0; 2]; 3];
+    let mut count = 0;
+    driver.fill_buffer_sync(&[1, 2]);
+    let result = Adc::sample_stream(2, 1000, &mut a, &mut b, |samples| {
+        // Each buffer is handed over once the kernel has given it back, and
+        // the capsule keeps sampling into the other one.
+        let (current, other) = match count % 2 {
+            0 => (driver.buffer_len(), driver.buffer_2_len()),
+            _ => (driver.buffer_2_len(), driver.buffer_len()),
+        };
+        assert_eq!((current, other), (0, 4));
+        assert!(driver.is_busy());
+        seen[count].copy_from_slice(samples);
+        count += 1;
+        if count == seen.len() {
+            return ControlFlow::Break(());
+        }
+        driver.fill_buffer(&[10 * count as u16, 10 * count as u16 + 1]);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(seen, [[1, 2], [10, 11], [20, 21]]);
+    assert!(!driver.is_busy());
+    assert_eq!((driver.buffer_len(), driver.buffer_2_len()), (0, 0));
+}
+
+#[test]
+fn try_sample() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    let reading: Cell<Option<u16>> = Cell::new(None);
+    let listener = crate::AdcListener(|_channel, sample| reading.set(Some(sample)));
+    share::scope(|subscribe| {
+        assert_eq!(Adc::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Adc::start_sample(2), Ok(()));
+        assert_eq!(Adc::try_sample(&reading), Poll::Pending);
+        assert_eq!(Adc::try_sample(&reading), Poll::Pending);
+
+        driver.set_value(512);
+        assert_eq!(Adc::try_sample(&reading), Poll::Ready(512));
+        assert_eq!(Adc::try_sample(&reading), Poll::Pending);
+    });
+}
+
+#[test]
+fn upcall_arguments() {
+    use libtock_platform::Upcall;
+
+    // The listener gets the channel from arg1 and the sample from arg2, and
+    // ignores upcalls for other modes.
+    let received = Cell::new(None);
+    let listener = super::AdcListener(|channel, sample| received.set(Some((channel, sample))));
+    listener.upcall(super::MODE_SINGLE_SAMPLE, 3, 0x1234);
+    assert_eq!(received.take(), Some((3, 0x1234)));
+    listener.upcall(super::MODE_SINGLE_BUFFER, 3, 0x1234);
+    assert_eq!(received.take(), None);
+
+    // The sample is taken from the upcall, without a follow-up command.
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+    driver.set_value_sync(0x0321);
+    assert_eq!(Adc::sample(1), Ok(0x0321));
+    let commands = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter(|entry| matches!(entry, SyscallLogEntry::Command { .. }));
+    assert!(commands.eq([SyscallLogEntry::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::SINGLE_SAMPLE,
+        argument0: 1,
+        argument1: 0,
+    }]));
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Adc::<4>::new();
+    kernel.add_driver(&driver);
+
+    // The ADC is already sampling channel 0, so sampling channel 1 fails.
+    assert_eq!(Adc::start_sample(0), Ok(()));
+    kernel.take_syscall_log();
+    assert_eq!(Adc::sample(1), Err(ErrorCode::Busy));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::SINGLE_SAMPLE,
+                argument0: 1,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
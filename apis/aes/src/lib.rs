@@ -0,0 +1,187 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// AES-128 in counter (CTR) mode, computed by the kernel's AES capsule.
+///
+/// CTR mode is symmetric, so [`crypt`](Self::crypt) both encrypts and
+/// decrypts. The counter starts at the IV and carries on across calls to
+/// `crypt`, so a long message can be processed piece by piece. Setting the
+/// key or the IV restarts the counter.
+///
+/// The capsule keeps one AES session per process, so only one `AesCtr`
+/// should be used at a time.
+///
+/// # Example
+/// ```ignore
+/// use libtock::aes::AesCtr;
+///
+/// let mut aes = AesCtr::new();
+/// aes.set_key(&key);
+/// aes.set_iv(&iv);
+/// aes.crypt(&mut message)?;
+/// ```
+pub struct AesCtr<S: Syscalls> {
+    key: Option<[u8; BLOCK_LEN]>,
+    iv: Option<[u8; BLOCK_LEN]>,
+    // Whether the key and IV have been loaded into the capsule.
+    session_started: bool,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> AesCtr<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Creates a cipher without a key or an IV.
+    pub const fn new() -> Self {
+        AesCtr {
+            key: None,
+            iv: None,
+            session_started: false,
+            _syscalls: PhantomData,
+        }
+    }
+
+    pub fn set_key(&mut self, key: &[u8; 16]) {
+        self.key = Some(*key);
+        self.session_started = false;
+    }
+
+    /// Sets the initial counter block.
+    pub fn set_iv(&mut self, iv: &[u8; 16]) {
+        self.iv = Some(*iv);
+        self.session_started = false;
+    }
+
+    /// Encrypts or decrypts `data` in place, blocking until it has been
+    /// processed.
+    ///
+    /// The capsule works on whole 16-byte blocks, so `data.len()` must be a
+    /// multiple of 16; otherwise `ErrorCode::Size` is returned before
+    /// anything is sent to the kernel. `ErrorCode::Reserve` is returned if
+    /// the key or the IV has not been set.
+    pub fn crypt(&mut self, data: &mut [u8]) -> Result<(), ErrorCode> {
+        let (key, iv) = match (self.key, self.iv) {
+            (Some(key), Some(iv)) => (key, iv),
+            _ => return Err(ErrorCode::Reserve),
+        };
+        if data.len() % BLOCK_LEN != 0 {
+            return Err(ErrorCode::Size);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        if !self.session_started {
+            Self::setup(&key, &iv)?;
+            self.session_started = true;
+        }
+        // The capsule reads from a read-only buffer and writes to a
+        // read-write one, which cannot alias, so each chunk is copied out
+        // before being overwritten by the kernel.
+        let mut source = [0; CHUNK_LEN];
+        for chunk in data.chunks_mut(CHUNK_LEN) {
+            let source = &mut source[..chunk.len()];
+            source.copy_from_slice(chunk);
+            Self::crypt_chunk(source, chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Syscalls> Default for AesCtr<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> AesCtr<S> {
+    // Selects CTR mode and loads the key and IV into the capsule.
+    fn setup(key: &[u8; BLOCK_LEN], iv: &[u8; BLOCK_LEN]) -> Result<(), ErrorCode> {
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+                AllowRo<_, DRIVER_NUM, ALLOW_IV>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_iv) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, key)?;
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_IV>(allow_iv, iv)?;
+            S::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_AES128_CTR, 0)
+                .to_result::<(), ErrorCode>()?;
+            S::command(DRIVER_NUM, SETUP, 0, 0).to_result()
+        })
+    }
+
+    fn crypt_chunk(source: &[u8], dest: &mut [u8]) -> Result<(), ErrorCode> {
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_SOURCE>,
+                AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_source, allow_dest, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_SOURCE>(allow_source, source)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, dest)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &done)?;
+            S::command(DRIVER_NUM, CRYPT, 0, 0).to_result::<(), ErrorCode>()?;
+            loop {
+                match done.get() {
+                    Some((0,)) => return Ok(()),
+                    Some((status,)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40006;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SET_ALGORITHM: u32 = 1;
+const SETUP: u32 = 2;
+const CRYPT: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_KEY: u32 = 0;
+const ALLOW_IV: u32 = 1;
+const ALLOW_SOURCE: u32 = 2;
+const ALLOW_DEST: u32 = 0;
+
+// Algorithms
+
+const ALGORITHM_AES128_CTR: u32 = 0;
+
+const BLOCK_LEN: usize = 16;
+// The number of bytes processed per crypt command. This is a multiple of the
+// block size, so the counter carries on between chunks.
+const CHUNK_LEN: usize = 4 * BLOCK_LEN;
@@ -0,0 +1,117 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type AesCtr = super::AesCtr<fake::Syscalls>;
+
+// NIST SP 800-38A, F.5.1 CTR-AES128.Encrypt.
+const KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+];
+const IV: [u8; 16] = [
+    0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff,
+];
+const PLAINTEXT: [u8; 64] = [
+    0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17, 0x2a,
+    0xae, 0x2d, 0x8a, 0x57, 0x1e, 0x03, 0xac, 0x9c, 0x9e, 0xb7, 0x6f, 0xac, 0x45, 0xaf, 0x8e, 0x51,
+    0x30, 0xc8, 0x1c, 0x46, 0xa3, 0x5c, 0xe4, 0x11, 0xe5, 0xfb, 0xc1, 0x19, 0x1a, 0x0a, 0x52, 0xef,
+    0xf6, 0x9f, 0x24, 0x45, 0xdf, 0x4f, 0x9b, 0x17, 0xad, 0x2b, 0x41, 0x7b, 0xe6, 0x6c, 0x37, 0x10,
+];
+const CIPHERTEXT: [u8; 64] = [
+    0x87, 0x4d, 0x61, 0x91, 0xb6, 0x20, 0xe3, 0x26, 0x1b, 0xef, 0x68, 0x64, 0x99, 0x0d, 0xb6, 0xce,
+    0x98, 0x06, 0xf6, 0x6b, 0x79, 0x70, 0xfd, 0xff, 0x86, 0x17, 0x18, 0x7b, 0xb9, 0xff, 0xfd, 0xff,
+    0x5a, 0xe4, 0xdf, 0x3e, 0xdb, 0xd5, 0xd3, 0x5e, 0x5b, 0x4f, 0x09, 0x02, 0x0d, 0xb0, 0x3e, 0xab,
+    0x1e, 0x03, 0x1d, 0xda, 0x2f, 0xbe, 0x03, 0xd1, 0x79, 0x21, 0x70, 0xa0, 0xf3, 0x00, 0x9c, 0xee,
+];
+
+fn cipher() -> AesCtr {
+    let mut aes = AesCtr::new();
+    aes.set_key(&KEY);
+    aes.set_iv(&IV);
+    aes
+}
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(AesCtr::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(cipher().crypt(&mut [0; 16]), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AesCtr::exists(), Ok(()));
+}
+
+#[test]
+fn encrypt_decrypt() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    let mut data = PLAINTEXT;
+    assert_eq!(cipher().crypt(&mut data), Ok(()));
+    assert_eq!(data, CIPHERTEXT);
+    assert_eq!(cipher().crypt(&mut data), Ok(()));
+    assert_eq!(data, PLAINTEXT);
+}
+
+#[test]
+fn counter_continues() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    let mut aes = cipher();
+    let mut data = PLAINTEXT;
+    assert_eq!(aes.crypt(&mut data[..16]), Ok(()));
+    assert_eq!(aes.crypt(&mut data[16..]), Ok(()));
+    assert_eq!(data, CIPHERTEXT);
+
+    // Setting the IV restarts the counter.
+    aes.set_iv(&IV);
+    assert_eq!(aes.crypt(&mut data), Ok(()));
+    assert_eq!(data, PLAINTEXT);
+}
+
+#[test]
+fn chunked() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    // Encrypting zeroes yields the keystream. The fifth block is produced by
+    // a second crypt command.
+    let mut data = [0; 80];
+    assert_eq!(cipher().crypt(&mut data), Ok(()));
+    assert_eq!(driver.crypt_count(), 2);
+    for ((keystream, plaintext), ciphertext) in data.iter().zip(PLAINTEXT).zip(CIPHERTEXT) {
+        assert_eq!(*keystream, plaintext ^ ciphertext);
+    }
+    assert_eq!(
+        data[64..],
+        [
+            0xb0, 0x0d, 0x47, 0xf8, 0x14, 0x8a, 0x91, 0x0e, 0xf0, 0x68, 0x30, 0x97, 0x90, 0x4b,
+            0xa5, 0x02
+        ]
+    );
+}
+
+#[test]
+fn invalid_arguments() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Aes::new();
+    kernel.add_driver(&driver);
+
+    let mut aes = AesCtr::new();
+    assert_eq!(aes.crypt(&mut [0; 16]), Err(ErrorCode::Reserve));
+    aes.set_key(&KEY);
+    assert_eq!(aes.crypt(&mut [0; 16]), Err(ErrorCode::Reserve));
+    aes.set_iv(&IV);
+    assert_eq!(aes.crypt(&mut [0; 15]), Err(ErrorCode::Size));
+    assert_eq!(aes.crypt(&mut []), Ok(()));
+    assert_eq!(kernel.take_syscall_log(), []);
+}
@@ -1,6 +1,8 @@
 #![no_std]
 
 use core::cell::Cell;
+use core::marker::PhantomData;
+use core::time::Duration;
 use libtock_platform as platform;
 use libtock_platform::share;
 use libtock_platform::{DefaultConfig, ErrorCode, Syscalls};
@@ -25,7 +27,7 @@ pub trait Convert {
     fn to_ticks(self, freq: Hz) -> Ticks;
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Ticks(pub u32);
 
 impl Convert for Ticks {
@@ -42,21 +44,43 @@ impl Convert for Milliseconds {
         // Saturating multiplication will top out at about 1 hour at 1MHz.
         // It's large enough for an alarm, and much simpler than failing
         // or losing precision for short sleeps.
-
-        /// u32::div_ceil is still unstable.
-        fn div_ceil(a: u32, other: u32) -> u32 {
-            let d = a / other;
-            let m = a % other;
-            if m == 0 {
-                d
-            } else {
-                d + 1
-            }
-        }
         Ticks(div_ceil(self.0.saturating_mul(freq.0), 1000))
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct Microseconds(pub u32);
+
+impl Convert for Microseconds {
+    fn to_ticks(self, freq: Hz) -> Ticks {
+        // Computed in 64 bits, as the product overflows u32 for more than a
+        // few milliseconds at common frequencies. Rounding up means that any
+        // non-zero duration shorter than a tick lasts a full tick.
+        let ticks = (self.0 as u64 * freq.0 as u64 + 999_999) / 1_000_000;
+        Ticks(ticks.min(u32::MAX as u64) as u32)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Seconds(pub u32);
+
+impl Convert for Seconds {
+    fn to_ticks(self, freq: Hz) -> Ticks {
+        Ticks(self.0.saturating_mul(freq.0))
+    }
+}
+
+/// u32::div_ceil is still unstable.
+fn div_ceil(a: u32, other: u32) -> u32 {
+    let d = a / other;
+    let m = a % other;
+    if m == 0 {
+        d
+    } else {
+        d + 1
+    }
+}
+
 impl<S: Syscalls, C: platform::subscribe::Config> Alarm<S, C> {
     /// Run a check against the console capsule to ensure it is present.
     #[inline(always)]
@@ -70,6 +94,96 @@ impl<S: Syscalls, C: platform::subscribe::Config> Alarm<S, C> {
             .map(Hz)
     }
 
+    /// Returns the current value of the alarm's tick counter. The counter
+    /// wraps around to 0 after `u32::MAX`.
+    pub fn get_ticks() -> Result<Ticks, ErrorCode> {
+        S::command(DRIVER_NUM, command::TIME, 0, 0)
+            .to_result()
+            .map(Ticks)
+    }
+
+    /// Sleeps until the tick counter reaches `deadline`.
+    ///
+    /// As the counter wraps around, a deadline is interpreted relative to the
+    /// current time: deadlines up to half the counter range ahead are in
+    /// the future, while more distant ones are taken to have already passed,
+    /// in which case this returns immediately.
+    pub fn sleep_until(deadline: Ticks) -> Result<(), ErrorCode> {
+        let now = Self::get_ticks()?;
+        let dt = deadline.0.wrapping_sub(now.0);
+        if dt > u32::MAX / 2 {
+            return Ok(());
+        }
+
+        let called: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::CALLBACK }>(subscribe, &called)?;
+
+            // The alarm is set relative to the time read above, so time
+            // passing since then does not delay it.
+            S::command(DRIVER_NUM, command::SET_ABSOLUTE, now.0, dt)
+                .to_result()
+                .map(|_when: u32| ())?;
+
+            loop {
+                S::yield_wait();
+                if let Some((_when, _ref)) = called.get() {
+                    return Ok(());
+                }
+            }
+        })
+    }
+
+    /// Waits for an operation to complete, giving up after `time`.
+    ///
+    /// `poll` is called after every upcall and returns `Some` once the
+    /// operation is complete. The operation is expected to have been started
+    /// already, with its upcall subscribed in a `share::scope` enclosing this
+    /// call. On timeout, leaving that scope unsubscribes the upcall, so a
+    /// late completion cannot reach state that no longer exists.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    /// share::scope(|subscribe| {
+    ///     Humidity::register_listener(&listener, subscribe)?;
+    ///     Humidity::read()?;
+    ///     Alarm::timeout(Milliseconds(500), || listener.get())
+    ///         .map_err(|_| ErrorCode::Fail)
+    /// })
+    /// ```
+    pub fn timeout<T: Convert, R, F: FnMut() -> Option<R>>(
+        time: T,
+        mut poll: F,
+    ) -> Result<R, TimeoutError> {
+        if let Some(result) = poll() {
+            return Ok(result);
+        }
+        let freq = Self::get_frequency()?;
+        let ticks = time.to_ticks(freq);
+
+        let called: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::CALLBACK }>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, command::SET_RELATIVE, ticks.0, 0)
+                .to_result::<u32, ErrorCode>()?;
+
+            loop {
+                S::yield_wait();
+                if let Some(result) = poll() {
+                    // The alarm is no longer needed. Its upcall, if still
+                    // pending, is discarded when leaving the scope.
+                    let _ = S::command(DRIVER_NUM, command::STOP, 0, 0);
+                    return Ok(result);
+                }
+                if called.get().is_some() {
+                    return Err(TimeoutError::TimedOut);
+                }
+            }
+        })
+    }
+
     pub fn sleep_for<T: Convert>(time: T) -> Result<(), ErrorCode> {
         let freq = Self::get_frequency()?;
         let ticks = time.to_ticks(freq);
@@ -90,6 +204,296 @@ impl<S: Syscalls, C: platform::subscribe::Config> Alarm<S, C> {
             }
         })
     }
+
+    /// Sleeps for `time`, which may be longer than the tick counter's range,
+    /// such as hours at 32kHz or more. `sleep_for` saturates at `u32::MAX`
+    /// ticks instead.
+    ///
+    /// The sleep is split into alarms of at most half the counter range, each
+    /// set relative to the previous deadline rather than to the time it fires,
+    /// so the steps add up to exactly `time` across any number of counter
+    /// wraps. `time` is rounded up to a whole number of ticks.
+    pub fn sleep_for_duration(time: Duration) -> Result<(), ErrorCode> {
+        let freq = Self::get_frequency()?;
+        let ticks = (time.as_nanos() * freq.0 as u128 + 999_999_999) / 1_000_000_000;
+        let mut remaining = ticks.min(u64::MAX as u128) as u64;
+        if remaining == 0 {
+            return Ok(());
+        }
+        let mut reference = Self::get_ticks()?.0;
+
+        let called: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::CALLBACK }>(subscribe, &called)?;
+
+            while remaining > 0 {
+                let dt = remaining.min(u64::from(u32::MAX / 2)) as u32;
+                called.set(None);
+                S::command(DRIVER_NUM, command::SET_ABSOLUTE, reference, dt)
+                    .to_result()
+                    .map(|_when: u32| ())?;
+                while called.get().is_none() {
+                    S::yield_wait();
+                }
+                reference = reference.wrapping_add(dt);
+                remaining -= dt as u64;
+            }
+            Ok(())
+        })
+    }
+
+    /// Calls `f` up to `attempts` times (at least once), sleeping for `delay`
+    /// between calls, for as long as it fails with `ErrorCode::Busy`. Returns
+    /// the first success or other error, or `Busy` after the last attempt.
+    ///
+    /// # Example
+    /// ```ignore
+    /// Alarm::retry_on_busy(3, Milliseconds(10), || Temperature::read_temperature_sync())
+    /// ```
+    pub fn retry_on_busy<T: Convert + Copy, R, F: FnMut() -> Result<R, ErrorCode>>(
+        attempts: usize,
+        delay: T,
+        mut f: F,
+    ) -> Result<R, ErrorCode> {
+        for _ in 1..attempts {
+            match f() {
+                Err(ErrorCode::Busy) => Self::sleep_for(delay)?,
+                result => return result,
+            }
+        }
+        f()
+    }
+}
+
+/// The error returned by [`Alarm::timeout`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimeoutError {
+    /// The operation did not complete in time.
+    TimedOut,
+    /// The alarm could not be set.
+    Alarm(ErrorCode),
+}
+
+impl From<ErrorCode> for TimeoutError {
+    fn from(error: ErrorCode) -> TimeoutError {
+        TimeoutError::Alarm(error)
+    }
+}
+
+/// What a [`PeriodicTimer`] does when a period has already elapsed by the
+/// time [`PeriodicTimer::tick`] is called.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MissedTickPolicy {
+    /// Skip the missed periods and wait for the next one, so that wakeups
+    /// always stay aligned to the period.
+    Skip,
+    /// Return immediately for each missed period, so that the number of
+    /// wakeups matches the elapsed time.
+    Immediate,
+}
+
+/// Wakes up at a fixed rate.
+///
+/// Each wakeup is scheduled relative to the time the timer was created,
+/// rather than to the previous wakeup, so the time spent working between
+/// ticks does not accumulate: the Nth tick returns at `start + N * period`.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::{MissedTickPolicy, Milliseconds, PeriodicTimer};
+///
+/// // Log a sensor at 10 Hz
+/// let mut timer = PeriodicTimer::new(Milliseconds(100), MissedTickPolicy::Skip)?;
+/// loop {
+///     timer.tick()?;
+///     // read and log the sensor
+/// }
+/// ```
+pub struct PeriodicTimer<S: Syscalls, C: platform::subscribe::Config = DefaultConfig> {
+    next: Ticks,
+    period: Ticks,
+    policy: MissedTickPolicy,
+    _alarm: PhantomData<Alarm<S, C>>,
+}
+
+impl<S: Syscalls, C: platform::subscribe::Config> PeriodicTimer<S, C> {
+    /// Starts a timer that ticks every `period`, starting one period from
+    /// now. `period` is rounded up to a whole, non-zero number of ticks.
+    pub fn new<T: Convert>(period: T, policy: MissedTickPolicy) -> Result<Self, ErrorCode> {
+        let period = Ticks(period.to_ticks(Alarm::<S, C>::get_frequency()?).0.max(1));
+        let now = Alarm::<S, C>::get_ticks()?;
+        Ok(PeriodicTimer {
+            next: Ticks(now.0.wrapping_add(period.0)),
+            period,
+            policy,
+            _alarm: PhantomData,
+        })
+    }
+
+    /// Sleeps until the next period starts.
+    pub fn tick(&mut self) -> Result<(), ErrorCode> {
+        let now = Alarm::<S, C>::get_ticks()?;
+        let late = now.0.wrapping_sub(self.next.0);
+        // As in `Alarm::sleep_until`, a deadline more than half the counter
+        // range ahead is treated as having passed.
+        if late != 0 && late <= u32::MAX / 2 {
+            match self.policy {
+                MissedTickPolicy::Skip => {
+                    let missed = late / self.period.0 + 1;
+                    self.next.0 = self.next.0.wrapping_add(missed.wrapping_mul(self.period.0));
+                }
+                MissedTickPolicy::Immediate => {
+                    self.next.0 = self.next.0.wrapping_add(self.period.0);
+                    return Ok(());
+                }
+            }
+        }
+        Alarm::<S, C>::sleep_until(self.next)?;
+        self.next.0 = self.next.0.wrapping_add(self.period.0);
+        Ok(())
+    }
+}
+
+/// A point in time, as read from the alarm's tick counter, for measuring how
+/// long something took.
+///
+/// Durations are computed with wrapping subtraction, so they are correct
+/// across a wrap of the counter, as long as less than one full counter period
+/// separates the two instants.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::Instant;
+///
+/// let start = Instant::now()?;
+/// // the work being profiled
+/// let took = start.elapsed()?;
+/// ```
+pub struct Instant<S: Syscalls, C: platform::subscribe::Config = DefaultConfig> {
+    ticks: Ticks,
+    freq: Hz,
+    _alarm: PhantomData<Alarm<S, C>>,
+}
+
+impl<S: Syscalls, C: platform::subscribe::Config> Instant<S, C> {
+    pub fn now() -> Result<Self, ErrorCode> {
+        let freq = Alarm::<S, C>::get_frequency()?;
+        let ticks = Alarm::<S, C>::get_ticks()?;
+        Ok(Instant {
+            ticks,
+            freq,
+            _alarm: PhantomData,
+        })
+    }
+
+    /// Returns the value of the tick counter at this instant.
+    pub fn ticks(&self) -> Ticks {
+        self.ticks
+    }
+
+    /// Returns the time from `earlier` to this instant.
+    pub fn duration_since(&self, earlier: Instant<S, C>) -> Duration {
+        let ticks = self.ticks.0.wrapping_sub(earlier.ticks.0);
+        // Less than 2^32 * 10^9, so this does not overflow.
+        let nanos = u64::from(ticks) * 1_000_000_000 / u64::from(self.freq.0.max(1));
+        Duration::from_nanos(nanos)
+    }
+
+    /// Returns the time since this instant.
+    pub fn elapsed(&self) -> Result<Duration, ErrorCode> {
+        Ok(Self::now()?.duration_since(*self))
+    }
+}
+
+impl<S: Syscalls, C: platform::subscribe::Config> Clone for Instant<S, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: Syscalls, C: platform::subscribe::Config> Copy for Instant<S, C> {}
+
+/// Measures how long sections of code take, built on [`Instant`].
+///
+/// The time is accumulated while the stopwatch is running, across laps and
+/// across stops and restarts, until it is reset.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::Stopwatch;
+///
+/// let mut stopwatch = Stopwatch::new();
+/// stopwatch.start()?;
+/// // first step
+/// let first = stopwatch.lap()?;
+/// // second step
+/// let total = stopwatch.stop()?;
+/// ```
+pub struct Stopwatch<S: Syscalls, C: platform::subscribe::Config = DefaultConfig> {
+    // The start of the current lap, if running.
+    lap_start: Option<Instant<S, C>>,
+    total: Duration,
+}
+
+impl<S: Syscalls, C: platform::subscribe::Config> Stopwatch<S, C> {
+    pub const fn new() -> Self {
+        Stopwatch {
+            lap_start: None,
+            total: Duration::ZERO,
+        }
+    }
+
+    /// Starts the stopwatch. Does nothing if it is already running.
+    pub fn start(&mut self) -> Result<(), ErrorCode> {
+        if self.lap_start.is_none() {
+            self.lap_start = Some(Instant::now()?);
+        }
+        Ok(())
+    }
+
+    /// Ends the current lap and starts the next one, returning the duration
+    /// of the lap that ended. Returns zero if the stopwatch is not running.
+    pub fn lap(&mut self) -> Result<Duration, ErrorCode> {
+        let lap_start = match self.lap_start {
+            Some(lap_start) => lap_start,
+            None => return Ok(Duration::ZERO),
+        };
+        let now = Instant::now()?;
+        let lap = now.duration_since(lap_start);
+        self.total += lap;
+        self.lap_start = Some(now);
+        Ok(lap)
+    }
+
+    /// Stops the stopwatch, returning the total time it has been running.
+    pub fn stop(&mut self) -> Result<Duration, ErrorCode> {
+        self.lap()?;
+        self.lap_start = None;
+        Ok(self.total)
+    }
+
+    /// Returns the time the stopwatch has been running, up to the end of the
+    /// last lap or stop.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Returns true if the stopwatch is running.
+    pub fn is_running(&self) -> bool {
+        self.lap_start.is_some()
+    }
+
+    /// Stops the stopwatch and clears the accumulated time.
+    pub fn reset(&mut self) {
+        self.lap_start = None;
+        self.total = Duration::ZERO;
+    }
+}
+
+impl<S: Syscalls, C: platform::subscribe::Config> Default for Stopwatch<S, C> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -1,8 +1,20 @@
-use libtock_unittest::fake;
+extern crate std;
 
-use crate::{Hz, Milliseconds, Ticks};
+use libtock_unittest::{fake, SyscallLogEntry};
+
+use core::cell::Cell;
+use core::time::Duration;
+use libtock_platform::{share, DefaultConfig, ErrorCode, Syscalls, YieldNoWaitReturn};
+use std::vec::Vec;
+
+use crate::{
+    Convert, Hz, Microseconds, Milliseconds, MissedTickPolicy, Seconds, Ticks, TimeoutError,
+};
 
 type Alarm = crate::Alarm<fake::Syscalls>;
+type Instant = crate::Instant<fake::Syscalls>;
+type PeriodicTimer = crate::PeriodicTimer<fake::Syscalls>;
+type Stopwatch = crate::Stopwatch<fake::Syscalls>;
 
 #[test]
 fn get_freq() {
@@ -22,3 +34,447 @@ fn sleep() {
     assert_eq!(Alarm::sleep_for(Ticks(1000)), Ok(()));
     assert_eq!(Alarm::sleep_for(Milliseconds(1000)), Ok(()));
 }
+
+#[test]
+fn get_ticks() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(1234);
+    assert_eq!(Alarm::get_ticks(), Ok(Ticks(1234)));
+}
+
+#[test]
+fn sleep_until() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(100);
+    assert_eq!(Alarm::sleep_until(Ticks(150)), Ok(()));
+    assert_eq!(driver.get_now(), 150);
+}
+
+#[test]
+fn sleep_until_before_wrap() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(u32::MAX - 100);
+    assert_eq!(Alarm::sleep_until(Ticks(u32::MAX - 10)), Ok(()));
+    assert_eq!(driver.get_now(), u32::MAX - 10);
+}
+
+#[test]
+fn sleep_until_after_wrap() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(u32::MAX - 10);
+    kernel.take_syscall_log();
+    assert_eq!(Alarm::sleep_until(Ticks(10)), Ok(()));
+    assert!(kernel
+        .take_syscall_log()
+        .contains(&SyscallLogEntry::Command {
+            driver_id: 0,
+            command_id: 6,
+            argument0: u32::MAX - 10,
+            argument1: 21,
+        }));
+    assert_eq!(driver.get_now(), 10);
+}
+
+#[test]
+fn sleep_until_passed() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    // Just past a deadline that was set before the counter wrapped.
+    driver.set_now(10);
+    kernel.take_syscall_log();
+    assert_eq!(Alarm::sleep_until(Ticks(u32::MAX - 10)), Ok(()));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::Command {
+            driver_id: 0,
+            command_id: 2,
+            argument0: 0,
+            argument1: 0,
+        }]
+    );
+    assert_eq!(driver.get_now(), 10);
+}
+
+#[test]
+fn convert_1khz() {
+    let freq = Hz(1000);
+    assert_eq!(Milliseconds(25).to_ticks(freq), Ticks(25));
+    assert_eq!(Microseconds(0).to_ticks(freq), Ticks(0));
+    // Shorter than a tick rounds up to a full tick.
+    assert_eq!(Microseconds(1).to_ticks(freq), Ticks(1));
+    assert_eq!(Microseconds(1500).to_ticks(freq), Ticks(2));
+    assert_eq!(Microseconds(2000).to_ticks(freq), Ticks(2));
+    assert_eq!(Seconds(3).to_ticks(freq), Ticks(3000));
+}
+
+#[test]
+fn convert_32khz() {
+    let freq = Hz(32768);
+    assert_eq!(Milliseconds(1000).to_ticks(freq), Ticks(32768));
+    assert_eq!(Milliseconds(1).to_ticks(freq), Ticks(33));
+    assert_eq!(Microseconds(1).to_ticks(freq), Ticks(1));
+    assert_eq!(Microseconds(30).to_ticks(freq), Ticks(1));
+    assert_eq!(Microseconds(31).to_ticks(freq), Ticks(2));
+    assert_eq!(Microseconds(1_000_000).to_ticks(freq), Ticks(32768));
+    assert_eq!(Seconds(2).to_ticks(freq), Ticks(65536));
+}
+
+#[test]
+fn convert_saturates() {
+    let freq = Hz(1_000_000);
+    assert_eq!(Microseconds(u32::MAX).to_ticks(freq), Ticks(u32::MAX));
+    assert_eq!(Seconds(5000).to_ticks(freq), Ticks(u32::MAX));
+}
+
+#[test]
+fn sleep_microseconds() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(32768);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Alarm::sleep_for(Microseconds(10)), Ok(()));
+    assert_eq!(driver.get_now(), 1);
+    assert_eq!(Alarm::sleep_for(Microseconds(500)), Ok(()));
+    assert_eq!(driver.get_now(), 18);
+}
+
+// Returns the `(reference, dt)` of each alarm set with SET_ABSOLUTE.
+fn absolute_alarms(kernel: &fake::Kernel) -> Vec<(u32, u32)> {
+    kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            SyscallLogEntry::Command {
+                driver_id: 0,
+                command_id: 6,
+                argument0,
+                argument1,
+            } => Some((argument0, argument1)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn sleep_for_duration_24h() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(32768);
+    kernel.add_driver(&driver);
+
+    // 24 hours at 32kHz is 2_831_155_200 ticks, more than half the counter
+    // range, so it takes two alarms.
+    driver.set_now(1000);
+    assert_eq!(
+        Alarm::sleep_for_duration(Duration::from_secs(24 * 3600)),
+        Ok(())
+    );
+    let half = u32::MAX / 2;
+    assert_eq!(
+        absolute_alarms(&kernel),
+        [(1000, half), (1000 + half, 2_831_155_200 - half)]
+    );
+    assert_eq!(driver.get_now(), 1000 + 2_831_155_200);
+}
+
+#[test]
+fn sleep_for_duration_wraps() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1_000_000);
+    kernel.add_driver(&driver);
+
+    // 10_000 seconds at 1MHz is longer than two counter periods.
+    let total: u64 = 10_000_000_000;
+    driver.set_now(u32::MAX - 10);
+    assert_eq!(
+        Alarm::sleep_for_duration(Duration::from_secs(10_000)),
+        Ok(())
+    );
+    let alarms = absolute_alarms(&kernel);
+    assert_eq!(alarms.len(), 5);
+    assert_eq!(alarms.iter().map(|&(_, dt)| dt as u64).sum::<u64>(), total);
+    // Each alarm starts at the previous deadline.
+    let mut reference = u32::MAX - 10;
+    for &(alarm_reference, dt) in &alarms {
+        assert_eq!(alarm_reference, reference);
+        reference = reference.wrapping_add(dt);
+    }
+    assert_eq!(driver.get_now(), (u32::MAX - 10).wrapping_add(total as u32));
+}
+
+#[test]
+fn sleep_for_duration_zero() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Alarm::sleep_for_duration(Duration::ZERO), Ok(()));
+    assert_eq!(absolute_alarms(&kernel), []);
+}
+
+#[test]
+fn periodic_timer_no_drift() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(1000);
+    let mut timer = PeriodicTimer::new(Milliseconds(100), MissedTickPolicy::Skip).unwrap();
+    for (n, work) in [0, 30, 99, 5, 60].iter().enumerate() {
+        driver.set_now(driver.get_now() + work);
+        assert_eq!(timer.tick(), Ok(()));
+        assert_eq!(driver.get_now(), 1000 + 100 * (n as u32 + 1));
+    }
+}
+
+#[test]
+fn periodic_timer_wraps() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(u32::MAX - 149);
+    let mut timer = PeriodicTimer::new(Ticks(100), MissedTickPolicy::Skip).unwrap();
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), u32::MAX - 49);
+    driver.set_now(driver.get_now().wrapping_add(70));
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), 50);
+}
+
+#[test]
+fn periodic_timer_missed_skip() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    let mut timer = PeriodicTimer::new(Milliseconds(100), MissedTickPolicy::Skip).unwrap();
+    // The work takes 2.5 periods: the ticks at 100 and 200 are skipped.
+    driver.set_now(250);
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), 300);
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), 400);
+}
+
+#[test]
+fn periodic_timer_missed_immediate() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    let mut timer = PeriodicTimer::new(Milliseconds(100), MissedTickPolicy::Immediate).unwrap();
+    // The work takes 2.5 periods: the ticks at 100 and 200 fire immediately.
+    driver.set_now(250);
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), 250);
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), 250);
+    assert_eq!(timer.tick(), Ok(()));
+    assert_eq!(driver.get_now(), 300);
+}
+
+const TEMPERATURE_DRIVER_NUM: u32 = 0x60000;
+
+#[test]
+fn timeout_already_complete() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    assert_eq!(Alarm::timeout(Milliseconds(500), || Some(5)), Ok(5));
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn timeout_completes() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let temperature = fake::Temperature::new();
+    kernel.add_driver(&temperature);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, TEMPERATURE_DRIVER_NUM, 0>(
+            subscribe, &listener,
+        )
+        .unwrap();
+        temperature.set_value_sync(2345);
+        fake::Syscalls::command(TEMPERATURE_DRIVER_NUM, 1, 0, 0)
+            .to_result::<(), ErrorCode>()
+            .unwrap();
+
+        assert_eq!(
+            Alarm::timeout(Milliseconds(500), || listener.get()),
+            Ok((2345,))
+        );
+    });
+    // The alarm's upcall was discarded.
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+}
+
+#[test]
+fn timeout_expires() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let temperature = fake::Temperature::new();
+    kernel.add_driver(&temperature);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, TEMPERATURE_DRIVER_NUM, 0>(
+            subscribe, &listener,
+        )
+        .unwrap();
+        // Start a reading that never completes.
+        fake::Syscalls::command(TEMPERATURE_DRIVER_NUM, 1, 0, 0)
+            .to_result::<(), ErrorCode>()
+            .unwrap();
+
+        assert_eq!(
+            Alarm::timeout(Milliseconds(500), || listener.get()),
+            Err(TimeoutError::TimedOut)
+        );
+        assert_eq!(alarm.get_now(), 500);
+    });
+
+    // A late completion is not delivered.
+    temperature.set_value(2345);
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    assert_eq!(listener.get(), None);
+}
+
+#[test]
+fn timeout_no_alarm() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(
+        Alarm::timeout(Milliseconds(500), || None::<()>),
+        Err(TimeoutError::Alarm(ErrorCode::NoDevice))
+    );
+}
+
+#[test]
+fn retry_on_busy_succeeds() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    let mut calls = 0;
+    let result = Alarm::retry_on_busy(5, Milliseconds(10), || {
+        calls += 1;
+        match calls {
+            1 | 2 => Err(ErrorCode::Busy),
+            _ => Ok(calls),
+        }
+    });
+    assert_eq!(result, Ok(3));
+    // Slept between each attempt.
+    assert_eq!(driver.get_now(), 20);
+}
+
+#[test]
+fn retry_on_busy_gives_up() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    let mut calls = 0;
+    let result = Alarm::retry_on_busy(3, Milliseconds(10), || {
+        calls += 1;
+        Err::<(), _>(ErrorCode::Busy)
+    });
+    assert_eq!(result, Err(ErrorCode::Busy));
+    assert_eq!(calls, 3);
+    assert_eq!(driver.get_now(), 20);
+}
+
+#[test]
+fn retry_on_busy_other_error() {
+    let _kernel = fake::Kernel::new();
+
+    // No sleep is needed, so no alarm driver either.
+    let mut calls = 0;
+    let result = Alarm::retry_on_busy(3, Milliseconds(10), || {
+        calls += 1;
+        Err::<(), _>(ErrorCode::Fail)
+    });
+    assert_eq!(result, Err(ErrorCode::Fail));
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn instant_duration_since() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    driver.set_now(1000);
+    let start = Instant::now().unwrap();
+    assert_eq!(start.ticks(), Ticks(1000));
+    driver.set_now(1250);
+    let end = Instant::now().unwrap();
+    assert_eq!(end.duration_since(start), Duration::from_millis(250));
+    assert_eq!(start.elapsed(), Ok(Duration::from_millis(250)));
+}
+
+#[test]
+fn instant_across_wrap() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(32768);
+    kernel.add_driver(&driver);
+
+    driver.set_now(u32::MAX - 9);
+    let start = Instant::now().unwrap();
+    driver.set_now(22);
+    let end = Instant::now().unwrap();
+    // 32 ticks at 32768 Hz.
+    assert_eq!(end.duration_since(start), Duration::from_nanos(976_562));
+}
+
+#[test]
+fn stopwatch_laps() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Alarm::new(1000);
+    kernel.add_driver(&driver);
+
+    let mut stopwatch = Stopwatch::new();
+    // Not started yet.
+    assert_eq!(stopwatch.lap(), Ok(Duration::ZERO));
+    assert!(!stopwatch.is_running());
+
+    driver.set_now(100);
+    stopwatch.start().unwrap();
+    driver.set_now(130);
+    assert_eq!(stopwatch.lap(), Ok(Duration::from_millis(30)));
+    driver.set_now(200);
+    assert_eq!(stopwatch.lap(), Ok(Duration::from_millis(70)));
+    assert_eq!(stopwatch.total(), Duration::from_millis(100));
+    driver.set_now(205);
+    assert_eq!(stopwatch.stop(), Ok(Duration::from_millis(105)));
+    assert!(!stopwatch.is_running());
+
+    // Time while stopped is not counted; restarting keeps accumulating.
+    driver.set_now(1000);
+    stopwatch.start().unwrap();
+    driver.set_now(1010);
+    assert_eq!(stopwatch.stop(), Ok(Duration::from_millis(115)));
+
+    stopwatch.reset();
+    assert_eq!(stopwatch.total(), Duration::ZERO);
+    assert_eq!(stopwatch.lap(), Ok(Duration::ZERO));
+}
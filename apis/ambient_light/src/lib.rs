@@ -0,0 +1,123 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::task::Poll;
+
+use libtock_platform::{share, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+use libtock_sensor::{Sensor, Unit};
+
+/// The ambient light sensor driver
+///
+/// # Example
+/// ```ignore
+/// use libtock::ambient_light::AmbientLight;
+///
+/// // Blocking read
+/// let lux = AmbientLight::read_intensity_sync();
+///
+/// // Start a reading and do other work while the sensor converts
+/// let listener: Cell<Option<(u32,)>> = Cell::new(None);
+/// share::scope(|subscribe| {
+///     AmbientLight::register_listener(&listener, subscribe)?;
+///     AmbientLight::read_intensity()?;
+///     // ... other work, then yield until `listener` is set
+/// });
+/// ```
+///
+/// A value is only needed to use the driver as a [`Sensor`]; all other
+/// operations are associated functions.
+pub struct AmbientLight<S: Syscalls>(PhantomData<S>);
+
+impl<S: Syscalls> AmbientLight<S> {
+    pub const fn new() -> Self {
+        AmbientLight(PhantomData)
+    }
+
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Register an events listener. The listener receives the light
+    /// intensity in lux.
+    pub fn register_listener<'share>(
+        listener: &'share Cell<Option<(u32,)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the events listener
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, 0)
+    }
+
+    /// Initiate a light intensity measurement without waiting for it to
+    /// complete. The result is delivered to the registered listener, so the
+    /// conversion can overlap with other work.
+    pub fn read_intensity() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, READ_INTENSITY, 0, 0).to_result()
+    }
+
+    /// Non-blocking counterpart of `read_intensity_sync`, for apps that poll
+    /// from their own main loop. Before polling, `listener` must be
+    /// registered with `register_listener` and a measurement started with
+    /// `read_intensity`. Each call handles at most one pending upcall, and
+    /// returns `Poll::Pending` until the measurement completes.
+    pub fn try_read_intensity(listener: &Cell<Option<(u32,)>>) -> Poll<u32> {
+        S::yield_no_wait();
+        match listener.take() {
+            Some((intensity,)) => Poll::Ready(intensity),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Initiate a synchronous light intensity measurement.
+    /// Returns Ok(intensity) if the operation was successful
+    /// intensity is returned in lux
+    pub fn read_intensity_sync() -> Result<u32, ErrorCode> {
+        let listener: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::read_intensity()?;
+            loop {
+                if let Some((intensity,)) = listener.get() {
+                    return Ok(intensity);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+impl<S: Syscalls> Default for AmbientLight<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Syscalls> Sensor for AmbientLight<S> {
+    fn read(&self) -> Result<i32, ErrorCode> {
+        Self::read_intensity_sync().map(|intensity| intensity.min(i32::MAX as u32) as i32)
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::Lux
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60002;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ_INTENSITY: u32 = 1;
@@ -0,0 +1,176 @@
+use core::cell::Cell;
+use core::task::Poll;
+use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_sensor::{Sensor, Unit};
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type AmbientLight = super::AmbientLight<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(AmbientLight::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        AmbientLight::read_intensity_sync(),
+        Err(ErrorCode::NoDevice)
+    );
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AmbientLight::exists(), Ok(()));
+}
+
+#[test]
+fn read_intensity() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AmbientLight::read_intensity(), Ok(()));
+    assert!(driver.is_busy());
+
+    assert_eq!(AmbientLight::read_intensity(), Err(ErrorCode::Busy));
+    assert_eq!(AmbientLight::read_intensity_sync(), Err(ErrorCode::Busy));
+}
+
+#[test]
+fn register_unregister_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(AmbientLight::read_intensity(), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert_eq!(
+            AmbientLight::register_listener(&listener, subscribe),
+            Ok(())
+        );
+        assert_eq!(AmbientLight::read_intensity(), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((100,)));
+
+        AmbientLight::unregister_listener();
+        assert_eq!(AmbientLight::read_intensity(), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn read_intensity_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync(250);
+    assert_eq!(AmbientLight::read_intensity_sync(), Ok(250));
+}
+
+// Starts a reading, does other work while the sensor converts, then collects
+// the result. Leaving the scope unsubscribes the listener.
+#[test]
+fn read_intensity_overlapped() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(
+            AmbientLight::register_listener(&listener, subscribe),
+            Ok(())
+        );
+        assert_eq!(AmbientLight::read_intensity(), Ok(()));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert_eq!(listener.get(), None);
+
+        driver.set_value(250);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((250,)));
+        kernel.take_syscall_log();
+    });
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::Subscribe {
+            driver_num: 0x60002,
+            subscribe_num: 0,
+        }]
+    );
+}
+
+#[test]
+fn try_read_intensity() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(
+            AmbientLight::register_listener(&listener, subscribe),
+            Ok(())
+        );
+        assert_eq!(AmbientLight::read_intensity(), Ok(()));
+        assert_eq!(AmbientLight::try_read_intensity(&listener), Poll::Pending);
+        assert_eq!(AmbientLight::try_read_intensity(&listener), Poll::Pending);
+
+        driver.set_value(300);
+        assert_eq!(
+            AmbientLight::try_read_intensity(&listener),
+            Poll::Ready(300)
+        );
+        assert_eq!(AmbientLight::try_read_intensity(&listener), Poll::Pending);
+    });
+}
+
+#[test]
+fn sensor() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    let sensor: &dyn Sensor = &AmbientLight::new();
+    driver.set_value_sync(320);
+    assert_eq!(sensor.read(), Ok(320));
+    assert_eq!(sensor.unit(), Unit::Lux);
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AmbientLight::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AmbientLight::read_intensity(), Ok(()));
+    kernel.take_syscall_log();
+    assert_eq!(AmbientLight::read_intensity_sync(), Err(ErrorCode::Busy));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::READ_INTENSITY,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
@@ -0,0 +1,118 @@
+#![no_std]
+
+use core::cell::Cell;
+use libtock_platform::{
+    share, subscribe::OneId, DefaultConfig, ErrorCode, Subscribe, Syscalls, Upcall,
+};
+
+/// The analog comparator driver
+///
+/// # Example
+/// ```ignore
+/// use libtock::analog_comparator::AnalogComparator;
+///
+/// // Block until the input on channel 0 crosses the reference.
+/// AnalogComparator::wait_for_crossing(0)?;
+/// let above = AnalogComparator::compare(0)?;
+/// ```
+pub struct AnalogComparator<S: Syscalls>(S);
+
+impl<S: Syscalls> AnalogComparator<S> {
+    /// Run a check against the analog comparator capsule to ensure it is
+    /// present.
+    ///
+    /// Returns `Ok(())` if the driver was present. This does not necessarily
+    /// mean that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result::<u32, _>()?;
+        Ok(())
+    }
+
+    /// Returns the number of comparator channels available to the process.
+    pub fn count() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Compares the two inputs of `channel`, returning `true` if the positive
+    /// input is higher than the negative one.
+    pub fn compare(channel: u32) -> Result<bool, ErrorCode> {
+        let result: u32 = S::command(DRIVER_NUM, COMPARISON, channel, 0).to_result()?;
+        Ok(result != 0)
+    }
+
+    /// Enable the interrupt on `channel`. The registered listener is called
+    /// whenever the comparator output rises.
+    pub fn start_comparing(channel: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, START_COMPARING, channel, 0).to_result()
+    }
+
+    /// Disable the interrupt on `channel`.
+    pub fn stop_comparing(channel: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, STOP_COMPARING, channel, 0).to_result()
+    }
+
+    /// Register an events listener
+    pub fn register_listener<'share, F: Fn(u32)>(
+        listener: &'share AnalogComparatorListener<F>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the events listener
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, 0)
+    }
+
+    /// Blocks until the comparator output on `channel` rises.
+    ///
+    /// The interrupt is only enabled while this call is waiting: it is
+    /// disabled again before returning.
+    pub fn wait_for_crossing(channel: u32) -> Result<(), ErrorCode> {
+        let crossed = Cell::new(false);
+        let listener = AnalogComparatorListener(|crossed_channel| {
+            if crossed_channel == channel {
+                crossed.set(true);
+            }
+        });
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::start_comparing(channel)?;
+            while !crossed.get() {
+                S::yield_wait();
+            }
+            Self::stop_comparing(channel)
+        })
+    }
+}
+
+/// A wrapper around a closure to be registered and called when a comparator
+/// output rises. The closure receives the channel.
+///
+/// ```ignore
+/// let listener = AnalogComparatorListener(|channel| {
+///     // react to the crossing
+/// });
+/// ```
+pub struct AnalogComparatorListener<F: Fn(u32)>(pub F);
+impl<F: Fn(u32)> Upcall<OneId<DRIVER_NUM, 0>> for AnalogComparatorListener<F> {
+    fn upcall(&self, channel: u32, _arg1: u32, _arg2: u32) {
+        self.0(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x7;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const COMPARISON: u32 = 1;
+const START_COMPARING: u32 = 2;
+const STOP_COMPARING: u32 = 3;
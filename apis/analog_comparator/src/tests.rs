@@ -0,0 +1,116 @@
+use core::cell::Cell;
+use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type AnalogComparator = super::AnalogComparator<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(AnalogComparator::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(AnalogComparator::count(), Err(ErrorCode::NoDevice));
+    assert_eq!(AnalogComparator::compare(0), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        AnalogComparator::wait_for_crossing(0),
+        Err(ErrorCode::NoDevice)
+    );
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AnalogComparator::exists(), Ok(()));
+    assert_eq!(AnalogComparator::count(), Ok(2));
+}
+
+#[test]
+fn compare() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AnalogComparator::compare(1), Ok(false));
+    driver.set_value(1, true);
+    assert_eq!(AnalogComparator::compare(1), Ok(true));
+    assert_eq!(AnalogComparator::compare(0), Ok(false));
+    assert_eq!(AnalogComparator::compare(2), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn register_unregister_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    let crossed: Cell<Option<u32>> = Cell::new(None);
+    let listener = crate::AnalogComparatorListener(|channel| crossed.set(Some(channel)));
+    share::scope(|subscribe| {
+        assert_eq!(AnalogComparator::start_comparing(1), Ok(()));
+        driver.crossing(1);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert_eq!(
+            AnalogComparator::register_listener(&listener, subscribe),
+            Ok(())
+        );
+        driver.crossing(1);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(crossed.get(), Some(1));
+
+        AnalogComparator::unregister_listener();
+        driver.crossing(1);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn wait_for_crossing() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    driver.crossing_sync();
+    assert_eq!(AnalogComparator::wait_for_crossing(1), Ok(()));
+    // The interrupt is disabled once the crossing has been seen.
+    assert!(!driver.is_comparing(1));
+
+    assert_eq!(
+        AnalogComparator::wait_for_crossing(2),
+        Err(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::AnalogComparator::<2>::new();
+    kernel.add_driver(&driver);
+
+    // Channel 2 does not exist.
+    assert_eq!(
+        AnalogComparator::wait_for_crossing(2),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::START_COMPARING,
+                argument0: 2,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
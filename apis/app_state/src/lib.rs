@@ -0,0 +1,268 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// Persistent application state, kept in the process's nonvolatile storage
+/// region.
+///
+/// The state is an opaque byte buffer, typically a serialized `Config`
+/// struct, stored at the start of the region. The region has a fixed size,
+/// reported by [`size`](Self::size) and set by the board; saving a larger
+/// buffer returns `ErrorCode::Size`. The nonvolatile storage capsule reads
+/// and rewrites whole flash pages internally, so the buffer has no alignment
+/// requirement and may have any length up to the region size.
+///
+/// Nothing records how many bytes were saved: [`load`](Self::load) fills as
+/// much of the buffer as the region holds. State that changes size should
+/// therefore carry its own length or version field.
+///
+/// # Example
+/// ```ignore
+/// use libtock::app_state::AppState;
+///
+/// let mut config = [0; 16];
+/// AppState::load(&mut config)?;
+/// config[0] += 1;
+/// AppState::save(&config)?;
+/// ```
+pub struct AppState<S: Syscalls>(S);
+
+impl<S: Syscalls> AppState<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Returns the size of the storage region, in bytes.
+    pub fn size() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, GET_SIZE, 0, 0).to_result()
+    }
+
+    /// Reads the saved state into `buffer`, blocking until the read completes.
+    /// Returns the number of bytes read, which is less than `buffer.len()` if
+    /// the region is smaller than `buffer`.
+    pub fn load(buffer: &mut [u8]) -> Result<usize, ErrorCode> {
+        let len = buffer.len().min(Self::size()? as usize);
+        if len == 0 {
+            return Ok(0);
+        }
+        Self::read_at(0, &mut buffer[..len])
+    }
+
+    /// Saves `buffer` as the state, blocking until the flash write has
+    /// completed, so the state survives a power loss once this returns.
+    ///
+    /// A power loss during the write may leave the region partially written;
+    /// [`save_atomic`](Self::save_atomic) avoids this at the cost of storage.
+    pub fn save(buffer: &[u8]) -> Result<(), ErrorCode> {
+        if buffer.len() > Self::size()? as usize {
+            return Err(ErrorCode::Size);
+        }
+        if buffer.is_empty() {
+            return Ok(());
+        }
+        Self::write_at(0, buffer)
+    }
+
+    /// Returns the largest state [`save_atomic`](Self::save_atomic) can save:
+    /// a little under half of the region.
+    pub fn atomic_capacity() -> Result<usize, ErrorCode> {
+        Ok((Self::size()? as usize / 2).saturating_sub(HEADER_LEN))
+    }
+
+    /// Reads the state last saved by [`save_atomic`](Self::save_atomic) into
+    /// `buffer`, returning its length. If `buffer` is too short, only the
+    /// start of the state is read, and the full length is still returned.
+    /// Returns `ErrorCode::Fail` if no state has been saved.
+    pub fn load_atomic(buffer: &mut [u8]) -> Result<usize, ErrorCode> {
+        let slot_size = Self::slot_size()?;
+        let (slot, header) = Self::current_slot(slot_size)?.ok_or(ErrorCode::Fail)?;
+        let len = buffer.len().min(header.len as usize);
+        if len > 0 {
+            Self::read_at(slot * slot_size + HEADER_LEN, &mut buffer[..len])?;
+        }
+        Ok(header.len as usize)
+    }
+
+    /// Saves `buffer` as the state, such that a power loss at any point
+    /// leaves either the new state or the previous one for
+    /// [`load_atomic`](Self::load_atomic) to find.
+    ///
+    /// The region is split into two slots, each holding a copy of the state
+    /// behind a 12-byte header. The new state is written to the slot not
+    /// holding the current state, and only marked valid once it has been
+    /// fully written; [`load_atomic`](Self::load_atomic) reads the valid copy
+    /// with the highest sequence number. This takes twice the storage of
+    /// [`save`](Self::save), so the state may be at most
+    /// [`atomic_capacity`](Self::atomic_capacity) bytes long; otherwise
+    /// `ErrorCode::Size` is returned. The two kinds of save use the region
+    /// differently and must not be mixed.
+    pub fn save_atomic(buffer: &[u8]) -> Result<(), ErrorCode> {
+        let slot_size = Self::slot_size()?;
+        if buffer.len() > slot_size.saturating_sub(HEADER_LEN) {
+            return Err(ErrorCode::Size);
+        }
+        let (slot, sequence) = match Self::current_slot(slot_size)? {
+            Some((current, header)) => (1 - current, header.sequence.wrapping_add(1)),
+            None => (0, 0),
+        };
+        let start = slot * slot_size;
+        // Invalidate the slot before overwriting it, then mark it valid once
+        // the state has been written.
+        Self::write_at(start, &INVALID.to_le_bytes())?;
+        let mut header = [0; HEADER_LEN - 4];
+        header[..4].copy_from_slice(&sequence.to_le_bytes());
+        header[4..].copy_from_slice(&(buffer.len() as u32).to_le_bytes());
+        Self::write_at(start + 4, &header)?;
+        if !buffer.is_empty() {
+            Self::write_at(start + HEADER_LEN, buffer)?;
+        }
+        Self::write_at(start, &VALID.to_le_bytes())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+// The header of a slot used by `save_atomic`, when it holds a valid state.
+#[derive(Clone, Copy)]
+struct SlotHeader {
+    sequence: u32,
+    len: u32,
+}
+
+impl<S: Syscalls> AppState<S> {
+    fn slot_size() -> Result<usize, ErrorCode> {
+        Ok(Self::size()? as usize / 2)
+    }
+
+    // Returns the slot holding the current state, and its header, or None if
+    // neither slot holds a valid state.
+    fn current_slot(slot_size: usize) -> Result<Option<(usize, SlotHeader)>, ErrorCode> {
+        let mut current: Option<(usize, SlotHeader)> = None;
+        for slot in 0..2 {
+            let header = match Self::read_header(slot * slot_size, slot_size)? {
+                Some(header) => header,
+                None => continue,
+            };
+            // Sequence numbers wrap, so compare their difference.
+            let newer = match current {
+                Some((_, other)) => (header.sequence.wrapping_sub(other.sequence) as i32) > 0,
+                None => true,
+            };
+            if newer {
+                current = Some((slot, header));
+            }
+        }
+        Ok(current)
+    }
+
+    // Reads the header of the slot at `start`, if the slot is valid.
+    fn read_header(start: usize, slot_size: usize) -> Result<Option<SlotHeader>, ErrorCode> {
+        let mut bytes = [0; HEADER_LEN];
+        if slot_size < HEADER_LEN || Self::read_at(start, &mut bytes)? != HEADER_LEN {
+            return Ok(None);
+        }
+        let word =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        let header = SlotHeader {
+            sequence: word(4),
+            len: word(8),
+        };
+        if word(0) != VALID || header.len as usize > slot_size - HEADER_LEN {
+            return Ok(None);
+        }
+        Ok(Some(header))
+    }
+
+    // Reads `buffer.len()` bytes at `offset` in the region, returning the
+    // number of bytes read.
+    fn read_at(offset: usize, buffer: &mut [u8]) -> Result<usize, ErrorCode> {
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_READ>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_READ>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            let len = buffer.len() as u32;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_READ>(allow_rw, buffer)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_READ>(subscribe, &done)?;
+            S::command(DRIVER_NUM, READ, offset as u32, len).to_result::<(), ErrorCode>()?;
+            loop {
+                if let Some((read,)) = done.get() {
+                    return Ok(read as usize);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+
+    // Writes `buffer` at `offset` in the region, blocking until the flash
+    // write has completed.
+    fn write_at(offset: usize, buffer: &[u8]) -> Result<(), ErrorCode> {
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_WRITE>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_WRITE>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_WRITE>(allow_ro, buffer)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_WRITE>(subscribe, &done)?;
+            S::command(DRIVER_NUM, WRITE, offset as u32, buffer.len() as u32)
+                .to_result::<(), ErrorCode>()?;
+            loop {
+                if let Some((written,)) = done.get() {
+                    if written as usize != buffer.len() {
+                        return Err(ErrorCode::Fail);
+                    }
+                    return Ok(());
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_SIZE: u32 = 1;
+const READ: u32 = 2;
+const WRITE: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_READ: u32 = 0;
+const ALLOW_WRITE: u32 = 0;
+
+// Subscribe IDs
+
+const SUBSCRIBE_READ: u32 = 0;
+const SUBSCRIBE_WRITE: u32 = 1;
+
+// The layout of a `save_atomic` slot header: the validity marker, the
+// sequence number, and the state length, each a little-endian u32.
+const HEADER_LEN: usize = 12;
+const VALID: u32 = 0x5afe_57a7;
+const INVALID: u32 = 0;
@@ -0,0 +1,110 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type AppState = super::AppState<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(AppState::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(AppState::load(&mut [0; 4]), Err(ErrorCode::NoDevice));
+    assert_eq!(AppState::save(&[0; 4]), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AppState::exists(), Ok(()));
+    assert_eq!(AppState::size(), Ok(1024));
+}
+
+#[test]
+fn save_then_load() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new();
+    kernel.add_driver(&driver);
+
+    let config = [0x12, 0x34, 0x56, 0x78, 0x9a];
+    assert_eq!(AppState::save(&config), Ok(()));
+    assert_eq!(driver.contents()[..5], config);
+
+    let mut loaded = [0; 5];
+    assert_eq!(AppState::load(&mut loaded), Ok(5));
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn region_size() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AppState::save(&[0; 1025]), Err(ErrorCode::Size));
+    assert_eq!(AppState::save(&[7; 1024]), Ok(()));
+
+    // Only the region is read into a larger buffer.
+    let mut loaded = [0; 1100];
+    assert_eq!(AppState::load(&mut loaded), Ok(1024));
+    assert!(loaded[..1024].iter().all(|&b| b == 7));
+    assert!(loaded[1024..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn atomic_save_then_load() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AppState::atomic_capacity(), Ok(500));
+    assert_eq!(AppState::load_atomic(&mut [0; 4]), Err(ErrorCode::Fail));
+    assert_eq!(AppState::save_atomic(&[0; 501]), Err(ErrorCode::Size));
+
+    // Each save goes to the other slot, and the latest is loaded.
+    let mut loaded = [0; 4];
+    for (i, state) in [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12]]
+        .iter()
+        .enumerate()
+    {
+        assert_eq!(AppState::save_atomic(state), Ok(()));
+        assert_eq!(AppState::load_atomic(&mut loaded), Ok(4));
+        assert_eq!(loaded, *state);
+        let slot = (i % 2) * 512;
+        assert_eq!(driver.contents()[slot + 12..slot + 16], *state);
+    }
+
+    // A shorter buffer receives the start of the state.
+    let mut start = [0; 2];
+    assert_eq!(AppState::load_atomic(&mut start), Ok(4));
+    assert_eq!(start, [9, 10]);
+}
+
+#[test]
+fn atomic_save_power_loss() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NonvolatileStorage::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(AppState::save_atomic(&[1, 2, 3]), Ok(()));
+    assert_eq!(AppState::save_atomic(&[4, 5, 6]), Ok(()));
+
+    // Power is lost at each step of the save, the last being after the state
+    // is written but before it is marked valid.
+    for writes in 0..4 {
+        driver.fail_writes_after(Some(writes));
+        assert_eq!(AppState::save_atomic(&[7, 8, 9, 10]), Err(ErrorCode::Fail));
+        driver.fail_writes_after(None);
+        let mut loaded = [0; 4];
+        assert_eq!(AppState::load_atomic(&mut loaded), Ok(3));
+        assert_eq!(loaded[..3], [4, 5, 6]);
+    }
+    // The slot holding the previous state is reused.
+    assert_eq!(driver.contents()[12..16], [7, 8, 9, 10]);
+
+    assert_eq!(AppState::save_atomic(&[7, 8, 9, 10]), Ok(()));
+    let mut loaded = [0; 4];
+    assert_eq!(AppState::load_atomic(&mut loaded), Ok(4));
+    assert_eq!(loaded, [7, 8, 9, 10]);
+}
@@ -1,7 +1,9 @@
 #![no_std]
 
+use core::cell::Cell;
+use libtock_alarm::{Alarm, Convert, TimeoutError};
 use libtock_platform::{
-    share::Handle, subscribe::OneId, DefaultConfig, ErrorCode, Subscribe, Syscalls, Upcall,
+    share, share::Handle, subscribe::OneId, DefaultConfig, ErrorCode, Subscribe, Syscalls, Upcall,
 };
 
 /// The Buttons driver
@@ -34,6 +36,13 @@ pub enum ButtonState {
     Released,
 }
 
+/// How long a button was held, as classified by [`Buttons::classify_press`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PressKind {
+    Short,
+    Long,
+}
+
 impl From<u32> for ButtonState {
     fn from(value: u32) -> ButtonState {
         match value {
@@ -111,6 +120,122 @@ impl<S: Syscalls> Buttons<S> {
     pub fn unregister_listener() {
         S::unsubscribe(DRIVER_NUM, 0)
     }
+
+    /// Wait until a button is pressed.
+    ///
+    /// Interrupts are enabled for the button while waiting, and disabled
+    /// before returning. The upcall used to wait replaces any registered
+    /// listener, and is unregistered before returning, on both the success
+    /// and the error paths.
+    pub fn wait_for_press(button: u32) -> Result<(), ErrorCode> {
+        let pressed = Cell::new(false);
+        let listener = ButtonListener(|pressed_button, state| {
+            if pressed_button == button && state == ButtonState::Pressed {
+                pressed.set(true);
+            }
+        });
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::enable_interrupts(button)?;
+            while !pressed.get() {
+                S::yield_wait();
+            }
+            Self::disable_interrupts(button)
+        })
+    }
+
+    /// Wait for `button` to be pressed and released, and classify the press:
+    /// `Short` if it was released within `long_threshold`, `Long` otherwise.
+    /// A long press is only reported once the button is released.
+    ///
+    /// As in `wait_for_press`, interrupts are enabled for the button while
+    /// waiting, and the upcall used replaces any registered listener. Both
+    /// are undone before returning.
+    pub fn classify_press<T: Convert>(
+        button: u32,
+        long_threshold: T,
+    ) -> Result<PressKind, ErrorCode> {
+        let pressed = Cell::new(false);
+        let released = Cell::new(false);
+        let listener = ButtonListener(|event_button, state| {
+            if event_button != button {
+                return;
+            }
+            match state {
+                ButtonState::Pressed => pressed.set(true),
+                // Only a release that follows the press ends it.
+                ButtonState::Released => released.set(pressed.get()),
+            }
+        });
+        let result = share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::enable_interrupts(button)?;
+            while !pressed.get() {
+                S::yield_wait();
+            }
+            match Alarm::<S>::timeout(long_threshold, || released.get().then(|| ())) {
+                Ok(()) => Ok(PressKind::Short),
+                Err(TimeoutError::TimedOut) => {
+                    while !released.get() {
+                        S::yield_wait();
+                    }
+                    Ok(PressKind::Long)
+                }
+                Err(TimeoutError::Alarm(error)) => Err(error),
+            }
+        });
+        let _ = Self::disable_interrupts(button);
+        result
+    }
+
+    /// Wait until all of `buttons` are held down together for `hold`. If any
+    /// of them is released before `hold` has passed, waiting starts over once
+    /// they are all pressed again. With a single button, this waits for a
+    /// long press. Returns `ErrorCode::Invalid` if `buttons` is empty.
+    ///
+    /// As in `wait_for_press`, interrupts are enabled for the buttons while
+    /// waiting, and the upcall used replaces any registered listener. Both
+    /// are undone before returning.
+    pub fn wait_for_combo<T: Convert + Copy>(buttons: &[u32], hold: T) -> Result<(), ErrorCode> {
+        if buttons.is_empty() {
+            return Err(ErrorCode::Invalid);
+        }
+        let changed = Cell::new(false);
+        let released = Cell::new(false);
+        let listener = ButtonListener(|button, state| {
+            if buttons.contains(&button) {
+                changed.set(true);
+                if state == ButtonState::Released {
+                    released.set(true);
+                }
+            }
+        });
+        let result = share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            for &button in buttons {
+                Self::enable_interrupts(button)?;
+            }
+            loop {
+                if !buttons.iter().all(|&button| Self::is_pressed(button)) {
+                    changed.set(false);
+                    while !changed.get() {
+                        S::yield_wait();
+                    }
+                    continue;
+                }
+                released.set(false);
+                match Alarm::<S>::timeout(hold, || released.get().then(|| ())) {
+                    Ok(()) => continue,
+                    Err(TimeoutError::TimedOut) => return Ok(()),
+                    Err(TimeoutError::Alarm(error)) => return Err(error),
+                }
+            }
+        });
+        for &button in buttons {
+            let _ = Self::disable_interrupts(button);
+        }
+        result
+    }
 }
 
 /// A wrapper around a closure to be registered and called when
@@ -1,7 +1,10 @@
+extern crate std;
+
 use core::cell::Cell;
+use std::rc::Rc;
 
-use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
-use libtock_unittest::fake;
+use libtock_platform::{share, CommandReturn, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_unittest::{fake, SyscallLogEntry};
 
 use crate::{ButtonListener, ButtonState};
 
@@ -36,6 +39,38 @@ fn read() {
     assert_eq!(Buttons::read(11), Err(ErrorCode::Invalid));
 }
 
+#[test]
+fn command_ids() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    let _ = Buttons::count();
+    let _ = Buttons::enable_interrupts(2);
+    let _ = Buttons::disable_interrupts(3);
+    let _ = Buttons::read(4);
+    assert!(!Buttons::is_pressed(5));
+    assert!(Buttons::is_released(6));
+
+    let command = |command_id, argument0| SyscallLogEntry::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id,
+        argument0,
+        argument1: 0,
+    };
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            command(super::BUTTONS_COUNT, 0),
+            command(super::BUTTONS_ENABLE_INTERRUPTS, 2),
+            command(super::BUTTONS_DISABLE_INTERRUPTS, 3),
+            command(super::BUTTONS_READ, 4),
+            command(super::BUTTONS_READ, 5),
+            command(super::BUTTONS_READ, 6),
+        ]
+    );
+}
+
 #[test]
 fn interrupts() {
     let kernel = fake::Kernel::new();
@@ -113,3 +148,193 @@ fn subscribe() {
     });
     assert!(!pressed_interrupt_count.get());
 }
+
+#[test]
+fn wait_for_press() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    // A release does not end the wait.
+    assert_eq!(driver.set_pressed(0, true), Ok(()));
+    driver.set_pressed_sync(0, false);
+    driver.set_pressed_sync(0, true);
+    assert_eq!(Buttons::wait_for_press(0), Ok(()));
+    let yields = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter(|entry| *entry == SyscallLogEntry::YieldWait)
+        .count();
+    assert_eq!(yields, 2);
+    assert!(!driver.get_button_state(0).unwrap().interrupt_enabled);
+    assert!(driver.get_button_state(0).unwrap().pressed);
+
+    // The upcall was unsubscribed, so later button events do not reach it.
+    assert_eq!(Buttons::enable_interrupts(0), Ok(()));
+    assert_eq!(driver.set_pressed(0, false), Ok(()));
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+}
+
+#[test]
+fn wait_for_press_invalid() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Buttons::wait_for_press(11), Err(ErrorCode::Invalid));
+    // The failed wait still unsubscribed its upcall.
+    assert_eq!(
+        kernel.take_syscall_log().last(),
+        Some(&SyscallLogEntry::Subscribe {
+            driver_num: super::DRIVER_NUM,
+            subscribe_num: 0,
+        })
+    );
+    assert_eq!(Buttons::enable_interrupts(0), Ok(()));
+    assert_eq!(driver.set_pressed(0, true), Ok(()));
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+}
+
+// Returns the number of alarms set, which is the number of times a hold was
+// started.
+fn holds_started(log: &[SyscallLogEntry]) -> usize {
+    log.iter()
+        .filter(|entry| {
+            matches!(
+                entry,
+                SyscallLogEntry::Command {
+                    driver_id: 0,
+                    command_id: 5,
+                    ..
+                }
+            )
+        })
+        .count()
+}
+
+#[test]
+fn wait_for_combo() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    driver.set_pressed_sync(0, true);
+    driver.set_pressed_sync(1, true);
+    assert_eq!(
+        Buttons::wait_for_combo(&[0, 1], libtock_alarm::Milliseconds(3000)),
+        Ok(())
+    );
+    assert_eq!(holds_started(&kernel.take_syscall_log()), 1);
+    assert_eq!(alarm.get_now(), 3000);
+    assert!(!driver.get_button_state(0).unwrap().interrupt_enabled);
+    assert!(!driver.get_button_state(1).unwrap().interrupt_enabled);
+}
+
+#[test]
+fn wait_for_combo_released_mid_hold() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    // Button 1 is released and pressed again while the first hold is timed.
+    driver.set_pressed_sync(0, true);
+    driver.set_pressed_sync(1, true);
+    driver.set_pressed_sync(1, false);
+    driver.set_pressed_sync(1, true);
+    assert_eq!(
+        Buttons::wait_for_combo(&[0, 1], libtock_alarm::Milliseconds(3000)),
+        Ok(())
+    );
+    // The release restarted the hold, which then completed.
+    assert_eq!(holds_started(&kernel.take_syscall_log()), 2);
+}
+
+#[test]
+fn wait_for_combo_long_press() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        Buttons::wait_for_combo(&[], libtock_alarm::Milliseconds(3000)),
+        Err(ErrorCode::Invalid)
+    );
+
+    driver.set_pressed_sync(2, true);
+    assert_eq!(
+        Buttons::wait_for_combo(&[2], libtock_alarm::Milliseconds(500)),
+        Ok(())
+    );
+    assert_eq!(alarm.get_now(), 500);
+}
+
+#[test]
+fn classify_short_press() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&driver);
+
+    driver.set_pressed_sync(0, true);
+    driver.set_pressed_sync(0, false);
+    assert_eq!(
+        Buttons::classify_press(0, libtock_alarm::Milliseconds(800)),
+        Ok(crate::PressKind::Short)
+    );
+    // The release ended the wait before the threshold.
+    assert_eq!(alarm.get_now(), 0);
+    assert!(!driver.get_button_state(0).unwrap().interrupt_enabled);
+}
+
+// An alarm that releases a button when it is set, so that the release
+// arrives after the alarm expires.
+struct ReleaseOnAlarm {
+    alarm: Rc<fake::Alarm>,
+    buttons: Rc<fake::Buttons<10>>,
+}
+
+impl fake::SyscallDriver for ReleaseOnAlarm {
+    fn info(&self) -> libtock_unittest::DriverInfo {
+        self.alarm.info()
+    }
+
+    fn register(&self, share_ref: libtock_unittest::DriverShareRef) {
+        self.alarm.register(share_ref)
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        let result = self.alarm.command(command_id, argument0, argument1);
+        // Command 5 sets a relative alarm.
+        if command_id == 5 {
+            self.buttons.set_pressed(0, false).unwrap();
+        }
+        result
+    }
+}
+
+#[test]
+fn classify_long_press() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    let driver = fake::Buttons::<10>::new();
+    kernel.add_driver(&Rc::new(ReleaseOnAlarm {
+        alarm: alarm.clone(),
+        buttons: driver.clone(),
+    }));
+    kernel.add_driver(&driver);
+
+    driver.set_pressed_sync(0, true);
+    assert_eq!(
+        Buttons::classify_press(0, libtock_alarm::Milliseconds(800)),
+        Ok(crate::PressKind::Long)
+    );
+    assert_eq!(alarm.get_now(), 800);
+    assert!(!driver.get_button_state(0).unwrap().pressed);
+}
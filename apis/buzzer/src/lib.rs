@@ -0,0 +1,103 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_alarm::{Alarm, Milliseconds};
+use libtock_platform::{share, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+mod note;
+
+pub use note::{Note, Pitch};
+
+/// The buzzer driver
+///
+/// # Example
+/// ```ignore
+/// use libtock::buzzer::Buzzer;
+///
+/// // Play a short melody
+/// for (frequency_hz, duration_ms) in [(440, 200), (494, 200), (523, 400)] {
+///     Buzzer::tone(frequency_hz, duration_ms)?;
+/// }
+/// ```
+pub struct Buzzer<S: Syscalls>(S);
+
+impl<S: Syscalls> Buzzer<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Register an events listener, called when a tone finishes playing.
+    pub fn register_listener<'share>(
+        listener: &'share Cell<Option<()>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the events listener
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, 0)
+    }
+
+    /// Start playing a tone without waiting for it to finish.
+    /// Returns Err(ErrorCode::Invalid) if `frequency_hz` is 0, and
+    /// Err(ErrorCode::Busy) if a tone is already playing.
+    pub fn start(frequency_hz: u32, duration_ms: u32) -> Result<(), ErrorCode> {
+        if frequency_hz == 0 {
+            return Err(ErrorCode::Invalid);
+        }
+        S::command(DRIVER_NUM, TONE, frequency_hz, duration_ms).to_result()
+    }
+
+    /// Stop the tone that is playing.
+    pub fn stop() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, STOP, 0, 0).to_result()
+    }
+
+    /// Play a tone, returning once it has finished. Consecutive calls play
+    /// one tone after the other.
+    /// Returns Err(ErrorCode::Invalid) if `frequency_hz` is 0.
+    pub fn tone(frequency_hz: u32, duration_ms: u32) -> Result<(), ErrorCode> {
+        let listener: Cell<Option<()>> = Cell::new(None);
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::start(frequency_hz, duration_ms)?;
+            while listener.get().is_none() {
+                S::yield_wait();
+            }
+            Ok(())
+        })
+    }
+
+    /// Plays `notes` one after the other, returning once the last has
+    /// finished. Rests are silent: the buzzer is left idle while sleeping on
+    /// the alarm. Durations are rounded down to whole milliseconds.
+    pub fn play_melody(notes: &[Note]) -> Result<(), ErrorCode> {
+        for note in notes {
+            let duration_ms = note.duration.as_millis().min(u32::MAX as u128) as u32;
+            match note.pitch {
+                Pitch::Rest => Alarm::<S>::sleep_for(Milliseconds(duration_ms))?,
+                pitch => Self::tone(pitch.frequency_hz(), duration_ms)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90000;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const TONE: u32 = 1;
+const STOP: u32 = 2;
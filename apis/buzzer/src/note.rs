@@ -0,0 +1,105 @@
+use core::time::Duration;
+
+/// A note pitch, in twelve-tone equal temperament with A4 at 440 Hz, from C3
+/// to B6. Sharps are written with an `s`: `Cs4` is C-sharp 4. `Rest` is silence.
+///
+/// The discriminants are MIDI note numbers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Pitch {
+    Rest = 0,
+    C3 = 48,
+    Cs3 = 49,
+    D3 = 50,
+    Ds3 = 51,
+    E3 = 52,
+    F3 = 53,
+    Fs3 = 54,
+    G3 = 55,
+    Gs3 = 56,
+    A3 = 57,
+    As3 = 58,
+    B3 = 59,
+    C4 = 60,
+    Cs4 = 61,
+    D4 = 62,
+    Ds4 = 63,
+    E4 = 64,
+    F4 = 65,
+    Fs4 = 66,
+    G4 = 67,
+    Gs4 = 68,
+    A4 = 69,
+    As4 = 70,
+    B4 = 71,
+    C5 = 72,
+    Cs5 = 73,
+    D5 = 74,
+    Ds5 = 75,
+    E5 = 76,
+    F5 = 77,
+    Fs5 = 78,
+    G5 = 79,
+    Gs5 = 80,
+    A5 = 81,
+    As5 = 82,
+    B5 = 83,
+    C6 = 84,
+    Cs6 = 85,
+    D6 = 86,
+    Ds6 = 87,
+    E6 = 88,
+    F6 = 89,
+    Fs6 = 90,
+    G6 = 91,
+    Gs6 = 92,
+    A6 = 93,
+    As6 = 94,
+    B6 = 95,
+}
+
+impl Pitch {
+    /// Returns the frequency of the pitch, rounded to the nearest Hz, or 0
+    /// for `Rest`.
+    pub const fn frequency_hz(self) -> u32 {
+        if let Pitch::Rest = self {
+            return 0;
+        }
+        let note = self as u32;
+        // Each octave below the eighth halves the frequency.
+        let shift = 8 - (note / 12 - 1);
+        (OCTAVE_8_HZ[(note % 12) as usize] + (1 << shift >> 1)) >> shift
+    }
+}
+
+/// A note of a melody played by
+/// [`Buzzer::play_melody`](crate::Buzzer::play_melody).
+///
+/// Notes can be built in constants, so melodies can be declared as statics:
+///
+/// ```ignore
+/// use core::time::Duration;
+/// use libtock_buzzer::{Note, Pitch};
+///
+/// static CHIME: [Note; 3] = [
+///     Note::new(Pitch::E5, Duration::from_millis(120)),
+///     Note::new(Pitch::Rest, Duration::from_millis(40)),
+///     Note::new(Pitch::A5, Duration::from_millis(240)),
+/// ];
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Note {
+    pub pitch: Pitch,
+    pub duration: Duration,
+}
+
+impl Note {
+    pub const fn new(pitch: Pitch, duration: Duration) -> Note {
+        Note { pitch, duration }
+    }
+}
+
+// The frequencies of the notes of the eighth octave, from C8 to B8, in Hz.
+const OCTAVE_8_HZ: [u32; 12] = [
+    4186, 4435, 4699, 4978, 5274, 5588, 5920, 6272, 6645, 7040, 7459, 7902,
+];
@@ -0,0 +1,134 @@
+use core::time::Duration;
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+use crate::{Note, Pitch};
+
+type Buzzer = super::Buzzer<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Buzzer::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Buzzer::tone(440, 100), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Buzzer::exists(), Ok(()));
+}
+
+#[test]
+fn start_stop() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Buzzer::start(440, 1000), Ok(()));
+    assert_eq!(driver.playing(), Some((440, 1000)));
+    assert_eq!(Buzzer::start(440, 1000), Err(ErrorCode::Busy));
+    assert_eq!(Buzzer::tone(440, 1000), Err(ErrorCode::Busy));
+    assert_eq!(Buzzer::stop(), Ok(()));
+    assert_eq!(driver.playing(), None);
+}
+
+#[test]
+fn zero_frequency() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Buzzer::start(0, 100), Err(ErrorCode::Invalid));
+    assert_eq!(Buzzer::tone(0, 100), Err(ErrorCode::Invalid));
+    assert_eq!(driver.take_tones(), []);
+}
+
+#[test]
+fn melody() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    driver.set_finish_immediately(true);
+    kernel.add_driver(&driver);
+
+    let melody = [(440, 200), (494, 200), (523, 400)];
+    for (frequency_hz, duration_ms) in melody {
+        assert_eq!(Buzzer::tone(frequency_hz, duration_ms), Ok(()));
+        // Each tone is played to completion before returning.
+        assert_eq!(
+            kernel.take_syscall_log(),
+            [
+                SyscallLogEntry::Subscribe {
+                    driver_num: 0x90000,
+                    subscribe_num: 0,
+                },
+                SyscallLogEntry::Command {
+                    driver_id: 0x90000,
+                    command_id: 1,
+                    argument0: frequency_hz,
+                    argument1: duration_ms,
+                },
+                SyscallLogEntry::YieldWait,
+                SyscallLogEntry::Subscribe {
+                    driver_num: 0x90000,
+                    subscribe_num: 0,
+                },
+            ]
+        );
+    }
+    assert_eq!(driver.take_tones(), melody);
+}
+
+#[test]
+fn pitch() {
+    assert_eq!(Pitch::Rest.frequency_hz(), 0);
+    assert_eq!(Pitch::A4.frequency_hz(), 440);
+    assert_eq!(Pitch::C4.frequency_hz(), 262);
+    assert_eq!(Pitch::Cs4.frequency_hz(), 277);
+    assert_eq!(Pitch::A5.frequency_hz(), 880);
+    assert_eq!(Pitch::C3.frequency_hz(), 131);
+    assert_eq!(Pitch::B6.frequency_hz(), 1976);
+}
+
+#[test]
+fn play_melody() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Buzzer::new();
+    driver.set_finish_immediately(true);
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    const MELODY: [Note; 3] = [
+        Note::new(Pitch::C5, Duration::from_millis(100)),
+        Note::new(Pitch::Rest, Duration::from_millis(50)),
+        Note::new(Pitch::E5, Duration::from_micros(200_900)),
+    ];
+    assert_eq!(Buzzer::play_melody(&MELODY), Ok(()));
+
+    // The tones, with the rest slept on the alarm in between.
+    let commands = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            SyscallLogEntry::Command {
+                driver_id,
+                command_id,
+                argument0,
+                argument1,
+            } if driver_id == 0x90000 || command_id == 5 => {
+                Some((driver_id, command_id, argument0, argument1))
+            }
+            _ => None,
+        });
+    assert!(commands.eq([
+        (0x90000, 1, 523, 100),
+        (0, 5, 50, 0),
+        (0x90000, 1, 659, 200),
+    ]));
+    assert_eq!(driver.take_tones(), [(523, 100), (659, 200)]);
+    assert_eq!(alarm.get_now(), 50);
+}
@@ -0,0 +1,233 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The CAN bus driver, for classic CAN frames of up to 8 data bytes.
+///
+/// # Example
+/// ```ignore
+/// use libtock::can::Can;
+///
+/// Can::set_bitrate(500_000)?;
+/// Can::enable()?;
+/// // OBD-II request for the engine RPM.
+/// Can::send(0x7df, false, &[0x02, 0x01, 0x0c])?;
+/// let response = Can::receive()?;
+/// ```
+pub struct Can<S: Syscalls>(S);
+
+impl<S: Syscalls> Can<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Sets the bus bitrate, in bits per second. The capsule only accepts
+    /// changes while the peripheral is disabled.
+    pub fn set_bitrate(bps: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_BITRATE, bps, 0).to_result()
+    }
+
+    /// Enables the peripheral, joining the bus. Frames can only be sent and
+    /// received while it is enabled.
+    pub fn enable() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, ENABLE, 0, 0).to_result()
+    }
+
+    /// Sends a frame, blocking until it has been transmitted.
+    ///
+    /// `id` must fit in 11 bits for a standard frame, or in 29 bits if
+    /// `extended` is set; otherwise `ErrorCode::Invalid` is returned. Frames
+    /// carry at most 8 data bytes, and longer `data` is rejected with
+    /// `ErrorCode::Size`.
+    pub fn send(id: u32, extended: bool, data: &[u8]) -> Result<(), ErrorCode> {
+        if id > max_id(extended) {
+            return Err(ErrorCode::Invalid);
+        }
+        if data.len() > MAX_DATA_LEN {
+            return Err(ErrorCode::Size);
+        }
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_ro, data)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(subscribe, &done)?;
+            S::command(DRIVER_NUM, SEND, id, encode_flags(extended, data.len()))
+                .to_result::<(), ErrorCode>()?;
+            loop {
+                match done.get() {
+                    Some((0,)) => return Ok(()),
+                    Some((status,)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+
+    /// Adds a hardware acceptance filter, so that only matching frames are
+    /// received. A frame matches if it uses the same frame format (standard or
+    /// extended, per `extended`) and its ID equals `id` in every bit set in
+    /// `mask`. Once a filter has been added, frames that match no filter are
+    /// dropped by the hardware and never reach [`receive`](Self::receive).
+    ///
+    /// `id` and `mask` must fit in the frame format's ID width, as for
+    /// [`send`](Self::send); otherwise `ErrorCode::Invalid` is returned. The
+    /// number of filters is limited by the controller's filter banks (for
+    /// example 14 on the STM32F4), and `ErrorCode::NoMem` is returned once they
+    /// are all in use.
+    ///
+    /// The upstream capsule has no filter support and returns
+    /// `ErrorCode::NoSupport`; kernels that extend it with the `ADD_FILTER`
+    /// and `CLEAR_FILTERS` commands are supported.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // Only receive OBD-II responses, 0x7e8 to 0x7ef.
+    /// Can::add_filter(0x7e8, 0x7f8, false)?;
+    /// ```
+    pub fn add_filter(id: u32, mask: u32, extended: bool) -> Result<(), ErrorCode> {
+        let max_id = max_id(extended);
+        if id > max_id || mask > max_id {
+            return Err(ErrorCode::Invalid);
+        }
+        let id = if extended { id | EXTENDED_FILTER } else { id };
+        S::command(DRIVER_NUM, ADD_FILTER, id, mask).to_result()
+    }
+
+    /// Removes every acceptance filter, so that all frames are received again.
+    pub fn clear_filters() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, CLEAR_FILTERS, 0, 0).to_result()
+    }
+
+    /// Receives a single frame, blocking until one arrives.
+    ///
+    /// The capsule only receives while this call is waiting, so frames that
+    /// arrive between calls are dropped.
+    pub fn receive() -> Result<CanFrame, ErrorCode> {
+        let mut data = [0; MAX_DATA_LEN];
+        let received: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        let (id, flags) = share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rw, &mut data)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(subscribe, &received)?;
+            S::command(DRIVER_NUM, START_RECEIVING, 0, 0).to_result::<(), ErrorCode>()?;
+            let result = loop {
+                match received.get() {
+                    Some((0, id, flags)) => break Ok((id, flags)),
+                    Some((status, _, _)) => {
+                        break Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            };
+            S::command(DRIVER_NUM, STOP_RECEIVING, 0, 0).to_result::<(), ErrorCode>()?;
+            result
+        })?;
+        let (extended, len) = decode_flags(flags);
+        Ok(CanFrame {
+            id,
+            extended,
+            len: len.min(MAX_DATA_LEN) as u8,
+            data,
+        })
+    }
+}
+
+/// A classic CAN frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CanFrame {
+    /// The frame identifier: 11 bits for a standard frame, 29 bits for an
+    /// extended one.
+    pub id: u32,
+    /// Whether the frame uses a 29-bit extended identifier.
+    pub extended: bool,
+    len: u8,
+    data: [u8; MAX_DATA_LEN],
+}
+
+impl CanFrame {
+    /// Returns the frame's data bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+const MAX_STANDARD_ID: u32 = 0x7ff;
+const MAX_EXTENDED_ID: u32 = 0x1fff_ffff;
+const MAX_DATA_LEN: usize = 8;
+
+fn max_id(extended: bool) -> u32 {
+    if extended {
+        MAX_EXTENDED_ID
+    } else {
+        MAX_STANDARD_ID
+    }
+}
+
+// Set in the ID passed to the add filter command for extended frames.
+const EXTENDED_FILTER: u32 = 1 << 31;
+
+// The second argument of the send command, and the third argument of the
+// receive upcall, hold the data length in bits 0-7 and the extended flag in
+// bit 8.
+fn encode_flags(extended: bool, len: usize) -> u32 {
+    (extended as u32) << 8 | len as u32
+}
+
+fn decode_flags(flags: u32) -> (bool, usize) {
+    (flags & (1 << 8) != 0, (flags & 0xff) as usize)
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20007;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SET_BITRATE: u32 = 1;
+const ENABLE: u32 = 3;
+const SEND: u32 = 5;
+const START_RECEIVING: u32 = 6;
+const STOP_RECEIVING: u32 = 7;
+// Not part of the upstream capsule; see `add_filter`.
+const ADD_FILTER: u32 = 16;
+const CLEAR_FILTERS: u32 = 17;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+
+// Subscribe IDs
+
+const SUBSCRIBE_TX: u32 = 0;
+const SUBSCRIBE_RX: u32 = 1;
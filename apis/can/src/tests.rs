@@ -0,0 +1,187 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, ExpectedSyscall};
+
+type Can = super::Can<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Can::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Can::set_bitrate(500_000), Err(ErrorCode::NoDevice));
+    assert_eq!(Can::send(0x7df, false, &[]), Err(ErrorCode::NoDevice));
+    assert_eq!(Can::receive(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn enable() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Can::exists(), Ok(()));
+    assert_eq!(Can::set_bitrate(500_000), Ok(()));
+    assert_eq!(Can::enable(), Ok(()));
+    assert_eq!(driver.bitrate(), Some(500_000));
+    assert!(driver.is_enabled());
+    assert_eq!(Can::set_bitrate(250_000), Err(ErrorCode::Busy));
+}
+
+fn expect_send(kernel: &fake::Kernel, id: u32, flags: u32) {
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: super::DRIVER_NUM,
+        buffer_num: super::ALLOW_TX,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: super::DRIVER_NUM,
+        subscribe_num: super::SUBSCRIBE_TX,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::SEND,
+        argument0: id,
+        argument1: flags,
+        override_return: None,
+    });
+}
+
+#[test]
+fn send_standard() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    assert_eq!(Can::set_bitrate(500_000), Ok(()));
+    assert_eq!(Can::enable(), Ok(()));
+
+    expect_send(&kernel, 0x7df, 3);
+    assert_eq!(Can::send(0x7df, false, &[0x02, 0x01, 0x0c]), Ok(()));
+    assert_eq!(
+        driver.take_sent(),
+        [fake::CanMessage {
+            id: 0x7df,
+            extended: false,
+            data: [0x02, 0x01, 0x0c].into(),
+        }]
+    );
+
+    assert_eq!(Can::send(0x7ff, false, &[]), Ok(()));
+    assert_eq!(Can::send(0x800, false, &[]), Err(ErrorCode::Invalid));
+    assert_eq!(driver.take_sent().len(), 1);
+}
+
+#[test]
+fn send_extended() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    assert_eq!(Can::set_bitrate(500_000), Ok(()));
+    assert_eq!(Can::enable(), Ok(()));
+
+    expect_send(&kernel, 0x18db33f1, 1 << 8 | 8);
+    assert_eq!(Can::send(0x18db33f1, true, &[0; 8]), Ok(()));
+    assert_eq!(
+        driver.take_sent(),
+        [fake::CanMessage {
+            id: 0x18db33f1,
+            extended: true,
+            data: [0; 8].into(),
+        }]
+    );
+
+    assert_eq!(Can::send(0x1fff_ffff, true, &[]), Ok(()));
+    assert_eq!(Can::send(0x2000_0000, true, &[]), Err(ErrorCode::Invalid));
+    assert_eq!(driver.take_sent().len(), 1);
+}
+
+#[test]
+fn send_too_long() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    assert_eq!(Can::set_bitrate(500_000), Ok(()));
+    assert_eq!(Can::enable(), Ok(()));
+
+    assert_eq!(Can::send(0x7df, false, &[0; 9]), Err(ErrorCode::Size));
+    assert_eq!(driver.take_sent(), []);
+}
+
+#[test]
+fn receive() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+    assert_eq!(Can::receive(), Err(ErrorCode::Off));
+
+    assert_eq!(Can::set_bitrate(500_000), Ok(()));
+    assert_eq!(Can::enable(), Ok(()));
+    driver.receive_frame_sync(fake::CanMessage {
+        id: 0x7e8,
+        extended: false,
+        data: [0x04, 0x41, 0x0c, 0x1a, 0xf8].into(),
+    });
+    let frame = Can::receive().unwrap();
+    assert_eq!(frame.id, 0x7e8);
+    assert!(!frame.extended);
+    assert_eq!(frame.data(), [0x04, 0x41, 0x0c, 0x1a, 0xf8]);
+    assert!(!driver.is_receiving());
+}
+
+#[test]
+fn filters() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Can::new();
+    kernel.add_driver(&driver);
+
+    // Standard filters pass the ID as is, extended ones set bit 31.
+    for (id, mask, extended, argument0) in [
+        (0x7e8, 0x7f8, false, 0x7e8),
+        (0x18da_f110, 0x1fff_ff00, true, 0x98da_f110),
+    ] {
+        kernel.add_expected_syscall(ExpectedSyscall::Command {
+            driver_id: super::DRIVER_NUM,
+            command_id: super::ADD_FILTER,
+            argument0,
+            argument1: mask,
+            override_return: None,
+        });
+        assert_eq!(Can::add_filter(id, mask, extended), Ok(()));
+    }
+    assert_eq!(
+        driver.filters(),
+        [
+            fake::CanFilter {
+                id: 0x7e8,
+                mask: 0x7f8,
+                extended: false,
+            },
+            fake::CanFilter {
+                id: 0x18da_f110,
+                mask: 0x1fff_ff00,
+                extended: true,
+            },
+        ]
+    );
+
+    // IDs and masks must fit the frame format.
+    assert_eq!(
+        Can::add_filter(0x800, 0x7ff, false),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Can::add_filter(0x7e8, 0x800, false),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Can::add_filter(0x2000_0000, 0, true),
+        Err(ErrorCode::Invalid)
+    );
+
+    // The fake has four filters.
+    assert_eq!(Can::add_filter(0, 0, false), Ok(()));
+    assert_eq!(Can::add_filter(0, 0, false), Ok(()));
+    assert_eq!(Can::add_filter(0, 0, false), Err(ErrorCode::NoMem));
+
+    assert_eq!(Can::clear_filters(), Ok(()));
+    assert!(driver.filters().is_empty());
+}
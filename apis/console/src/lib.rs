@@ -3,6 +3,7 @@
 use core::cell::Cell;
 use core::fmt;
 use core::marker::PhantomData;
+use libtock_alarm::{Alarm, Convert, Ticks, TimeoutError};
 use libtock_platform as platform;
 use libtock_platform::allow_ro::AllowRo;
 use libtock_platform::allow_rw::AllowRw;
@@ -38,31 +39,20 @@ impl<S: Syscalls, C: Config> Console<S, C> {
     /// Writes bytes.
     /// This is an alternative to `fmt::Write::write`
     /// because this can actually return an error code.
+    ///
+    /// The kernel may accept fewer bytes than were passed in a single
+    /// operation; the remaining bytes are written by further operations.
+    /// Returns once the kernel has signalled completion of the whole buffer.
     pub fn write(s: &[u8]) -> Result<(), ErrorCode> {
-        let called: Cell<Option<(u32,)>> = Cell::new(None);
-        share::scope::<
-            (
-                AllowRo<_, DRIVER_NUM, { allow_ro::WRITE }>,
-                Subscribe<_, DRIVER_NUM, { subscribe::WRITE }>,
-            ),
-            _,
-            _,
-        >(|handle| {
-            let (allow_ro, subscribe) = handle.split();
-
-            S::allow_ro::<C, DRIVER_NUM, { allow_ro::WRITE }>(allow_ro, s)?;
-
-            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::WRITE }>(subscribe, &called)?;
-
-            S::command(DRIVER_NUM, command::WRITE, s.len() as u32, 0).to_result()?;
-
-            loop {
-                S::yield_wait();
-                if let Some((_,)) = called.get() {
-                    return Ok(());
-                }
+        let mut remaining = s;
+        while !remaining.is_empty() {
+            let written = Self::write_chunk(remaining)?;
+            if written == 0 {
+                return Err(ErrorCode::Fail);
             }
-        })
+            remaining = &remaining[written.min(remaining.len())..];
+        }
+        Ok(())
     }
 
     /// Reads bytes
@@ -103,6 +93,78 @@ impl<S: Syscalls, C: Config> Console<S, C> {
         (bytes_received, r)
     }
 
+    /// Reads a line
+    /// Reads from the device into `buf` until a newline is received or `buf`
+    /// is full, whichever comes first. The newline, if received, is stored in
+    /// `buf`. Bytes that do not fit are left for the next read.
+    /// Returns count of bytes written to `buf`.
+    pub fn read_line(buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        let mut count = 0;
+        // Read one byte at a time so that nothing past the newline is
+        // consumed.
+        while count < buf.len() {
+            let (received, result) = Self::read(&mut buf[count..count + 1]);
+            result?;
+            if received == 0 {
+                break;
+            }
+            count += 1;
+            if buf[count - 1] == b'\n' {
+                break;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Reads a line, like `read_line`, but gives up if no newline arrives
+    /// within `timeout`. Returns `Ok(Some(count))` once a newline is received
+    /// or `buf` is full, or `Ok(None)` on timeout. On timeout, the pending read
+    /// is aborted, so no input is lost: bytes received before the timeout are
+    /// left in `buf`, and later bytes are left for the next read.
+    pub fn read_line_timeout<T: Convert>(
+        buf: &mut [u8],
+        timeout: T,
+    ) -> Result<Option<usize>, ErrorCode> {
+        let freq = Alarm::<S>::get_frequency()?;
+        let ticks = timeout.to_ticks(freq);
+        let start = Alarm::<S>::get_ticks()?;
+        let mut count = 0;
+        while count < buf.len() {
+            let elapsed = Alarm::<S>::get_ticks()?.0.wrapping_sub(start.0);
+            let remaining = match ticks.0.checked_sub(elapsed) {
+                Some(remaining) if remaining > 0 => Ticks(remaining),
+                _ => return Ok(None),
+            };
+            let (received, timed_out) = Self::read_byte_timeout(&mut buf[count], remaining)?;
+            count += received;
+            if received > 0 && buf[count - 1] == b'\n' {
+                break;
+            }
+            if timed_out {
+                return Ok(None);
+            }
+        }
+        Ok(Some(count))
+    }
+
+    /// Writes `data` as a hex dump in the format of `hexdump -C`. Each line
+    /// shows the offset of its first byte, then up to 16 bytes in hex, then
+    /// those bytes as ASCII, with unprintable bytes shown as `.`:
+    /// ```text
+    /// 00000000  48 65 6c 6c 6f 0a                                 |Hello.|
+    /// ```
+    /// Lines are formatted in a stack buffer and written one at a time, so
+    /// this does not allocate.
+    pub fn hexdump(data: &[u8]) -> Result<(), ErrorCode> {
+        for (index, chunk) in data.chunks(HEXDUMP_WIDTH).enumerate() {
+            let mut line = HexdumpLine::new();
+            Self::format_hexdump_line(&mut line, index * HEXDUMP_WIDTH, chunk)
+                .map_err(|_| ErrorCode::Fail)?;
+            Self::write(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
     pub fn writer() -> ConsoleWriter<S> {
         ConsoleWriter {
             syscalls: Default::default(),
@@ -110,6 +172,155 @@ impl<S: Syscalls, C: Config> Console<S, C> {
     }
 }
 
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls, C: Config> Console<S, C> {
+    /// Performs a single write operation, returning the number of bytes the
+    /// kernel reported as written.
+    fn write_chunk(s: &[u8]) -> Result<usize, ErrorCode> {
+        let called: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, { allow_ro::WRITE }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::WRITE }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+
+            S::allow_ro::<C, DRIVER_NUM, { allow_ro::WRITE }>(allow_ro, s)?;
+
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::WRITE }>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, command::WRITE, s.len() as u32, 0).to_result()?;
+
+            loop {
+                S::yield_wait();
+                if let Some((written,)) = called.get() {
+                    return Ok(written as usize);
+                }
+            }
+        })
+    }
+
+    /// Reads one byte into `byte`, waiting at most `timeout`. Returns the
+    /// number of bytes received, and whether the read timed out. On timeout,
+    /// the read is aborted so that the kernel is done with `byte` before it is
+    /// unshared.
+    fn read_byte_timeout(byte: &mut u8, timeout: Ticks) -> Result<(usize, bool), ErrorCode> {
+        let called: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, { allow_rw::READ }>,
+                Subscribe<_, DRIVER_NUM, { subscribe::READ }>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<C, DRIVER_NUM, { allow_rw::READ }>(
+                allow_rw,
+                core::slice::from_mut(byte),
+            )?;
+            S::subscribe::<_, _, C, DRIVER_NUM, { subscribe::READ }>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, command::READ, 1, 0).to_result()?;
+
+            let (status, received, timed_out) = match Alarm::<S>::timeout(timeout, || called.get())
+            {
+                Ok((status, received)) => (status, received, false),
+                Err(TimeoutError::TimedOut) => {
+                    // An aborted read still completes with an upcall,
+                    // carrying any byte received in the meantime.
+                    if S::command(DRIVER_NUM, command::ABORT, 0, 0).is_success() {
+                        let (status, received) = loop {
+                            if let Some(result) = called.get() {
+                                break result;
+                            }
+                            S::yield_wait();
+                        };
+                        (status, received, true)
+                    } else {
+                        (0, 0, true)
+                    }
+                }
+                Err(TimeoutError::Alarm(e)) => return Err(e),
+            };
+            // An aborted read reports CANCEL, which is expected here.
+            if status != 0 && !timed_out {
+                return Err(status.try_into().unwrap_or(ErrorCode::Fail));
+            }
+            Ok((received.min(1) as usize, timed_out))
+        })
+    }
+}
+
+impl<S: Syscalls, C: Config> Console<S, C> {
+    fn format_hexdump_line(
+        line: &mut HexdumpLine,
+        offset: usize,
+        chunk: &[u8],
+    ) -> Result<(), fmt::Error> {
+        use fmt::Write;
+        write!(line, "{:08x} ", offset)?;
+        for i in 0..HEXDUMP_WIDTH {
+            // An extra space separates the two halves of the line.
+            if i % (HEXDUMP_WIDTH / 2) == 0 {
+                line.write_str(" ")?;
+            }
+            match chunk.get(i) {
+                Some(byte) => write!(line, "{:02x} ", byte)?,
+                None => line.write_str("   ")?,
+            }
+        }
+        line.write_str(" |")?;
+        for &byte in chunk {
+            let shown = match byte {
+                b' '..=b'~' => byte as char,
+                _ => '.',
+            };
+            line.write_char(shown)?;
+        }
+        line.write_str("|\n")
+    }
+}
+
+const HEXDUMP_WIDTH: usize = 16;
+
+// A line of hexdump output: the offset, the hex bytes, and the ASCII bytes.
+struct HexdumpLine {
+    len: usize,
+    buffer: [u8; 80],
+}
+
+impl HexdumpLine {
+    fn new() -> Self {
+        HexdumpLine {
+            len: 0,
+            buffer: [0; 80],
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+impl fmt::Write for HexdumpLine {
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        let end = self.len + s.len();
+        self.buffer
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
 pub struct ConsoleWriter<S: Syscalls> {
     syscalls: PhantomData<S>,
 }
@@ -130,6 +341,30 @@ impl<T: platform::allow_ro::Config + platform::allow_rw::Config + platform::subs
 {
 }
 
+/// Formats its arguments, like `print!`, and writes them to `$console`, a
+/// [`Console`] type. Output is silently dropped if the console driver is absent
+/// or a write fails, so this is meant for quick debugging output.
+#[macro_export]
+macro_rules! console_print {
+    ($console:ty, $($arg:tt)*) => {{
+        let _ = ::core::fmt::Write::write_fmt(
+            &mut <$console>::writer(),
+            ::core::format_args!($($arg)*),
+        );
+    }};
+}
+
+/// Like [`console_print!`], followed by a newline.
+#[macro_export]
+macro_rules! console_println {
+    ($console:ty $(,)?) => {
+        $crate::console_print!($console, "\n")
+    };
+    ($console:ty, $($arg:tt)*) => {
+        $crate::console_print!($console, "{}\n", ::core::format_args!($($arg)*))
+    };
+}
+
 #[cfg(test)]
 mod tests;
 
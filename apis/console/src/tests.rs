@@ -42,6 +42,30 @@ fn write_str() {
     assert_eq!(driver.take_bytes(), b"foo");
 }
 
+#[test]
+fn write_multiple_chunks() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new();
+    driver.set_max_write_len(4);
+    kernel.add_driver(&driver);
+
+    Console::write(b"0123456789").unwrap();
+    assert_eq!(driver.take_bytes(), b"0123456789");
+
+    writeln!(Console::writer(), "{} + {} = {}", 10, 20, 10 + 20).unwrap();
+    assert_eq!(driver.take_bytes(), b"10 + 20 = 30\n");
+}
+
+#[test]
+fn write_empty() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new();
+    kernel.add_driver(&driver);
+
+    Console::write(b"").unwrap();
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
 #[test]
 fn read_bytes_short() {
     let kernel = fake::Kernel::new();
@@ -72,6 +96,60 @@ fn read_bytes_alot() {
     assert_eq!(&buf[..count], b" Alot");
 }
 
+#[test]
+fn read_line_short() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new_with_input(b"ls\nrest");
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 10];
+    assert_eq!(Console::read_line(&mut buf), Ok(3));
+    assert_eq!(&buf[..3], b"ls\n");
+
+    let (count, res) = Console::read(&mut buf);
+    res.unwrap();
+    assert_eq!(&buf[..count], b"rest");
+}
+
+#[test]
+fn read_line_exact_fill() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new_with_input(b"help\nls\n");
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 5];
+    assert_eq!(Console::read_line(&mut buf), Ok(5));
+    assert_eq!(&buf, b"help\n");
+
+    assert_eq!(Console::read_line(&mut buf), Ok(3));
+    assert_eq!(&buf[..3], b"ls\n");
+}
+
+#[test]
+fn read_line_overflow() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new_with_input(b"echo hello\n");
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 4];
+    assert_eq!(Console::read_line(&mut buf), Ok(4));
+    assert_eq!(&buf, b"echo");
+    assert_eq!(Console::read_line(&mut buf), Ok(4));
+    assert_eq!(&buf, b" hel");
+    assert_eq!(Console::read_line(&mut buf), Ok(3));
+    assert_eq!(&buf[..3], b"lo\n");
+}
+
+#[test]
+fn read_line_empty_buffer() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new_with_input(b"ls\n");
+    kernel.add_driver(&driver);
+
+    assert_eq!(Console::read_line(&mut []), Ok(0));
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
 #[test]
 fn failed_print() {
     let kernel = fake::Kernel::new();
@@ -129,3 +207,90 @@ fn failed_read() {
     assert_eq!(res, Err(ErrorCode::Fail));
     assert_eq!(count, 0);
 }
+
+#[test]
+fn hexdump() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new();
+    kernel.add_driver(&driver);
+
+    Console::hexdump(b"Hello, world!\n\x00\x7f\xff libtock").unwrap();
+    let expected: &[u8] = concat!(
+        "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 7f  |Hello, world!...|\n",
+        "00000010  ff 20 6c 69 62 74 6f 63  6b                       |. libtock|\n",
+    )
+    .as_bytes();
+    assert_eq!(driver.take_bytes(), expected);
+
+    Console::hexdump(&[]).unwrap();
+    assert_eq!(driver.take_bytes(), &[]);
+}
+
+#[test]
+fn read_line_timeout() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    let driver = fake::Console::new_with_input(b"ls\nrest");
+    kernel.add_driver(&alarm);
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 10];
+    assert_eq!(
+        Console::read_line_timeout(&mut buf, libtock_alarm::Milliseconds(100)),
+        Ok(Some(3))
+    );
+    assert_eq!(&buf[..3], b"ls\n");
+
+    // The rest of the input has no newline, so the read times out after
+    // receiving it.
+    assert_eq!(
+        Console::read_line_timeout(&mut buf, libtock_alarm::Milliseconds(100)),
+        Ok(None)
+    );
+    assert_eq!(&buf[..4], b"rest");
+    assert!(!driver.is_reading());
+}
+
+#[test]
+fn read_line_timeout_no_input() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    let driver = fake::Console::new();
+    kernel.add_driver(&alarm);
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 10];
+    assert_eq!(
+        Console::read_line_timeout(&mut buf, libtock_alarm::Milliseconds(100)),
+        Ok(None)
+    );
+    assert_eq!(alarm.get_now(), 100);
+    // The read was aborted, so later input is kept for the next read.
+    assert!(!driver.is_reading());
+    driver.add_input(b"ok\n");
+    assert_eq!(
+        Console::read_line_timeout(&mut buf, libtock_alarm::Milliseconds(100)),
+        Ok(Some(3))
+    );
+    assert_eq!(&buf[..3], b"ok\n");
+}
+
+#[test]
+fn print_macros() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Console::new();
+    kernel.add_driver(&driver);
+
+    crate::console_println!(Console, "x={}", 5);
+    assert_eq!(driver.take_bytes(), b"x=5\n");
+    crate::console_print!(Console, "{}-{}", 1, 2);
+    crate::console_println!(Console);
+    assert_eq!(driver.take_bytes(), b"1-2\n");
+}
+
+#[test]
+fn print_macros_no_driver() {
+    let _kernel = fake::Kernel::new();
+    // Output is dropped without panicking.
+    crate::console_println!(Console, "x={}", 5);
+}
@@ -0,0 +1,79 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::convert::TryFrom;
+
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The CRC algorithms supported by the CRC capsule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// CRC-32 (polynomial 0x04C11DB7, reflected), as used by Ethernet and
+    /// zlib.
+    Crc32 = 0,
+    /// CRC-32C (Castagnoli, polynomial 0x1EDC6F41, reflected).
+    Crc32C = 1,
+    /// CRC-16-CCITT (polynomial 0x1021, initial value 0xFFFF, not
+    /// reflected).
+    Crc16Ccitt = 2,
+}
+
+/// The CRC driver
+///
+/// # Example
+/// ```ignore
+/// use libtock::crc::{Algorithm, Crc};
+///
+/// let crc = Crc::compute(Algorithm::Crc32, b"123456789")?;
+/// assert_eq!(crc, 0xCBF43926);
+/// ```
+pub struct Crc<S: Syscalls>(S);
+
+impl<S: Syscalls> Crc<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Computes the CRC of `data` using `algorithm`, blocking until the
+    /// result is available. 16-bit CRCs are returned in the low bits.
+    ///
+    /// `data` is shared with the kernel for the duration of the call. The
+    /// capsule feeds it to the CRC engine in blocks that fit its internal
+    /// buffer, so `data` may be larger than that buffer.
+    pub fn compute(algorithm: Algorithm, data: &[u8]) -> Result<u32, ErrorCode> {
+        let called: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<(AllowRo<_, DRIVER_NUM, 0>, Subscribe<_, DRIVER_NUM, 0>), _, _>(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, 0>(allow_ro, data)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, COMPUTE, algorithm as u32, data.len() as u32).to_result()?;
+
+            loop {
+                if let Some((status, crc)) = called.get() {
+                    return match status {
+                        0 => Ok(crc),
+                        e_status => Err(ErrorCode::try_from(e_status).unwrap_or(ErrorCode::Fail)),
+                    };
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40002;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const COMPUTE: u32 = 1;
@@ -0,0 +1,82 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+use crate::Algorithm;
+
+type Crc = super::Crc<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Crc::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        Crc::compute(Algorithm::Crc32, b"123456789"),
+        Err(ErrorCode::NoDevice)
+    );
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Crc::exists(), Ok(()));
+}
+
+#[test]
+fn test_vectors() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Crc::compute(Algorithm::Crc32, b"123456789"), Ok(0xCBF43926));
+    assert_eq!(
+        Crc::compute(Algorithm::Crc32C, b"123456789"),
+        Ok(0xE3069283)
+    );
+    assert_eq!(
+        Crc::compute(Algorithm::Crc16Ccitt, b"123456789"),
+        Ok(0x29B1)
+    );
+    assert_eq!(Crc::compute(Algorithm::Crc32, b""), Ok(0));
+}
+
+#[test]
+fn large_buffer() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    // The CRC-32 of 1 KiB of zeroes.
+    assert_eq!(Crc::compute(Algorithm::Crc32, &[0; 1024]), Ok(0xEFB5AF2E));
+}
+
+#[test]
+fn compute_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Crc::new();
+    kernel.add_driver(&driver);
+
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRo {
+        driver_num: 0x40002,
+        buffer_num: 0,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: 0x40002,
+        subscribe_num: 0,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: 0x40002,
+        command_id: 1,
+        argument0: Algorithm::Crc32C as u32,
+        argument1: 3,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+    assert_eq!(
+        Crc::compute(Algorithm::Crc32C, b"abc"),
+        Err(ErrorCode::Busy)
+    );
+}
@@ -0,0 +1,152 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use libtock_platform::{share, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The length of every USB HID report exchanged with the CTAP capsule.
+pub const REPORT_LEN: usize = 64;
+
+/// A CTAP (FIDO2) USB HID endpoint, exchanging reports through a pair of
+/// buffers owned by the caller.
+///
+/// Each buffer is only shared with the capsule while a transfer is in
+/// progress, so the received report can be read with
+/// [`recv_buffer`](Self::recv_buffer), and the next report written with
+/// [`send_buffer`](Self::send_buffer), between transfers.
+///
+/// # Example
+/// ```ignore
+/// use libtock::ctap::{Ctap, REPORT_LEN};
+///
+/// let mut recv = [0; REPORT_LEN];
+/// let mut send = [0; REPORT_LEN];
+/// let mut ctap = Ctap::allow_report_buffers(&mut recv, &mut send)?;
+/// loop {
+///     ctap.receive_report()?;
+///     let response = handle_request(ctap.recv_buffer());
+///     ctap.send_buffer().copy_from_slice(&response);
+///     ctap.send_report()?;
+/// }
+/// ```
+pub struct Ctap<'buffers, S: Syscalls> {
+    recv: &'buffers mut [u8],
+    send: &'buffers mut [u8],
+    _syscalls: PhantomData<S>,
+}
+
+impl<'buffers, S: Syscalls> Ctap<'buffers, S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Uses `recv` and `send` as the report buffers. Both must be exactly
+    /// [`REPORT_LEN`] bytes long; otherwise `ErrorCode::Size` is returned.
+    pub fn allow_report_buffers(
+        recv: &'buffers mut [u8],
+        send: &'buffers mut [u8],
+    ) -> Result<Self, ErrorCode> {
+        if recv.len() != REPORT_LEN || send.len() != REPORT_LEN {
+            return Err(ErrorCode::Size);
+        }
+        Ok(Ctap {
+            recv,
+            send,
+            _syscalls: PhantomData,
+        })
+    }
+
+    /// Returns the last report received.
+    pub fn recv_buffer(&self) -> &[u8] {
+        self.recv
+    }
+
+    /// Returns the report to be sent by the next `send_report`.
+    pub fn send_buffer(&mut self) -> &mut [u8] {
+        self.send
+    }
+
+    /// Sends the contents of the send buffer to the host, blocking until the
+    /// host has read it.
+    pub fn send_report(&mut self) -> Result<(), ErrorCode> {
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_SEND>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_SEND>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_SEND>(allow_rw, self.send)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_SEND>(subscribe, &done)?;
+            S::command(DRIVER_NUM, SEND, 0, 0).to_result::<(), ErrorCode>()?;
+            loop {
+                match done.get() {
+                    Some((0,)) => return Ok(()),
+                    Some((status,)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+
+    /// Receives a report from the host into the receive buffer, blocking until
+    /// one arrives, and returns its length.
+    pub fn receive_report(&mut self) -> Result<usize, ErrorCode> {
+        let received: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_RECV>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_RECV>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RECV>(allow_rw, self.recv)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RECV>(subscribe, &received)?;
+            S::command(DRIVER_NUM, RECEIVE, 0, 0).to_result::<(), ErrorCode>()?;
+            loop {
+                match received.get() {
+                    Some((0, len)) => return Ok(len as usize),
+                    Some((status, _)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20009;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SEND: u32 = 1;
+const RECEIVE: u32 = 2;
+
+// Allow IDs
+
+const ALLOW_RECV: u32 = 0;
+const ALLOW_SEND: u32 = 1;
+
+// Subscribe IDs
+
+const SUBSCRIBE_SEND: u32 = 0;
+const SUBSCRIBE_RECV: u32 = 1;
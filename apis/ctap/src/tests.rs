@@ -0,0 +1,94 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+use super::REPORT_LEN;
+
+type Ctap<'buffers> = super::Ctap<'buffers, fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Ctap::exists(), Err(ErrorCode::NoDevice));
+
+    let mut recv = [0; REPORT_LEN];
+    let mut send = [0; REPORT_LEN];
+    let mut ctap = Ctap::allow_report_buffers(&mut recv, &mut send).unwrap();
+    assert_eq!(ctap.send_report(), Err(ErrorCode::NoDevice));
+    assert_eq!(ctap.receive_report(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn exists() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ctap::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Ctap::exists(), Ok(()));
+}
+
+#[test]
+fn buffer_size() {
+    let mut short = [0; REPORT_LEN - 1];
+    let mut long = [0; REPORT_LEN + 1];
+    let mut report = [0; REPORT_LEN];
+    let mut other = [0; REPORT_LEN];
+    assert_eq!(
+        Ctap::allow_report_buffers(&mut short, &mut report).err(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        Ctap::allow_report_buffers(&mut report, &mut long).err(),
+        Some(ErrorCode::Size)
+    );
+    assert!(Ctap::allow_report_buffers(&mut report, &mut other).is_ok());
+}
+
+#[test]
+fn send_receive() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ctap::new();
+    kernel.add_driver(&driver);
+
+    let mut recv = [0; REPORT_LEN];
+    let mut send = [0; REPORT_LEN];
+    let mut ctap = Ctap::allow_report_buffers(&mut recv, &mut send).unwrap();
+
+    let mut request = [0; REPORT_LEN];
+    request[..4].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+    driver.push_report(&request);
+    assert_eq!(ctap.receive_report(), Ok(REPORT_LEN));
+    assert_eq!(ctap.recv_buffer(), request);
+
+    ctap.send_buffer()[0] = 0x86;
+    assert_eq!(ctap.send_report(), Ok(()));
+    let mut response = [0; REPORT_LEN];
+    response[0] = 0x86;
+    assert_eq!(driver.take_sent(), [response.to_vec()]);
+}
+
+#[test]
+fn loopback_echo() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ctap::new();
+    kernel.add_driver(&driver);
+    driver.set_loopback(true);
+
+    let mut recv = [0; REPORT_LEN];
+    let mut send = [0; REPORT_LEN];
+    let mut ctap = Ctap::allow_report_buffers(&mut recv, &mut send).unwrap();
+
+    let mut expected = [0; REPORT_LEN];
+    for (i, byte) in expected.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    ctap.send_buffer().copy_from_slice(&expected);
+    assert_eq!(ctap.send_report(), Ok(()));
+    assert_eq!(ctap.receive_report(), Ok(REPORT_LEN));
+    assert_eq!(ctap.recv_buffer(), expected);
+
+    // Echo the report back as an application would.
+    let (recv, send) = (ctap.recv_buffer().to_vec(), ctap.send_buffer());
+    send.copy_from_slice(&recv);
+    assert_eq!(ctap.send_report(), Ok(()));
+    assert_eq!(driver.take_sent(), [expected.to_vec(), expected.to_vec()]);
+}
@@ -1,9 +1,11 @@
 #![no_std]
 
+use core::cell::Cell;
 use core::marker::PhantomData;
 
+use libtock_alarm::{Alarm, Convert, Ticks};
 use libtock_platform::{
-    share::Handle, subscribe::OneId, DefaultConfig, ErrorCode, Subscribe, Syscalls, Upcall,
+    share, share::Handle, subscribe::OneId, DefaultConfig, ErrorCode, Subscribe, Syscalls, Upcall,
 };
 
 /// The Gpio driver
@@ -53,6 +55,22 @@ impl Pull for PullNone {
     const MODE: u32 = 0;
 }
 
+/// How an output pin drives the line.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum OutputMode {
+    /// The pin drives the line both high and low.
+    PushPull,
+    /// The pin only drives the line low. Setting the pin releases the line
+    /// (high impedance) instead of driving it high, so several open-drain pins
+    /// can share a line, as in I2C or a wired-OR interrupt line. The line needs
+    /// a pull-up resistor to go high when released, which must be external,
+    /// as the pull-ups of input pins are not used in this mode.
+    ///
+    /// The upstream GPIO capsule does not support open-drain output yet, so
+    /// this requires a kernel that implements it, as command 16.
+    OpenDrain,
+}
+
 pub struct Gpio<S: Syscalls>(S);
 
 impl<S: Syscalls> Gpio<S> {
@@ -92,6 +110,63 @@ impl<S: Syscalls> Gpio<S> {
     pub fn unregister_listener() {
         S::unsubscribe(DRIVER_NUM, 0)
     }
+
+    /// Reads `pins` and packs their states into a bitmask, where bit `i` is
+    /// set if `pins[i]` is high. The pins must be configured as inputs. At most
+    /// 32 pins can be read; more are rejected with `ErrorCode::Invalid`.
+    ///
+    /// The GPIO capsule has no command to read several pins at once, so the
+    /// pins are read one at a time. The result is not a snapshot: a pin that
+    /// changes while the others are read may be seen in either state, so
+    /// inputs that change together (such as a parallel bus) should be read
+    /// only while they are stable.
+    pub fn read_mask(pins: &[u32]) -> Result<u32, ErrorCode> {
+        if pins.len() > u32::BITS as usize {
+            return Err(ErrorCode::Invalid);
+        }
+        let mut mask = 0;
+        for (bit, &pin) in pins.iter().enumerate() {
+            if Self::read(pin)? == GpioState::High {
+                mask |= 1 << bit;
+            }
+        }
+        Ok(mask)
+    }
+
+    /// Drives `pin` as an output with a software-generated PWM signal, for pins
+    /// without a PWM peripheral. The pin is high for `duty_percent` of each
+    /// `period`, and low for the rest. Returns `ErrorCode::Invalid` if
+    /// `duty_percent` is above 100, or `period` is zero.
+    ///
+    /// The signal is only generated while the app calls
+    /// [`SoftPwm::cycle`] in a loop, and each edge is timed by the alarm
+    /// driver, so it is much less accurate than hardware PWM: edges are
+    /// delayed by the system call and scheduling latency, and by anything else
+    /// the app or the kernel does. Periods of a few milliseconds or more, as
+    /// needed to dim an LED, work best. Edges are scheduled relative to the
+    /// start, so the delays do not accumulate over many periods.
+    pub fn soft_pwm<T: Convert>(
+        pin: u32,
+        duty_percent: u8,
+        period: T,
+    ) -> Result<SoftPwm<S>, ErrorCode> {
+        if duty_percent > 100 {
+            return Err(ErrorCode::Invalid);
+        }
+        let period = period.to_ticks(Alarm::<S>::get_frequency()?).0;
+        if period == 0 {
+            return Err(ErrorCode::Invalid);
+        }
+        let high = (u64::from(period) * u64::from(duty_percent) / 100) as u32;
+        Self::enable_gpio_output(pin)?;
+        Ok(SoftPwm {
+            pin,
+            high: Ticks(high),
+            low: Ticks(period - high),
+            next: Alarm::<S>::get_ticks()?,
+            _syscalls: PhantomData,
+        })
+    }
 }
 
 /// A wrapper around a closure to be registered and called when
@@ -130,6 +205,17 @@ impl<S: Syscalls> Pin<S> {
         Ok(OutputPin { pin: self })
     }
 
+    /// Like [`make_output`](Self::make_output), with the given output mode.
+    /// With [`OutputMode::OpenDrain`], [`OutputPin::set`] releases the line,
+    /// and [`OutputPin::clear`] pulls it low.
+    pub fn make_output_mode(&mut self, mode: OutputMode) -> Result<OutputPin<S>, ErrorCode> {
+        match mode {
+            OutputMode::PushPull => Gpio::<S>::enable_gpio_output(self.pin_number)?,
+            OutputMode::OpenDrain => Gpio::<S>::enable_gpio_output_open_drain(self.pin_number)?,
+        }
+        Ok(OutputPin { pin: self })
+    }
+
     pub fn make_input<P: Pull>(&self) -> Result<InputPin<S, P>, ErrorCode> {
         Gpio::<S>::enable_gpio_input(self.pin_number, P::MODE)?;
         Ok(InputPin {
@@ -172,6 +258,84 @@ impl<'a, S: Syscalls, P: Pull> InputPin<'a, S, P> {
     pub fn disable_interrupts(&self) -> Result<(), ErrorCode> {
         Gpio::<S>::disable_interrupts(self.pin.pin_number)
     }
+
+    /// Wait for an interrupt on this pin and return the pin state reported
+    /// with it.
+    ///
+    /// Interrupts are enabled for the pin while waiting, and disabled before
+    /// returning. The upcall used to wait replaces any registered
+    /// [`GpioInterruptListener`], and is unregistered before returning.
+    pub fn wait_for_edge(&self, edge: PinInterruptEdge) -> Result<GpioState, ErrorCode> {
+        let pin_number = self.pin.pin_number;
+        let state: Cell<Option<GpioState>> = Cell::new(None);
+        let listener = GpioInterruptListener(|gpio, gpio_state| {
+            if gpio == pin_number {
+                state.set(Some(gpio_state));
+            }
+        });
+        share::scope(|subscribe| {
+            Gpio::<S>::register_listener(&listener, subscribe)?;
+            self.enable_interrupts(edge)?;
+            loop {
+                S::yield_wait();
+                if let Some(gpio_state) = state.get() {
+                    self.disable_interrupts()?;
+                    return Ok(gpio_state);
+                }
+            }
+        })
+    }
+
+    /// Like [`wait_for_edge`](Self::wait_for_edge), but ignores edges arriving
+    /// within `min_interval` of the returned one, to debounce noisy inputs such
+    /// as mechanical switches.
+    ///
+    /// After the first edge, this sleeps for `min_interval` using the alarm
+    /// driver with the pin's interrupts disabled, so the edges of a bounce are
+    /// dropped rather than seen by the next call. This occupies the alarm
+    /// driver for that time, and fails if it is not present.
+    pub fn wait_for_edge_debounced<T: Convert>(
+        &self,
+        edge: PinInterruptEdge,
+        min_interval: T,
+    ) -> Result<GpioState, ErrorCode> {
+        let gpio_state = self.wait_for_edge(edge)?;
+        Alarm::<S>::sleep_for(min_interval)?;
+        Ok(gpio_state)
+    }
+}
+
+/// A software PWM output, created by [`Gpio::soft_pwm`]. The pin is disabled
+/// when this is dropped.
+pub struct SoftPwm<S: Syscalls> {
+    pin: u32,
+    high: Ticks,
+    low: Ticks,
+    // When the current period ends.
+    next: Ticks,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> SoftPwm<S> {
+    /// Generates one period of the signal, returning at its end.
+    pub fn cycle(&mut self) -> Result<(), ErrorCode> {
+        for (state, duration) in [(GpioState::High, self.high), (GpioState::Low, self.low)] {
+            // A duty cycle of 0 or 100% has no edge to make.
+            if duration.0 == 0 {
+                continue;
+            }
+            Gpio::<S>::write(self.pin, state)?;
+            self.next.0 = self.next.0.wrapping_add(duration.0);
+            Alarm::<S>::sleep_until(self.next)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: Syscalls> Drop for SoftPwm<S> {
+    fn drop(&mut self) {
+        let _ = Gpio::<S>::disable(self.pin);
+    }
 }
 
 impl<S: Syscalls> Drop for OutputPin<'_, S> {
@@ -195,6 +359,10 @@ impl<S: Syscalls> Gpio<S> {
         S::command(DRIVER_NUM, GPIO_ENABLE_OUTPUT, pin, 0).to_result()
     }
 
+    fn enable_gpio_output_open_drain(pin: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, GPIO_ENABLE_OUTPUT_OPEN_DRAIN, pin, 0).to_result()
+    }
+
     fn enable_gpio_input(pin: u32, mode: u32) -> Result<(), ErrorCode> {
         S::command(DRIVER_NUM, GPIO_ENABLE_INPUT, pin, mode).to_result()
     }
@@ -253,3 +421,6 @@ const GPIO_ENABLE_INTERRUPTS: u32 = 7;
 const GPIO_DISABLE_INTERRUPTS: u32 = 8;
 
 const GPIO_DISABLE: u32 = 9;
+
+// Not supported by the upstream capsule yet.
+const GPIO_ENABLE_OUTPUT_OPEN_DRAIN: u32 = 16;
@@ -2,8 +2,11 @@ use core::cell::Cell;
 
 use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
 use libtock_unittest::fake::{self, GpioMode, InterruptEdge, PullMode};
+use libtock_unittest::SyscallLogEntry;
 
-use crate::{GpioInterruptListener, GpioState, PinInterruptEdge, PullDown, PullNone, PullUp};
+use crate::{
+    GpioInterruptListener, GpioState, OutputMode, PinInterruptEdge, PullDown, PullNone, PullUp,
+};
 
 type Gpio = super::Gpio<fake::Syscalls>;
 
@@ -55,6 +58,41 @@ fn output() {
     });
 }
 
+#[test]
+fn open_drain_output() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Gpio::<2>::new();
+    kernel.add_driver(&driver);
+
+    let mut pin = Gpio::get_pin(1).unwrap();
+    kernel.take_syscall_log();
+    let mut output = pin.make_output_mode(OutputMode::OpenDrain).unwrap();
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::Command {
+            driver_id: 4,
+            command_id: 16,
+            argument0: 1,
+            argument1: 0,
+        }]
+    );
+    assert_eq!(
+        driver.get_gpio_state(1).unwrap().mode,
+        GpioMode::OutputOpenDrain
+    );
+    // Clearing pulls the line low, and setting releases it.
+    assert_eq!(output.clear(), Ok(()));
+    assert!(!driver.get_gpio_state(1).unwrap().value);
+    assert_eq!(output.set(), Ok(()));
+    assert!(driver.get_gpio_state(1).unwrap().value);
+    drop(output);
+    assert_eq!(driver.get_gpio_state(1).unwrap().mode, GpioMode::Disable);
+
+    // Push-pull is the same as make_output.
+    let _output = pin.make_output_mode(OutputMode::PushPull).unwrap();
+    assert_eq!(driver.get_gpio_state(1).unwrap().mode, GpioMode::Output);
+}
+
 // Tests the InputPin implementation
 #[test]
 fn input() {
@@ -103,6 +141,39 @@ fn input() {
     });
 }
 
+// Tests that each pull mode is passed to the kernel as the documented argument.
+#[test]
+fn pull_mode_arguments() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Gpio::<10>::new();
+    kernel.add_driver(&driver);
+
+    let pin = Gpio::get_pin(3).unwrap();
+    fn expected(mode: u32) -> SyscallLogEntry {
+        SyscallLogEntry::Command {
+            driver_id: super::DRIVER_NUM,
+            command_id: super::GPIO_ENABLE_INPUT,
+            argument0: 3,
+            argument1: mode,
+        }
+    }
+    let _ = kernel.take_syscall_log();
+
+    let input_pin = pin.make_input::<PullNone>();
+    assert!(input_pin.is_ok());
+    assert_eq!(kernel.take_syscall_log().last(), Some(&expected(0)));
+    drop(input_pin);
+
+    let input_pin = pin.make_input::<PullUp>();
+    assert!(input_pin.is_ok());
+    assert_eq!(kernel.take_syscall_log().last(), Some(&expected(1)));
+    drop(input_pin);
+
+    let input_pin = pin.make_input::<PullDown>();
+    assert!(input_pin.is_ok());
+    assert_eq!(kernel.take_syscall_log().last(), Some(&expected(2)));
+}
+
 // Tests the pin interrupts implementation
 #[test]
 fn interrupts() {
@@ -294,3 +365,156 @@ fn subscribe() {
     assert_eq!(driver.set_value(0, false), Ok(()));
     assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
 }
+
+#[test]
+fn wait_for_edge() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Gpio::<10>::new();
+    kernel.add_driver(&driver);
+
+    let pin = Gpio::get_pin(0).unwrap();
+    let input_pin = pin.make_input::<PullNone>().unwrap();
+
+    // The falling edge does not trigger, so the rising one is reported.
+    assert_eq!(driver.set_value(0, true), Ok(()));
+    driver.set_value_sync(0, false);
+    driver.set_value_sync(0, true);
+    assert_eq!(
+        input_pin.wait_for_edge(PinInterruptEdge::Rising),
+        Ok(GpioState::High)
+    );
+    assert_eq!(driver.get_gpio_state(0).unwrap().interrupt_enabled, None);
+
+    driver.set_value_sync(0, false);
+    assert_eq!(
+        input_pin.wait_for_edge(PinInterruptEdge::Either),
+        Ok(GpioState::Low)
+    );
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+}
+
+#[test]
+fn wait_for_edge_debounced() {
+    use libtock_alarm::Milliseconds;
+
+    let kernel = fake::Kernel::new();
+    let driver = fake::Gpio::<10>::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let pin = Gpio::get_pin(0).unwrap();
+    let input_pin = pin.make_input::<PullNone>().unwrap();
+
+    // A burst of bounces results in a single edge being reported.
+    for _ in 0..5 {
+        driver.set_value_sync(0, true);
+        driver.set_value_sync(0, false);
+    }
+    assert_eq!(
+        input_pin.wait_for_edge_debounced(PinInterruptEdge::Either, Milliseconds(20)),
+        Ok(GpioState::High)
+    );
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    let alarms = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter(|entry| matches!(entry, SyscallLogEntry::Command { driver_id: 0, .. }))
+        .count();
+    // Frequency query plus one relative alarm.
+    assert_eq!(alarms, 2);
+}
+
+#[test]
+fn wait_for_edge_debounced_no_alarm() {
+    use libtock_alarm::Milliseconds;
+
+    let kernel = fake::Kernel::new();
+    let driver = fake::Gpio::<10>::new();
+    kernel.add_driver(&driver);
+
+    let pin = Gpio::get_pin(0).unwrap();
+    let input_pin = pin.make_input::<PullNone>().unwrap();
+    driver.set_value_sync(0, true);
+    assert_eq!(
+        input_pin.wait_for_edge_debounced(PinInterruptEdge::Either, Milliseconds(20)),
+        Err(ErrorCode::NoDevice)
+    );
+}
+
+#[test]
+fn read_mask() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Gpio::<6>::new();
+    kernel.add_driver(&driver);
+
+    let pins = [0, 1, 2, 3, 5].map(|pin| Gpio::get_pin(pin).unwrap());
+    let _inputs = [&pins[0], &pins[1], &pins[2], &pins[3], &pins[4]]
+        .map(|pin| pin.make_input::<PullNone>().unwrap());
+    for (pin, value) in [(0, true), (1, false), (2, true), (3, true), (5, true)] {
+        assert_eq!(driver.set_value(pin, value), Ok(()));
+    }
+
+    // Bit i is the state of the i-th listed pin, not of pin number i.
+    assert_eq!(Gpio::read_mask(&[0, 1, 2, 3, 5]), Ok(0b11101));
+    assert_eq!(Gpio::read_mask(&[5, 1]), Ok(0b01));
+    assert_eq!(Gpio::read_mask(&[]), Ok(0));
+    assert_eq!(Gpio::read_mask(&[0, 6]), Err(ErrorCode::Invalid));
+    assert_eq!(Gpio::read_mask(&[0; 33]), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn soft_pwm() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let driver = fake::Gpio::<10>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        Gpio::soft_pwm(1, 101, libtock_alarm::Milliseconds(20)).err(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Gpio::soft_pwm(1, 50, libtock_alarm::Milliseconds(0)).err(),
+        Some(ErrorCode::Invalid)
+    );
+
+    let mut pwm = Gpio::soft_pwm(1, 25, libtock_alarm::Milliseconds(20)).unwrap();
+    assert_eq!(driver.get_gpio_state(1).unwrap().mode, GpioMode::Output);
+    kernel.take_syscall_log();
+    pwm.cycle().unwrap();
+
+    // Collect how long the pin stayed in each state, from the GPIO commands
+    // and the alarms that followed them.
+    let mut high = 0;
+    let mut low = 0;
+    let mut state = None;
+    for entry in kernel.take_syscall_log() {
+        if let SyscallLogEntry::Command {
+            driver_id,
+            command_id,
+            argument1,
+            ..
+        } = entry
+        {
+            match (driver_id, command_id) {
+                (4, 2) => state = Some(true),
+                (4, 3) => state = Some(false),
+                (0, 6) if state == Some(true) => high += argument1,
+                (0, 6) if state == Some(false) => low += argument1,
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(high + low, 20);
+    // 25% of the period, within one tick.
+    assert!((4..=6).contains(&high), "high for {} ticks", high);
+    assert_eq!(alarm.get_now(), 20);
+
+    // Later periods follow on from the first.
+    pwm.cycle().unwrap();
+    assert_eq!(alarm.get_now(), 40);
+    drop(pwm);
+    assert_eq!(driver.get_gpio_state(1).unwrap().mode, GpioMode::Disable);
+}
@@ -0,0 +1,213 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// An incremental HMAC-SHA256, computed by the kernel's HMAC capsule.
+///
+/// The key is installed with [`set_key`](Self::set_key) before any data is
+/// added. The capsule reads the key when the computation starts, so the key
+/// is shared with the kernel alongside the data, which is why `HmacSha256`
+/// borrows it. Data is then added with [`update`](Self::update), and the MAC
+/// is produced by [`finalize`](Self::finalize) or checked by
+/// [`verify`](Self::verify). Either of the latter may only be called once.
+///
+/// The capsule keeps one computation in progress per process, so only one
+/// `HmacSha256` should be used at a time.
+///
+/// # Example
+/// ```ignore
+/// use libtock::hmac::HmacSha256;
+///
+/// let mut hmac = HmacSha256::new();
+/// hmac.set_key(&key)?;
+/// hmac.update(message)?;
+/// if !hmac.verify(&tag)? {
+///     // Reject the message.
+/// }
+/// ```
+pub struct HmacSha256<'key, S: Syscalls> {
+    key: Option<&'key [u8]>,
+    state: State,
+    _syscalls: PhantomData<S>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum State {
+    // No key has been installed yet.
+    New,
+    // A key has been installed but no data has been added.
+    Keyed,
+    Updating,
+    Finalized,
+}
+
+impl<'key, S: Syscalls> HmacSha256<'key, S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    ///
+    /// The HMAC capsule uses command 0 to select the algorithm, so this also
+    /// selects HMAC-SHA256.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_SHA256, 0).to_result()
+    }
+
+    /// Starts a new computation. A key must be installed before adding data.
+    pub const fn new() -> Self {
+        HmacSha256 {
+            key: None,
+            state: State::New,
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Installs the key used for this computation.
+    ///
+    /// The key can be replaced until data is added; afterwards this returns
+    /// `ErrorCode::Already`.
+    pub fn set_key(&mut self, key: &'key [u8]) -> Result<(), ErrorCode> {
+        match self.state {
+            State::New | State::Keyed => {}
+            State::Updating | State::Finalized => return Err(ErrorCode::Already),
+        }
+        S::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_SHA256, 0).to_result::<(), ErrorCode>()?;
+        self.key = Some(key);
+        self.state = State::Keyed;
+        Ok(())
+    }
+
+    /// Adds `data` to the computation, blocking until the capsule has
+    /// consumed it.
+    ///
+    /// Returns `ErrorCode::Reserve` if no key has been installed and
+    /// `ErrorCode::Already` if the computation was already finalized.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), ErrorCode> {
+        let key = self.key()?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.state = State::Updating;
+        let done = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+                AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_data, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, key)?;
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(allow_data, data)?;
+            Self::run_operation(&done, subscribe, UPDATE)
+        })
+    }
+
+    /// Completes the computation and writes the MAC to `out`.
+    ///
+    /// The computation can only be finalized once: later calls to `finalize`,
+    /// `verify` and `update` return `ErrorCode::Already`, even if the first
+    /// call failed.
+    pub fn finalize(&mut self, out: &mut [u8; 32]) -> Result<(), ErrorCode> {
+        let key = self.key()?;
+        self.state = State::Finalized;
+        let done = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+                AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_dest, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, key)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, out)?;
+            Self::run_operation(&done, subscribe, FINISH)
+        })
+    }
+
+    /// Completes the computation and returns whether the MAC matches
+    /// `expected`.
+    ///
+    /// The comparison takes the same time wherever the first mismatching byte
+    /// is, so it does not reveal how much of a forged MAC was correct.
+    pub fn verify(&mut self, expected: &[u8; 32]) -> Result<bool, ErrorCode> {
+        let mut mac = [0; 32];
+        self.finalize(&mut mac)?;
+        let difference = mac
+            .iter()
+            .zip(expected)
+            .fold(0, |difference, (a, b)| difference | (a ^ b));
+        Ok(difference == 0)
+    }
+}
+
+impl<'key, S: Syscalls> Default for HmacSha256<'key, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<'key, S: Syscalls> HmacSha256<'key, S> {
+    // Returns the installed key, if the computation can still proceed.
+    fn key(&self) -> Result<&'key [u8], ErrorCode> {
+        match (self.state, self.key) {
+            (State::Finalized, _) => Err(ErrorCode::Already),
+            (_, Some(key)) => Ok(key),
+            (_, None) => Err(ErrorCode::Reserve),
+        }
+    }
+
+    // Starts `command` and waits for the capsule to report its completion.
+    fn run_operation<'share>(
+        done: &'share Cell<Option<(u32,)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+        command: u32,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, done)?;
+        S::command(DRIVER_NUM, command, 0, 0).to_result::<(), ErrorCode>()?;
+        loop {
+            match done.get() {
+                Some((0,)) => return Ok(()),
+                Some((status,)) => {
+                    return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                }
+                None => S::yield_wait(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40003;
+
+// Command IDs
+
+const SET_ALGORITHM: u32 = 0;
+const UPDATE: u32 = 2;
+const FINISH: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_KEY: u32 = 0;
+const ALLOW_DATA: u32 = 1;
+const ALLOW_DEST: u32 = 0;
+
+// Algorithms
+
+const ALGORITHM_SHA256: u32 = 0;
@@ -0,0 +1,94 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type HmacSha256<'key> = super::HmacSha256<'key, fake::Syscalls>;
+
+// RFC 4231 test case 1.
+const TC1_KEY: [u8; 20] = [0x0b; 20];
+const TC1_DATA: &[u8] = b"Hi There";
+const TC1_MAC: [u8; 32] = [
+    0xb0, 0x34, 0x4c, 0x61, 0xd8, 0xdb, 0x38, 0x53, 0x5c, 0xa8, 0xaf, 0xce, 0xaf, 0x0b, 0xf1, 0x2b,
+    0x88, 0x1d, 0xc2, 0x00, 0xc9, 0x83, 0x3d, 0xa7, 0x26, 0xe9, 0x37, 0x6c, 0x2e, 0x32, 0xcf, 0xf7,
+];
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(HmacSha256::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        HmacSha256::new().set_key(&TC1_KEY),
+        Err(ErrorCode::NoDevice)
+    );
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(HmacSha256::exists(), Ok(()));
+}
+
+#[test]
+fn rfc4231_test_case_1() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut hmac = HmacSha256::new();
+    assert_eq!(hmac.set_key(&TC1_KEY), Ok(()));
+    assert_eq!(hmac.update(&TC1_DATA[..2]), Ok(()));
+    assert_eq!(hmac.update(&TC1_DATA[2..]), Ok(()));
+    let mut mac = [0; 32];
+    assert_eq!(hmac.finalize(&mut mac), Ok(()));
+    assert_eq!(mac, TC1_MAC);
+}
+
+#[test]
+fn verify() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut hmac = HmacSha256::new();
+    assert_eq!(hmac.set_key(&TC1_KEY), Ok(()));
+    assert_eq!(hmac.update(TC1_DATA), Ok(()));
+    assert_eq!(hmac.verify(&TC1_MAC), Ok(true));
+    assert_eq!(hmac.verify(&TC1_MAC), Err(ErrorCode::Already));
+
+    let mut forged = TC1_MAC;
+    forged[31] ^= 1;
+    let mut hmac = HmacSha256::new();
+    assert_eq!(hmac.set_key(&TC1_KEY), Ok(()));
+    assert_eq!(hmac.update(TC1_DATA), Ok(()));
+    assert_eq!(hmac.verify(&forged), Ok(false));
+}
+
+#[test]
+fn key_required() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut hmac = HmacSha256::new();
+    let mut mac = [0; 32];
+    assert_eq!(hmac.update(TC1_DATA), Err(ErrorCode::Reserve));
+    assert_eq!(hmac.finalize(&mut mac), Err(ErrorCode::Reserve));
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn key_locked_after_update() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Hmac::new();
+    kernel.add_driver(&driver);
+
+    let mut hmac = HmacSha256::new();
+    assert_eq!(hmac.set_key(b"first"), Ok(()));
+    // The key may be replaced until data is added.
+    assert_eq!(hmac.set_key(&TC1_KEY), Ok(()));
+    assert_eq!(hmac.update(TC1_DATA), Ok(()));
+    assert_eq!(hmac.set_key(b"second"), Err(ErrorCode::Already));
+    assert_eq!(hmac.verify(&TC1_MAC), Ok(true));
+}
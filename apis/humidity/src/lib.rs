@@ -0,0 +1,104 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::task::Poll;
+
+use libtock_platform::{share, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+use libtock_sensor::{Sensor, Unit};
+
+/// The humidity sensor driver. A value is only needed to use the driver as a
+/// [`Sensor`]; all other operations are associated functions.
+pub struct Humidity<S: Syscalls>(PhantomData<S>);
+
+impl<S: Syscalls> Humidity<S> {
+    pub const fn new() -> Self {
+        Humidity(PhantomData)
+    }
+
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Register an events listener
+    pub fn register_listener<'share>(
+        listener: &'share Cell<Option<(u32,)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the events listener
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, 0)
+    }
+
+    /// Initiate a humidity measurement
+    pub fn read() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, READ, 0, 0).to_result()
+    }
+
+    /// Non-blocking counterpart of `read_sync`, for apps that poll from their
+    /// own main loop. Before polling, `listener` must be registered with
+    /// `register_listener` and a measurement started with `read`. Each call
+    /// handles at most one pending upcall, and returns `Poll::Pending` until
+    /// the measurement completes.
+    pub fn try_read(listener: &Cell<Option<(u32,)>>) -> Poll<u32> {
+        S::yield_no_wait();
+        match listener.take() {
+            Some((humidity,)) => Poll::Ready(humidity),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Initiate a synchronous humidity measurement.
+    /// Returns Ok(humidity_value) if the operation was successful
+    /// humidity_value is the relative humidity in hundredths of a percent.
+    /// Returns Err(ErrorCode::Busy) if a measurement is already in progress.
+    pub fn read_sync() -> Result<u32, ErrorCode> {
+        let listener: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            Self::read()?;
+            loop {
+                if let Some((humidity,)) = listener.get() {
+                    return Ok(humidity);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+impl<S: Syscalls> Default for Humidity<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Syscalls> Sensor for Humidity<S> {
+    fn read(&self) -> Result<i32, ErrorCode> {
+        // At most 10000, so always fits.
+        Self::read_sync().map(|humidity| humidity as i32)
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::CentiPercentRh
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ: u32 = 1;
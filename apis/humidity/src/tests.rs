@@ -0,0 +1,147 @@
+use core::cell::Cell;
+use core::task::Poll;
+use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_sensor::{Sensor, Unit};
+use libtock_unittest::{fake, ExpectedSyscall, SyscallLogEntry};
+
+type Humidity = super::Humidity<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Humidity::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Humidity::read_sync(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Humidity::exists(), Ok(()));
+}
+
+#[test]
+fn read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Humidity::read(), Ok(()));
+    assert!(driver.is_busy());
+
+    assert_eq!(Humidity::read(), Err(ErrorCode::Busy));
+    assert_eq!(Humidity::read_sync(), Err(ErrorCode::Busy));
+}
+
+#[test]
+fn register_unregister_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(Humidity::read(), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert_eq!(Humidity::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Humidity::read(), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((100,)));
+
+        Humidity::unregister_listener();
+        assert_eq!(Humidity::read(), Ok(()));
+        driver.set_value(100);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn read_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync(5000);
+    assert_eq!(Humidity::read_sync(), Ok(5000));
+}
+
+#[test]
+fn read_sync_subscribe_fail() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: 0x60001,
+        subscribe_num: 0,
+        skip_with_error: Some(ErrorCode::NoMem),
+    });
+    assert_eq!(Humidity::read_sync(), Err(ErrorCode::NoMem));
+    assert!(!driver.is_busy());
+}
+
+#[test]
+fn try_read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    let listener: Cell<Option<(u32,)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(Humidity::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Humidity::read(), Ok(()));
+        assert_eq!(Humidity::try_read(&listener), Poll::Pending);
+        assert_eq!(Humidity::try_read(&listener), Poll::Pending);
+
+        driver.set_value(4550);
+        assert_eq!(Humidity::try_read(&listener), Poll::Ready(4550));
+        assert_eq!(Humidity::try_read(&listener), Poll::Pending);
+    });
+}
+
+#[test]
+fn sensor() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    let sensor: &dyn Sensor = &Humidity::new();
+    driver.set_value_sync(4520);
+    assert_eq!(sensor.read(), Ok(4520));
+    assert_eq!(sensor.unit(), Unit::CentiPercentRh);
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Humidity::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Humidity::read(), Ok(()));
+    kernel.take_syscall_log();
+    assert_eq!(Humidity::read_sync(), Err(ErrorCode::Busy));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::READ,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
@@ -0,0 +1,187 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The I2C master driver.
+///
+/// Transfers block until the capsule reports their completion. If the device
+/// does not acknowledge its address or a byte, the transfer fails with
+/// `ErrorCode::NoAck`.
+///
+/// # Example
+/// ```ignore
+/// use libtock::i2c_master::I2cMaster;
+///
+/// // Read the WHO_AM_I register of the device at address 0x1e.
+/// let mut id = [0];
+/// I2cMaster::write_read(0x1e, &[0x0f], &mut id)?;
+/// ```
+pub struct I2cMaster<S: Syscalls>(S);
+
+impl<S: Syscalls> I2cMaster<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Writes `data` to the device at the 7-bit address `addr`.
+    ///
+    /// The capsule only transfers data through a read-write buffer, so `data`
+    /// is copied to the stack first and may be at most 64 bytes long;
+    /// otherwise `ErrorCode::Size` is returned.
+    pub fn write(addr: u8, data: &[u8]) -> Result<(), ErrorCode> {
+        let mut buffer = [0; MAX_COPY_LEN];
+        let buffer = buffer.get_mut(..data.len()).ok_or(ErrorCode::Size)?;
+        buffer.copy_from_slice(data);
+        Self::transfer(WRITE, addr, data.len(), buffer)
+    }
+
+    /// Fills `buf` with bytes read from the device at the 7-bit address
+    /// `addr`.
+    pub fn read(addr: u8, buf: &mut [u8]) -> Result<(), ErrorCode> {
+        Self::transfer(READ, addr, buf.len(), buf)
+    }
+
+    /// Writes `out` to the device at the 7-bit address `addr`, then fills
+    /// `in_buf` with bytes read from it.
+    ///
+    /// The two phases form a single transaction: they are separated by a
+    /// repeated start rather than a stop, as most devices require to read a
+    /// register. `out` and `in_buf` may be at most 65535 bytes long. If `out`
+    /// is longer than `in_buf`, both are staged on the stack and may be at
+    /// most 64 bytes long. Lengths beyond these limits return
+    /// `ErrorCode::Size`.
+    pub fn write_read(addr: u8, out: &[u8], in_buf: &mut [u8]) -> Result<(), ErrorCode> {
+        if out.len() > u16::MAX as usize || in_buf.len() > u16::MAX as usize {
+            return Err(ErrorCode::Size);
+        }
+        let lengths = out.len() | in_buf.len() << 16;
+        // The capsule sends the start of the buffer and then overwrites it
+        // with the bytes it reads.
+        if out.len() <= in_buf.len() {
+            in_buf[..out.len()].copy_from_slice(out);
+            return Self::transfer(WRITE_READ, addr, lengths, in_buf);
+        }
+        let mut buffer = [0; MAX_COPY_LEN];
+        let buffer = buffer.get_mut(..out.len()).ok_or(ErrorCode::Size)?;
+        buffer.copy_from_slice(out);
+        Self::transfer(WRITE_READ, addr, lengths, buffer)?;
+        in_buf.copy_from_slice(&buffer[..in_buf.len()]);
+        Ok(())
+    }
+
+    /// Reads the register `reg` of the device at the 7-bit address `addr`,
+    /// using the register-address-then-data protocol most sensors follow.
+    pub fn read_reg(addr: u8, reg: u8) -> Result<u8, ErrorCode> {
+        let mut value = [0];
+        Self::write_read(addr, &[reg], &mut value)?;
+        Ok(value[0])
+    }
+
+    /// Fills `buf` with consecutive registers of the device at the 7-bit
+    /// address `addr`, starting at `reg`. The whole block is read in a single
+    /// transaction, so multi-byte values are read consistently.
+    pub fn read_regs(addr: u8, reg: u8, buf: &mut [u8]) -> Result<(), ErrorCode> {
+        Self::write_read(addr, &[reg], buf)
+    }
+
+    /// Writes `value` to the register `reg` of the device at the 7-bit address
+    /// `addr`.
+    pub fn write_reg(addr: u8, reg: u8, value: u8) -> Result<(), ErrorCode> {
+        Self::write(addr, &[reg, value])
+    }
+
+    /// Probes every address from 0x08 to 0x77 and stores the addresses that
+    /// acknowledge in `out`, in ascending order. Returns the number of devices
+    /// found. Scanning stops early once `out` is full.
+    ///
+    /// Each address is probed with a 1-byte read, which devices do not treat
+    /// as a command. An address that is not acknowledged is skipped; any other
+    /// error aborts the scan.
+    pub fn scan(out: &mut [u8]) -> Result<usize, ErrorCode> {
+        let mut found = 0;
+        for addr in SCAN_FIRST..=SCAN_LAST {
+            if found == out.len() {
+                break;
+            }
+            match Self::read(addr, &mut [0]) {
+                Ok(()) => {
+                    out[found] = addr;
+                    found += 1;
+                }
+                Err(ErrorCode::NoAck) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(found)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> I2cMaster<S> {
+    // Shares `buffer` with the capsule and runs the transfer started by
+    // `command`, blocking until it completes.
+    fn transfer(command: u32, addr: u8, length: usize, buffer: &mut [u8]) -> Result<(), ErrorCode> {
+        if addr > MAX_ADDRESS {
+            return Err(ErrorCode::Invalid);
+        }
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, buffer)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &done)?;
+            S::command(DRIVER_NUM, command, addr as u32, length as u32)
+                .to_result::<(), ErrorCode>()?;
+            loop {
+                match done.get() {
+                    Some((_, 0)) => return Ok(()),
+                    Some((_, status)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20003;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const WRITE: u32 = 1;
+const READ: u32 = 2;
+const WRITE_READ: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 1;
+
+const MAX_ADDRESS: u8 = 0x7f;
+// The addresses probed by `scan`. The others are reserved by the I2C
+// specification.
+const SCAN_FIRST: u8 = 0x08;
+const SCAN_LAST: u8 = 0x77;
+// The largest buffer copied to the stack for a transfer.
+const MAX_COPY_LEN: usize = 64;
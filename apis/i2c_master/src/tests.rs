@@ -0,0 +1,172 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake::{self, I2cTransfer};
+
+type I2cMaster = super::I2cMaster<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(I2cMaster::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(I2cMaster::write(0x10, &[1]), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(I2cMaster::exists(), Ok(()));
+}
+
+#[test]
+fn write_then_read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(0x10);
+
+    assert_eq!(I2cMaster::write(0x10, &[1, 2, 3]), Ok(()));
+    let mut buf = [0; 3];
+    assert_eq!(I2cMaster::read(0x10, &mut buf), Ok(()));
+    assert_eq!(buf, [1, 2, 3]);
+    assert_eq!(
+        driver.take_transfers(),
+        [
+            I2cTransfer::Write {
+                address: 0x10,
+                data: [1, 2, 3].into()
+            },
+            I2cTransfer::Read {
+                address: 0x10,
+                len: 3
+            },
+        ]
+    );
+}
+
+#[test]
+fn write_read() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(0x1e);
+
+    // A single transaction, so the written register address is echoed back.
+    let mut buf = [0; 2];
+    assert_eq!(I2cMaster::write_read(0x1e, &[0x0f], &mut buf), Ok(()));
+    assert_eq!(buf, [0x0f, 0]);
+
+    // Writes longer than the read are staged on the stack.
+    let mut buf = [0; 1];
+    assert_eq!(I2cMaster::write_read(0x1e, &[0x20, 0x21], &mut buf), Ok(()));
+    assert_eq!(buf, [0x20]);
+
+    assert_eq!(
+        driver.take_transfers(),
+        [
+            I2cTransfer::WriteRead {
+                address: 0x1e,
+                data: [0x0f].into(),
+                read_len: 2
+            },
+            I2cTransfer::WriteRead {
+                address: 0x1e,
+                data: [0x20, 0x21].into(),
+                read_len: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn nak() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 1];
+    assert_eq!(I2cMaster::write(0x10, &[1]), Err(ErrorCode::NoAck));
+    assert_eq!(I2cMaster::read(0x10, &mut buf), Err(ErrorCode::NoAck));
+    assert_eq!(
+        I2cMaster::write_read(0x10, &[1], &mut buf),
+        Err(ErrorCode::NoAck)
+    );
+}
+
+#[test]
+fn invalid_arguments() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(0x10);
+
+    assert_eq!(I2cMaster::write(0x80, &[1]), Err(ErrorCode::Invalid));
+    assert_eq!(I2cMaster::write(0x10, &[0; 65]), Err(ErrorCode::Size));
+    assert_eq!(
+        I2cMaster::write_read(0x10, &[0; 65], &mut [0; 1]),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn scan() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_device(0x1e);
+    driver.add_device(0x68);
+    // Reserved addresses are not probed.
+    driver.add_device(0x03);
+
+    let mut found = [0; 8];
+    assert_eq!(I2cMaster::scan(&mut found), Ok(2));
+    assert_eq!(found[..2], [0x1e, 0x68]);
+    let transfers = driver.take_transfers();
+    assert_eq!(transfers.len(), 0x78 - 0x08);
+    assert_eq!(
+        transfers[0],
+        I2cTransfer::Read {
+            address: 0x08,
+            len: 1
+        }
+    );
+
+    // The scan stops once the output is full.
+    let mut found = [0; 1];
+    assert_eq!(I2cMaster::scan(&mut found), Ok(1));
+    assert_eq!(found, [0x1e]);
+    assert_eq!(driver.take_transfers().len(), 0x1f - 0x08);
+}
+
+#[test]
+fn registers() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::I2cMaster::new();
+    kernel.add_driver(&driver);
+    driver.add_register_device(0x76, &[0x60, 0x01, 0x02, 0x03]);
+
+    assert_eq!(I2cMaster::read_reg(0x76, 0), Ok(0x60));
+    assert_eq!(I2cMaster::write_reg(0x76, 2, 0x20), Ok(()));
+    assert_eq!(
+        driver.get_registers(0x76),
+        Some([0x60, 0x01, 0x20, 0x03].into())
+    );
+    driver.take_transfers();
+
+    let mut buf = [0; 3];
+    assert_eq!(I2cMaster::read_regs(0x76, 1, &mut buf), Ok(()));
+    assert_eq!(buf, [0x01, 0x20, 0x03]);
+    // The block is read in a single repeated-start transaction.
+    assert_eq!(
+        driver.take_transfers(),
+        [I2cTransfer::WriteRead {
+            address: 0x76,
+            data: [1].into(),
+            read_len: 3
+        }]
+    );
+
+    assert_eq!(I2cMaster::read_reg(0x77, 0), Err(ErrorCode::NoAck));
+}
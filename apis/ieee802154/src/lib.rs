@@ -0,0 +1,219 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The IEEE 802.15.4 radio driver.
+///
+/// # Example
+/// ```ignore
+/// use libtock::ieee802154::Ieee802154;
+///
+/// Ieee802154::set_address(0x1540)?;
+/// Ieee802154::set_pan(0xabcd)?;
+/// Ieee802154::set_channel(26)?;
+/// Ieee802154::send(0x0802, b"hello")?;
+///
+/// let mut frame = [0; 127];
+/// let len = Ieee802154::receive(&mut frame)?;
+/// ```
+pub struct Ieee802154<S: Syscalls>(S);
+
+impl<S: Syscalls> Ieee802154<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Sets the short (16-bit) address of the radio.
+    pub fn set_address(addr: u16) -> Result<(), ErrorCode> {
+        Self::configure(SET_SHORT_ADDR, addr as u32)
+    }
+
+    /// Sets the PAN identifier of the radio.
+    pub fn set_pan(pan: u16) -> Result<(), ErrorCode> {
+        Self::configure(SET_PAN, pan as u32)
+    }
+
+    /// Sets the radio channel. IEEE 802.15.4 defines channels 11 to 26 in the
+    /// 2.4 GHz band; any other channel is rejected with `ErrorCode::Invalid`
+    /// without reaching the capsule.
+    pub fn set_channel(ch: u8) -> Result<(), ErrorCode> {
+        if !(MIN_CHANNEL..=MAX_CHANNEL).contains(&ch) {
+            return Err(ErrorCode::Invalid);
+        }
+        Self::configure(SET_CHANNEL, ch as u32)
+    }
+
+    /// Enables or disables promiscuous mode. In promiscuous mode the radio
+    /// receives every frame on its channel, whatever its destination, and
+    /// [`receive`](Self::receive) returns the raw frame starting with its MAC
+    /// header rather than just the payload. Useful for sniffing traffic.
+    ///
+    /// The upstream capsule does not support promiscuous mode and returns
+    /// `ErrorCode::NoSupport`; kernels that extend it with the
+    /// `SET_PROMISCUOUS` command are supported.
+    pub fn set_promiscuous(enabled: bool) -> Result<(), ErrorCode> {
+        Self::configure(SET_PROMISCUOUS, enabled as u32)
+    }
+
+    /// Transmits `payload` to the radio with short address `dest`, blocking
+    /// until the transmission completes. Use `BROADCAST_ADDR` to send to every
+    /// radio in the PAN.
+    ///
+    /// The capsule rejects payloads that do not fit in a single frame with
+    /// `ErrorCode::Size`.
+    pub fn send(dest: u16, payload: &[u8]) -> Result<(), ErrorCode> {
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_ro, payload)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(subscribe, &done)?;
+            S::command(DRIVER_NUM, TRANSMIT, dest as u32, payload.len() as u32)
+                .to_result::<(), ErrorCode>()?;
+            loop {
+                match done.get() {
+                    Some((0, _)) => return Ok(()),
+                    Some((status, _)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+
+    /// Shares `buf` with the capsule and registers `received` to be set to
+    /// `(length, source address)` when a frame is received into it.
+    ///
+    /// Frames keep being received into `buf` until the handle's scope ends, so
+    /// the caller should copy each frame out before yielding again. The
+    /// upcall is subscribed before the buffer is shared so a frame that
+    /// arrives in between is not missed.
+    pub fn register_receiver<'share>(
+        buf: &'share mut [u8],
+        received: &'share Cell<Option<(u32, u32)>>,
+        handle: share::Handle<
+            '_,
+            (
+                AllowRw<'share, S, DRIVER_NUM, ALLOW_RX>,
+                Subscribe<'share, S, DRIVER_NUM, SUBSCRIBE_RX>,
+            ),
+        >,
+    ) -> Result<(), ErrorCode> {
+        let (allow_rw, subscribe) = handle.split();
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(subscribe, received)?;
+        S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rw, buf)
+    }
+
+    /// Receives a single frame into `buf`, blocking until one arrives, and
+    /// returns its length. Frames longer than `buf` are truncated by the
+    /// capsule.
+    ///
+    /// `buf` is only shared for the duration of this call, so frames that
+    /// arrive between calls are dropped. Use `register_receiver` to keep
+    /// receiving while doing other work.
+    pub fn receive(buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        Ok(Self::receive_frame(buf)?.len)
+    }
+
+    /// Like [`receive`](Self::receive), but also returns the frame's source
+    /// address and link quality.
+    pub fn receive_frame(buf: &mut [u8]) -> Result<RxFrame, ErrorCode> {
+        let received: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(subscribe, &received)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rw, buf)?;
+            loop {
+                if let Some((len, src, link_quality)) = received.get() {
+                    return Ok(RxFrame {
+                        len: len as usize,
+                        src: src as u16,
+                        rssi: link_quality as u8 as i8,
+                        lqi: (link_quality >> 8) as u8,
+                    });
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+/// A frame received by [`Ieee802154::receive_frame`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RxFrame {
+    /// The number of bytes received into the buffer.
+    pub len: usize,
+    /// The short address of the sender.
+    pub src: u16,
+    /// The received signal strength, in dBm.
+    pub rssi: i8,
+    /// The link quality indicator reported by the radio, from 0 (worst) to
+    /// 255 (best).
+    pub lqi: u8,
+}
+
+/// The short address that every radio in the PAN receives frames for.
+pub const BROADCAST_ADDR: u16 = 0xffff;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> Ieee802154<S> {
+    // Configuration changes only take effect once committed.
+    fn configure(command_id: u32, value: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, command_id, value, 0).to_result::<(), ErrorCode>()?;
+        S::command(DRIVER_NUM, COMMIT_CONFIG, 0, 0).to_result()
+    }
+}
+
+const MIN_CHANNEL: u8 = 11;
+const MAX_CHANNEL: u8 = 26;
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SET_SHORT_ADDR: u32 = 3;
+const SET_PAN: u32 = 5;
+const SET_CHANNEL: u32 = 6;
+const COMMIT_CONFIG: u32 = 8;
+const TRANSMIT: u32 = 27;
+// Not part of the upstream capsule; see `set_promiscuous`.
+const SET_PROMISCUOUS: u32 = 32;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+
+// Subscribe IDs
+
+const SUBSCRIBE_RX: u32 = 0;
+const SUBSCRIBE_TX: u32 = 1;
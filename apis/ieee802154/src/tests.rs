@@ -0,0 +1,211 @@
+use core::cell::Cell;
+use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_unittest::{command_return, fake, ExpectedSyscall, SyscallLogEntry};
+
+use crate::RxFrame;
+
+type Ieee802154 = super::Ieee802154<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Ieee802154::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Ieee802154::set_address(0x1540), Err(ErrorCode::NoDevice));
+    assert_eq!(Ieee802154::send(0x0802, b"hi"), Err(ErrorCode::NoDevice));
+    assert_eq!(Ieee802154::receive(&mut [0; 4]), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn exists() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Ieee802154::exists(), Ok(()));
+}
+
+#[test]
+fn config() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Ieee802154::set_address(0x1540), Ok(()));
+    assert_eq!(Ieee802154::set_pan(0xabcd), Ok(()));
+    assert_eq!(Ieee802154::set_channel(11), Ok(()));
+    assert_eq!(driver.address(), 0x1540);
+    assert_eq!(driver.pan(), 0xabcd);
+    assert_eq!(driver.channel(), 11);
+
+    assert_eq!(Ieee802154::set_channel(26), Ok(()));
+    assert_eq!(driver.channel(), 26);
+}
+
+#[test]
+fn config_commits() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    // A configuration change that the capsule rejects is not committed.
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::SET_PAN,
+        argument0: 0xabcd,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+    assert_eq!(Ieee802154::set_pan(0xabcd), Err(ErrorCode::Busy));
+
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::SET_PAN,
+        argument0: 0xabcd,
+        argument1: 0,
+        override_return: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::COMMIT_CONFIG,
+        argument0: 0,
+        argument1: 0,
+        override_return: None,
+    });
+    assert_eq!(Ieee802154::set_pan(0xabcd), Ok(()));
+    assert_eq!(driver.pan(), 0xabcd);
+}
+
+#[test]
+fn invalid_channel() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Ieee802154::set_channel(10), Err(ErrorCode::Invalid));
+    assert_eq!(Ieee802154::set_channel(27), Err(ErrorCode::Invalid));
+    // Out-of-range channels never reach the capsule.
+    assert_eq!(kernel.take_syscall_log(), []);
+    assert_eq!(driver.channel(), 0);
+}
+
+#[test]
+fn send() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Ieee802154::send(0x0802, b"hello"), Ok(()));
+    assert_eq!(Ieee802154::send(super::BROADCAST_ADDR, b"all"), Ok(()));
+    assert_eq!(
+        driver.take_transmitted(),
+        [
+            (0x0802, b"hello".to_vec()),
+            (super::BROADCAST_ADDR, b"all".to_vec())
+        ]
+    );
+
+    assert_eq!(Ieee802154::send(0x0802, &[0; 117]), Err(ErrorCode::Size));
+    assert_eq!(driver.take_transmitted(), []);
+}
+
+#[test]
+fn receive() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 8];
+    driver.receive_frame_sync(0x0802, b"frame");
+    assert_eq!(Ieee802154::receive(&mut buf), Ok(5));
+    assert_eq!(buf[..5], *b"frame");
+
+    // Frames are truncated to the buffer.
+    let mut buf = [0; 2];
+    driver.receive_frame_sync(0x0802, b"frame");
+    assert_eq!(Ieee802154::receive(&mut buf), Ok(2));
+    assert_eq!(buf, *b"fr");
+}
+
+#[test]
+fn loopback() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+    driver.set_loopback(true);
+    assert_eq!(Ieee802154::set_address(0x1540), Ok(()));
+
+    let mut buf = [0; 16];
+    let received = Cell::new(None);
+    share::scope(|handle| {
+        assert_eq!(
+            Ieee802154::register_receiver(&mut buf, &received, handle),
+            Ok(())
+        );
+        assert_eq!(Ieee802154::send(0x0802, b"elsewhere"), Ok(()));
+        assert_eq!(received.get(), None);
+
+        assert_eq!(Ieee802154::send(0x1540, b"ping"), Ok(()));
+        assert_eq!(received.get(), Some((4, 0x1540)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+    assert_eq!(buf[..4], *b"ping");
+}
+
+#[test]
+fn promiscuous() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Ieee802154::new();
+    kernel.add_driver(&driver);
+    assert_eq!(Ieee802154::set_address(0x1540), Ok(()));
+    assert_eq!(Ieee802154::set_pan(0xabcd), Ok(()));
+    driver.set_link_quality(-72, 96);
+
+    let _ = kernel.take_syscall_log();
+    assert_eq!(Ieee802154::set_promiscuous(true), Ok(()));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::SET_PROMISCUOUS,
+                argument0: 1,
+                argument1: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::COMMIT_CONFIG,
+                argument0: 0,
+                argument1: 0,
+            },
+        ]
+    );
+    assert!(driver.is_promiscuous());
+
+    // A frame for another radio is received, with its MAC header.
+    let mut buf = [0; 16];
+    let received = Cell::new(None);
+    share::scope(|handle| {
+        assert_eq!(
+            Ieee802154::register_receiver(&mut buf, &received, handle),
+            Ok(())
+        );
+        driver.receive_frame_to(0x0802, 0x0803, b"hi");
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    assert_eq!(received.get(), Some((11, 0x0803)));
+    assert_eq!(buf[5..11], [0x02, 0x08, 0x03, 0x08, b'h', b'i']);
+
+    driver.receive_frame_sync(0x0803, b"hi");
+    assert_eq!(
+        Ieee802154::receive_frame(&mut buf),
+        Ok(RxFrame {
+            len: 11,
+            src: 0x0803,
+            rssi: -72,
+            lqi: 96,
+        })
+    );
+
+    assert_eq!(Ieee802154::set_promiscuous(false), Ok(()));
+    assert!(!driver.is_promiscuous());
+}
@@ -0,0 +1,228 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The key-value store driver, for data that persists across reboots.
+///
+/// # Example
+/// ```ignore
+/// use libtock::kv::{KeyValue, KvError};
+///
+/// let mut calibration = [0; 8];
+/// match KeyValue::get(b"calibration", &mut calibration) {
+///     Ok(len) => { /* use calibration[..len] */ }
+///     Err(KvError::NotFound) => KeyValue::set(b"calibration", &DEFAULT)?,
+///     Err(KvError::Driver(error)) => return Err(error),
+/// }
+/// ```
+pub struct KeyValue<S: Syscalls>(S);
+
+impl<S: Syscalls> KeyValue<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Reads the value stored under `key` into `value`, returning the length
+    /// of the stored value.
+    ///
+    /// If `value` is too small, it is filled with the start of the stored
+    /// value and the full length is still returned, so the caller can retry
+    /// with a larger buffer. Returns `KvError::NotFound` if there is no value
+    /// stored under `key`.
+    pub fn get(key: &[u8], value: &mut [u8]) -> Result<usize, KvError> {
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+                AllowRw<_, DRIVER_NUM, ALLOW_OUTPUT>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_output, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, key)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_OUTPUT>(allow_output, value)?;
+            match Self::run(&done, subscribe, GET, 0) {
+                Ok(len) => Ok(len as usize),
+                // The capsule copied as much of the value as fit.
+                Err((KvError::Driver(ErrorCode::Size), len)) => Ok(len as usize),
+                Err((error, _)) => Err(error),
+            }
+        })
+    }
+
+    /// Stores `value` under `key`, replacing any value already stored there.
+    pub fn set(key: &[u8], value: &[u8]) -> Result<(), ErrorCode> {
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+                AllowRo<_, DRIVER_NUM, ALLOW_INPUT>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, allow_input, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, key)?;
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_INPUT>(allow_input, value)?;
+            match Self::run(&done, subscribe, SET, 0) {
+                Ok(_) => Ok(()),
+                Err((KvError::Driver(error), _)) => Err(error),
+                // Not expected when storing a value; report the raw status.
+                Err((KvError::NotFound, _)) => Err(ErrorCode::NoSupport),
+            }
+        })
+    }
+
+    /// Deletes the value stored under `key`. Returns `KvError::NotFound` if
+    /// there is no value stored under `key`.
+    pub fn delete(key: &[u8]) -> Result<(), KvError> {
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_key, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, key)?;
+            Self::run(&done, subscribe, DELETE, 0).map_err(|(error, _)| error)?;
+            Ok(())
+        })
+    }
+
+    /// Calls `callback` with each key in the store, in the order the capsule
+    /// enumerates them.
+    ///
+    /// The upstream capsule cannot enumerate keys, in which case
+    /// `ErrorCode::NoSupport` is returned; kernels that extend it with the
+    /// `GET_KEY` command are supported. Keys longer than 64 bytes are skipped,
+    /// and once every other key has been visited `ErrorCode::Size` is returned
+    /// to report that some were. Keys added or deleted by `callback` may cause
+    /// other keys to be skipped or visited twice.
+    pub fn keys<F: FnMut(&[u8])>(mut callback: F) -> Result<(), ErrorCode> {
+        let mut skipped = false;
+        for index in 0.. {
+            let mut key = [0; MAX_KEY_LEN];
+            match Self::get_key(index, &mut key) {
+                Ok(len) if len <= key.len() => callback(&key[..len]),
+                Ok(_) => skipped = true,
+                Err(KvError::NotFound) => break,
+                Err(KvError::Driver(error)) => return Err(error),
+            }
+        }
+        match skipped {
+            true => Err(ErrorCode::Size),
+            false => Ok(()),
+        }
+    }
+}
+
+/// The error returned when looking up a key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KvError {
+    /// No value is stored under the key.
+    NotFound,
+    /// The key-value store reported an error.
+    Driver(ErrorCode),
+}
+
+impl From<ErrorCode> for KvError {
+    fn from(error: ErrorCode) -> KvError {
+        KvError::Driver(error)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> KeyValue<S> {
+    // Reads the key at `index` into `key`, returning its full length. Returns
+    // `KvError::NotFound` past the last key.
+    fn get_key(index: u32, key: &mut [u8]) -> Result<usize, KvError> {
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_OUTPUT>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_output, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_OUTPUT>(allow_output, key)?;
+            match Self::run(&done, subscribe, GET_KEY, index) {
+                Ok(len) => Ok(len as usize),
+                Err((KvError::Driver(ErrorCode::Size), len)) => Ok(len as usize),
+                Err((error, _)) => Err(error),
+            }
+        })
+    }
+
+    // Runs `command` and waits for its completion. Returns the length
+    // reported by the capsule, which is also reported on failure. The capsule
+    // reports a missing key as `ErrorCode::NoSupport` in the upcall, which is
+    // returned as `KvError::NotFound`.
+    fn run<'share>(
+        done: &'share Cell<Option<(u32, u32)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+        command: u32,
+        argument: u32,
+    ) -> Result<u32, (KvError, u32)> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, done)
+            .map_err(|error| (error.into(), 0))?;
+        S::command(DRIVER_NUM, command, argument, 0)
+            .to_result::<(), ErrorCode>()
+            .map_err(|error| (error.into(), 0))?;
+        loop {
+            match done.get() {
+                Some((0, len)) => return Ok(len),
+                Some((status, len)) => {
+                    let error = match ErrorCode::try_from(status) {
+                        Ok(ErrorCode::NoSupport) => KvError::NotFound,
+                        Ok(error) => KvError::Driver(error),
+                        Err(_) => KvError::Driver(ErrorCode::Fail),
+                    };
+                    return Err((error, len));
+                }
+                None => S::yield_wait(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50003;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET: u32 = 1;
+const SET: u32 = 2;
+const DELETE: u32 = 3;
+// Not part of the upstream capsule; see `keys`.
+const GET_KEY: u32 = 16;
+
+// Allow IDs
+
+const ALLOW_KEY: u32 = 0;
+const ALLOW_INPUT: u32 = 1;
+const ALLOW_OUTPUT: u32 = 0;
+
+// The longest key `keys` can report.
+const MAX_KEY_LEN: usize = 64;
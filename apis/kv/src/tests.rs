@@ -0,0 +1,116 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+use crate::KvError;
+
+type KeyValue = super::KeyValue<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(KeyValue::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        KeyValue::get(b"key", &mut [0; 4]),
+        Err(KvError::Driver(ErrorCode::NoDevice))
+    );
+    assert_eq!(KeyValue::set(b"key", b"value"), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::exists(), Ok(()));
+}
+
+#[test]
+fn set_then_get() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::set(b"calibration", &[1, 2, 3, 4]), Ok(()));
+    let mut value = [0; 8];
+    assert_eq!(KeyValue::get(b"calibration", &mut value), Ok(4));
+    assert_eq!(value[..4], [1, 2, 3, 4]);
+
+    // Values are replaced.
+    assert_eq!(KeyValue::set(b"calibration", &[5]), Ok(()));
+    assert_eq!(KeyValue::get(b"calibration", &mut value), Ok(1));
+    assert_eq!(value[0], 5);
+}
+
+#[test]
+fn buffer_too_small() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::set(b"key", &[1, 2, 3, 4, 5]), Ok(()));
+    let mut value = [0; 2];
+    assert_eq!(KeyValue::get(b"key", &mut value), Ok(5));
+    assert_eq!(value, [1, 2]);
+}
+
+#[test]
+fn missing_key() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        KeyValue::get(b"missing", &mut [0; 4]),
+        Err(KvError::NotFound)
+    );
+    assert_eq!(KeyValue::delete(b"missing"), Err(KvError::NotFound));
+}
+
+#[test]
+fn delete() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(KeyValue::set(b"key", b"value"), Ok(()));
+    assert_eq!(KeyValue::delete(b"key"), Ok(()));
+    assert_eq!(KeyValue::get(b"key", &mut [0; 8]), Err(KvError::NotFound));
+    assert_eq!(
+        KeyValue::delete(b""),
+        Err(KvError::Driver(ErrorCode::Invalid))
+    );
+}
+
+#[test]
+fn keys() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::KeyValue::new();
+    kernel.add_driver(&driver);
+
+    let mut visited = 0;
+    assert_eq!(KeyValue::keys(|_| visited += 1), Ok(()));
+    assert_eq!(visited, 0);
+
+    for key in [&b"brightness"[..], b"volume", b"name"] {
+        assert_eq!(KeyValue::set(key, b"1"), Ok(()));
+    }
+    let mut seen = [0; 3];
+    assert_eq!(
+        KeyValue::keys(|key| match key {
+            b"brightness" => seen[0] += 1,
+            b"volume" => seen[1] += 1,
+            b"name" => seen[2] += 1,
+            _ => panic!("Unexpected key {:?}", key),
+        }),
+        Ok(())
+    );
+    assert_eq!(seen, [1, 1, 1]);
+
+    // Keys that do not fit are skipped, and reported once the others have
+    // been visited.
+    assert_eq!(KeyValue::set(&[b'k'; 65], b"1"), Ok(()));
+    let mut visited = 0;
+    assert_eq!(KeyValue::keys(|_| visited += 1), Err(ErrorCode::Size));
+    assert_eq!(visited, 3);
+}
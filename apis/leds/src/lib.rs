@@ -1,6 +1,10 @@
 #![no_std]
 
+use core::cell::Cell;
+use core::marker::PhantomData;
+use libtock_alarm::{Alarm, Milliseconds};
 use libtock_platform::{ErrorCode, Syscalls};
+use libtock_pwm::Pwm;
 
 /// The LEDs driver
 ///
@@ -35,6 +39,312 @@ impl<S: Syscalls> Leds<S> {
     pub fn toggle(led: u32) -> Result<(), ErrorCode> {
         S::command(DRIVER_NUM, LED_TOGGLE, led, 0).to_result()
     }
+
+    /// Like [`on`](Self::on), but first checks `led` against
+    /// [`count`](Self::count), returning `ErrorCode::Invalid` without issuing
+    /// the command if it is out of range. Costs an extra system call.
+    pub fn on_checked(led: u32) -> Result<(), ErrorCode> {
+        Self::check_index(led)?;
+        Self::on(led)
+    }
+
+    /// Like [`off`](Self::off), but first checks `led` against
+    /// [`count`](Self::count). See [`on_checked`](Self::on_checked).
+    pub fn off_checked(led: u32) -> Result<(), ErrorCode> {
+        Self::check_index(led)?;
+        Self::off(led)
+    }
+
+    /// Like [`toggle`](Self::toggle), but first checks `led` against
+    /// [`count`](Self::count). See [`on_checked`](Self::on_checked).
+    pub fn toggle_checked(led: u32) -> Result<(), ErrorCode> {
+        Self::check_index(led)?;
+        Self::toggle(led)
+    }
+
+    /// Turn on every LED reported by [`count`](Self::count).
+    ///
+    /// Stops at, and returns, the first error encountered. Succeeds without
+    /// issuing any further commands if there are no LEDs.
+    pub fn on_all() -> Result<(), ErrorCode> {
+        for led in 0..Self::count()? {
+            Self::on(led)?;
+        }
+        Ok(())
+    }
+
+    /// Turn off every LED reported by [`count`](Self::count).
+    ///
+    /// Stops at, and returns, the first error encountered. Succeeds without
+    /// issuing any further commands if there are no LEDs.
+    pub fn off_all() -> Result<(), ErrorCode> {
+        for led in 0..Self::count()? {
+            Self::off(led)?;
+        }
+        Ok(())
+    }
+
+    /// Plays `animation` for `cycles` cycles, or forever if `cycles` is `None`,
+    /// in which case this only returns on error. The LEDs are turned off
+    /// first, and are left off at the end, even if the animation fails.
+    ///
+    /// This sleeps using the alarm driver, and fails if it is not present.
+    /// Animations on the LEDs driver return immediately if there are no LEDs.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use libtock::alarm::Milliseconds;
+    /// use libtock::leds::{LedAnimation, Leds};
+    ///
+    /// // Run around the LEDs three times.
+    /// Leds::animate(LedAnimation::Chase { interval: Milliseconds(100) }, Some(3))?;
+    /// ```
+    pub fn animate(animation: LedAnimation, cycles: Option<u32>) -> Result<(), ErrorCode> {
+        let count = match animation {
+            LedAnimation::Breathe { channel, .. } => {
+                PwmLed::<S>::new(channel).off()?;
+                1
+            }
+            _ => {
+                Self::off_all()?;
+                Self::count()?
+            }
+        };
+        if count == 0 {
+            return Ok(());
+        }
+        let result = Self::play(animation, count, cycles);
+        // Turn the LEDs off even if the animation failed partway through.
+        let off = match animation {
+            LedAnimation::Breathe { channel, .. } => PwmLed::<S>::new(channel).off(),
+            _ => Self::off_all(),
+        };
+        result.and(off)
+    }
+}
+
+/// An LED animation, played by [`Leds::animate`].
+#[derive(Copy, Clone)]
+pub enum LedAnimation {
+    /// Lights one LED at a time, in index order, each for `interval`. A cycle
+    /// goes through every LED once.
+    Chase { interval: Milliseconds },
+    /// Turns every LED on for `on`, then off for `off`.
+    Blink { on: Milliseconds, off: Milliseconds },
+    /// Fades the LED on PWM `channel` in and out, once per `period`. See
+    /// [`PwmLed`].
+    Breathe { channel: u32, period: Milliseconds },
+}
+
+/// LEDs driver wrapper that remembers the state each LED was last set to, so
+/// it can be read back with [`is_on`](Self::is_on).
+///
+/// The LEDs capsule does not report LED state, so the state is tracked in
+/// userspace. All LEDs are assumed to be off when the `TrackedLeds` is created.
+/// The tracked state is only correct as long as every change goes through this
+/// `TrackedLeds`: if an LED was already on at creation time, or is changed by
+/// other code (e.g. by calling [`Leds`] directly, or by the kernel), the state
+/// desynchronizes, and `toggle` keeps it desynchronized.
+///
+/// At most 32 LEDs are tracked; additional LEDs are not accessible.
+///
+/// # Example
+/// ```ignore
+/// use libtock::leds::TrackedLeds;
+///
+/// let leds = TrackedLeds::new()?;
+/// let was_on = leds.is_on(0)?;
+/// leds.toggle(0)?;
+/// // ...
+/// if was_on { leds.on(0)? } else { leds.off(0)? }
+/// ```
+pub struct TrackedLeds<S: Syscalls> {
+    count: u32,
+    state: Cell<u32>,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> TrackedLeds<S> {
+    /// Queries the number of LEDs and starts tracking them, all off.
+    pub fn new() -> Result<Self, ErrorCode> {
+        Ok(TrackedLeds {
+            count: Leds::<S>::count()?.min(u32::BITS),
+            state: Cell::new(0),
+            _syscalls: PhantomData,
+        })
+    }
+
+    /// Returns the number of tracked LEDs.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn on(&self, led: u32) -> Result<(), ErrorCode> {
+        self.check(led)?;
+        Leds::<S>::on(led)?;
+        self.state.set(self.state.get() | (1 << led));
+        Ok(())
+    }
+
+    pub fn off(&self, led: u32) -> Result<(), ErrorCode> {
+        self.check(led)?;
+        Leds::<S>::off(led)?;
+        self.state.set(self.state.get() & !(1 << led));
+        Ok(())
+    }
+
+    pub fn toggle(&self, led: u32) -> Result<(), ErrorCode> {
+        self.check(led)?;
+        Leds::<S>::toggle(led)?;
+        self.state.set(self.state.get() ^ (1 << led));
+        Ok(())
+    }
+
+    /// Returns the tracked state of an LED. See the type-level documentation
+    /// for when this may not match the physical LED.
+    pub fn is_on(&self, led: u32) -> Result<bool, ErrorCode> {
+        self.check(led)?;
+        Ok(self.state.get() & (1 << led) != 0)
+    }
+
+    fn check(&self, led: u32) -> Result<(), ErrorCode> {
+        if led < self.count {
+            Ok(())
+        } else {
+            Err(ErrorCode::Invalid)
+        }
+    }
+}
+
+/// An LED wired to a PWM channel, so it can be dimmed rather than only turned
+/// on and off. Brightness is the duty cycle of the channel, in percent.
+///
+/// The brightness is tracked in userspace, so that fades start from the current
+/// brightness. The LED is assumed to be off when the `PwmLed` is created.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::Milliseconds;
+/// use libtock::leds::PwmLed;
+///
+/// // Breathe: fade in and out, once per two seconds.
+/// let led = PwmLed::new(0);
+/// loop {
+///     led.fade_to(100, Milliseconds(1000))?;
+///     led.fade_to(0, Milliseconds(1000))?;
+/// }
+/// ```
+pub struct PwmLed<S: Syscalls> {
+    channel: u32,
+    brightness: Cell<u8>,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> PwmLed<S> {
+    /// Creates an LED driven by PWM `channel`. This does not change its output.
+    pub fn new(channel: u32) -> Self {
+        PwmLed {
+            channel,
+            brightness: Cell::new(0),
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Returns the brightness the LED was last set to.
+    pub fn brightness(&self) -> u8 {
+        self.brightness.get()
+    }
+
+    /// Sets the brightness to `percent`, which must be at most 100. A
+    /// brightness of 0 stops the channel.
+    pub fn set_brightness(&self, percent: u8) -> Result<(), ErrorCode> {
+        match percent {
+            0 => Pwm::<S>::stop(self.channel)?,
+            _ => Pwm::<S>::set_duty(self.channel, percent)?,
+        }
+        self.brightness.set(percent);
+        Ok(())
+    }
+
+    pub fn on(&self) -> Result<(), ErrorCode> {
+        self.set_brightness(100)
+    }
+
+    pub fn off(&self) -> Result<(), ErrorCode> {
+        self.set_brightness(0)
+    }
+
+    /// Ramps the brightness linearly from its current value to
+    /// `target_percent` over `duration`, changing it every
+    /// [`FADE_INTERVAL_MS`], and returns once the target is reached. A
+    /// `duration` of 0 sets the target immediately.
+    ///
+    /// This sleeps using the alarm driver between steps, and fails if it is
+    /// not present. A `target_percent` above 100 is rejected with
+    /// `ErrorCode::Invalid`.
+    pub fn fade_to(&self, target_percent: u8, duration: Milliseconds) -> Result<(), ErrorCode> {
+        if target_percent > 100 {
+            return Err(ErrorCode::Invalid);
+        }
+        let steps = (duration.0 / FADE_INTERVAL_MS).max(1);
+        let interval = Milliseconds(duration.0 / steps);
+        let start = self.brightness() as i64;
+        let change = target_percent as i64 - start;
+        for step in 1..=steps {
+            if duration.0 > 0 {
+                Alarm::<S>::sleep_for(interval)?;
+            }
+            let percent = start + change * step as i64 / steps as i64;
+            self.set_brightness(percent as u8)?;
+        }
+        Ok(())
+    }
+}
+
+/// How often [`PwmLed::fade_to`] changes the brightness.
+pub const FADE_INTERVAL_MS: u32 = 20;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> Leds<S> {
+    // Plays `animation` on `count` LEDs, stopping at the first error.
+    fn play(animation: LedAnimation, count: u32, cycles: Option<u32>) -> Result<(), ErrorCode> {
+        let mut cycle = 0;
+        while cycles.map_or(true, |cycles| cycle < cycles) {
+            match animation {
+                LedAnimation::Chase { interval } => {
+                    for led in 0..count {
+                        Self::on(led)?;
+                        Alarm::<S>::sleep_for(interval)?;
+                        Self::off(led)?;
+                    }
+                }
+                LedAnimation::Blink { on, off } => {
+                    Self::on_all()?;
+                    Alarm::<S>::sleep_for(on)?;
+                    Self::off_all()?;
+                    Alarm::<S>::sleep_for(off)?;
+                }
+                LedAnimation::Breathe { channel, period } => {
+                    let led = PwmLed::<S>::new(channel);
+                    led.fade_to(100, Milliseconds(period.0 / 2))?;
+                    led.fade_to(0, Milliseconds(period.0 - period.0 / 2))?;
+                }
+            }
+            cycle += 1;
+        }
+        Ok(())
+    }
+
+    fn check_index(led: u32) -> Result<(), ErrorCode> {
+        if led < Self::count()? {
+            Ok(())
+        } else {
+            Err(ErrorCode::Invalid)
+        }
+    }
 }
 
 #[cfg(test)]
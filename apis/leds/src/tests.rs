@@ -1,7 +1,12 @@
+use libtock_alarm::Milliseconds;
 use libtock_platform::ErrorCode;
-use libtock_unittest::fake;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+use crate::{LedAnimation, DRIVER_NUM, LED_OFF, LED_ON};
 
 type Leds = super::Leds<fake::Syscalls>;
+type TrackedLeds = super::TrackedLeds<fake::Syscalls>;
+type PwmLed = super::PwmLed<fake::Syscalls>;
 
 #[test]
 fn no_driver() {
@@ -84,3 +89,342 @@ fn no_led() {
         assert_eq!(driver.get_led(led), Some(false));
     }
 }
+
+#[test]
+fn on_all_off_all() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<4>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Leds::on_all(), Ok(()));
+    for led in 0..4 {
+        assert_eq!(driver.get_led(led), Some(true));
+    }
+    // One command to count the LEDs, then one per LED.
+    let log = kernel.take_syscall_log();
+    assert_eq!(log.len(), 5);
+    for (led, entry) in log[1..].iter().enumerate() {
+        assert_eq!(
+            *entry,
+            SyscallLogEntry::Command {
+                driver_id: DRIVER_NUM,
+                command_id: LED_ON,
+                argument0: led as u32,
+                argument1: 0,
+            }
+        );
+    }
+
+    assert_eq!(Leds::off_all(), Ok(()));
+    for led in 0..4 {
+        assert_eq!(driver.get_led(led), Some(false));
+    }
+    assert_eq!(kernel.take_syscall_log().len(), 5);
+}
+
+#[test]
+fn on_all_no_leds() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<0>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Leds::on_all(), Ok(()));
+    assert_eq!(Leds::off_all(), Ok(()));
+    assert_eq!(kernel.take_syscall_log().len(), 2);
+}
+
+#[test]
+fn on_all_no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Leds::on_all(), Err(ErrorCode::NoDevice));
+    assert_eq!(Leds::off_all(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn tracked_leds() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<10>::new();
+    kernel.add_driver(&driver);
+
+    let leds = TrackedLeds::new().unwrap();
+    assert_eq!(leds.count(), 10);
+    assert_eq!(leds.is_on(0), Ok(false));
+
+    assert_eq!(leds.on(0), Ok(()));
+    assert_eq!(leds.is_on(0), Ok(true));
+    assert_eq!(leds.is_on(1), Ok(false));
+
+    assert_eq!(leds.toggle(1), Ok(()));
+    assert_eq!(leds.is_on(1), Ok(true));
+    assert_eq!(leds.toggle(0), Ok(()));
+    assert_eq!(leds.is_on(0), Ok(false));
+    assert_eq!(leds.toggle(0), Ok(()));
+    assert_eq!(leds.is_on(0), Ok(true));
+
+    assert_eq!(leds.off(1), Ok(()));
+    assert_eq!(leds.is_on(1), Ok(false));
+
+    for led in 0..10 {
+        assert_eq!(driver.get_led(led), leds.is_on(led).ok());
+    }
+
+    assert_eq!(leds.is_on(10), Err(ErrorCode::Invalid));
+    assert_eq!(leds.on(10), Err(ErrorCode::Invalid));
+}
+
+#[test]
+fn tracked_leds_no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert!(matches!(TrackedLeds::new(), Err(ErrorCode::NoDevice)));
+}
+
+#[test]
+fn checked() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<10>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Leds::on_checked(9), Ok(()));
+    assert_eq!(driver.get_led(9), Some(true));
+    assert_eq!(Leds::toggle_checked(9), Ok(()));
+    assert_eq!(driver.get_led(9), Some(false));
+    assert_eq!(Leds::toggle_checked(9), Ok(()));
+    assert_eq!(Leds::off_checked(9), Ok(()));
+    assert_eq!(driver.get_led(9), Some(false));
+    kernel.take_syscall_log();
+
+    // Out of range indices are rejected without issuing the LED command.
+    assert_eq!(Leds::on_checked(10), Err(ErrorCode::Invalid));
+    assert_eq!(Leds::off_checked(10), Err(ErrorCode::Invalid));
+    assert_eq!(Leds::toggle_checked(10), Err(ErrorCode::Invalid));
+    assert_eq!(kernel.take_syscall_log().len(), 3);
+}
+
+#[test]
+fn checked_no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Leds::on_checked(0), Err(ErrorCode::NoDevice));
+    assert_eq!(Leds::off_checked(0), Err(ErrorCode::NoDevice));
+    assert_eq!(Leds::toggle_checked(0), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn pwm_led() {
+    let kernel = fake::Kernel::new();
+    let pwm = fake::Pwm::<2>::new();
+    kernel.add_driver(&pwm);
+    let led = PwmLed::new(1);
+
+    assert_eq!(led.set_brightness(30), Ok(()));
+    assert_eq!(
+        pwm.get_output(1).map(|output| output.duty_cycle),
+        Some(3000)
+    );
+    assert_eq!(led.on(), Ok(()));
+    assert_eq!(
+        pwm.get_output(1).map(|output| output.duty_cycle),
+        Some(10_000)
+    );
+    assert_eq!(led.set_brightness(101), Err(ErrorCode::Invalid));
+    assert_eq!(led.off(), Ok(()));
+    assert_eq!(pwm.get_output(1), None);
+}
+
+#[test]
+fn pwm_led_fade() {
+    let kernel = fake::Kernel::new();
+    let pwm = fake::Pwm::<1>::new();
+    kernel.add_driver(&pwm);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let led = PwmLed::new(0);
+    let duty_cycles_are = |expected: &[u16]| {
+        let starts = pwm.take_starts();
+        starts
+            .iter()
+            .map(|(_, output)| output.duty_cycle)
+            .eq(expected.iter().copied())
+    };
+
+    assert_eq!(led.fade_to(80, Milliseconds(100)), Ok(()));
+    assert!(duty_cycles_are(&[1600, 3200, 4800, 6400, 8000]));
+    assert!(alarm.get_now() >= 100);
+    // The next fade continues from where the last one ended.
+    assert_eq!(led.fade_to(20, Milliseconds(60)), Ok(()));
+    assert!(duty_cycles_are(&[6000, 4000, 2000]));
+    assert_eq!(led.brightness(), 20);
+}
+
+#[test]
+fn pwm_led_fade_immediate() {
+    let kernel = fake::Kernel::new();
+    let pwm = fake::Pwm::<1>::new();
+    kernel.add_driver(&pwm);
+    let led = PwmLed::new(0);
+
+    // No alarm is needed to jump straight to the target.
+    assert_eq!(led.fade_to(70, Milliseconds(0)), Ok(()));
+    assert_eq!(
+        pwm.get_output(0).map(|output| output.duty_cycle),
+        Some(7000)
+    );
+    assert_eq!(led.fade_to(101, Milliseconds(0)), Err(ErrorCode::Invalid));
+    assert_eq!(led.brightness(), 70);
+}
+
+// Returns the LED on/off commands and alarm sleeps (as driver number, command
+// ID, and first argument) in `log`, in order.
+fn led_and_sleep_commands(
+    log: impl IntoIterator<Item = SyscallLogEntry>,
+) -> impl Iterator<Item = (u32, u32, u32)> {
+    log.into_iter().filter_map(|entry| match entry {
+        SyscallLogEntry::Command {
+            driver_id,
+            command_id,
+            argument0,
+            ..
+        } if (driver_id == DRIVER_NUM && (command_id == LED_ON || command_id == LED_OFF))
+            || (driver_id == 0 && command_id == 5) =>
+        {
+            Some((driver_id, command_id, argument0))
+        }
+        _ => None,
+    })
+}
+
+#[test]
+fn animate_chase() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<3>::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let chase = LedAnimation::Chase {
+        interval: Milliseconds(100),
+    };
+    assert_eq!(Leds::animate(chase, Some(1)), Ok(()));
+    // Each LED is lit in turn for 100 ticks, after all of them are turned off,
+    // and they are all turned off again at the end.
+    assert!(led_and_sleep_commands(kernel.take_syscall_log()).eq([
+        (DRIVER_NUM, LED_OFF, 0),
+        (DRIVER_NUM, LED_OFF, 1),
+        (DRIVER_NUM, LED_OFF, 2),
+        (DRIVER_NUM, LED_ON, 0),
+        (0, 5, 100),
+        (DRIVER_NUM, LED_OFF, 0),
+        (DRIVER_NUM, LED_ON, 1),
+        (0, 5, 100),
+        (DRIVER_NUM, LED_OFF, 1),
+        (DRIVER_NUM, LED_ON, 2),
+        (0, 5, 100),
+        (DRIVER_NUM, LED_OFF, 2),
+        (DRIVER_NUM, LED_OFF, 0),
+        (DRIVER_NUM, LED_OFF, 1),
+        (DRIVER_NUM, LED_OFF, 2),
+    ]));
+    assert_eq!(alarm.get_now(), 300);
+
+    assert_eq!(Leds::animate(chase, Some(2)), Ok(()));
+    assert_eq!(alarm.get_now(), 900);
+    assert!((0..3).all(|led| driver.get_led(led) == Some(false)));
+}
+
+#[test]
+fn animate_blink() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<2>::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let blink = LedAnimation::Blink {
+        on: Milliseconds(30),
+        off: Milliseconds(70),
+    };
+    assert_eq!(Leds::animate(blink, Some(3)), Ok(()));
+    assert_eq!(alarm.get_now(), 300);
+    assert!(led_and_sleep_commands(kernel.take_syscall_log())
+        .skip(2)
+        .take(6)
+        .eq([
+            (DRIVER_NUM, LED_ON, 0),
+            (DRIVER_NUM, LED_ON, 1),
+            (0, 5, 30),
+            (DRIVER_NUM, LED_OFF, 0),
+            (DRIVER_NUM, LED_OFF, 1),
+            (0, 5, 70),
+        ]));
+}
+
+#[test]
+fn animate_breathe() {
+    let kernel = fake::Kernel::new();
+    let pwm = fake::Pwm::<1>::new();
+    kernel.add_driver(&pwm);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let breathe = LedAnimation::Breathe {
+        channel: 0,
+        period: Milliseconds(80),
+    };
+    assert_eq!(Leds::animate(breathe, Some(1)), Ok(()));
+    assert!(pwm
+        .take_starts()
+        .iter()
+        .map(|(_, output)| output.duty_cycle)
+        .eq([5000, 10000, 5000]));
+    assert_eq!(pwm.get_output(0), None);
+    assert!(alarm.get_now() >= 80);
+}
+
+#[test]
+fn animate_no_leds() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<0>::new();
+    kernel.add_driver(&driver);
+
+    // Returns without needing an alarm.
+    let chase = LedAnimation::Chase {
+        interval: Milliseconds(100),
+    };
+    assert_eq!(Leds::animate(chase, None), Ok(()));
+}
+
+#[test]
+fn animate_no_alarm() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Leds::<2>::new();
+    kernel.add_driver(&driver);
+
+    // The first sleep fails with the LEDs on, but they are still turned off.
+    let blink = LedAnimation::Blink {
+        on: Milliseconds(30),
+        off: Milliseconds(70),
+    };
+    assert_eq!(Leds::animate(blink, None), Err(ErrorCode::NoDevice));
+    assert_eq!(driver.get_led(0), Some(false));
+    assert_eq!(driver.get_led(1), Some(false));
+
+    let chase = LedAnimation::Chase {
+        interval: Milliseconds(100),
+    };
+    assert_eq!(Leds::animate(chase, Some(1)), Err(ErrorCode::NoDevice));
+    assert_eq!(driver.get_led(0), Some(false));
+    assert_eq!(driver.get_led(1), Some(false));
+}
+
+#[test]
+fn animate_breathe_no_alarm() {
+    let kernel = fake::Kernel::new();
+    let pwm = fake::Pwm::<1>::new();
+    kernel.add_driver(&pwm);
+
+    let breathe = LedAnimation::Breathe {
+        channel: 0,
+        period: Milliseconds(80),
+    };
+    assert_eq!(Leds::animate(breathe, None), Err(ErrorCode::NoDevice));
+    assert_eq!(pwm.get_output(0), None);
+}
@@ -29,10 +29,21 @@ impl<S: Syscalls> LowLevelDebug<S> {
         S::command(DRIVER_NUM, DRIVER_CHECK, 0, 0).is_success()
     }
 
-    /// Print one of the predefined alerts in [`AlertCode`].
+    /// Print an alert code: either one of the predefined alerts in
+    /// [`AlertCode`], or a raw code defined by the app.
     #[inline(always)]
-    pub fn print_alert_code(code: AlertCode) {
-        let _ = S::command(DRIVER_NUM, PRINT_ALERT_CODE, code as u32, 0);
+    pub fn print_alert_code<C: Into<u32>>(code: C) {
+        let _ = S::command(DRIVER_NUM, PRINT_ALERT_CODE, code.into(), 0);
+    }
+
+    /// Print a status code, as a debug marker that does not need a console.
+    /// The code will be printed in hexadecimal.
+    ///
+    /// This uses the same command as `print_1`; the capsule does not
+    /// distinguish status codes from other numbers.
+    #[inline(always)]
+    pub fn print_status(code: u32) {
+        Self::print_1(code)
     }
 
     /// Print a single number. The number will be printed in hexadecimal.
@@ -66,6 +77,25 @@ pub enum AlertCode {
     WrongLocation = 0x02,
 }
 
+impl From<AlertCode> for u32 {
+    fn from(code: AlertCode) -> u32 {
+        code as u32
+    }
+}
+
+/// Checks `cond` in debug builds, and prints `code` as a status code through
+/// `$lld`, a [`LowLevelDebug`] type, if it is false. Unlike `debug_assert!`,
+/// a failed check does not panic, so the app keeps running. In release
+/// builds, `cond` is not evaluated and nothing is printed.
+#[macro_export]
+macro_rules! debug_check {
+    ($lld:ty, $cond:expr, $code:expr $(,)?) => {
+        if cfg!(debug_assertions) && !$cond {
+            <$lld>::print_status($code);
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests;
 
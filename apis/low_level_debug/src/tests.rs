@@ -1,6 +1,6 @@
 use super::*;
 use libtock_platform::ErrorCode;
-use libtock_unittest::{command_return, fake, ExpectedSyscall};
+use libtock_unittest::{command_return, fake, ExpectedSyscall, SyscallLogEntry};
 
 type LowLevelDebug = super::LowLevelDebug<fake::Syscalls>;
 
@@ -37,6 +37,55 @@ fn print_alert_code() {
     );
 }
 
+#[test]
+fn print_raw_alert_code() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::LowLevelDebug::new();
+    kernel.add_driver(&driver);
+
+    LowLevelDebug::print_alert_code(0x80u32);
+    assert_eq!(driver.take_messages(), [fake::Message::AlertCode(0x80)]);
+}
+
+#[test]
+fn print_status() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::LowLevelDebug::new();
+    kernel.add_driver(&driver);
+
+    LowLevelDebug::print_status(1);
+    assert_eq!(driver.take_messages(), [fake::Message::Print1(1)]);
+}
+
+#[test]
+fn command_numbers() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::LowLevelDebug::new();
+    kernel.add_driver(&driver);
+
+    LowLevelDebug::print_alert_code(AlertCode::Panic);
+    LowLevelDebug::print_alert_code(0x80u32);
+    LowLevelDebug::print_status(7);
+    LowLevelDebug::print_1(42);
+    LowLevelDebug::print_2(42, 27);
+    let command = |command_id, argument0, argument1| SyscallLogEntry::Command {
+        driver_id: DRIVER_NUM,
+        command_id,
+        argument0,
+        argument1,
+    };
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            command(1, 0x01, 0),
+            command(1, 0x80, 0),
+            command(2, 7, 0),
+            command(2, 42, 0),
+            command(3, 42, 27),
+        ]
+    );
+}
+
 #[test]
 fn print_1() {
     let kernel = fake::Kernel::new();
@@ -80,3 +129,17 @@ fn failed_print() {
     // The fake driver still receives the command even if a fake error is injected.
     assert_eq!(driver.take_messages(), [fake::Message::Print1(72)]);
 }
+
+#[test]
+fn debug_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::LowLevelDebug::new();
+    kernel.add_driver(&driver);
+
+    crate::debug_check!(LowLevelDebug, 1 + 1 == 2, 0x10);
+    assert_eq!(driver.take_messages(), []);
+    // Tests are built with debug assertions, so the failed check prints its
+    // code and execution continues.
+    crate::debug_check!(LowLevelDebug, 1 + 1 == 3, 0x11);
+    assert_eq!(driver.take_messages(), [fake::Message::Print1(0x11)]);
+}
@@ -0,0 +1,237 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// An IPv6 address, in network byte order.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Ipv6Addr(pub [u8; 16]);
+
+impl Ipv6Addr {
+    /// The unspecified address `::`.
+    pub const UNSPECIFIED: Ipv6Addr = Ipv6Addr([0; 16]);
+
+    /// The loopback address `::1`.
+    pub const LOOPBACK: Ipv6Addr = Ipv6Addr([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    /// Returns true if this is a multicast address, in `ff00::/8`.
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] == 0xff
+    }
+}
+
+/// A UDP socket, using the kernel's UDP capsule.
+///
+/// The capsule binds one port per process, so only one `UdpSocket` should
+/// exist at a time. The binding is not released when the socket is dropped.
+///
+/// # Example
+/// ```ignore
+/// use libtock::net::{Ipv6Addr, UdpSocket};
+///
+/// let socket = UdpSocket::bind(2000)?;
+/// socket.send_to(COLLECTOR, 2001, &reading)?;
+///
+/// let mut buf = [0; 64];
+/// let (len, addr, port) = socket.recv_from(&mut buf)?;
+/// ```
+pub struct UdpSocket<S: Syscalls> {
+    port: u16,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> UdpSocket<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Binds a socket to `port` on every interface. Port 0 is rejected with
+    /// `ErrorCode::Invalid`.
+    pub fn bind(port: u16) -> Result<Self, ErrorCode> {
+        if port == 0 {
+            return Err(ErrorCode::Invalid);
+        }
+        let mut cfg = [0; CFG_LEN];
+        encode_sock_addr(&mut cfg[..SOCK_ADDR_LEN], Ipv6Addr::UNSPECIFIED, port);
+        encode_sock_addr(&mut cfg[SOCK_ADDR_LEN..], Ipv6Addr::UNSPECIFIED, 0);
+        share::scope::<AllowRw<_, DRIVER_NUM, ALLOW_CFG>, _, _>(|allow_rw| {
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CFG>(allow_rw, &mut cfg)?;
+            S::command(DRIVER_NUM, BIND, 0, 0).to_result::<(), ErrorCode>()
+        })?;
+        Ok(UdpSocket {
+            port,
+            _syscalls: PhantomData,
+        })
+    }
+
+    /// Returns the port this socket is bound to.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the largest payload the capsule can send in one datagram.
+    pub fn max_payload_len() -> Result<usize, ErrorCode> {
+        let len: u32 = S::command(DRIVER_NUM, GET_MAX_TX_LEN, 0, 0).to_result()?;
+        Ok(len as usize)
+    }
+
+    /// Sends `data` to `port` on `addr`, blocking until it has been sent.
+    ///
+    /// Returns `ErrorCode::Size` without sending anything if `data` is longer
+    /// than [`max_payload_len`](Self::max_payload_len).
+    pub fn send_to(&self, addr: Ipv6Addr, port: u16, data: &[u8]) -> Result<(), ErrorCode> {
+        if data.len() > Self::max_payload_len()? {
+            return Err(ErrorCode::Size);
+        }
+        let mut cfg = [0; CFG_LEN];
+        encode_sock_addr(&mut cfg[..SOCK_ADDR_LEN], Ipv6Addr::UNSPECIFIED, self.port);
+        encode_sock_addr(&mut cfg[SOCK_ADDR_LEN..], addr, port);
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_CFG>,
+                AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_cfg, allow_tx, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CFG>(allow_cfg, &mut cfg)?;
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, data)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(subscribe, &done)?;
+            S::command(DRIVER_NUM, SEND, 0, 0).to_result::<(), ErrorCode>()?;
+            loop {
+                match done.get() {
+                    Some((0,)) => return Ok(()),
+                    Some((status,)) => {
+                        return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                    }
+                    None => S::yield_wait(),
+                }
+            }
+        })
+    }
+
+    /// Joins the multicast group `group`, so that datagrams sent to the group
+    /// on this socket's port are received by [`recv_from`](Self::recv_from).
+    /// Returns `ErrorCode::Invalid` if `group` is not a multicast address.
+    ///
+    /// The upstream capsule has no multicast support and returns
+    /// `ErrorCode::NoSupport`; kernels that extend it with the
+    /// `JOIN_MULTICAST` and `LEAVE_MULTICAST` commands are supported.
+    pub fn join_multicast(&self, group: Ipv6Addr) -> Result<(), ErrorCode> {
+        self.multicast(JOIN_MULTICAST, group)
+    }
+
+    /// Leaves the multicast group `group`, joined by
+    /// [`join_multicast`](Self::join_multicast).
+    pub fn leave_multicast(&self, group: Ipv6Addr) -> Result<(), ErrorCode> {
+        self.multicast(LEAVE_MULTICAST, group)
+    }
+
+    /// Receives a single datagram sent to this socket's port into `buf`,
+    /// blocking until one arrives. Returns the length of the datagram and the
+    /// address and port it was sent from. Datagrams longer than `buf` are
+    /// truncated by the capsule.
+    pub fn recv_from(&self, buf: &mut [u8]) -> Result<(usize, Ipv6Addr, u16), ErrorCode> {
+        let mut rx_cfg = [0; CFG_LEN];
+        let received: Cell<Option<(u32,)>> = Cell::new(None);
+        let len = share::scope::<
+            (
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+                AllowRw<_, DRIVER_NUM, ALLOW_RX_CFG>,
+                AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (subscribe, allow_rx_cfg, allow_rx) = handle.split();
+            // Subscribe first: the capsule may deliver a datagram as soon as
+            // the receive buffer is shared.
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(subscribe, &received)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX_CFG>(allow_rx_cfg, &mut rx_cfg)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, buf)?;
+            loop {
+                if let Some((len,)) = received.get() {
+                    return Ok::<_, ErrorCode>(len as usize);
+                }
+                S::yield_wait();
+            }
+        })?;
+        let (addr, port) = decode_sock_addr(&rx_cfg[..SOCK_ADDR_LEN]);
+        Ok((len, addr, port))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> UdpSocket<S> {
+    // Shares `group` as the local address of the configuration and runs the
+    // multicast membership `command`.
+    fn multicast(&self, command: u32, group: Ipv6Addr) -> Result<(), ErrorCode> {
+        if !group.is_multicast() {
+            return Err(ErrorCode::Invalid);
+        }
+        let mut cfg = [0; CFG_LEN];
+        encode_sock_addr(&mut cfg[..SOCK_ADDR_LEN], group, self.port);
+        share::scope::<AllowRw<_, DRIVER_NUM, ALLOW_CFG>, _, _>(|allow_rw| {
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CFG>(allow_rw, &mut cfg)?;
+            S::command(DRIVER_NUM, command, 0, 0).to_result()
+        })
+    }
+}
+
+// The capsule's configuration buffers hold two socket addresses: the local
+// address first, then the remote one. Each is a 16-byte IPv6 address followed
+// by a port in the kernel's byte order.
+const SOCK_ADDR_LEN: usize = 18;
+const CFG_LEN: usize = 2 * SOCK_ADDR_LEN;
+
+fn encode_sock_addr(buf: &mut [u8], addr: Ipv6Addr, port: u16) {
+    buf[..16].copy_from_slice(&addr.0);
+    buf[16..SOCK_ADDR_LEN].copy_from_slice(&port.to_ne_bytes());
+}
+
+fn decode_sock_addr(buf: &[u8]) -> (Ipv6Addr, u16) {
+    let mut addr = [0; 16];
+    addr.copy_from_slice(&buf[..16]);
+    (Ipv6Addr(addr), u16::from_ne_bytes([buf[16], buf[17]]))
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30002;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SEND: u32 = 2;
+const BIND: u32 = 3;
+const GET_MAX_TX_LEN: u32 = 4;
+// Not part of the upstream capsule; see `join_multicast`.
+const JOIN_MULTICAST: u32 = 16;
+const LEAVE_MULTICAST: u32 = 17;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+const ALLOW_CFG: u32 = 1;
+const ALLOW_RX_CFG: u32 = 2;
+
+// Subscribe IDs
+
+const SUBSCRIBE_RX: u32 = 0;
+const SUBSCRIBE_TX: u32 = 1;
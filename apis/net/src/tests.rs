@@ -0,0 +1,152 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+use super::Ipv6Addr;
+
+type UdpSocket = super::UdpSocket<fake::Syscalls>;
+
+const COLLECTOR: Ipv6Addr = Ipv6Addr([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x15, 0x40]);
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(UdpSocket::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(UdpSocket::bind(2000).err(), Some(ErrorCode::NoDevice));
+}
+
+#[test]
+fn exists() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(UdpSocket::exists(), Ok(()));
+}
+
+#[test]
+fn bind() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(UdpSocket::bind(0).err(), Some(ErrorCode::Invalid));
+    assert_eq!(driver.bound_port(), None);
+
+    let socket = UdpSocket::bind(2000).unwrap();
+    assert_eq!(socket.port(), 2000);
+    assert_eq!(driver.bound_port(), Some(2000));
+}
+
+#[test]
+fn send_to() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+
+    let socket = UdpSocket::bind(2000).unwrap();
+    assert_eq!(socket.send_to(COLLECTOR, 2001, b"21.5C"), Ok(()));
+    assert_eq!(
+        driver.take_sent(),
+        [fake::UdpDatagram {
+            src_port: 2000,
+            dst_addr: COLLECTOR.0,
+            dst_port: 2001,
+            payload: b"21.5C".to_vec(),
+        }]
+    );
+}
+
+#[test]
+fn send_too_long() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+
+    let socket = UdpSocket::bind(2000).unwrap();
+    let max = UdpSocket::max_payload_len().unwrap();
+    assert_eq!(socket.send_to(COLLECTOR, 2001, &[0; 201][..max]), Ok(()));
+    assert_eq!(
+        socket.send_to(COLLECTOR, 2001, &[0; 201][..max + 1]),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(driver.take_sent().len(), 1);
+}
+
+#[test]
+fn recv_from() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+
+    let socket = UdpSocket::bind(2000).unwrap();
+    driver.receive_datagram(COLLECTOR.0, 2001, 2000, b"ack");
+    let mut buf = [0; 8];
+    assert_eq!(socket.recv_from(&mut buf), Ok((3, COLLECTOR, 2001)));
+    assert_eq!(buf[..3], *b"ack");
+}
+
+#[test]
+fn loopback() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+
+    let socket = UdpSocket::bind(2000).unwrap();
+    assert_eq!(socket.send_to(Ipv6Addr::LOOPBACK, 2000, b"ping"), Ok(()));
+    let mut buf = [0; 2];
+    assert_eq!(
+        socket.recv_from(&mut buf),
+        Ok((2, Ipv6Addr::LOOPBACK, 2000))
+    );
+    assert_eq!(buf, *b"pi");
+}
+
+const ALL_NODES: Ipv6Addr = Ipv6Addr([0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+#[test]
+fn multicast_address() {
+    assert!(ALL_NODES.is_multicast());
+    assert!(!COLLECTOR.is_multicast());
+    assert!(!Ipv6Addr::UNSPECIFIED.is_multicast());
+
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+    let socket = UdpSocket::bind(2000).unwrap();
+    let _ = kernel.take_syscall_log();
+    assert_eq!(socket.join_multicast(COLLECTOR), Err(ErrorCode::Invalid));
+    assert_eq!(socket.leave_multicast(COLLECTOR), Err(ErrorCode::Invalid));
+    // Invalid groups never reach the capsule.
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn join_multicast() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Udp::new();
+    kernel.add_driver(&driver);
+    let socket = UdpSocket::bind(2000).unwrap();
+    let _ = kernel.take_syscall_log();
+
+    assert_eq!(socket.join_multicast(ALL_NODES), Ok(()));
+    assert_eq!(driver.multicast_groups(), [ALL_NODES.0]);
+    let log = kernel.take_syscall_log();
+    assert!(log.contains(&SyscallLogEntry::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::JOIN_MULTICAST,
+        argument0: 0,
+        argument1: 0,
+    }));
+    assert_eq!(socket.join_multicast(ALL_NODES), Err(ErrorCode::Already));
+
+    // Datagrams are only received from joined groups.
+    let other = Ipv6Addr([0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xfb]);
+    driver.receive_multicast(other.0, COLLECTOR.0, 2001, 2000, b"other");
+    driver.receive_multicast(ALL_NODES.0, COLLECTOR.0, 2001, 2000, b"hello");
+    let mut buf = [0; 8];
+    assert_eq!(socket.recv_from(&mut buf), Ok((5, COLLECTOR, 2001)));
+    assert_eq!(buf[..5], *b"hello");
+
+    assert_eq!(socket.leave_multicast(ALL_NODES), Ok(()));
+    assert!(driver.multicast_groups().is_empty());
+}
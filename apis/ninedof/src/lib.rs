@@ -0,0 +1,111 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The nine degrees of freedom (accelerometer, magnetometer and gyroscope)
+/// driver.
+///
+/// All three sensors share a single upcall, so only one reading can be in
+/// progress at a time. Starting a reading while another one is pending fails
+/// with `ErrorCode::Busy`.
+///
+/// # Example
+/// ```ignore
+/// use libtock::ninedof::NineDof;
+///
+/// let (x, y, z) = NineDof::read_accelerometer_sync()?;
+/// ```
+pub struct NineDof<S: Syscalls>(S);
+
+impl<S: Syscalls> NineDof<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Register an events listener. The listener receives the (x, y, z)
+    /// values of the last reading.
+    pub fn register_listener<'share>(
+        listener: &'share Cell<Option<(u32, u32, u32)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, listener)
+    }
+
+    /// Unregister the events listener
+    pub fn unregister_listener() {
+        S::unsubscribe(DRIVER_NUM, 0)
+    }
+
+    /// Initiate an accelerometer measurement.
+    pub fn read_accelerometer() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, READ_ACCELEROMETER, 0, 0).to_result()
+    }
+
+    /// Initiate a magnetometer measurement.
+    pub fn read_magnetometer() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, READ_MAGNETOMETER, 0, 0).to_result()
+    }
+
+    /// Initiate a gyroscope measurement.
+    pub fn read_gyroscope() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, READ_GYROSCOPE, 0, 0).to_result()
+    }
+
+    /// Initiate a synchronous accelerometer measurement.
+    /// Returns Ok((x, y, z)) if the operation was successful
+    pub fn read_accelerometer_sync() -> Result<(i32, i32, i32), ErrorCode> {
+        Self::read_sync(READ_ACCELEROMETER)
+    }
+
+    /// Initiate a synchronous magnetometer measurement.
+    /// Returns Ok((x, y, z)) if the operation was successful
+    pub fn read_magnetometer_sync() -> Result<(i32, i32, i32), ErrorCode> {
+        Self::read_sync(READ_MAGNETOMETER)
+    }
+
+    /// Initiate a synchronous gyroscope measurement.
+    /// Returns Ok((x, y, z)) if the operation was successful
+    pub fn read_gyroscope_sync() -> Result<(i32, i32, i32), ErrorCode> {
+        Self::read_sync(READ_GYROSCOPE)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> NineDof<S> {
+    fn read_sync(command_id: u32) -> Result<(i32, i32, i32), ErrorCode> {
+        let listener: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            Self::register_listener(&listener, subscribe)?;
+            S::command(DRIVER_NUM, command_id, 0, 0).to_result()?;
+            loop {
+                if let Some((x, y, z)) = listener.get() {
+                    return Ok((x as i32, y as i32, z as i32));
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60004;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ_ACCELEROMETER: u32 = 1;
+const READ_MAGNETOMETER: u32 = 100;
+const READ_GYROSCOPE: u32 = 200;
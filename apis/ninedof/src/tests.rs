@@ -0,0 +1,123 @@
+use core::cell::Cell;
+use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type NineDof = super::NineDof<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(NineDof::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(NineDof::read_accelerometer_sync(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(NineDof::exists(), Ok(()));
+}
+
+#[test]
+fn overlapping_reads() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(NineDof::read_accelerometer(), Ok(()));
+    assert!(driver.is_busy());
+
+    assert_eq!(NineDof::read_accelerometer(), Err(ErrorCode::Busy));
+    assert_eq!(NineDof::read_magnetometer(), Err(ErrorCode::Busy));
+    assert_eq!(NineDof::read_gyroscope(), Err(ErrorCode::Busy));
+    assert_eq!(NineDof::read_magnetometer_sync(), Err(ErrorCode::Busy));
+    assert_eq!(driver.reading(), Some(fake::NineDofSensor::Accelerometer));
+}
+
+#[test]
+fn register_unregister_listener() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    let listener: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(NineDof::read_gyroscope(), Ok(()));
+        driver.set_value((1, 2, 3));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert_eq!(NineDof::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(NineDof::read_gyroscope(), Ok(()));
+        driver.set_value((1, 2, 3));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((1, 2, 3)));
+
+        NineDof::unregister_listener();
+        assert_eq!(NineDof::read_gyroscope(), Ok(()));
+        driver.set_value((1, 2, 3));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+}
+
+#[test]
+fn read_accelerometer_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync((1, -2, 981));
+    assert_eq!(NineDof::read_accelerometer_sync(), Ok((1, -2, 981)));
+}
+
+#[test]
+fn read_magnetometer_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync((-30, 15, 42));
+    assert_eq!(NineDof::read_magnetometer_sync(), Ok((-30, 15, 42)));
+}
+
+#[test]
+fn read_gyroscope_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync((7, 8, -9));
+    assert_eq!(NineDof::read_gyroscope_sync(), Ok((7, 8, -9)));
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::NineDof::new();
+    kernel.add_driver(&driver);
+
+    // A gyroscope reading keeps the sensor busy for the accelerometer too.
+    assert_eq!(NineDof::read_gyroscope(), Ok(()));
+    kernel.take_syscall_log();
+    assert_eq!(NineDof::read_accelerometer_sync(), Err(ErrorCode::Busy));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::READ_ACCELEROMETER,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
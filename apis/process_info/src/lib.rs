@@ -0,0 +1,49 @@
+#![no_std]
+
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// Information about the calling process, for telemetry: which app it is, and
+/// how much of its RAM is in use. Built on the Memop system call.
+///
+/// The process' RAM region holds, from the bottom, the stack, the static
+/// data, and the heap, which ends at the memory break. The kernel's grant
+/// region sits at the top of the RAM region, and the break cannot grow into
+/// it.
+///
+/// # Example
+/// ```ignore
+/// use libtock::process_info::ProcessInfo;
+///
+/// let used = ProcessInfo::memory_used()?;
+/// let total = ProcessInfo::memory_total()?;
+/// ```
+pub struct ProcessInfo<S: Syscalls>(S);
+
+impl<S: Syscalls> ProcessInfo<S> {
+    /// Returns an ID for this app: the address its flash region starts at.
+    /// It is unique among the apps installed on a board, and stays the same
+    /// across restarts, but changes if the app is installed elsewhere.
+    pub fn app_id() -> Result<u32, ErrorCode> {
+        Ok(S::flash_start()? as usize as u32)
+    }
+
+    /// Returns the number of bytes of RAM in use: the stack, the static data,
+    /// and the heap, up to the memory break.
+    pub fn memory_used() -> Result<usize, ErrorCode> {
+        Ok(S::app_heap_break()? as usize - S::memory_start()? as usize)
+    }
+
+    /// Returns the number of bytes of RAM the process can use, which is its
+    /// RAM region excluding the grant region.
+    pub fn memory_total() -> Result<usize, ErrorCode> {
+        Ok(S::grant_start()? as usize - S::memory_start()? as usize)
+    }
+
+    /// Returns the number of bytes the heap can still grow by.
+    pub fn memory_available() -> Result<usize, ErrorCode> {
+        Ok(S::grant_start()? as usize - S::app_heap_break()? as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,24 @@
+use libtock_platform::Syscalls;
+use libtock_unittest::fake;
+
+type ProcessInfo = super::ProcessInfo<fake::Syscalls>;
+
+#[test]
+fn app_id() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(ProcessInfo::app_id(), Ok(fake::FLASH_START as u32));
+}
+
+#[test]
+fn memory() {
+    let kernel = fake::Kernel::new();
+    let region = fake::MEMORY_END - fake::MEMORY_START;
+    assert_eq!(ProcessInfo::memory_used(), Ok(0));
+    assert_eq!(ProcessInfo::memory_total(), Ok(region));
+
+    kernel.set_grant_size(0x800);
+    unsafe { fake::Syscalls::memop_sbrk(0x300) }.unwrap();
+    assert_eq!(ProcessInfo::memory_used(), Ok(0x300));
+    assert_eq!(ProcessInfo::memory_total(), Ok(region - 0x800));
+    assert_eq!(ProcessInfo::memory_available(), Ok(region - 0x800 - 0x300));
+}
@@ -35,20 +35,19 @@ impl<S: Syscalls> Proximity<S> {
     /// Returns Ok(proximity_value) if the operation was successful
     /// proximity_value is in [0, 255] range,
     /// where '255' indicates the closest measurable distance and '0' that no object is detected
+    /// If the measurement cannot be started, its error is returned without
+    /// waiting.
     pub fn read_sync() -> Result<u8, ErrorCode> {
         let listener: Cell<Option<(u32,)>> = Cell::new(None);
         share::scope(|subscribe| {
-            if let Ok(()) = Self::register_listener(&listener, subscribe) {
-                if let Ok(()) = Self::read() {
-                    S::yield_wait();
-                }
-            }
-        });
-
-        match listener.get() {
-            None => Err(ErrorCode::Busy),
-            Some(proximity) => Ok(proximity.0 as u8),
-        }
+            // The listener is registered before the reading starts, because
+            // the upcall it is waiting for may be scheduled before the command
+            // returns. A failed start is undone by unsubscribing as the scope
+            // ends.
+            Self::register_listener(&listener, subscribe)?;
+            Self::read()?;
+            Self::wait(&listener)
+        })
     }
 
     /// Initiate an on_interrupt proximity measurement
@@ -75,18 +74,20 @@ impl<S: Syscalls> Proximity<S> {
         }
         let listener: Cell<Option<(u32,)>> = Cell::new(None);
         share::scope(|subscribe| {
-            if let Ok(()) = Self::register_listener(&listener, subscribe) {
-                if let Ok(()) = Self::read_on_interrupt(lower, upper) {
-                    while listener.get() == None {
-                        S::yield_wait();
-                    }
-                }
-            }
-        });
+            // Registered first, as in read_sync.
+            Self::register_listener(&listener, subscribe)?;
+            Self::read_on_interrupt(lower, upper)?;
+            Self::wait(&listener)
+        })
+    }
 
-        match listener.get() {
-            None => Err(ErrorCode::Busy),
-            Some(proximity) => Ok(proximity.0 as u8),
+    // Waits for the reading started by `read` or `read_on_interrupt`.
+    fn wait(listener: &Cell<Option<(u32,)>>) -> Result<u8, ErrorCode> {
+        loop {
+            if let Some((proximity,)) = listener.get() {
+                return Ok(proximity as u8);
+            }
+            S::yield_wait();
         }
     }
 }
@@ -1,6 +1,6 @@
 use core::cell::Cell;
 use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
-use libtock_unittest::fake;
+use libtock_unittest::{fake, SyscallLogEntry};
 
 type Proximity = super::Proximity<fake::Syscalls>;
 
@@ -84,3 +84,82 @@ fn bad_arguments() {
         Err(ErrorCode::Invalid)
     );
 }
+
+#[test]
+fn bad_arguments_no_syscalls() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        Proximity::wait_for_value_between(1, 0),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn wait_for_value_between() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync(20);
+    assert_eq!(Proximity::wait_for_value_between(50, 50), Ok(20));
+
+    let log = kernel.take_syscall_log();
+    assert!(log.contains(&SyscallLogEntry::Command {
+        driver_id: 0x60005,
+        command_id: 2,
+        argument0: 50,
+        argument1: 50,
+    }));
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Proximity::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Proximity::read(), Ok(()));
+    kernel.take_syscall_log();
+    assert_eq!(Proximity::read_sync(), Err(ErrorCode::Busy));
+    assert_eq!(
+        Proximity::wait_for_value_between(10, 20),
+        Err(ErrorCode::Busy)
+    );
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::READ,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::READ_ON_INT,
+                argument0: 10,
+                argument1: 20,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
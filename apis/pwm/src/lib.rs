@@ -0,0 +1,76 @@
+#![no_std]
+
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The PWM driver. Channels are the PWM pins the board exposes to processes,
+/// numbered from 0.
+///
+/// # Example
+/// ```ignore
+/// use libtock::pwm::Pwm;
+///
+/// // Drive channel 0 at a quarter duty cycle.
+/// Pwm::set_duty(0, 25)?;
+/// // ...
+/// Pwm::stop(0)?;
+/// ```
+pub struct Pwm<S: Syscalls>(S);
+
+impl<S: Syscalls> Pwm<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Starts `channel` with a duty cycle of `percent`, at
+    /// [`DEFAULT_FREQUENCY_HZ`]. Returns `ErrorCode::Invalid` if `percent` is
+    /// above 100.
+    pub fn set_duty(channel: u32, percent: u8) -> Result<(), ErrorCode> {
+        Self::start(channel, DEFAULT_FREQUENCY_HZ, percent)
+    }
+
+    /// Starts `channel` at `frequency_hz`, with a duty cycle of `percent`.
+    /// Returns `ErrorCode::Invalid` if `percent` is above 100, or `channel`
+    /// does not fit in 16 bits. The capsule rejects frequencies its hardware
+    /// cannot produce.
+    pub fn start(channel: u32, frequency_hz: u32, percent: u8) -> Result<(), ErrorCode> {
+        if percent > 100 || channel > u16::MAX as u32 {
+            return Err(ErrorCode::Invalid);
+        }
+        // The capsule takes the duty cycle in hundredths of a percent, packed
+        // with the channel into the first argument.
+        let duty_cycle = percent as u32 * 100;
+        S::command(DRIVER_NUM, START, duty_cycle << 16 | channel, frequency_hz).to_result()
+    }
+
+    /// Stops the output of `channel`.
+    pub fn stop(channel: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, STOP, channel, 0).to_result()
+    }
+
+    /// Returns the highest frequency `channel` can be started at.
+    pub fn get_maximum_frequency(channel: u32) -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, GET_MAX_FREQUENCY, channel, 0).to_result()
+    }
+}
+
+/// The frequency used by [`Pwm::set_duty`]. This is fast enough that an LED
+/// driven at it does not visibly flicker.
+pub const DEFAULT_FREQUENCY_HZ: u32 = 1000;
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x10000;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const START: u32 = 1;
+const STOP: u32 = 2;
+const GET_MAX_FREQUENCY: u32 = 3;
@@ -0,0 +1,70 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type Pwm = super::Pwm<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Pwm::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Pwm::set_duty(0, 50), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Pwm::exists(), Ok(()));
+    assert_eq!(Pwm::get_maximum_frequency(0), Ok(1_000_000));
+}
+
+#[test]
+fn duty_cycle_encoding() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Pwm::start(1, 500, 42), Ok(()));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::Command {
+            driver_id: super::DRIVER_NUM,
+            command_id: super::START,
+            argument0: 4200 << 16 | 1,
+            argument1: 500,
+        }]
+    );
+    assert_eq!(
+        driver.get_output(1),
+        Some(fake::PwmOutput {
+            frequency_hz: 500,
+            duty_cycle: 4200
+        })
+    );
+
+    assert_eq!(Pwm::set_duty(0, 100), Ok(()));
+    assert_eq!(
+        driver.get_output(0),
+        Some(fake::PwmOutput {
+            frequency_hz: super::DEFAULT_FREQUENCY_HZ,
+            duty_cycle: 10_000
+        })
+    );
+    assert_eq!(Pwm::stop(0), Ok(()));
+    assert_eq!(driver.get_output(0), None);
+}
+
+#[test]
+fn percent_bound() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Pwm::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Pwm::set_duty(0, 101), Err(ErrorCode::Invalid));
+    assert_eq!(Pwm::start(0x1_0000, 500, 50), Err(ErrorCode::Invalid));
+    // Invalid arguments never reach the capsule.
+    assert!(kernel.take_syscall_log().is_empty());
+    assert_eq!(driver.get_output(0), None);
+}
@@ -0,0 +1,38 @@
+#![no_std]
+
+use libtock_platform::Syscalls;
+
+/// Restarts or stops the process, for apps that detect a state they cannot
+/// recover from. Neither function returns.
+///
+/// These use the Exit system call, which affects only the calling process:
+/// Tock does not let processes reset the whole system.
+///
+/// # Example
+/// ```ignore
+/// use libtock::reset::Reset;
+///
+/// if state_is_corrupt() {
+///     Reset::restart();
+/// }
+/// ```
+pub struct Reset<S: Syscalls>(S);
+
+impl<S: Syscalls> Reset<S> {
+    /// Asks the kernel to restart the process from its entry point. All of
+    /// its memory is reinitialized.
+    #[cold]
+    pub fn restart() -> ! {
+        S::exit_restart(0)
+    }
+
+    /// Asks the kernel to stop the process. It does not run again unless it
+    /// is restarted externally, e.g. from the process console.
+    #[cold]
+    pub fn shutdown() -> ! {
+        S::exit_terminate(0)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,23 @@
+use libtock_unittest::{exit_test, fake, ExitCall};
+
+type Reset = super::Reset<fake::Syscalls>;
+
+#[cfg(not(miri))]
+#[test]
+fn restart() {
+    let exit = exit_test("tests::restart", || {
+        let _kernel = fake::Kernel::new();
+        Reset::restart();
+    });
+    assert_eq!(exit, ExitCall::Restart(0));
+}
+
+#[cfg(not(miri))]
+#[test]
+fn shutdown() {
+    let exit = exit_test("tests::shutdown", || {
+        let _kernel = fake::Kernel::new();
+        Reset::shutdown();
+    });
+    assert_eq!(exit, ExitCall::Terminate(0));
+}
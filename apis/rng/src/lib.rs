@@ -0,0 +1,207 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The random number generator driver.
+///
+/// # Example
+/// ```ignore
+/// use libtock::rng::Rng;
+///
+/// let mut key = [0; 16];
+/// let count = Rng::fill(&mut key)?;
+/// let nonce = Rng::next_u32()?;
+/// ```
+pub struct Rng<S: Syscalls>(S);
+
+impl<S: Syscalls> Rng<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Fills `buf` with random bytes, blocking until the kernel is done.
+    ///
+    /// The kernel may provide fewer bytes than requested. Returns the number
+    /// of bytes written to the start of `buf`; the rest of `buf` is left
+    /// unmodified.
+    pub fn fill(buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        let called: Cell<Option<(u32, u32)>> = Cell::new(None);
+        let len = buf.len();
+        share::scope::<(AllowRw<_, DRIVER_NUM, 0>, Subscribe<_, DRIVER_NUM, 0>), _, _>(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, 0>(allow_rw, buf)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &called)?;
+
+            S::command(DRIVER_NUM, GET_BYTES, len as u32, 0).to_result()?;
+
+            loop {
+                if let Some((_, count)) = called.get() {
+                    return Ok((count as usize).min(len));
+                }
+                S::yield_wait();
+            }
+        })
+    }
+
+    /// Returns a random `u32`, built from 4 random bytes in little-endian
+    /// order.
+    pub fn next_u32() -> Result<u32, ErrorCode> {
+        let mut bytes = [0; 4];
+        Self::fill_exact(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Returns a random `u64`, built from 8 random bytes in little-endian
+    /// order.
+    pub fn next_u64() -> Result<u64, ErrorCode> {
+        let mut bytes = [0; 8];
+        Self::fill_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Returns a uniformly distributed random value in `0..upper`, without
+    /// modulo bias. Random words that would bias the result are discarded,
+    /// so this may draw several words. Returns `Invalid` if `upper` is 0.
+    pub fn gen_range(upper: u32) -> Result<u32, ErrorCode> {
+        gen_range(upper, Self::next_u32)
+    }
+}
+
+/// A userspace pseudo-random number generator with the same `fill`,
+/// `next_u32`, and `next_u64` methods as [`Rng`], for tests that need
+/// reproducible randomness. It never uses the kernel: its output is fully
+/// determined by the seed, so it must not be used where unpredictability
+/// matters. Apps can select it in place of [`Rng`] under a test feature.
+///
+/// The generator is xorshift64. As xorshift cannot run from a zero state, a
+/// seed of 0 is replaced by a fixed non-zero seed.
+///
+/// # Example
+/// ```ignore
+/// use libtock::rng::DeterministicRng;
+///
+/// let rng = DeterministicRng::new(42);
+/// let mut key = [0; 16];
+/// rng.fill(&mut key)?;
+/// ```
+pub struct DeterministicRng {
+    state: Cell<u64>,
+}
+
+impl DeterministicRng {
+    pub fn new(seed: u64) -> DeterministicRng {
+        DeterministicRng {
+            state: Cell::new(if seed == 0 {
+                ZERO_SEED_REPLACEMENT
+            } else {
+                seed
+            }),
+        }
+    }
+
+    /// Fills all of `buf` with pseudo-random bytes. Returns the number of
+    /// bytes written, which is always `buf.len()`.
+    pub fn fill(&self, buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.step().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        Ok(buf.len())
+    }
+
+    /// Returns a pseudo-random `u32`, built from 4 bytes of `fill` output in
+    /// little-endian order.
+    pub fn next_u32(&self) -> Result<u32, ErrorCode> {
+        let mut bytes = [0; 4];
+        self.fill(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Returns a pseudo-random `u64`, built from 8 bytes of `fill` output in
+    /// little-endian order.
+    pub fn next_u64(&self) -> Result<u64, ErrorCode> {
+        let mut bytes = [0; 8];
+        self.fill(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Returns a uniformly distributed pseudo-random value in `0..upper`, like
+    /// [`Rng::gen_range`].
+    pub fn gen_range(&self, upper: u32) -> Result<u32, ErrorCode> {
+        gen_range(upper, || self.next_u32())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> Rng<S> {
+    // Fills all of `buf`, requesting more bytes while the kernel only
+    // provides part of them. Fails if the kernel stops providing bytes.
+    fn fill_exact(mut buf: &mut [u8]) -> Result<(), ErrorCode> {
+        while !buf.is_empty() {
+            let count = Self::fill(buf)?;
+            if count == 0 {
+                return Err(ErrorCode::Fail);
+            }
+            buf = &mut buf[count..];
+        }
+        Ok(())
+    }
+}
+
+impl DeterministicRng {
+    // Advances the xorshift64 state, returning the new state.
+    fn step(&self) -> u64 {
+        let mut x = self.state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state.set(x);
+        x
+    }
+}
+
+// Returns a value in `0..upper` using random words from `next_u32`. Powers of
+// two are masked; otherwise, words below `2^32 % upper` are rejected, so that
+// every remainder is produced by the same number of words.
+fn gen_range<F: FnMut() -> Result<u32, ErrorCode>>(
+    upper: u32,
+    mut next_u32: F,
+) -> Result<u32, ErrorCode> {
+    if upper == 0 {
+        return Err(ErrorCode::Invalid);
+    }
+    if upper.is_power_of_two() {
+        return Ok(next_u32()? & (upper - 1));
+    }
+    let threshold = upper.wrapping_neg() % upper;
+    loop {
+        let word = next_u32()?;
+        if word >= threshold {
+            return Ok(word % upper);
+        }
+    }
+}
+
+// Used in place of a zero seed, from which xorshift only produces zeros.
+const ZERO_SEED_REPLACEMENT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_BYTES: u32 = 1;
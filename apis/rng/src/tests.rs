@@ -0,0 +1,151 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type Rng = super::Rng<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Rng::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Rng::fill(&mut [0; 4]), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Rng::exists(), Ok(()));
+}
+
+#[test]
+fn fill() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 4];
+    driver.add_bytes(&[0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(Rng::fill(&mut buf), Ok(4));
+    assert_eq!(buf, [0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[test]
+fn partial_fill() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    let mut buf = [0; 4];
+    driver.add_bytes(&[1, 2]);
+    assert_eq!(Rng::fill(&mut buf), Ok(2));
+    assert_eq!(buf, [1, 2, 0, 0]);
+}
+
+#[test]
+fn next_u32() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    driver.add_bytes(&[0x78, 0x56, 0x34, 0x12]);
+    assert_eq!(Rng::next_u32(), Ok(0x12345678));
+}
+
+#[test]
+fn next_u64() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    driver.add_bytes(&[0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01]);
+    assert_eq!(Rng::next_u64(), Ok(0x0123456789abcdef));
+}
+
+#[test]
+fn busy() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    assert!(fake::Syscalls::command(0x40001, 1, 4, 0).is_success());
+    assert_eq!(Rng::fill(&mut [0; 4]), Err(ErrorCode::Busy));
+    assert_eq!(Rng::next_u32(), Err(ErrorCode::Busy));
+}
+
+#[test]
+fn gen_range() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Rng::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Rng::gen_range(0), Err(ErrorCode::Invalid));
+    assert_eq!(kernel.take_syscall_log(), []);
+
+    // Powers of two mask the word.
+    driver.add_bytes(&[0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(Rng::gen_range(8), Ok(7));
+
+    // 2^32 % 3 == 1, so a word of 0 would bias the result towards 0 and is
+    // rejected.
+    driver.add_bytes(&[0, 0, 0, 0, 5, 0, 0, 0]);
+    assert_eq!(Rng::gen_range(3), Ok(2));
+    assert!(!driver.is_busy());
+}
+
+#[test]
+fn gen_range_distribution() {
+    let rng = super::DeterministicRng::new(7);
+    let mut counts = [0u32; 10];
+    for _ in 0..10_000 {
+        let value = rng.gen_range(10).unwrap();
+        assert!(value < 10);
+        counts[value as usize] += 1;
+    }
+    // Every value, including both boundaries, is reached roughly equally
+    // often.
+    for count in counts {
+        assert!((800..1200).contains(&count), "counts: {:?}", counts);
+    }
+
+    assert_eq!(rng.gen_range(1), Ok(0));
+    for _ in 0..100 {
+        assert!(rng.gen_range(u32::MAX).unwrap() < u32::MAX);
+    }
+}
+
+#[test]
+fn deterministic_same_seed() {
+    let kernel = fake::Kernel::new();
+    let first = super::DeterministicRng::new(1234);
+    let second = super::DeterministicRng::new(1234);
+
+    let mut a = [0; 20];
+    let mut b = [0; 20];
+    assert_eq!(first.fill(&mut a), Ok(20));
+    assert_eq!(second.fill(&mut b), Ok(20));
+    assert_eq!(a, b);
+    assert_eq!(first.next_u32(), second.next_u32());
+    assert_eq!(first.next_u64(), second.next_u64());
+    // The kernel is never used.
+    assert_eq!(kernel.take_syscall_log(), []);
+
+    let other = super::DeterministicRng::new(1235);
+    let mut c = [0; 20];
+    other.fill(&mut c).unwrap();
+    assert_ne!(a, c);
+}
+
+#[test]
+fn deterministic_output() {
+    let rng = super::DeterministicRng::new(1);
+    let mut buf = [0; 10];
+    rng.fill(&mut buf).unwrap();
+    assert_eq!(buf, [0x41, 0x20, 0x82, 0x40, 0, 0, 0, 0, 0x41, 0x14]);
+
+    // A zero seed still produces output.
+    let rng = super::DeterministicRng::new(0);
+    assert_ne!(rng.next_u64(), Ok(0));
+}
@@ -0,0 +1,95 @@
+use crate::{font, Screen, CELL_WIDTH};
+use core::marker::PhantomData;
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// An off-screen frame, created by [`Screen::with_backbuffer`].
+///
+/// Drawing outside of the frame is clipped. Colors are pixel values in the
+/// screen's pixel format, as for [`Screen::draw_text`].
+pub struct Backbuffer<'buf, S: Syscalls> {
+    buffer: &'buf mut [u8],
+    width: u32,
+    height: u32,
+    bytes_per_pixel: usize,
+    _syscalls: PhantomData<S>,
+}
+
+impl<'buf, S: Syscalls> Backbuffer<'buf, S> {
+    pub(crate) fn new(
+        buffer: &'buf mut [u8],
+        width: u32,
+        height: u32,
+        bytes_per_pixel: usize,
+    ) -> Self {
+        Backbuffer {
+            buffer,
+            width,
+            height,
+            bytes_per_pixel,
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Returns the size of the frame as (width, height), in pixels.
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Sets every pixel of the frame to `color`.
+    pub fn clear(&mut self, color: u32) {
+        self.fill_rect(0, 0, self.width, self.height, color);
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: u32) {
+        self.fill_rect(x, y, 1, 1, color);
+    }
+
+    /// Sets the pixels of the `width` by `height` rectangle with its top-left
+    /// corner at (`x`, `y`) to `color`.
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: u32) {
+        let color = color.to_be_bytes();
+        let color = &color[color.len() - self.bytes_per_pixel..];
+        let x_end = x.saturating_add(width).min(self.width);
+        let y_end = y.saturating_add(height).min(self.height);
+        for row in y..y_end {
+            for column in x..x_end {
+                self.pixel_mut(column, row).copy_from_slice(color);
+            }
+        }
+    }
+
+    /// Draws `text` like [`Screen::draw_text`] does, but into the frame.
+    pub fn draw_text(&mut self, x: u32, y: u32, text: &[u8], color: u32) {
+        let color = color.to_be_bytes();
+        let color = &color[color.len() - self.bytes_per_pixel..];
+        let mut cell_x = x;
+        for &c in text {
+            for column in 0..CELL_WIDTH {
+                for row in 0..font::GLYPH_HEIGHT {
+                    let (px, py) = (cell_x as u64 + column as u64, y as u64 + row as u64);
+                    if px >= self.width as u64 || py >= self.height as u64 {
+                        continue;
+                    }
+                    let pixel = self.pixel_mut(px as u32, py as u32);
+                    match font::pixel(c, column, row) {
+                        true => pixel.copy_from_slice(color),
+                        false => pixel.fill(0),
+                    }
+                }
+            }
+            cell_x = cell_x.saturating_add(CELL_WIDTH as u32);
+        }
+    }
+
+    /// Writes the whole frame to the screen.
+    pub fn present(&self) -> Result<(), ErrorCode> {
+        Screen::<S>::set_write_frame(0, 0, self.width, self.height)?;
+        Screen::<S>::write(self.buffer)
+    }
+
+    // Returns the bytes of the pixel at (x, y), which must be in the frame.
+    fn pixel_mut(&mut self, x: u32, y: u32) -> &mut [u8] {
+        let start = (y as usize * self.width as usize + x as usize) * self.bytes_per_pixel;
+        &mut self.buffer[start..start + self.bytes_per_pixel]
+    }
+}
@@ -0,0 +1,258 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+mod backbuffer;
+mod font;
+
+pub use backbuffer::Backbuffer;
+
+/// The pixel formats a screen may use.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// One bit per pixel.
+    Mono = 0,
+    Rgb233 = 1,
+    Rgb565 = 2,
+    Rgb888 = 3,
+    Argb8888 = 4,
+}
+
+impl PixelFormat {
+    pub fn bits_per_pixel(self) -> u32 {
+        match self {
+            PixelFormat::Mono => 1,
+            PixelFormat::Rgb233 => 8,
+            PixelFormat::Rgb565 => 16,
+            PixelFormat::Rgb888 => 24,
+            PixelFormat::Argb8888 => 32,
+        }
+    }
+}
+
+impl TryFrom<u32> for PixelFormat {
+    type Error = ErrorCode;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PixelFormat::Mono),
+            1 => Ok(PixelFormat::Rgb233),
+            2 => Ok(PixelFormat::Rgb565),
+            3 => Ok(PixelFormat::Rgb888),
+            4 => Ok(PixelFormat::Argb8888),
+            _ => Err(ErrorCode::NoSupport),
+        }
+    }
+}
+
+/// The graphical screen driver.
+///
+/// Pixels are written to a rectangular write frame, so part of the screen can
+/// be updated without sending the whole framebuffer.
+///
+/// # Example
+/// ```ignore
+/// use libtock::screen::Screen;
+///
+/// // Update a 16x8 region at (32, 0).
+/// Screen::set_write_frame(32, 0, 16, 8)?;
+/// Screen::write(&pixels)?;
+/// ```
+pub struct Screen<S: Syscalls>(S);
+
+impl<S: Syscalls> Screen<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Returns the resolution of the screen as (width, height), in pixels.
+    pub fn get_resolution() -> Result<(u32, u32), ErrorCode> {
+        S::command(DRIVER_NUM, GET_RESOLUTION, 0, 0).to_result()
+    }
+
+    pub fn get_pixel_format() -> Result<PixelFormat, ErrorCode> {
+        let format: u32 = S::command(DRIVER_NUM, GET_PIXEL_FORMAT, 0, 0).to_result()?;
+        PixelFormat::try_from(format)
+    }
+
+    /// Sets the rectangle written to by [`write`](Self::write). Its top-left
+    /// corner is at (`x`, `y`), and it is `width` by `height` pixels.
+    ///
+    /// Each value must fit into 16 bits; otherwise `ErrorCode::Invalid` is
+    /// returned.
+    pub fn set_write_frame(x: u32, y: u32, width: u32, height: u32) -> Result<(), ErrorCode> {
+        if [x, y, width, height].iter().any(|&v| v > u16::MAX as u32) {
+            return Err(ErrorCode::Invalid);
+        }
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope(|subscribe| {
+            Self::run(
+                &done,
+                subscribe,
+                SET_WRITE_FRAME,
+                x | y << 16,
+                width | height << 16,
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Writes `pixels`, in the screen's pixel format, to the write frame.
+    ///
+    /// The capsule may accept fewer bytes than were passed in a single
+    /// operation. The remaining bytes are written by further operations, and
+    /// continue where the previous ones stopped within the write frame.
+    pub fn write(pixels: &[u8]) -> Result<(), ErrorCode> {
+        let mut remaining = pixels;
+        while !remaining.is_empty() {
+            let written = Self::write_chunk(remaining)?;
+            if written == 0 {
+                return Err(ErrorCode::Fail);
+            }
+            remaining = &remaining[written.min(remaining.len())..];
+        }
+        Ok(())
+    }
+
+    /// Draws `text` with its top-left corner at (`x`, `y`), using a built-in
+    /// 5x7 font. Bytes outside printable ASCII are drawn as `?`.
+    ///
+    /// Each character is drawn as a 6x7 cell, including a column of spacing,
+    /// with its glyph in `color` on a background of 0. `color` is a pixel value
+    /// in the screen's pixel format; its bytes are sent most significant
+    /// first. Text that runs off the right or bottom edge of the screen is
+    /// clipped. Screens with less than 8 bits per pixel return
+    /// `ErrorCode::NoSupport`.
+    pub fn draw_text(x: u32, y: u32, text: &[u8], color: u32) -> Result<(), ErrorCode> {
+        let (width, height) = Self::get_resolution()?;
+        let bytes_per_pixel = Self::bytes_per_pixel()?;
+        let color = color.to_be_bytes();
+        let color = &color[color.len() - bytes_per_pixel..];
+        let rows = (height.saturating_sub(y) as usize).min(font::GLYPH_HEIGHT);
+        if rows == 0 {
+            return Ok(());
+        }
+
+        let mut cell_x = x;
+        for &c in text {
+            let columns = (width.saturating_sub(cell_x) as usize).min(CELL_WIDTH);
+            if columns == 0 {
+                break;
+            }
+            let mut pixels = [0; CELL_WIDTH * font::GLYPH_HEIGHT * MAX_BYTES_PER_PIXEL];
+            let pixels = &mut pixels[..columns * rows * bytes_per_pixel];
+            for (i, pixel) in pixels.chunks_exact_mut(bytes_per_pixel).enumerate() {
+                if font::pixel(c, i % columns, i / columns) {
+                    pixel.copy_from_slice(color);
+                }
+            }
+            Self::set_write_frame(cell_x, y, columns as u32, rows as u32)?;
+            Self::write(pixels)?;
+            cell_x = cell_x.saturating_add(CELL_WIDTH as u32);
+        }
+        Ok(())
+    }
+
+    /// Starts drawing to `buffer` instead of the screen. Draw operations on
+    /// the returned [`Backbuffer`] only change `buffer`, until
+    /// [`present`](Backbuffer::present) writes all of it to the screen at
+    /// once, so the screen never shows a partially drawn frame.
+    ///
+    /// `buffer` must hold exactly one frame of the screen's resolution and
+    /// pixel format; otherwise `ErrorCode::Size` is returned. Screens with less
+    /// than 8 bits per pixel return `ErrorCode::NoSupport`.
+    pub fn with_backbuffer(buffer: &mut [u8]) -> Result<Backbuffer<S>, ErrorCode> {
+        let (width, height) = Self::get_resolution()?;
+        let bytes_per_pixel = Self::bytes_per_pixel()?;
+        if buffer.len() as u64 != width as u64 * height as u64 * bytes_per_pixel as u64 {
+            return Err(ErrorCode::Size);
+        }
+        Ok(Backbuffer::new(buffer, width, height, bytes_per_pixel))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> Screen<S> {
+    // Returns the number of bytes in each pixel, for pixel formats that use
+    // whole bytes.
+    fn bytes_per_pixel() -> Result<usize, ErrorCode> {
+        match Self::get_pixel_format()?.bits_per_pixel() {
+            bits if bits < 8 => Err(ErrorCode::NoSupport),
+            bits => Ok(bits as usize / 8),
+        }
+    }
+
+    // Performs a single write operation, returning the number of bytes the
+    // capsule reported as written.
+    fn write_chunk(pixels: &[u8]) -> Result<usize, ErrorCode> {
+        let done: Cell<Option<(u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_ro, pixels)?;
+            let written = Self::run(&done, subscribe, WRITE, pixels.len() as u32, 0)?;
+            Ok(written as usize)
+        })
+    }
+
+    // Runs `command` and waits for its completion, returning the data
+    // reported by the capsule.
+    fn run<'share>(
+        done: &'share Cell<Option<(u32, u32)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+        command: u32,
+        arg0: u32,
+        arg1: u32,
+    ) -> Result<u32, ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, done)?;
+        S::command(DRIVER_NUM, command, arg0, arg1).to_result::<(), ErrorCode>()?;
+        loop {
+            match done.get() {
+                Some((0, data)) => return Ok(data),
+                Some((status, _)) => {
+                    return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                }
+                None => S::yield_wait(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_RESOLUTION: u32 = 23;
+const GET_PIXEL_FORMAT: u32 = 25;
+const SET_WRITE_FRAME: u32 = 100;
+const WRITE: u32 = 200;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 0;
+
+// The width of a character drawn by `draw_text`: a glyph and a column of
+// spacing.
+pub(crate) const CELL_WIDTH: usize = font::GLYPH_WIDTH + 1;
+const MAX_BYTES_PER_PIXEL: usize = 4;
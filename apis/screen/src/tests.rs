@@ -0,0 +1,170 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+use libtock_unittest::SyscallLogEntry;
+
+use crate::PixelFormat;
+
+type Screen = super::Screen<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Screen::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Screen::get_resolution(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Screen::exists(), Ok(()));
+    assert_eq!(Screen::get_resolution(), Ok((128, 64)));
+    assert_eq!(Screen::get_pixel_format(), Ok(PixelFormat::Rgb565));
+}
+
+#[test]
+fn set_write_frame() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Screen::set_write_frame(32, 8, 16, 4), Ok(()));
+    assert_eq!(driver.frame(), (32, 8, 16, 4));
+    assert_eq!(
+        Screen::set_write_frame(120, 0, 16, 4),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Screen::set_write_frame(0x10000, 0, 1, 1),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(driver.frame(), (32, 8, 16, 4));
+}
+
+#[test]
+fn partial_update() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new();
+    kernel.add_driver(&driver);
+    driver.set_max_write_len(100);
+
+    let (width, height) = (16, 8);
+    let bytes_per_pixel = PixelFormat::Rgb565.bits_per_pixel() / 8;
+    let pixels = [0x5a; 16 * 8 * 2];
+    let _ = kernel.take_syscall_log();
+    assert_eq!(Screen::set_write_frame(32, 0, width, height), Ok(()));
+    assert_eq!(Screen::write(&pixels), Ok(()));
+
+    // The frame is set once, before the pixels are written in chunks.
+    let commands = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            SyscallLogEntry::Command { command_id, .. } => Some(command_id),
+            _ => None,
+        });
+    assert!(commands.eq([100, 200, 200, 200]));
+
+    let writes = driver.take_writes();
+    assert!(writes.iter().all(|(frame, _)| *frame == (32, 0, 16, 8)));
+    let total: usize = writes.iter().map(|(_, pixels)| pixels.len()).sum();
+    assert_eq!(total, (width * height * bytes_per_pixel) as usize);
+    assert!(writes
+        .iter()
+        .all(|(_, written)| written.iter().all(|&b| b == 0x5a)));
+}
+
+#[test]
+fn pixel_format() {
+    assert_eq!(PixelFormat::try_from(4), Ok(PixelFormat::Argb8888));
+    assert_eq!(PixelFormat::try_from(5), Err(ErrorCode::NoSupport));
+    assert_eq!(PixelFormat::Mono.bits_per_pixel(), 1);
+    assert_eq!(PixelFormat::Rgb888.bits_per_pixel(), 24);
+}
+
+#[test]
+fn draw_text() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new();
+    kernel.add_driver(&driver);
+
+    // The glyph for 'A', one row per line, with the spacing column.
+    const A: [&str; 7] = [
+        " ### ", //
+        "#   #", //
+        "#   #", //
+        "#   #", //
+        "#####", //
+        "#   #", //
+        "#   #",
+    ];
+    assert_eq!(Screen::draw_text(10, 20, b"A", 0xf800), Ok(()));
+    let writes = driver.take_writes();
+    assert_eq!(writes.len(), 1);
+    let (frame, pixels) = &writes[0];
+    assert_eq!(*frame, (10, 20, 6, 7));
+    let expected = A.iter().flat_map(|row| {
+        row.bytes().chain(core::iter::once(b' ')).flat_map(|c| {
+            if c == b'#' {
+                [0xf8, 0x00]
+            } else {
+                [0, 0]
+            }
+        })
+    });
+    assert!(pixels.iter().copied().eq(expected));
+
+    // Text running off the right edge is clipped.
+    assert_eq!(Screen::draw_text(120, 60, b"Hi!", 0xffff), Ok(()));
+    let frames = [(120, 60, 6, 4), (126, 60, 2, 4)];
+    let writes = driver.take_writes();
+    assert!(writes.iter().map(|(frame, _)| *frame).eq(frames));
+    assert_eq!(writes[1].1.len(), 2 * 4 * 2);
+
+    // Nothing is drawn below the screen.
+    assert_eq!(Screen::draw_text(0, 64, b"A", 0xffff), Ok(()));
+    assert_eq!(driver.take_writes(), []);
+}
+
+#[test]
+fn backbuffer() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Screen::new();
+    kernel.add_driver(&driver);
+
+    let mut small = [0; 128 * 64];
+    assert_eq!(
+        Screen::with_backbuffer(&mut small).err(),
+        Some(ErrorCode::Size)
+    );
+
+    let mut buffer = [0; 128 * 64 * 2];
+    let mut frame = Screen::with_backbuffer(&mut buffer).unwrap();
+    assert_eq!(frame.resolution(), (128, 64));
+    let _ = kernel.take_syscall_log();
+    frame.clear(0x0101);
+    frame.fill_rect(126, 62, 10, 10, 0xffff);
+    frame.set_pixel(1, 0, 0x1234);
+    frame.draw_text(8, 0, b"A", 0xf800);
+    // Drawing does not touch the screen.
+    assert_eq!(kernel.take_syscall_log(), []);
+    assert_eq!(driver.take_writes(), []);
+
+    assert_eq!(frame.present(), Ok(()));
+    let writes = driver.take_writes();
+    assert_eq!(writes.len(), 1);
+    let (written_frame, pixels) = &writes[0];
+    assert_eq!(*written_frame, (0, 0, 128, 64));
+    let pixel = |x: usize, y: usize| [pixels[(y * 128 + x) * 2], pixels[(y * 128 + x) * 2 + 1]];
+    assert_eq!(pixel(0, 0), [0x01, 0x01]);
+    assert_eq!(pixel(1, 0), [0x12, 0x34]);
+    assert_eq!(pixel(127, 63), [0xff, 0xff]);
+    assert_eq!(pixel(125, 63), [0x01, 0x01]);
+    // The top of 'A' is " ### ", and its spacing column is cleared.
+    assert_eq!(pixel(8, 0), [0, 0]);
+    assert_eq!(pixel(9, 0), [0xf8, 0x00]);
+    assert_eq!(pixel(13, 0), [0, 0]);
+    assert_eq!(pixel(14, 0), [0x01, 0x01]);
+}
@@ -0,0 +1,40 @@
+//! A common interface to the sensor drivers that produce a single value per
+//! reading, so apps can treat different kinds of sensors alike.
+//!
+//! # Example
+//! ```ignore
+//! use libtock::ambient_light::AmbientLight;
+//! use libtock::humidity::Humidity;
+//! use libtock::sensor::Sensor;
+//! use libtock::temperature::Temperature;
+//!
+//! let sensors: [&dyn Sensor; 3] = [&Temperature::new(), &Humidity::new(), &AmbientLight::new()];
+//! for sensor in sensors {
+//!     log(sensor.read()?, sensor.unit());
+//! }
+//! ```
+#![no_std]
+
+use libtock_platform::ErrorCode;
+
+/// A sensor that is read one value at a time. The trait is object safe, so
+/// sensors of different kinds can be stored together as `&dyn Sensor`.
+pub trait Sensor {
+    /// Takes a reading and waits for it to complete. The value is in the
+    /// sensor's [`unit`](Sensor::unit).
+    fn read(&self) -> Result<i32, ErrorCode>;
+
+    /// Returns the unit of the values returned by [`read`](Sensor::read).
+    fn unit(&self) -> Unit;
+}
+
+/// The unit of a [`Sensor`] reading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    /// Hundredths of a degree Celsius.
+    CentiCelsius,
+    /// Hundredths of a percent of relative humidity.
+    CentiPercentRh,
+    /// Lux.
+    Lux,
+}
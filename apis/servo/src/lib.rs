@@ -0,0 +1,111 @@
+#![no_std]
+
+use libtock_alarm::{Alarm, Convert};
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The servo motor driver.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::Milliseconds;
+/// use libtock::servo::Servo;
+///
+/// Servo::set_angle(0, 90)?;
+/// // Pan slowly back to 0 degrees.
+/// Servo::sweep(0, 90, 0, 5, Milliseconds(20))?;
+/// ```
+pub struct Servo<S: Syscalls>(S);
+
+impl<S: Syscalls> Servo<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Returns the number of servos available to the process.
+    pub fn count() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, SERVO_COUNT, 0, 0).to_result()
+    }
+
+    /// Moves `servo` to `degrees`, which must be at most 180. The capsule
+    /// converts the angle into the servo's PWM pulse width.
+    pub fn set_angle(servo: u32, degrees: u16) -> Result<(), ErrorCode> {
+        if degrees > MAX_ANGLE {
+            return Err(ErrorCode::Invalid);
+        }
+        S::command(DRIVER_NUM, SET_ANGLE, servo, degrees as u32).to_result()
+    }
+
+    /// Moves several servos at once, given as `(servo, degrees)` pairs. The
+    /// commands are issued back-to-back, so the servos start moving as close
+    /// together as possible.
+    ///
+    /// Every angle is checked before any servo is moved: if one is above 180,
+    /// this returns `ErrorCode::Invalid` without moving anything. An error from
+    /// the capsule stops the update, leaving the earlier servos moved.
+    pub fn set_angles(angles: &[(u32, u16)]) -> Result<(), ErrorCode> {
+        if angles.iter().any(|&(_, degrees)| degrees > MAX_ANGLE) {
+            return Err(ErrorCode::Invalid);
+        }
+        for &(servo, degrees) in angles {
+            S::command(DRIVER_NUM, SET_ANGLE, servo, degrees as u32).to_result::<(), _>()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the angle, in degrees, that `servo` was last moved to.
+    pub fn get_angle(servo: u32) -> Result<u16, ErrorCode> {
+        let degrees: u32 = S::command(DRIVER_NUM, GET_ANGLE, servo, 0).to_result()?;
+        Ok(degrees as u16)
+    }
+
+    /// Moves `servo` gradually from `from` to `to` degrees, by `step` degrees
+    /// every `interval`. The last step is shortened so the servo ends exactly
+    /// at `to`.
+    ///
+    /// This sleeps using the alarm driver between steps, and fails if it is
+    /// not present. A `step` of 0 is rejected with `ErrorCode::Invalid`.
+    pub fn sweep<T: Convert + Copy>(
+        servo: u32,
+        from: u16,
+        to: u16,
+        step: u16,
+        interval: T,
+    ) -> Result<(), ErrorCode> {
+        if from > MAX_ANGLE || to > MAX_ANGLE || step == 0 {
+            return Err(ErrorCode::Invalid);
+        }
+        let mut angle = from;
+        loop {
+            Self::set_angle(servo, angle)?;
+            if angle == to {
+                return Ok(());
+            }
+            Alarm::<S>::sleep_for(interval)?;
+            angle = if to > angle {
+                to.min(angle + step)
+            } else {
+                to.max(angle.saturating_sub(step))
+            };
+        }
+    }
+}
+
+const MAX_ANGLE: u16 = 180;
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90009;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SERVO_COUNT: u32 = 1;
+const SET_ANGLE: u32 = 2;
+const GET_ANGLE: u32 = 3;
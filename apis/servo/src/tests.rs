@@ -0,0 +1,97 @@
+use libtock_alarm::Milliseconds;
+use libtock_platform::ErrorCode;
+use libtock_unittest::fake;
+
+type Servo = super::Servo<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Servo::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Servo::count(), Err(ErrorCode::NoDevice));
+    assert_eq!(Servo::set_angle(0, 90), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Servo::exists(), Ok(()));
+    assert_eq!(Servo::count(), Ok(2));
+}
+
+#[test]
+fn set_get_angle() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<2>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Servo::set_angle(1, 180), Ok(()));
+    assert_eq!(Servo::get_angle(1), Ok(180));
+    assert_eq!(driver.get_angle(1), Some(180));
+
+    assert_eq!(Servo::set_angle(1, 181), Err(ErrorCode::Invalid));
+    assert_eq!(Servo::set_angle(2, 0), Err(ErrorCode::Invalid));
+    // Invalid angles never reach the capsule.
+    assert_eq!(driver.take_moves(), [(1, 180)]);
+}
+
+#[test]
+fn sweep() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<2>::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    assert_eq!(Servo::sweep(0, 10, 30, 7, Milliseconds(20)), Ok(()));
+    assert_eq!(driver.take_moves(), [(0, 10), (0, 17), (0, 24), (0, 30)]);
+    // One interval between each pair of moves.
+    assert_eq!(alarm.get_now(), 60);
+
+    assert_eq!(Servo::sweep(1, 20, 0, 10, Milliseconds(20)), Ok(()));
+    let moves = driver.take_moves();
+    assert_eq!(moves, [(1, 20), (1, 10), (1, 0)]);
+    assert!(moves.windows(2).all(|pair| pair[1].1 < pair[0].1));
+}
+
+#[test]
+fn sweep_invalid() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<2>::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    assert_eq!(
+        Servo::sweep(0, 0, 181, 10, Milliseconds(20)),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        Servo::sweep(0, 0, 90, 0, Milliseconds(20)),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(driver.take_moves(), []);
+}
+
+#[test]
+fn set_angles() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Servo::<3>::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Servo::set_angles(&[(0, 45), (2, 180), (1, 0)]), Ok(()));
+    assert_eq!(driver.take_moves(), [(0, 45), (2, 180), (1, 0)]);
+    assert_eq!(Servo::set_angles(&[]), Ok(()));
+
+    // One bad angle, even the last, means no servo is moved.
+    kernel.take_syscall_log();
+    assert_eq!(
+        Servo::set_angles(&[(0, 90), (1, 90), (2, 181)]),
+        Err(ErrorCode::Invalid)
+    );
+    assert!(kernel.take_syscall_log().is_empty());
+    assert_eq!(driver.get_angle(0), Some(45));
+}
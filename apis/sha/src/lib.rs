@@ -0,0 +1,174 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// An incremental SHA-256 digest, computed by the kernel's SHA capsule.
+///
+/// Data is fed to the capsule with [`update`](Self::update) as it becomes
+/// available, so the whole message never has to be in RAM at once. Each call
+/// to `update` shares its slice with the kernel until the capsule has
+/// consumed it; the capsule copies it into its internal buffer in as many
+/// blocks as needed. [`finalize`](Self::finalize) produces the digest and may
+/// only be called once.
+///
+/// The capsule keeps one digest in progress per process, so only one
+/// `Sha256` should be used at a time.
+///
+/// # Example
+/// ```ignore
+/// use libtock::sha::Sha256;
+///
+/// let mut sha = Sha256::new();
+/// sha.update(b"a")?;
+/// sha.update(b"bc")?;
+/// let mut digest = [0; 32];
+/// sha.finalize(&mut digest)?;
+/// ```
+pub struct Sha256<S: Syscalls> {
+    state: State,
+    _syscalls: PhantomData<S>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum State {
+    // The algorithm has not yet been selected in the capsule.
+    New,
+    Updating,
+    Finalized,
+}
+
+impl<S: Syscalls> Sha256<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    ///
+    /// The SHA capsule uses command 0 to select the algorithm, so this also
+    /// selects SHA-256.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_SHA256, 0).to_result()
+    }
+
+    /// Starts a new, empty digest.
+    pub const fn new() -> Self {
+        Sha256 {
+            state: State::New,
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Adds `data` to the digest, blocking until the capsule has consumed it.
+    ///
+    /// Returns `ErrorCode::Already` if the digest was already finalized.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), ErrorCode> {
+        self.select_algorithm()?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        let done = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(allow_ro, data)?;
+            Self::run_operation(&done, subscribe, UPDATE)
+        })
+    }
+
+    /// Completes the digest and writes it to `out`.
+    ///
+    /// The digest can only be finalized once: later calls, as well as calls
+    /// to [`update`](Self::update), return `ErrorCode::Already`, even if the
+    /// first call failed.
+    pub fn finalize(&mut self, out: &mut [u8; 32]) -> Result<(), ErrorCode> {
+        self.select_algorithm()?;
+        self.state = State::Finalized;
+        let done = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_rw, out)?;
+            Self::run_operation(&done, subscribe, FINISH)
+        })
+    }
+}
+
+impl<S: Syscalls> Default for Sha256<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> Sha256<S> {
+    fn select_algorithm(&mut self) -> Result<(), ErrorCode> {
+        match self.state {
+            State::New => {
+                S::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_SHA256, 0)
+                    .to_result::<(), ErrorCode>()?;
+                self.state = State::Updating;
+                Ok(())
+            }
+            State::Updating => Ok(()),
+            State::Finalized => Err(ErrorCode::Already),
+        }
+    }
+
+    // Starts `command` and waits for the capsule to report its completion.
+    fn run_operation<'share>(
+        done: &'share Cell<Option<(u32,)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+        command: u32,
+    ) -> Result<(), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, done)?;
+        S::command(DRIVER_NUM, command, 0, 0).to_result::<(), ErrorCode>()?;
+        loop {
+            match done.get() {
+                Some((0,)) => return Ok(()),
+                Some((status,)) => {
+                    return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                }
+                None => S::yield_wait(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40005;
+
+// Command IDs
+
+const SET_ALGORITHM: u32 = 0;
+const UPDATE: u32 = 2;
+const FINISH: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_DATA: u32 = 1;
+const ALLOW_DEST: u32 = 2;
+
+// Algorithms
+
+const ALGORITHM_SHA256: u32 = 0;
@@ -0,0 +1,119 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{command_return, fake, ExpectedSyscall};
+
+type Sha256 = super::Sha256<fake::Syscalls>;
+
+const EMPTY_DIGEST: [u8; 32] = [
+    0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f, 0xb9, 0x24,
+    0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+];
+
+const ABC_DIGEST: [u8; 32] = [
+    0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+    0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+];
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Sha256::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Sha256::new().update(b"abc"), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Sha256::exists(), Ok(()));
+}
+
+#[test]
+fn empty_string() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut sha = Sha256::new();
+    let mut digest = [0; 32];
+    assert_eq!(sha.finalize(&mut digest), Ok(()));
+    assert_eq!(digest, EMPTY_DIGEST);
+}
+
+#[test]
+fn abc() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut sha = Sha256::new();
+    let mut digest = [0; 32];
+    assert_eq!(sha.update(b"abc"), Ok(()));
+    assert_eq!(sha.finalize(&mut digest), Ok(()));
+    assert_eq!(digest, ABC_DIGEST);
+}
+
+#[test]
+fn incremental() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut sha = Sha256::new();
+    let mut digest = [0; 32];
+    assert_eq!(sha.update(b"a"), Ok(()));
+    assert_eq!(sha.update(b""), Ok(()));
+    assert_eq!(sha.update(b"bc"), Ok(()));
+    assert_eq!(driver.data_len(), 3);
+    assert_eq!(sha.finalize(&mut digest), Ok(()));
+    assert_eq!(digest, ABC_DIGEST);
+}
+
+#[test]
+fn finalize_once() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut sha = Sha256::new();
+    let mut digest = [0; 32];
+    assert_eq!(sha.finalize(&mut digest), Ok(()));
+    let _ = kernel.take_syscall_log();
+
+    let mut digest = [0; 32];
+    assert_eq!(sha.finalize(&mut digest), Err(ErrorCode::Already));
+    assert_eq!(sha.update(b"abc"), Err(ErrorCode::Already));
+    assert_eq!(digest, [0; 32]);
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn finalize_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Sha::new();
+    kernel.add_driver(&driver);
+
+    let mut sha = Sha256::new();
+    assert_eq!(sha.update(b"abc"), Ok(()));
+    kernel.add_expected_syscall(ExpectedSyscall::AllowRw {
+        driver_num: 0x40005,
+        buffer_num: 2,
+        return_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: 0x40005,
+        subscribe_num: 0,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: 0x40005,
+        command_id: 3,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::Busy)),
+    });
+    let mut digest = [0; 32];
+    assert_eq!(sha.finalize(&mut digest), Err(ErrorCode::Busy));
+    assert_eq!(sha.finalize(&mut digest), Err(ErrorCode::Already));
+}
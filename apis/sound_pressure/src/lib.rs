@@ -0,0 +1,102 @@
+#![no_std]
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use libtock_platform::{share, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The sound pressure sensor driver.
+///
+/// Some boards only return valid data once the sensor has been enabled, so
+/// `SoundPressure` remembers whether [`enable`](Self::enable) was called and
+/// refuses to read with `ErrorCode::Off` until it was. The state is tracked in
+/// userspace: enabling or disabling the sensor through another
+/// `SoundPressure` is not noticed.
+///
+/// # Example
+/// ```ignore
+/// use libtock::sound_pressure::SoundPressure;
+///
+/// let sound_pressure = SoundPressure::new();
+/// sound_pressure.enable()?;
+/// let db = sound_pressure.read_sync()?;
+/// ```
+pub struct SoundPressure<S: Syscalls> {
+    enabled: Cell<bool>,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> SoundPressure<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Creates a handle to the sensor, which is assumed to be disabled.
+    pub const fn new() -> Self {
+        SoundPressure {
+            enabled: Cell::new(false),
+            _syscalls: PhantomData,
+        }
+    }
+
+    pub fn enable(&self) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, ENABLE, 0, 0).to_result::<(), _>()?;
+        self.enabled.set(true);
+        Ok(())
+    }
+
+    pub fn disable(&self) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, DISABLE, 0, 0).to_result::<(), _>()?;
+        self.enabled.set(false);
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Initiate a synchronous sound pressure measurement.
+    /// Returns Ok(sound_pressure) if the operation was successful
+    /// sound_pressure is returned in dB
+    /// Returns Err(ErrorCode::Off) if the sensor has not been enabled.
+    pub fn read_sync(&self) -> Result<u8, ErrorCode> {
+        if !self.enabled.get() {
+            return Err(ErrorCode::Off);
+        }
+        let listener: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope(|subscribe: share::Handle<Subscribe<S, DRIVER_NUM, 0>>| {
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)?;
+            S::command(DRIVER_NUM, READ, 0, 0).to_result::<(), _>()?;
+            loop {
+                if let Some((sound_pressure,)) = listener.get() {
+                    return Ok(sound_pressure as u8);
+                }
+                S::yield_wait();
+            }
+        })
+    }
+}
+
+impl<S: Syscalls> Default for SoundPressure<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60006;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ: u32 = 1;
+const ENABLE: u32 = 2;
+const DISABLE: u32 = 3;
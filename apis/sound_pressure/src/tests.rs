@@ -0,0 +1,129 @@
+use libtock_platform::{ErrorCode, Syscalls};
+use libtock_unittest::{fake, ExpectedSyscall, SyscallLogEntry};
+
+type SoundPressure = super::SoundPressure<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(SoundPressure::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(SoundPressure::new().enable(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(SoundPressure::exists(), Ok(()));
+}
+
+#[test]
+fn enable_disable() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    let sound_pressure = SoundPressure::new();
+    assert!(!sound_pressure.is_enabled());
+    assert_eq!(sound_pressure.enable(), Ok(()));
+    assert!(sound_pressure.is_enabled());
+    assert!(driver.is_enabled());
+    assert_eq!(sound_pressure.disable(), Ok(()));
+    assert!(!sound_pressure.is_enabled());
+    assert!(!driver.is_enabled());
+}
+
+#[test]
+fn enable_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: 0x60006,
+        command_id: 2,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(libtock_unittest::command_return::failure(ErrorCode::Fail)),
+    });
+    let sound_pressure = SoundPressure::new();
+    assert_eq!(sound_pressure.enable(), Err(ErrorCode::Fail));
+    assert!(!sound_pressure.is_enabled());
+}
+
+#[test]
+fn read_requires_enable() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    let sound_pressure = SoundPressure::new();
+    driver.set_value_sync(60);
+    assert_eq!(sound_pressure.read_sync(), Err(ErrorCode::Off));
+    assert_eq!(kernel.take_syscall_log(), []);
+
+    assert_eq!(sound_pressure.enable(), Ok(()));
+    assert_eq!(sound_pressure.read_sync(), Ok(60));
+
+    assert_eq!(sound_pressure.disable(), Ok(()));
+    assert_eq!(sound_pressure.read_sync(), Err(ErrorCode::Off));
+}
+
+#[test]
+fn read_busy() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+
+    let sound_pressure = SoundPressure::new();
+    assert_eq!(sound_pressure.enable(), Ok(()));
+    assert!(fake::Syscalls::command(0x60006, 1, 0, 0).is_success());
+    assert_eq!(sound_pressure.read_sync(), Err(ErrorCode::Busy));
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SoundPressure::new();
+    kernel.add_driver(&driver);
+    let sound_pressure = SoundPressure::new();
+    assert_eq!(sound_pressure.enable(), Ok(()));
+
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: super::DRIVER_NUM,
+        subscribe_num: 0,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::READ,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(libtock_unittest::command_return::failure(
+            ErrorCode::NoDevice,
+        )),
+    });
+    kernel.take_syscall_log();
+    assert_eq!(sound_pressure.read_sync(), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: super::DRIVER_NUM,
+                command_id: super::READ,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: super::DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
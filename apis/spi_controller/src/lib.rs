@@ -0,0 +1,167 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The clock level while the bus is idle (CPOL).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Polarity {
+    IdleLow = 0,
+    IdleHigh = 1,
+}
+
+/// The clock edge on which data is sampled (CPHA).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Phase {
+    SampleLeading = 0,
+    SampleTrailing = 1,
+}
+
+/// The SPI controller driver.
+///
+/// # Example
+/// ```ignore
+/// use libtock::spi_controller::{Phase, Polarity, SpiController};
+///
+/// SpiController::set_rate(1_000_000)?;
+/// SpiController::set_polarity(Polarity::IdleLow)?;
+/// SpiController::set_phase(Phase::SampleLeading)?;
+/// let mut rx = [0; 2];
+/// SpiController::read_write(&[0x9f, 0], &mut rx)?;
+/// ```
+pub struct SpiController<S: Syscalls>(S);
+
+impl<S: Syscalls> SpiController<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Shifts out `tx` while shifting `rx` in, blocking until the transfer
+    /// completes.
+    ///
+    /// SPI is full-duplex, so both buffers must have the same length;
+    /// otherwise `ErrorCode::Size` is returned.
+    pub fn read_write(tx: &[u8], rx: &mut [u8]) -> Result<(), ErrorCode> {
+        if tx.len() != rx.len() {
+            return Err(ErrorCode::Size);
+        }
+        if tx.is_empty() {
+            return Ok(());
+        }
+        let done: Cell<Option<(u32,)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+                AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_tx, allow_rx, subscribe) = handle.split();
+            let len = tx.len() as u32;
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, tx)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, rx)?;
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &done)?;
+            S::command(DRIVER_NUM, READ_WRITE, len, 0).to_result::<(), ErrorCode>()?;
+            loop {
+                if done.get().is_some() {
+                    return Ok(());
+                }
+                S::yield_wait();
+            }
+        })
+    }
+
+    /// Requests a clock rate of `hz`. The capsule picks the closest rate the
+    /// hardware supports, which can be read back with
+    /// [`get_rate`](Self::get_rate).
+    pub fn set_rate(hz: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_RATE, hz, 0).to_result()
+    }
+
+    /// Returns the clock rate in use, in Hz.
+    pub fn get_rate() -> Result<u32, ErrorCode> {
+        S::command(DRIVER_NUM, GET_RATE, 0, 0).to_result()
+    }
+
+    pub fn set_polarity(polarity: Polarity) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_POLARITY, polarity as u32, 0).to_result()
+    }
+
+    pub fn set_phase(phase: Phase) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_PHASE, phase as u32, 0).to_result()
+    }
+
+    /// Selects the chip select line asserted during transfers, for buses with
+    /// several devices. Line 0 is selected by default.
+    pub fn set_chip_select(device: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, SET_CHIP_SELECT, device, 0).to_result()
+    }
+
+    /// Keeps the chip select line asserted between transfers, until
+    /// [`release_low`](Self::release_low) is called. Some devices require
+    /// this to access several registers in one operation.
+    pub fn hold_low() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, HOLD_LOW, 0, 0).to_result()
+    }
+
+    /// Deasserts the chip select line after each transfer again.
+    pub fn release_low() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, RELEASE_LOW, 0, 0).to_result()
+    }
+
+    /// Selects `device` and runs `transfers` with its chip select line held
+    /// asserted throughout. The line is released afterwards, including when
+    /// `transfers` fails; its error takes precedence over one from releasing.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut rx = [0; 3];
+    /// SpiController::with_cs(1, || {
+    ///     SpiController::read_write(&[0x80 | REG], &mut rx[..1])?;
+    ///     SpiController::read_write(&[0, 0], &mut rx[1..])
+    /// })?;
+    /// ```
+    pub fn with_cs<R, F: FnOnce() -> Result<R, ErrorCode>>(
+        device: u32,
+        transfers: F,
+    ) -> Result<R, ErrorCode> {
+        Self::set_chip_select(device)?;
+        Self::hold_low()?;
+        let result = transfers();
+        let released = Self::release_low();
+        let value = result?;
+        released?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ_WRITE: u32 = 2;
+const SET_CHIP_SELECT: u32 = 3;
+const SET_RATE: u32 = 5;
+const GET_RATE: u32 = 6;
+const SET_PHASE: u32 = 7;
+const SET_POLARITY: u32 = 9;
+const HOLD_LOW: u32 = 11;
+const RELEASE_LOW: u32 = 12;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
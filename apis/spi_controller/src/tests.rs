@@ -0,0 +1,122 @@
+extern crate std;
+
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+use crate::{Phase, Polarity};
+
+type SpiController = super::SpiController<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(SpiController::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(
+        SpiController::read_write(&[1], &mut [0]),
+        Err(ErrorCode::NoDevice)
+    );
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(SpiController::exists(), Ok(()));
+}
+
+#[test]
+fn read_write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::new();
+    kernel.add_driver(&driver);
+
+    driver.set_response(&[0xde, 0xad, 0xbe, 0xef]);
+    let mut rx = [0; 4];
+    assert_eq!(SpiController::read_write(&[0x9f, 0, 0, 0], &mut rx), Ok(()));
+    assert_eq!(rx, [0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(driver.take_written(), [0x9f, 0, 0, 0]);
+}
+
+#[test]
+fn length_mismatch() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(
+        SpiController::read_write(&[1, 2], &mut [0]),
+        Err(ErrorCode::Size)
+    );
+    assert_eq!(SpiController::read_write(&[], &mut []), Ok(()));
+    assert_eq!(kernel.take_syscall_log(), []);
+}
+
+#[test]
+fn configuration() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(SpiController::set_rate(400_000), Ok(()));
+    assert_eq!(SpiController::get_rate(), Ok(400_000));
+    assert_eq!(SpiController::set_rate(0), Err(ErrorCode::Invalid));
+
+    assert_eq!(SpiController::set_polarity(Polarity::IdleHigh), Ok(()));
+    assert_eq!(driver.polarity(), 1);
+    assert_eq!(SpiController::set_phase(Phase::SampleTrailing), Ok(()));
+    assert_eq!(driver.phase(), 1);
+    assert_eq!(SpiController::set_phase(Phase::SampleLeading), Ok(()));
+    assert_eq!(driver.phase(), 0);
+}
+
+// Returns the IDs of the SPI commands in the syscall log.
+fn commands(kernel: &fake::Kernel) -> std::vec::Vec<u32> {
+    kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            SyscallLogEntry::Command {
+                driver_id: 0x20001,
+                command_id,
+                ..
+            } => Some(command_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn with_cs() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::SpiController::new();
+    kernel.add_driver(&driver);
+
+    let mut rx = [0; 3];
+    let result = SpiController::with_cs(1, || {
+        assert!(driver.is_held_low());
+        SpiController::read_write(&[0x80], &mut rx[..1])?;
+        SpiController::read_write(&[0, 0], &mut rx[1..])?;
+        Ok(7)
+    });
+    assert_eq!(result, Ok(7));
+    assert_eq!(driver.chip_select(), 1);
+    assert!(!driver.is_held_low());
+    assert_eq!(driver.take_written(), [0x80, 0, 0]);
+    // Set chip select and hold it low, the two transfers, then release it.
+    assert_eq!(commands(&kernel), [3, 11, 2, 2, 12]);
+
+    // The line is released when a transfer fails.
+    let result = SpiController::with_cs(2, || SpiController::read_write(&[1], &mut []));
+    assert_eq!(result, Err(ErrorCode::Size));
+    assert!(!driver.is_held_low());
+    assert_eq!(commands(&kernel), [3, 11, 12]);
+
+    // Nothing is held if the device does not exist.
+    assert_eq!(
+        SpiController::with_cs(9, || Ok(())),
+        Err(ErrorCode::Invalid)
+    );
+    assert_eq!(commands(&kernel), [3]);
+}
@@ -1,13 +1,23 @@
 #![no_std]
 
 use core::cell::Cell;
+use core::marker::PhantomData;
+use core::task::Poll;
+use libtock_alarm::{Alarm, Convert, MissedTickPolicy, PeriodicTimer, Ticks};
 use libtock_platform::{
     share, subscribe::OneId, DefaultConfig, ErrorCode, Subscribe, Syscalls, Upcall,
 };
+use libtock_sensor::{Sensor, Unit};
 
-pub struct Temperature<S: Syscalls>(S);
+/// The temperature sensor driver. A value is only needed to use the driver as
+/// a [`Sensor`]; all other operations are associated functions.
+pub struct Temperature<S: Syscalls>(PhantomData<S>);
 
 impl<S: Syscalls> Temperature<S> {
+    pub const fn new() -> Self {
+        Temperature(PhantomData)
+    }
+
     /// Returns Ok() if the driver was present.This does not necessarily mean
     /// that the driver is working.
     pub fn exists() -> Result<(), ErrorCode> {
@@ -34,28 +44,244 @@ impl<S: Syscalls> Temperature<S> {
         S::unsubscribe(DRIVER_NUM, 0)
     }
 
+    /// Non-blocking counterpart of `read_temperature_sync`, for apps that poll
+    /// from their own main loop. Before polling, a `TemperatureListener`
+    /// storing into `reading` must be registered and a measurement started
+    /// with `read_temperature`. Each call handles at most one pending upcall,
+    /// and returns `Poll::Pending` until the measurement completes.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let reading = Cell::new(None);
+    /// let listener = TemperatureListener(|temp_val| reading.set(Some(temp_val)));
+    /// share::scope(|subscribe| {
+    ///     Temperature::register_listener(&listener, subscribe)?;
+    ///     Temperature::read_temperature()?;
+    ///     loop {
+    ///         if let Poll::Ready(temp_val) = Temperature::try_read_temperature(&reading) {
+    ///             return Ok(temp_val);
+    ///         }
+    ///         do_other_work();
+    ///     }
+    /// })
+    /// ```
+    pub fn try_read_temperature(reading: &Cell<Option<i32>>) -> Poll<i32> {
+        S::yield_no_wait();
+        match reading.take() {
+            Some(temp_val) => Poll::Ready(temp_val),
+            None => Poll::Pending,
+        }
+    }
+
     /// Initiate a synchronous temperature measurement.
     /// Returns Ok(temperature_value) if the operation was successful
     /// temperature_value is returned in hundreds of centigrades
+    /// If the measurement cannot be started, its error is returned without
+    /// waiting.
     pub fn read_temperature_sync() -> Result<i32, ErrorCode> {
         let temperature_cell: Cell<Option<i32>> = Cell::new(None);
         let listener = TemperatureListener(|temp_val| {
             temperature_cell.set(Some(temp_val));
         });
         share::scope(|subscribe| {
-            if let Ok(()) = Self::register_listener(&listener, subscribe) {
-                if let Ok(()) = Self::read_temperature() {
-                    while temperature_cell.get() == None {
-                        S::yield_wait();
-                    }
+            // Subscribe before starting the measurement: the capsule may
+            // schedule the upcall before the command returns, and an upcall
+            // with no subscription is lost. If the start fails, leaving the
+            // scope unsubscribes again.
+            Self::register_listener(&listener, subscribe)?;
+            Self::read_temperature()?;
+            loop {
+                if let Some(temp_val) = temperature_cell.get() {
+                    return Ok(temp_val);
                 }
+                S::yield_wait();
             }
-        });
+        })
+    }
+
+    /// Initiate a synchronous temperature measurement.
+    /// Returns Ok(temperature_value) if the operation was successful
+    /// temperature_value is returned in degrees Celsius
+    #[cfg(feature = "float")]
+    pub fn read_celsius() -> Result<f32, ErrorCode> {
+        Self::read_temperature_sync().map(|temp_val| temp_val as f32 / 100.0)
+    }
+
+    /// Starts watching the temperature for crossings of the band from `low` to
+    /// `high` (in hundredths of a degree Celsius), reading it every `poll`.
+    /// See [`TemperatureWatch`]. Returns `ErrorCode::Invalid` if `low` is
+    /// above `high`, and fails if the alarm driver is not present.
+    pub fn watch<T: Convert>(
+        low: i32,
+        high: i32,
+        poll: T,
+    ) -> Result<TemperatureWatch<S>, ErrorCode> {
+        if low > high {
+            return Err(ErrorCode::Invalid);
+        }
+        Ok(TemperatureWatch {
+            low,
+            high,
+            last: None,
+            timer: PeriodicTimer::new(poll, MissedTickPolicy::Skip)?,
+        })
+    }
+}
+
+/// Temperature driver wrapper that caches the last reading, so code reading the
+/// temperature in several places does not measure it every time.
+///
+/// A reading is reused until it is `max_age` old, measured using the alarm
+/// driver's counter. Every [`read_cached`](Self::read_cached) queries the
+/// counter, but only stale readings cost a measurement.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::Seconds;
+/// use libtock::temperature::CachedTemperature;
+///
+/// let temperature = CachedTemperature::new(Seconds(5))?;
+/// loop {
+///     // Measures at most once every 5 seconds.
+///     let centigrades = temperature.read_cached()?;
+///     // ...
+/// }
+/// ```
+pub struct CachedTemperature<S: Syscalls> {
+    max_age: Ticks,
+    // The last reading and the tick count at which it was requested.
+    cache: Cell<Option<(i32, Ticks)>>,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls> CachedTemperature<S> {
+    /// Creates a cache that reuses readings younger than `max_age`. Fails if
+    /// the alarm driver is not present.
+    pub fn new<T: Convert>(max_age: T) -> Result<Self, ErrorCode> {
+        Ok(CachedTemperature {
+            max_age: max_age.to_ticks(Alarm::<S>::get_frequency()?),
+            cache: Cell::new(None),
+            _syscalls: PhantomData,
+        })
+    }
+
+    /// Returns the cached reading if it is younger than `max_age`, and
+    /// otherwise measures (and caches) a new one, like
+    /// [`Temperature::read_temperature_sync`].
+    pub fn read_cached(&self) -> Result<i32, ErrorCode> {
+        let now = Alarm::<S>::get_ticks()?;
+        if let Some((temperature, read_at)) = self.cache.get() {
+            if now.0.wrapping_sub(read_at.0) < self.max_age.0 {
+                return Ok(temperature);
+            }
+        }
+        let temperature = Temperature::<S>::read_temperature_sync()?;
+        self.cache.set(Some((temperature, now)));
+        Ok(temperature)
+    }
 
-        match temperature_cell.get() {
-            None => Err(ErrorCode::Busy),
-            Some(temp_val) => Ok(temp_val),
+    /// Discards the cached reading, so the next read measures a new one.
+    pub fn invalidate(&self) {
+        self.cache.set(None);
+    }
+}
+
+/// A crossing reported by [`TemperatureWatch::wait`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// The temperature rose above the high bound.
+    AboveHigh,
+    /// The temperature fell below the low bound.
+    BelowLow,
+}
+
+/// Reports when the temperature leaves a band, with hysteresis, as needed by
+/// a thermostat. Created by [`Temperature::watch`].
+///
+/// A reading above the high bound reports [`Transition::AboveHigh`], and a
+/// reading below the low bound [`Transition::BelowLow`]. After a transition,
+/// nothing more is reported until a reading is back within the band (bounds
+/// included), so a temperature hovering outside a bound reports a single
+/// transition. The first reading outside the band is reported, even though it
+/// was not seen crossing the bound.
+///
+/// # Example
+/// ```ignore
+/// use libtock::alarm::Seconds;
+/// use libtock::temperature::{Temperature, Transition};
+///
+/// // Keep the room between 20 and 22 degrees Celsius.
+/// let mut watch = Temperature::watch(2000, 2200, Seconds(10))?;
+/// loop {
+///     match watch.wait()? {
+///         Transition::BelowLow => heater_on(),
+///         Transition::AboveHigh => heater_off(),
+///     }
+/// }
+/// ```
+pub struct TemperatureWatch<S: Syscalls> {
+    low: i32,
+    high: i32,
+    // The transition reported since the temperature last was within the band.
+    last: Option<Transition>,
+    timer: PeriodicTimer<S>,
+}
+
+impl<S: Syscalls> TemperatureWatch<S> {
+    /// Reads the temperature every poll interval, starting one interval after
+    /// the previous reading (or the creation of the watch), until a reading
+    /// makes a transition, which is returned.
+    pub fn wait(&mut self) -> Result<Transition, ErrorCode> {
+        loop {
+            self.timer.tick()?;
+            let temperature = Temperature::<S>::read_temperature_sync()?;
+            if let Some(transition) = self.update(temperature) {
+                return Ok(transition);
+            }
+        }
+    }
+
+    // Returns the transition `temperature` makes, if any.
+    fn update(&mut self, temperature: i32) -> Option<Transition> {
+        let transition = if temperature > self.high {
+            Transition::AboveHigh
+        } else if temperature < self.low {
+            Transition::BelowLow
+        } else {
+            self.last = None;
+            return None;
+        };
+        if self.last.is_some() {
+            return None;
         }
+        self.last = Some(transition);
+        Some(transition)
+    }
+}
+
+impl<S: Syscalls> Default for Temperature<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Syscalls> Sensor for Temperature<S> {
+    fn read(&self) -> Result<i32, ErrorCode> {
+        Self::read_temperature_sync()
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::CentiCelsius
+    }
+}
+
+impl<S: Syscalls> Sensor for CachedTemperature<S> {
+    fn read(&self) -> Result<i32, ErrorCode> {
+        self.read_cached()
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::CentiCelsius
     }
 }
 
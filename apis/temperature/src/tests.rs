@@ -1,8 +1,13 @@
 use core::cell::Cell;
+use core::task::Poll;
 use libtock_platform::{share, ErrorCode, Syscalls, YieldNoWaitReturn};
-use libtock_unittest::fake;
+use libtock_sensor::{Sensor, Unit};
+use libtock_unittest::{command_return, fake, ExpectedSyscall, SyscallLogEntry};
+
+use super::{Transition, DRIVER_NUM};
 
 type Temperature = super::Temperature<fake::Syscalls>;
+type CachedTemperature = super::CachedTemperature<fake::Syscalls>;
 
 #[test]
 fn no_driver() {
@@ -79,3 +84,270 @@ fn negative_value() {
     driver.set_value_sync(-1000);
     assert_eq!(Temperature::read_temperature_sync(), Ok(-1000));
 }
+
+#[test]
+fn centidegrees() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync(2345);
+    assert_eq!(Temperature::read_temperature_sync(), Ok(2345));
+}
+
+#[cfg(feature = "float")]
+#[test]
+fn read_celsius() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+
+    driver.set_value_sync(2345);
+    assert_eq!(Temperature::read_celsius(), Ok(23.45));
+
+    assert_eq!(Temperature::read_temperature(), Ok(()));
+    assert_eq!(Temperature::read_celsius(), Err(ErrorCode::Busy));
+}
+
+#[test]
+fn try_read_temperature() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+
+    let reading: Cell<Option<i32>> = Cell::new(None);
+    let listener = crate::TemperatureListener(|temp_val| reading.set(Some(temp_val)));
+    share::scope(|subscribe| {
+        assert_eq!(Temperature::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Temperature::read_temperature(), Ok(()));
+        assert_eq!(Temperature::try_read_temperature(&reading), Poll::Pending);
+        assert_eq!(Temperature::try_read_temperature(&reading), Poll::Pending);
+
+        driver.set_value(-250);
+        assert_eq!(
+            Temperature::try_read_temperature(&reading),
+            Poll::Ready(-250)
+        );
+        assert_eq!(Temperature::try_read_temperature(&reading), Poll::Pending);
+    });
+}
+
+// The test decides when the reading completes, by injecting the upcall.
+#[test]
+fn injected_upcall() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+
+    let reading: Cell<Option<i32>> = Cell::new(None);
+    let listener = crate::TemperatureListener(|temp_val| reading.set(Some(temp_val)));
+    share::scope(|subscribe| {
+        assert_eq!(Temperature::register_listener(&listener, subscribe), Ok(()));
+        assert_eq!(Temperature::read_temperature(), Ok(()));
+        assert_eq!(Temperature::try_read_temperature(&reading), Poll::Pending);
+
+        kernel.inject_upcall(DRIVER_NUM, 0, (2150, 0, 0));
+        assert_eq!(reading.get(), None);
+        assert_eq!(
+            Temperature::try_read_temperature(&reading),
+            Poll::Ready(2150)
+        );
+    });
+}
+
+// An upcall injected before a blocking read is delivered while it waits.
+#[test]
+fn injected_upcall_sync() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+
+    kernel.inject_upcall(DRIVER_NUM, 0, (-300i32 as u32, 0, 0));
+    assert_eq!(Temperature::read_temperature_sync(), Ok(-300));
+}
+
+#[test]
+fn cached_temperature() {
+    use libtock_alarm::Milliseconds;
+
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+    let temperature = CachedTemperature::new(Milliseconds(100)).unwrap();
+    let measured = |log: &[SyscallLogEntry]| {
+        log.iter().any(|entry| {
+            matches!(entry, SyscallLogEntry::Command { driver_id, .. } if *driver_id == super::DRIVER_NUM)
+        })
+    };
+
+    driver.set_value_sync(2000);
+    assert_eq!(temperature.read_cached(), Ok(2000));
+    kernel.take_syscall_log();
+
+    // Within max_age, the cached value is returned without measuring.
+    alarm.set_now(alarm.get_now() + 99);
+    driver.set_value_sync(2500);
+    assert_eq!(temperature.read_cached(), Ok(2000));
+    assert!(!measured(&kernel.take_syscall_log()));
+
+    // Once it expires, a new measurement is taken.
+    alarm.set_now(alarm.get_now() + 1);
+    assert_eq!(temperature.read_cached(), Ok(2500));
+    assert!(measured(&kernel.take_syscall_log()));
+
+    temperature.invalidate();
+    driver.set_value_sync(3000);
+    assert_eq!(temperature.read_cached(), Ok(3000));
+}
+
+#[test]
+fn watch() {
+    use libtock_alarm::Milliseconds;
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let mut watch = Temperature::watch(2000, 2200, Milliseconds(100)).unwrap();
+    // Hovering above a bound after crossing it is a single transition, while
+    // re-entering the band and leaving it again on the same side is another.
+    let trajectory = [
+        2100, 2250, 2205, 2230, 2201, 2150, 2210, 2200, 1990, 1950, 1999, 2000, 1980,
+    ];
+    for temperature in trajectory {
+        driver.set_value_sync(temperature);
+    }
+    assert_eq!(watch.wait(), Ok(Transition::AboveHigh));
+    assert_eq!(alarm.get_now(), 200);
+    assert_eq!(watch.wait(), Ok(Transition::AboveHigh));
+    assert_eq!(alarm.get_now(), 700);
+    assert_eq!(watch.wait(), Ok(Transition::BelowLow));
+    assert_eq!(alarm.get_now(), 900);
+    assert_eq!(watch.wait(), Ok(Transition::BelowLow));
+    assert_eq!(alarm.get_now(), 1300);
+
+    assert!(matches!(
+        Temperature::watch(2200, 2000, Milliseconds(100)),
+        Err(ErrorCode::Invalid)
+    ));
+}
+
+#[test]
+fn sensor() {
+    use libtock_alarm::Milliseconds;
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    let temperature = Temperature::new();
+    let cached = CachedTemperature::new(Milliseconds(100)).unwrap();
+    let sensors: [&dyn Sensor; 2] = [&temperature, &cached];
+    let mut readings = [0; 2];
+    for (sensor, reading) in sensors.iter().zip(&mut readings) {
+        driver.set_value_sync(2150);
+        assert_eq!(sensor.unit(), Unit::CentiCelsius);
+        *reading = sensor.read().unwrap();
+    }
+    assert_eq!(readings, [2150, 2150]);
+}
+
+#[test]
+fn upcall_arguments() {
+    use libtock_platform::Upcall;
+
+    // The reading is the signed value of arg0.
+    let received = Cell::new(None);
+    let listener = super::TemperatureListener(|temp_val| received.set(Some(temp_val)));
+    listener.upcall(-1250i32 as u32, 7, 8);
+    assert_eq!(received.get(), Some(-1250));
+
+    // The reading is taken from the upcall, without a follow-up command.
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+    driver.set_value_sync(1800);
+    assert_eq!(Temperature::read_temperature_sync(), Ok(1800));
+    let commands = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter(|entry| matches!(entry, SyscallLogEntry::Command { .. }));
+    assert!(commands.eq([SyscallLogEntry::Command {
+        driver_id: super::DRIVER_NUM,
+        command_id: super::READ_TEMP,
+        argument0: 0,
+        argument1: 0,
+    }]));
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Temperature::new();
+    kernel.add_driver(&driver);
+
+    // A reading is already in progress.
+    assert_eq!(Temperature::read_temperature(), Ok(()));
+    kernel.take_syscall_log();
+    assert_eq!(Temperature::read_temperature_sync(), Err(ErrorCode::Busy));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: DRIVER_NUM,
+                command_id: super::READ_TEMP,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+
+    // The sensor behind the capsule is missing.
+    kernel.add_expected_syscall(ExpectedSyscall::Subscribe {
+        driver_num: DRIVER_NUM,
+        subscribe_num: 0,
+        skip_with_error: None,
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::Command {
+        driver_id: DRIVER_NUM,
+        command_id: super::READ_TEMP,
+        argument0: 0,
+        argument1: 0,
+        override_return: Some(command_return::failure(ErrorCode::NoDevice)),
+    });
+    assert_eq!(
+        Temperature::read_temperature_sync(),
+        Err(ErrorCode::NoDevice)
+    );
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: DRIVER_NUM,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::Command {
+                driver_id: DRIVER_NUM,
+                command_id: super::READ_TEMP,
+                argument0: 0,
+                argument1: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: DRIVER_NUM,
+                subscribe_num: 0,
+            },
+        ]
+    );
+}
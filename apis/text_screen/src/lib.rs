@@ -0,0 +1,156 @@
+#![no_std]
+
+use core::cell::Cell;
+
+use libtock_platform::{share, AllowRo, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+
+/// The text screen driver, for character displays such as HD44780 LCDs.
+///
+/// Every operation is asynchronous in the capsule; these functions block
+/// until it reports completion.
+///
+/// # Example
+/// ```ignore
+/// use libtock::text_screen::TextScreen;
+///
+/// TextScreen::on()?;
+/// TextScreen::clear()?;
+/// TextScreen::set_cursor(0, 1)?;
+/// TextScreen::write(b"Battery: 87%")?;
+/// ```
+pub struct TextScreen<S: Syscalls>(S);
+
+impl<S: Syscalls> TextScreen<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Returns the size of the display as (columns, rows).
+    pub fn dimensions() -> Result<(u32, u32), ErrorCode> {
+        Self::run(GET_SIZE, 0, 0)
+    }
+
+    /// Moves the cursor to column `col` of row `row`, both counted from 0.
+    ///
+    /// The position is checked against [`dimensions`](Self::dimensions),
+    /// which is queried on every call; off-screen positions return
+    /// `ErrorCode::Invalid`.
+    pub fn set_cursor(col: u32, row: u32) -> Result<(), ErrorCode> {
+        let (cols, rows) = Self::dimensions()?;
+        if col >= cols || row >= rows {
+            return Err(ErrorCode::Invalid);
+        }
+        Self::run(SET_CURSOR, col, row)?;
+        Ok(())
+    }
+
+    /// Writes `text` at the cursor position.
+    ///
+    /// The capsule may accept fewer bytes than were passed in a single
+    /// operation, in which case the remaining bytes are written by further
+    /// operations.
+    pub fn write(text: &[u8]) -> Result<(), ErrorCode> {
+        let mut remaining = text;
+        while !remaining.is_empty() {
+            let written = Self::write_chunk(remaining)?;
+            if written == 0 {
+                return Err(ErrorCode::Fail);
+            }
+            remaining = &remaining[written.min(remaining.len())..];
+        }
+        Ok(())
+    }
+
+    /// Clears the display and moves the cursor to the top-left corner.
+    pub fn clear() -> Result<(), ErrorCode> {
+        Self::run(CLEAR, 0, 0)?;
+        Ok(())
+    }
+
+    pub fn on() -> Result<(), ErrorCode> {
+        Self::run(ON, 0, 0)?;
+        Ok(())
+    }
+
+    pub fn off() -> Result<(), ErrorCode> {
+        Self::run(OFF, 0, 0)?;
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+impl<S: Syscalls> TextScreen<S> {
+    // Runs `command` and waits for its completion, returning the data
+    // reported by the capsule.
+    fn run(command: u32, arg0: u32, arg1: u32) -> Result<(u32, u32), ErrorCode> {
+        let done: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope(|subscribe| Self::run_subscribed(&done, subscribe, command, arg0, arg1))
+    }
+
+    // Performs a single write operation, returning the number of bytes the
+    // capsule reported as written.
+    fn write_chunk(text: &[u8]) -> Result<usize, ErrorCode> {
+        let done: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+                Subscribe<_, DRIVER_NUM, 0>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_ro, text)?;
+            let (written, _) = Self::run_subscribed(&done, subscribe, WRITE, text.len() as u32, 0)?;
+            Ok(written as usize)
+        })
+    }
+
+    fn run_subscribed<'share>(
+        done: &'share Cell<Option<(u32, u32, u32)>>,
+        subscribe: share::Handle<Subscribe<'share, S, DRIVER_NUM, 0>>,
+        command: u32,
+        arg0: u32,
+        arg1: u32,
+    ) -> Result<(u32, u32), ErrorCode> {
+        S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, done)?;
+        S::command(DRIVER_NUM, command, arg0, arg1).to_result::<(), ErrorCode>()?;
+        loop {
+            match done.get() {
+                Some((0, data0, data1)) => return Ok((data0, data1)),
+                Some((status, _, _)) => {
+                    return Err(ErrorCode::try_from(status).unwrap_or(ErrorCode::Fail))
+                }
+                None => S::yield_wait(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90003;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_SIZE: u32 = 1;
+const ON: u32 = 2;
+const OFF: u32 = 3;
+const WRITE: u32 = 8;
+const CLEAR: u32 = 9;
+const SET_CURSOR: u32 = 11;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 0;
@@ -0,0 +1,88 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type TextScreen = super::TextScreen<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(TextScreen::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(TextScreen::dimensions(), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(TextScreen::exists(), Ok(()));
+    assert_eq!(TextScreen::dimensions(), Ok((16, 2)));
+}
+
+#[test]
+fn on_off_clear() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(TextScreen::on(), Ok(()));
+    assert!(driver.is_on());
+    assert_eq!(TextScreen::set_cursor(5, 1), Ok(()));
+    assert_eq!(TextScreen::clear(), Ok(()));
+    assert_eq!(driver.cursor(), (0, 0));
+    assert_eq!(TextScreen::off(), Ok(()));
+    assert!(!driver.is_on());
+}
+
+#[test]
+fn set_cursor() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new();
+    kernel.add_driver(&driver);
+
+    let _ = kernel.take_syscall_log();
+    assert_eq!(TextScreen::set_cursor(15, 1), Ok(()));
+    assert_eq!(driver.cursor(), (15, 1));
+    let log = kernel.take_syscall_log();
+    assert!(log.contains(&SyscallLogEntry::Command {
+        driver_id: 0x90003,
+        command_id: 11,
+        argument0: 15,
+        argument1: 1,
+    }));
+
+    assert_eq!(TextScreen::set_cursor(16, 0), Err(ErrorCode::Invalid));
+    assert_eq!(TextScreen::set_cursor(0, 2), Err(ErrorCode::Invalid));
+    assert_eq!(driver.cursor(), (15, 1));
+}
+
+#[test]
+fn write() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(TextScreen::write(b"Hi"), Ok(()));
+    assert_eq!(driver.take_writes(), [((0, 0), b"Hi".to_vec())]);
+    assert_eq!(TextScreen::write(b""), Ok(()));
+    assert_eq!(driver.take_writes(), []);
+}
+
+#[test]
+fn write_chunked() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::TextScreen::new();
+    kernel.add_driver(&driver);
+
+    driver.set_max_write_len(4);
+    assert_eq!(TextScreen::write(b"Battery: 87%"), Ok(()));
+    assert_eq!(
+        driver.take_writes(),
+        [
+            ((0, 0), b"Batt".to_vec()),
+            ((4, 0), b"ery:".to_vec()),
+            ((8, 0), b" 87%".to_vec()),
+        ]
+    );
+}
@@ -0,0 +1,61 @@
+#![no_std]
+
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// The watchdog driver. Once started, the watchdog resets the whole system
+/// unless it is tickled at least once every `timeout_ms` milliseconds, so an
+/// app that hangs is restarted rather than left unresponsive.
+///
+/// # Example
+/// ```ignore
+/// use libtock::watchdog::Watchdog;
+///
+/// Watchdog::start(5000)?;
+/// loop {
+///     // one iteration of the app's work, taking well under 5 seconds
+///     Watchdog::tickle()?;
+/// }
+/// ```
+pub struct Watchdog<S: Syscalls>(S);
+
+impl<S: Syscalls> Watchdog<S> {
+    /// Returns Ok() if the driver was present. This does not necessarily mean
+    /// that the driver is working.
+    pub fn exists() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, EXISTS, 0, 0).to_result()
+    }
+
+    /// Starts the watchdog. If `tickle` is then not called within
+    /// `timeout_ms` milliseconds, the system is reset. Starting a running
+    /// watchdog changes its timeout and restarts the countdown.
+    pub fn start(timeout_ms: u32) -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, START, timeout_ms, 0).to_result()
+    }
+
+    /// Restarts the countdown of the running watchdog, postponing the reset
+    /// by another `timeout_ms`.
+    pub fn tickle() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, TICKLE, 0, 0).to_result()
+    }
+
+    /// Stops the watchdog, so the system is no longer reset.
+    pub fn stop() -> Result<(), ErrorCode> {
+        S::command(DRIVER_NUM, STOP, 0, 0).to_result()
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x9000A;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const START: u32 = 1;
+const TICKLE: u32 = 2;
+const STOP: u32 = 3;
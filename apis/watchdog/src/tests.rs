@@ -0,0 +1,76 @@
+use libtock_platform::ErrorCode;
+use libtock_unittest::{fake, SyscallLogEntry};
+
+type Watchdog = super::Watchdog<fake::Syscalls>;
+
+#[test]
+fn no_driver() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(Watchdog::exists(), Err(ErrorCode::NoDevice));
+    assert_eq!(Watchdog::start(1000), Err(ErrorCode::NoDevice));
+}
+
+#[test]
+fn driver_check() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Watchdog::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Watchdog::exists(), Ok(()));
+}
+
+#[test]
+fn start() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Watchdog::new();
+    kernel.add_driver(&driver);
+
+    assert_eq!(Watchdog::start(2500), Ok(()));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::Command {
+            driver_id: super::DRIVER_NUM,
+            command_id: super::START,
+            argument0: 2500,
+            argument1: 0,
+        }]
+    );
+    assert_eq!(driver.get_timeout_ms(), Some(2500));
+}
+
+#[test]
+fn tickle() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Watchdog::new();
+    kernel.add_driver(&driver);
+
+    // The watchdog must be running to be tickled.
+    assert_eq!(Watchdog::tickle(), Err(ErrorCode::Off));
+
+    Watchdog::start(1000).unwrap();
+    kernel.take_syscall_log();
+    assert_eq!(Watchdog::tickle(), Ok(()));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::Command {
+            driver_id: super::DRIVER_NUM,
+            command_id: super::TICKLE,
+            argument0: 0,
+            argument1: 0,
+        }]
+    );
+    assert_eq!(Watchdog::tickle(), Ok(()));
+    assert_eq!(driver.take_tickle_count(), 2);
+}
+
+#[test]
+fn stop() {
+    let kernel = fake::Kernel::new();
+    let driver = fake::Watchdog::new();
+    kernel.add_driver(&driver);
+
+    Watchdog::start(1000).unwrap();
+    assert_eq!(Watchdog::stop(), Ok(()));
+    assert_eq!(driver.get_timeout_ms(), None);
+    assert_eq!(Watchdog::stop(), Err(ErrorCode::Already));
+}
@@ -0,0 +1,276 @@
+//! Detects which capsules the kernel provides, so an app can skip features that
+//! the board does not support.
+//!
+//! [`CapsuleRegistry::detect`] probes every driver API in libtock-rs, using the
+//! same existence check that API provides (e.g. `Leds::count` or
+//! `Temperature::exists`). A new API crate is supported by implementing
+//! [`Probe`] for it and adding it to the `registry!` invocation below.
+
+#![no_std]
+
+use libtock_platform::Syscalls;
+
+/// A driver API whose capsule can be detected at runtime.
+pub trait Probe {
+    /// Returns true if the kernel responds to the API's existence check.
+    fn present() -> bool;
+}
+
+// Defines CapsuleRegistry, with one field per API. The APIs are generic over
+// `S`, which is the Syscalls implementation `detect` is called with.
+macro_rules! registry {
+    {$($name:ident: $api:ty,)*} => {
+        /// Which capsules responded to their existence check. Each field is
+        /// named after the API crate that drives the capsule.
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+        pub struct CapsuleRegistry {
+            $(pub $name: bool,)*
+        }
+
+        impl CapsuleRegistry {
+            /// Probes every capsule. This issues one command per API.
+            pub fn detect<S: Syscalls>() -> Self {
+                CapsuleRegistry {
+                    $($name: <$api as Probe>::present(),)*
+                }
+            }
+        }
+    }
+}
+
+registry! {
+    adc: libtock_adc::Adc<S>,
+    aes: libtock_aes::AesCtr<S>,
+    alarm: libtock_alarm::Alarm<S>,
+    ambient_light: libtock_ambient_light::AmbientLight<S>,
+    analog_comparator: libtock_analog_comparator::AnalogComparator<S>,
+    app_state: libtock_app_state::AppState<S>,
+    buttons: libtock_buttons::Buttons<S>,
+    buzzer: libtock_buzzer::Buzzer<S>,
+    can: libtock_can::Can<S>,
+    console: libtock_console::Console<S>,
+    crc: libtock_crc::Crc<S>,
+    ctap: libtock_ctap::Ctap<'static, S>,
+    gpio: libtock_gpio::Gpio<S>,
+    hmac: libtock_hmac::HmacSha256<'static, S>,
+    humidity: libtock_humidity::Humidity<S>,
+    i2c_master: libtock_i2c_master::I2cMaster<S>,
+    ieee802154: libtock_ieee802154::Ieee802154<S>,
+    kv: libtock_kv::KeyValue<S>,
+    leds: libtock_leds::Leds<S>,
+    low_level_debug: libtock_low_level_debug::LowLevelDebug<S>,
+    net: libtock_net::UdpSocket<S>,
+    ninedof: libtock_ninedof::NineDof<S>,
+    proximity: libtock_proximity::Proximity<S>,
+    pwm: libtock_pwm::Pwm<S>,
+    rng: libtock_rng::Rng<S>,
+    screen: libtock_screen::Screen<S>,
+    servo: libtock_servo::Servo<S>,
+    sha: libtock_sha::Sha256<S>,
+    sound_pressure: libtock_sound_pressure::SoundPressure<S>,
+    spi_controller: libtock_spi_controller::SpiController<S>,
+    temperature: libtock_temperature::Temperature<S>,
+    text_screen: libtock_text_screen::TextScreen<S>,
+    watchdog: libtock_watchdog::Watchdog<S>,
+}
+
+impl<S: Syscalls> Probe for libtock_adc::Adc<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_aes::AesCtr<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_alarm::Alarm<S> {
+    fn present() -> bool {
+        Self::get_frequency().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_ambient_light::AmbientLight<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_analog_comparator::AnalogComparator<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_app_state::AppState<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_buttons::Buttons<S> {
+    fn present() -> bool {
+        Self::count().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_buzzer::Buzzer<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_can::Can<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_console::Console<S> {
+    fn present() -> bool {
+        Self::driver_check()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_crc::Crc<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_ctap::Ctap<'static, S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_gpio::Gpio<S> {
+    fn present() -> bool {
+        Self::count().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_hmac::HmacSha256<'static, S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_humidity::Humidity<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_i2c_master::I2cMaster<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_ieee802154::Ieee802154<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_kv::KeyValue<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_leds::Leds<S> {
+    fn present() -> bool {
+        Self::count().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_low_level_debug::LowLevelDebug<S> {
+    fn present() -> bool {
+        Self::driver_check()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_net::UdpSocket<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_ninedof::NineDof<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_proximity::Proximity<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_pwm::Pwm<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_rng::Rng<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_screen::Screen<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_servo::Servo<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_sha::Sha256<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_sound_pressure::SoundPressure<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_spi_controller::SpiController<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_temperature::Temperature<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_text_screen::TextScreen<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+impl<S: Syscalls> Probe for libtock_watchdog::Watchdog<S> {
+    fn present() -> bool {
+        Self::exists().is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests;
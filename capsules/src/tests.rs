@@ -0,0 +1,32 @@
+use super::CapsuleRegistry;
+use libtock_unittest::fake;
+
+#[test]
+fn no_capsules() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(
+        CapsuleRegistry::detect::<fake::Syscalls>(),
+        CapsuleRegistry::default()
+    );
+}
+
+#[test]
+fn some_capsules() {
+    let kernel = fake::Kernel::new();
+    let leds = fake::Leds::<2>::new();
+    kernel.add_driver(&leds);
+    let temperature = fake::Temperature::new();
+    kernel.add_driver(&temperature);
+    let console = fake::Console::new();
+    kernel.add_driver(&console);
+
+    assert_eq!(
+        CapsuleRegistry::detect::<fake::Syscalls>(),
+        CapsuleRegistry {
+            console: true,
+            leds: true,
+            temperature: true,
+            ..CapsuleRegistry::default()
+        }
+    );
+}
@@ -0,0 +1,38 @@
+//! A simple libtock-rs example. Checks for the ADC driver and samples
+//! channel 0 every 2 seconds.
+
+#![no_main]
+#![no_std]
+
+use core::fmt::Write;
+use libtock::console::Console;
+
+use libtock::adc::Adc;
+use libtock::alarm::{Alarm, Milliseconds};
+use libtock::runtime::{set_main, stack_size};
+
+set_main! {main}
+stack_size! {0x200}
+
+fn main() {
+    match Adc::count() {
+        Ok(count) => writeln!(
+            Console::writer(),
+            "adc driver available, {} channels",
+            count
+        )
+        .unwrap(),
+        Err(_) => {
+            writeln!(Console::writer(), "adc driver unavailable").unwrap();
+            return;
+        }
+    }
+    loop {
+        match Adc::sample(0) {
+            Ok(sample) => writeln!(Console::writer(), "ADC channel 0: {}", sample).unwrap(),
+            Err(_) => writeln!(Console::writer(), "error while sampling the adc").unwrap(),
+        }
+
+        Alarm::sleep_for(Milliseconds(2000)).unwrap();
+    }
+}
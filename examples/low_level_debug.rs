@@ -13,4 +13,6 @@ stack_size! {0x100}
 fn main() {
     LowLevelDebug::print_1(1);
     LowLevelDebug::print_2(2, 3);
+    // Prints 4 in debug builds, as the check fails.
+    libtock::tock_debug_assert!(1 + 1 == 3, 4);
 }
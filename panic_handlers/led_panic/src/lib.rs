@@ -0,0 +1,217 @@
+//! A panic handler that signals a panic using the LowLevelDebug capsule and
+//! the board's LEDs.
+//!
+//! After the panic alert, the low 16 bits of the line number the panic
+//! occurred on are printed as a LowLevelDebug status code, to help narrow
+//! down where the app died without a debugger.
+//!
+//! By default, every LED blinks forever after a panic. Boards whose LEDs
+//! share pins with other hardware, or that have no LEDs at all, can replace
+//! that behavior by calling [`set_panic_behavior`] early in `main`:
+//!
+//! ```ignore
+//! use libtock_led_panic::{restart, set_panic_behavior};
+//! use libtock_runtime::TockSyscalls;
+//!
+//! set_panic_behavior(restart::<TockSyscalls>);
+//! ```
+//!
+//! The behavior can be one of [`blink_all_leds`], [`cycle_leds`], [`halt`],
+//! or [`restart`], or any function provided by the app, such as one blinking a
+//! board-specific pattern.
+//!
+//! With the `alloc` feature, this crate also provides an allocation error
+//! handler. It reports [`OUT_OF_MEMORY_ALERT`] instead of a panic, then lights
+//! the LEDs one at a time (see [`set_out_of_memory_behavior`] and
+//! [`set_cycle_interval`]), so the two failures can be told apart.
+#![no_std]
+#![cfg_attr(
+    all(target_os = "none", feature = "alloc"),
+    feature(alloc_error_handler)
+)]
+
+use core::panic::Location;
+use core::ptr::null_mut;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+use libtock_alarm::{Alarm, Milliseconds};
+use libtock_leds::Leds;
+use libtock_low_level_debug::{AlertCode, LowLevelDebug};
+use libtock_platform::{ErrorCode, Syscalls};
+
+/// What the process does once a panic has been reported. It never returns.
+pub type PanicBehavior = fn() -> !;
+
+/// Replaces the behavior run after a panic. The default is
+/// [`blink_all_leds`].
+pub fn set_panic_behavior(behavior: PanicBehavior) {
+    store_behavior(&PANIC_BEHAVIOR, behavior);
+}
+
+/// Replaces the behavior run after an allocation failure. The default is
+/// [`cycle_leds`].
+pub fn set_out_of_memory_behavior(behavior: PanicBehavior) {
+    store_behavior(&OUT_OF_MEMORY_BEHAVIOR, behavior);
+}
+
+/// Sets how long [`cycle_leds`] keeps each LED lit. The default is 100 ms.
+pub fn set_cycle_interval(interval_ms: u32) {
+    CYCLE_INTERVAL_MS.store(interval_ms, Ordering::Relaxed);
+}
+
+/// The LowLevelDebug alert code reported on allocation failure. Codes 0x01 and
+/// 0x02 are already used by [`AlertCode`].
+pub const OUT_OF_MEMORY_ALERT: u32 = 0x03;
+
+/// Reports the panic, and where it happened if known, through the
+/// LowLevelDebug capsule (if available), then runs the configured behavior.
+pub fn handle_panic<S: Syscalls>(location: Option<&Location>) -> ! {
+    LowLevelDebug::<S>::print_alert_code(AlertCode::Panic);
+    if let Some(location) = location {
+        LowLevelDebug::<S>::print_status(line_code(location.line()));
+    }
+    match load_behavior(&PANIC_BEHAVIOR) {
+        Some(behavior) => behavior(),
+        None => blink_all_leds::<S>(),
+    }
+}
+
+/// Reports an allocation failure through the LowLevelDebug capsule (if
+/// available), then runs the configured behavior.
+pub fn handle_out_of_memory<S: Syscalls>() -> ! {
+    LowLevelDebug::<S>::print_alert_code(OUT_OF_MEMORY_ALERT);
+    match load_behavior(&OUT_OF_MEMORY_BEHAVIOR) {
+        Some(behavior) => behavior(),
+        None => cycle_leds::<S>(),
+    }
+}
+
+/// Toggles every LED every 100 ms, forever. Without an alarm driver, the
+/// interval is measured with a busy-wait loop instead, so it is only
+/// approximate; without LEDs, this halts.
+pub fn blink_all_leds<S: Syscalls>() -> ! {
+    if Leds::<S>::count().is_err() {
+        halt::<S>();
+    }
+    let delay = BlinkDelay::select::<S>();
+    loop {
+        toggle_all_leds::<S>();
+        delay.wait::<S>(BLINK_INTERVAL_MS);
+    }
+}
+
+/// Lights each LED in turn, forever, for the interval set by
+/// [`set_cycle_interval`]. Like [`blink_all_leds`], this falls back to a
+/// busy-wait loop without an alarm driver, and halts without LEDs.
+pub fn cycle_leds<S: Syscalls>() -> ! {
+    let count = match Leds::<S>::count() {
+        Ok(count) if count > 0 => count,
+        _ => halt::<S>(),
+    };
+    let delay = BlinkDelay::select::<S>();
+    let mut led = 0;
+    loop {
+        led = cycle_step::<S>(count, led);
+        delay.wait::<S>(CYCLE_INTERVAL_MS.load(Ordering::Relaxed));
+    }
+}
+
+/// Stops doing anything, without exiting. The process stays visible in the
+/// kernel's process list, which can help debugging.
+pub fn halt<S: Syscalls>() -> ! {
+    loop {
+        S::yield_wait();
+    }
+}
+
+/// Asks the kernel to restart the process.
+pub fn restart<S: Syscalls>() -> ! {
+    S::exit_restart(ErrorCode::Fail as u32)
+}
+
+#[cfg(target_os = "none")]
+#[panic_handler]
+fn panic_handler(info: &core::panic::PanicInfo) -> ! {
+    handle_panic::<libtock_runtime::TockSyscalls>(info.location())
+}
+
+#[cfg(all(target_os = "none", feature = "alloc"))]
+#[alloc_error_handler]
+fn alloc_error_handler(_layout: core::alloc::Layout) -> ! {
+    handle_out_of_memory::<libtock_runtime::TockSyscalls>()
+}
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+const BLINK_INTERVAL_MS: u32 = 100;
+
+// The status code reported for a panic on `line`.
+fn line_code(line: u32) -> u32 {
+    line & 0xffff
+}
+
+// Rough number of spin loop iterations in a millisecond, for a core running
+// at a few tens of MHz.
+const SPIN_ITERATIONS_PER_MS: u32 = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BlinkDelay {
+    Alarm,
+    Spin,
+}
+
+impl BlinkDelay {
+    fn select<S: Syscalls>() -> BlinkDelay {
+        match Alarm::<S>::get_frequency() {
+            Ok(_) => BlinkDelay::Alarm,
+            Err(_) => BlinkDelay::Spin,
+        }
+    }
+
+    fn wait<S: Syscalls>(self, interval_ms: u32) {
+        if self == BlinkDelay::Alarm && Alarm::<S>::sleep_for(Milliseconds(interval_ms)).is_ok() {
+            return;
+        }
+        for _ in 0..interval_ms.saturating_mul(SPIN_ITERATIONS_PER_MS) {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+fn toggle_all_leds<S: Syscalls>() {
+    for led in 0..Leds::<S>::count().unwrap_or(0) {
+        let _ = Leds::<S>::toggle(led);
+    }
+}
+
+// Turns off `led` and lights the next one, returning its index.
+fn cycle_step<S: Syscalls>(count: u32, led: u32) -> u32 {
+    let _ = Leds::<S>::off(led);
+    let next = (led + 1) % count;
+    let _ = Leds::<S>::on(next);
+    next
+}
+
+// The behaviors are stored as type-erased pointers, because there is no atomic
+// type for function pointers. A null pointer selects the default behavior.
+static PANIC_BEHAVIOR: AtomicPtr<()> = AtomicPtr::new(null_mut());
+static OUT_OF_MEMORY_BEHAVIOR: AtomicPtr<()> = AtomicPtr::new(null_mut());
+static CYCLE_INTERVAL_MS: AtomicU32 = AtomicU32::new(BLINK_INTERVAL_MS);
+
+fn store_behavior(hook: &AtomicPtr<()>, behavior: PanicBehavior) {
+    hook.store(behavior as *mut (), Ordering::Relaxed);
+}
+
+fn load_behavior(hook: &AtomicPtr<()>) -> Option<PanicBehavior> {
+    let behavior = hook.load(Ordering::Relaxed);
+    if behavior.is_null() {
+        return None;
+    }
+    // Safety: the only non-null values stored in a hook are `PanicBehavior`s,
+    // by store_behavior.
+    Some(unsafe { core::mem::transmute::<*mut (), PanicBehavior>(behavior) })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,137 @@
+extern crate std;
+
+use super::{AlertCode, BlinkDelay, BLINK_INTERVAL_MS, OUT_OF_MEMORY_ALERT};
+use core::ptr::null_mut;
+use core::sync::atomic::Ordering;
+use libtock_unittest::fake;
+use libtock_unittest::fake::Message;
+// The hooks are process-wide, while tests run in parallel, so only the `hooks`
+// test sets them. This restores the defaults when it finishes, even by
+// panicking.
+struct ResetHooks;
+
+impl Drop for ResetHooks {
+    fn drop(&mut self) {
+        super::PANIC_BEHAVIOR.store(null_mut(), Ordering::Relaxed);
+        super::OUT_OF_MEMORY_BEHAVIOR.store(null_mut(), Ordering::Relaxed);
+        super::set_cycle_interval(BLINK_INTERVAL_MS);
+    }
+}
+
+fn custom_behavior() -> ! {
+    panic!("custom panic behavior");
+}
+
+fn custom_out_of_memory_behavior() -> ! {
+    panic!("custom out of memory behavior");
+}
+
+#[test]
+fn hooks() {
+    let _reset = ResetHooks;
+    assert!(super::load_behavior(&super::PANIC_BEHAVIOR).is_none());
+    assert!(super::load_behavior(&super::OUT_OF_MEMORY_BEHAVIOR).is_none());
+    assert_eq!(
+        super::CYCLE_INTERVAL_MS.load(Ordering::Relaxed),
+        BLINK_INTERVAL_MS
+    );
+
+    let kernel = fake::Kernel::new();
+    let leds = fake::Leds::<2>::new();
+    let low_level_debug = fake::LowLevelDebug::new();
+    kernel.add_driver(&leds);
+    kernel.add_driver(&low_level_debug);
+
+    super::set_panic_behavior(custom_behavior);
+    let message = std::panic::catch_unwind(|| super::handle_panic::<fake::Syscalls>(None))
+        .unwrap_err()
+        .downcast::<&str>()
+        .unwrap();
+    assert_eq!(*message, "custom panic behavior");
+    assert_eq!(
+        low_level_debug.take_messages(),
+        [Message::AlertCode(AlertCode::Panic as u32)]
+    );
+
+    super::set_out_of_memory_behavior(custom_out_of_memory_behavior);
+    let message = std::panic::catch_unwind(super::handle_out_of_memory::<fake::Syscalls>)
+        .unwrap_err()
+        .downcast::<&str>()
+        .unwrap();
+    assert_eq!(*message, "custom out of memory behavior");
+    assert_eq!(
+        low_level_debug.take_messages(),
+        [Message::AlertCode(OUT_OF_MEMORY_ALERT)]
+    );
+
+    super::set_cycle_interval(250);
+    assert_eq!(super::CYCLE_INTERVAL_MS.load(Ordering::Relaxed), 250);
+}
+
+#[test]
+fn cycle_step() {
+    let kernel = fake::Kernel::new();
+    let leds = fake::Leds::<3>::new();
+    kernel.add_driver(&leds);
+
+    assert_eq!(super::cycle_step::<fake::Syscalls>(3, 0), 1);
+    assert_eq!(leds.get_led(0), Some(false));
+    assert_eq!(leds.get_led(1), Some(true));
+    assert_eq!(super::cycle_step::<fake::Syscalls>(3, 1), 2);
+    assert_eq!(super::cycle_step::<fake::Syscalls>(3, 2), 0);
+    assert_eq!(leds.get_led(0), Some(true));
+    assert_eq!(leds.get_led(1), Some(false));
+    assert_eq!(leds.get_led(2), Some(false));
+}
+
+#[test]
+fn line_code() {
+    assert_eq!(super::line_code(42), 42);
+    assert_eq!(super::line_code(0x1_2345), 0x2345);
+}
+
+#[test]
+fn toggle_all_leds() {
+    let kernel = fake::Kernel::new();
+    let leds = fake::Leds::<3>::new();
+    kernel.add_driver(&leds);
+
+    super::toggle_all_leds::<fake::Syscalls>();
+    for led in 0..3 {
+        assert_eq!(leds.get_led(led), Some(true));
+    }
+    super::toggle_all_leds::<fake::Syscalls>();
+    for led in 0..3 {
+        assert_eq!(leds.get_led(led), Some(false));
+    }
+}
+
+#[test]
+fn toggle_without_leds() {
+    let _kernel = fake::Kernel::new();
+    super::toggle_all_leds::<fake::Syscalls>();
+}
+
+#[test]
+fn blink_delay_without_alarm() {
+    let kernel = fake::Kernel::new();
+    let leds = fake::Leds::<1>::new();
+    kernel.add_driver(&leds);
+
+    assert_eq!(BlinkDelay::select::<fake::Syscalls>(), BlinkDelay::Spin);
+    kernel.take_syscall_log();
+    // The busy-wait fallback does not need the kernel.
+    BlinkDelay::Spin.wait::<fake::Syscalls>(1);
+    assert!(kernel.take_syscall_log().is_empty());
+}
+
+#[test]
+fn blink_delay_with_alarm() {
+    let kernel = fake::Kernel::new();
+    let alarm = fake::Alarm::new(1000);
+    kernel.add_driver(&alarm);
+
+    assert_eq!(BlinkDelay::select::<fake::Syscalls>(), BlinkDelay::Alarm);
+    BlinkDelay::Alarm.wait::<fake::Syscalls>(100);
+    assert!(alarm.get_now() >= 100);
+}
@@ -5,6 +5,15 @@ pub mod exit_id {
     pub const RESTART: u32 = 1;
 }
 
+pub mod memop_id {
+    pub const BRK: u32 = 0;
+    pub const SBRK: u32 = 1;
+    pub const MEMORY_START: u32 = 2;
+    pub const MEMORY_END: u32 = 3;
+    pub const FLASH_START: u32 = 4;
+    pub const GRANT_START: u32 = 6;
+}
+
 pub mod syscall_class {
     pub const SUBSCRIBE: usize = 1;
     pub const COMMAND: usize = 2;
@@ -0,0 +1,173 @@
+//! `DoubleBuffer` streams data from a driver that fills two Read-Write Allow
+//! buffers in turn, so the process can work on one buffer while the kernel
+//! fills the other.
+
+use crate::share;
+use crate::subscribe::AnyId;
+use crate::{
+    return_variant, syscall_class, DefaultConfig, ErrorCode, ReturnVariant, Subscribe, Syscalls,
+    Upcall,
+};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::ops::ControlFlow;
+
+/// A streaming operation on driver `DRIVER_NUM` that alternates between two
+/// buffers, shared as Read-Write Allows `ALLOW_A` and `ALLOW_B`. The driver
+/// fills `ALLOW_A` first, then `ALLOW_B`, and so on, and signals each full
+/// buffer with an upcall on `SUBSCRIBE_NUM`.
+///
+/// Running the stream shares both buffers and issues the start command. On
+/// each upcall, the full buffer is unallowed and handed to the process, while
+/// the kernel fills the other one. Once the process is done with it, the buffer
+/// is allowed again, ready to be filled after the other. A buffer is never
+/// handed to the process while it is shared with the kernel, and both buffers
+/// are unshared before returning, including when a step fails.
+///
+/// The driver only has one buffer to fill while the process works on the
+/// other, so data is lost if processing a buffer takes longer than the driver
+/// takes to fill one.
+///
+/// # Example
+/// ```ignore
+/// DoubleBuffer::<S, DRIVER_NUM, 0, ALLOW_A, ALLOW_B>::new(START, 0, 0, STOP).run(
+///     &mut buffer_a,
+///     &mut buffer_b,
+///     |samples| {
+///         process(samples);
+///         ControlFlow::Continue(())
+///     },
+/// )?;
+/// ```
+pub struct DoubleBuffer<
+    S: Syscalls,
+    const DRIVER_NUM: u32,
+    const SUBSCRIBE_NUM: u32,
+    const ALLOW_A: u32,
+    const ALLOW_B: u32,
+> {
+    command_id: u32,
+    argument0: u32,
+    argument1: u32,
+    stop_command_id: u32,
+    _syscalls: PhantomData<S>,
+}
+
+impl<
+        S: Syscalls,
+        const DRIVER_NUM: u32,
+        const SUBSCRIBE_NUM: u32,
+        const ALLOW_A: u32,
+        const ALLOW_B: u32,
+    > DoubleBuffer<S, DRIVER_NUM, SUBSCRIBE_NUM, ALLOW_A, ALLOW_B>
+{
+    /// Creates a stream started by command `command_id`, and stopped by
+    /// command `stop_command_id` (with zero arguments).
+    pub fn new(command_id: u32, argument0: u32, argument1: u32, stop_command_id: u32) -> Self {
+        DoubleBuffer {
+            command_id,
+            argument0,
+            argument1,
+            stop_command_id,
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Runs the stream, calling `process` with each buffer the driver fills,
+    /// starting with `a`. Streaming stops when `process` returns
+    /// `ControlFlow::Break`, after which the stop command is issued. The start
+    /// and stop commands must return a plain success.
+    pub fn run<F: FnMut(&mut [u8]) -> ControlFlow<()>>(
+        self,
+        a: &mut [u8],
+        b: &mut [u8],
+        mut process: F,
+    ) -> Result<(), ErrorCode> {
+        let filled = FilledCount(Cell::new(0));
+        share::scope::<Subscribe<_, DRIVER_NUM, SUBSCRIBE_NUM>, _, _>(|subscribe| {
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_NUM>(subscribe, &filled)?;
+            // Revokes both allows when the stream ends, however it ends.
+            let _unallow = Unallow::<S, DRIVER_NUM, ALLOW_A, ALLOW_B>(PhantomData);
+            // Safety: `_unallow` revokes both allows before `a` and `b` are
+            // released by this function. The buffers are only accessed again
+            // after their allow is revoked.
+            unsafe {
+                allow_rw::<S>(DRIVER_NUM, ALLOW_A, a)?;
+                allow_rw::<S>(DRIVER_NUM, ALLOW_B, b)?;
+            }
+            S::command(DRIVER_NUM, self.command_id, self.argument0, self.argument1)
+                .to_result::<(), ErrorCode>()?;
+
+            let mut buffers = [(ALLOW_A, a), (ALLOW_B, b)];
+            for turn in (0..buffers.len()).cycle() {
+                while filled.0.get() == 0 {
+                    S::yield_wait();
+                }
+                filled.0.set(filled.0.get() - 1);
+
+                let (allow_num, buffer) = &mut buffers[turn];
+                let allow_num = *allow_num;
+                S::unallow_rw(DRIVER_NUM, allow_num);
+                if process(buffer).is_break() {
+                    break;
+                }
+                // Safety: as above.
+                unsafe { allow_rw::<S>(DRIVER_NUM, allow_num, buffer) }?;
+            }
+            S::command(DRIVER_NUM, self.stop_command_id, 0, 0).to_result()
+        })
+    }
+}
+
+// Counts the buffers the driver has filled that have not been processed yet.
+struct FilledCount(Cell<u32>);
+
+impl Upcall<AnyId> for FilledCount {
+    fn upcall(&self, _: u32, _: u32, _: u32) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+struct Unallow<S: Syscalls, const DRIVER_NUM: u32, const ALLOW_A: u32, const ALLOW_B: u32>(
+    PhantomData<S>,
+);
+
+impl<S: Syscalls, const DRIVER_NUM: u32, const ALLOW_A: u32, const ALLOW_B: u32> Drop
+    for Unallow<S, DRIVER_NUM, ALLOW_A, ALLOW_B>
+{
+    fn drop(&mut self) {
+        S::unallow_rw(DRIVER_NUM, ALLOW_A);
+        S::unallow_rw(DRIVER_NUM, ALLOW_B);
+    }
+}
+
+// Shares `buffer` as Read-Write Allow `buffer_num`. This is the unchecked
+// counterpart of `Syscalls::allow_rw`, which ties the buffer to a
+// `share::scope` and therefore cannot share it again after it is unallowed.
+//
+// Safety: the caller must revoke the allow before `buffer` is released, and
+// must not access `buffer` until then.
+unsafe fn allow_rw<S: Syscalls>(
+    driver_num: u32,
+    buffer_num: u32,
+    buffer: &mut [u8],
+) -> Result<(), ErrorCode> {
+    // Safety: syscall4's documentation indicates it can be used to call
+    // Read-Write Allow. These arguments follow TRD104, and the caller keeps
+    // the buffer valid for as long as it is shared.
+    let [r0, r1, _, _] = unsafe {
+        S::syscall4::<{ syscall_class::ALLOW_RW }>([
+            driver_num.into(),
+            buffer_num.into(),
+            buffer.as_mut_ptr().into(),
+            buffer.len().into(),
+        ])
+    };
+    let return_variant: ReturnVariant = r0.as_u32().into();
+    if return_variant == return_variant::FAILURE_2_U32 {
+        // Safety: TRD 104 guarantees that if r0 is Failure with 2 U32, then r1
+        // will contain a valid error code.
+        return Err(unsafe { core::mem::transmute(r1.as_u32()) });
+    }
+    Ok(())
+}
@@ -272,6 +272,14 @@ impl fmt::Debug for ErrorCode {
     }
 }
 
+/// Displays the error code's name from TRD 104 (e.g. `BUSY`), or `code N` for
+/// error codes reserved for future use.
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
 impl TryFrom<u32> for ErrorCode {
     type Error = NotAnErrorCode;
 
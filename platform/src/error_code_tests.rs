@@ -20,3 +20,28 @@ fn error_code_try_into() {
     }
     assert_eq!(TryInto::<ErrorCode>::try_into(1025u32), Err(NotAnErrorCode));
 }
+
+#[test]
+fn error_code_display() {
+    let names = [
+        (ErrorCode::Fail, "FAIL"),
+        (ErrorCode::Busy, "BUSY"),
+        (ErrorCode::Already, "ALREADY"),
+        (ErrorCode::Off, "OFF"),
+        (ErrorCode::Reserve, "RESERVE"),
+        (ErrorCode::Invalid, "INVALID"),
+        (ErrorCode::Size, "SIZE"),
+        (ErrorCode::Cancel, "CANCEL"),
+        (ErrorCode::NoMem, "NOMEM"),
+        (ErrorCode::NoSupport, "NOSUPPORT"),
+        (ErrorCode::NoDevice, "NODEVICE"),
+        (ErrorCode::Uninstalled, "UNINSTALLED"),
+        (ErrorCode::NoAck, "NOACK"),
+        (ErrorCode::BadRVal, "BADRVAL"),
+    ];
+    for (error_code, name) in names {
+        assert_eq!(format!("{}", error_code), name);
+    }
+    assert_eq!(format!("{}", ErrorCode::N00014), "code 14");
+    assert_eq!(format!("error: {}", ErrorCode::Invalid), "error: INVALID");
+}
@@ -0,0 +1,16 @@
+/// The result of [`Syscalls::select2`](crate::Syscalls::select2): which of
+/// the two waited-for events happened first, with its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Select2<A, B> {
+    First(A),
+    Second(B),
+}
+
+/// The result of [`Syscalls::select3`](crate::Syscalls::select3): which of
+/// the three waited-for events happened first, with its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Select3<A, B, C> {
+    First(A),
+    Second(B),
+    Third(C),
+}
@@ -1,6 +1,6 @@
 use crate::{
     allow_ro, allow_rw, share, subscribe, AllowRo, AllowRw, CommandReturn, ErrorCode, RawSyscalls,
-    Subscribe, Upcall, YieldNoWaitReturn,
+    Select2, Select3, Subscribe, Upcall, YieldNoWaitReturn,
 };
 
 /// `Syscalls` provides safe abstractions over Tock's system calls. It is
@@ -14,12 +14,76 @@ pub trait Syscalls: RawSyscalls + Sized {
     /// Runs the next pending callback, if a callback is pending. Unlike
     /// `yield_wait`, `yield_no_wait` returns immediately if no callback is
     /// pending.
+    ///
+    /// A duty-cycled app can drain pending callbacks with `yield_no_wait`,
+    /// then call `yield_wait` once it finds none, which lets the kernel put the
+    /// chip to sleep:
+    /// ```ignore
+    /// loop {
+    ///     while S::yield_no_wait().into() {}
+    ///     do_work();
+    ///     S::yield_wait();
+    /// }
+    /// ```
     fn yield_no_wait() -> YieldNoWaitReturn;
 
     /// Puts the process to sleep until a callback becomes pending, invokes the
-    /// callback, then returns.
+    /// callback, then returns. The blocking helpers in libtock-rs' APIs (such
+    /// as `Alarm::sleep_for`) call this in a loop until their operation
+    /// completes.
     fn yield_wait();
 
+    /// Calls `yield_wait` until `predicate` returns true, for event loops
+    /// waiting on flags set by several upcalls. `predicate` is checked before
+    /// each yield, so this returns immediately if it is already satisfied.
+    fn yield_until<F: Fn() -> bool>(predicate: F);
+
+    /// Yields until either `first` or `second` returns a value, and returns
+    /// which one did. Each is polled before every yield, `first` before
+    /// `second`, so the other is not polled again once one completes.
+    ///
+    /// The events are typically upcalls subscribed in an enclosing
+    /// `share::scope`, such as a button press and an alarm. Leaving that scope
+    /// unsubscribes the upcall of the event that did not happen, so it cannot
+    /// fire into state that no longer exists; an operation it started may
+    /// need to be cancelled through its own driver.
+    fn select2<A, B, FA: FnMut() -> Option<A>, FB: FnMut() -> Option<B>>(
+        first: FA,
+        second: FB,
+    ) -> Select2<A, B>;
+
+    /// Like `select2`, for three events.
+    fn select3<
+        A,
+        B,
+        C,
+        FA: FnMut() -> Option<A>,
+        FB: FnMut() -> Option<B>,
+        FC: FnMut() -> Option<C>,
+    >(
+        first: FA,
+        second: FB,
+        third: FC,
+    ) -> Select3<A, B, C>;
+
+    /// Yields until both `first` and `second` have returned a value, and
+    /// returns both. Each is polled before every yield until it completes, so
+    /// operations started beforehand (e.g. reads from two different sensors)
+    /// overlap. If either returns an error, that error is returned right
+    /// away; as with `select2`, leaving the enclosing `share::scope`
+    /// unsubscribes the upcall of the other.
+    fn join2<A, B, E, FA, FB>(first: FA, second: FB) -> Result<(A, B), E>
+    where
+        FA: FnMut() -> Option<Result<A, E>>,
+        FB: FnMut() -> Option<Result<B, E>>;
+
+    /// Like `join2`, for three operations.
+    fn join3<A, B, C, E, FA, FB, FC>(first: FA, second: FB, third: FC) -> Result<(A, B, C), E>
+    where
+        FA: FnMut() -> Option<Result<A, E>>,
+        FB: FnMut() -> Option<Result<B, E>>,
+        FC: FnMut() -> Option<Result<C, E>>;
+
     // -------------------------------------------------------------------------
     // Subscribe
     // -------------------------------------------------------------------------
@@ -77,7 +141,44 @@ pub trait Syscalls: RawSyscalls + Sized {
     /// `unallow_ro` does nothing.
     fn unallow_ro(driver_num: u32, buffer_num: u32);
 
-    // TODO: Add memop() methods.
+    // -------------------------------------------------------------------------
+    // Memop
+    // -------------------------------------------------------------------------
+
+    /// Sets the process' memory break (the end of its accessible RAM) to
+    /// `address`.
+    ///
+    /// # Safety
+    /// Memory above the new break becomes inaccessible, so no live references
+    /// may point past `address`.
+    unsafe fn memop_brk(address: *mut u8) -> Result<(), ErrorCode>;
+
+    /// Moves the process' memory break by `increment` bytes, and returns the
+    /// previous break, which is the start of the newly-accessible memory when
+    /// `increment` is positive.
+    ///
+    /// # Safety
+    /// When `increment` is negative, memory above the new break becomes
+    /// inaccessible, so no live references may point into it.
+    unsafe fn memop_sbrk(increment: i32) -> Result<*mut u8, ErrorCode>;
+
+    /// Returns the address of the start of the process' RAM.
+    fn memory_start() -> Result<*mut u8, ErrorCode>;
+
+    /// Returns the address just past the end of the process' RAM region. The
+    /// break can be moved up to this address.
+    fn memory_end() -> Result<*mut u8, ErrorCode>;
+
+    /// Returns the current memory break, which is the end of the heap.
+    fn app_heap_break() -> Result<*mut u8, ErrorCode>;
+
+    /// Returns the address of the start of the process' flash region.
+    fn flash_start() -> Result<*const u8, ErrorCode>;
+
+    /// Returns the address of the start of the grant region, where the kernel
+    /// keeps its per-process state, at the top of the process' RAM region.
+    /// The break cannot be moved above this address.
+    fn grant_start() -> Result<*mut u8, ErrorCode>;
 
     // -------------------------------------------------------------------------
     // Exit
@@ -1,9 +1,9 @@
 //! Implements `Syscalls` for all types that implement `RawSyscalls`.
 
 use crate::{
-    allow_ro, allow_rw, exit_id, exit_on_drop, return_variant, share, subscribe, syscall_class,
-    yield_id, AllowRo, AllowRw, CommandReturn, ErrorCode, RawSyscalls, Register, ReturnVariant,
-    Subscribe, Syscalls, Upcall, YieldNoWaitReturn,
+    allow_ro, allow_rw, exit_id, exit_on_drop, memop_id, return_variant, share, subscribe,
+    syscall_class, yield_id, AllowRo, AllowRw, CommandReturn, ErrorCode, RawSyscalls, Register,
+    ReturnVariant, Select2, Select3, Subscribe, Syscalls, Upcall, YieldNoWaitReturn,
 };
 
 impl<S: RawSyscalls> Syscalls for S {
@@ -35,6 +35,93 @@ impl<S: RawSyscalls> Syscalls for S {
         }
     }
 
+    fn yield_until<F: Fn() -> bool>(predicate: F) {
+        while !predicate() {
+            Self::yield_wait();
+        }
+    }
+
+    fn select2<A, B, FA: FnMut() -> Option<A>, FB: FnMut() -> Option<B>>(
+        mut first: FA,
+        mut second: FB,
+    ) -> Select2<A, B> {
+        loop {
+            if let Some(a) = first() {
+                return Select2::First(a);
+            }
+            if let Some(b) = second() {
+                return Select2::Second(b);
+            }
+            Self::yield_wait();
+        }
+    }
+
+    fn select3<
+        A,
+        B,
+        C,
+        FA: FnMut() -> Option<A>,
+        FB: FnMut() -> Option<B>,
+        FC: FnMut() -> Option<C>,
+    >(
+        mut first: FA,
+        mut second: FB,
+        mut third: FC,
+    ) -> Select3<A, B, C> {
+        loop {
+            if let Some(a) = first() {
+                return Select3::First(a);
+            }
+            if let Some(b) = second() {
+                return Select3::Second(b);
+            }
+            if let Some(c) = third() {
+                return Select3::Third(c);
+            }
+            Self::yield_wait();
+        }
+    }
+
+    fn join2<A, B, E, FA, FB>(mut first: FA, mut second: FB) -> Result<(A, B), E>
+    where
+        FA: FnMut() -> Option<Result<A, E>>,
+        FB: FnMut() -> Option<Result<B, E>>,
+    {
+        let (mut a, mut b) = (None, None);
+        loop {
+            poll_join(&mut a, &mut first)?;
+            poll_join(&mut b, &mut second)?;
+            (a, b) = match (a, b) {
+                (Some(a), Some(b)) => return Ok((a, b)),
+                pending => pending,
+            };
+            Self::yield_wait();
+        }
+    }
+
+    fn join3<A, B, C, E, FA, FB, FC>(
+        mut first: FA,
+        mut second: FB,
+        mut third: FC,
+    ) -> Result<(A, B, C), E>
+    where
+        FA: FnMut() -> Option<Result<A, E>>,
+        FB: FnMut() -> Option<Result<B, E>>,
+        FC: FnMut() -> Option<Result<C, E>>,
+    {
+        let (mut a, mut b, mut c) = (None, None, None);
+        loop {
+            poll_join(&mut a, &mut first)?;
+            poll_join(&mut b, &mut second)?;
+            poll_join(&mut c, &mut third)?;
+            (a, b, c) = match (a, b, c) {
+                (Some(a), Some(b), Some(c)) => return Ok((a, b, c)),
+                pending => pending,
+            };
+            Self::yield_wait();
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Subscribe
     // -------------------------------------------------------------------------
@@ -352,6 +439,65 @@ impl<S: RawSyscalls> Syscalls for S {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // Memop
+    // -------------------------------------------------------------------------
+
+    unsafe fn memop_brk(address: *mut u8) -> Result<(), ErrorCode> {
+        // Safety: brk does not modify memory the process can access, other
+        // than by revoking access above the new break, which the caller is
+        // responsible for.
+        let result = unsafe {
+            Self::syscall2::<{ syscall_class::MEMOP }>([memop_id::BRK.into(), address.into()])
+        };
+        memop_result(result).map(|_| ())
+    }
+
+    unsafe fn memop_sbrk(increment: i32) -> Result<*mut u8, ErrorCode> {
+        // Safety: see memop_brk. The increment is passed as a signed integer,
+        // sign-extended to the register size.
+        let result = unsafe {
+            Self::syscall2::<{ syscall_class::MEMOP }>([
+                memop_id::SBRK.into(),
+                (increment as isize as usize).into(),
+            ])
+        };
+        memop_result(result).map(Into::into)
+    }
+
+    fn memory_start() -> Result<*mut u8, ErrorCode> {
+        // Safety: querying the memory layout cannot cause undefined behavior.
+        let result =
+            unsafe { Self::syscall1::<{ syscall_class::MEMOP }>([memop_id::MEMORY_START.into()]) };
+        memop_result(result).map(Into::into)
+    }
+
+    fn memory_end() -> Result<*mut u8, ErrorCode> {
+        // Safety: querying the memory layout cannot cause undefined behavior.
+        let result =
+            unsafe { Self::syscall1::<{ syscall_class::MEMOP }>([memop_id::MEMORY_END.into()]) };
+        memop_result(result).map(Into::into)
+    }
+
+    fn app_heap_break() -> Result<*mut u8, ErrorCode> {
+        // Safety: sbrk(0) does not move the break.
+        unsafe { Self::memop_sbrk(0) }
+    }
+
+    fn flash_start() -> Result<*const u8, ErrorCode> {
+        // Safety: querying the memory layout cannot cause undefined behavior.
+        let result =
+            unsafe { Self::syscall1::<{ syscall_class::MEMOP }>([memop_id::FLASH_START.into()]) };
+        memop_result(result).map(Into::into)
+    }
+
+    fn grant_start() -> Result<*mut u8, ErrorCode> {
+        // Safety: querying the memory layout cannot cause undefined behavior.
+        let result =
+            unsafe { Self::syscall1::<{ syscall_class::MEMOP }>([memop_id::GRANT_START.into()]) };
+        memop_result(result).map(Into::into)
+    }
+
     // -------------------------------------------------------------------------
     // Exit
     // -------------------------------------------------------------------------
@@ -383,3 +529,29 @@ impl<S: RawSyscalls> Syscalls for S {
         }
     }
 }
+
+// Decodes the return value of a Memop call, which is either a Success, a
+// Success with u32 (returned as the register), or a Failure.
+fn memop_result([r0, r1]: [Register; 2]) -> Result<Register, ErrorCode> {
+    match ReturnVariant::from(r0.as_u32()) {
+        return_variant::SUCCESS | return_variant::SUCCESS_U32 => Ok(r1),
+        return_variant::FAILURE => {
+            Err(ErrorCode::try_from(r1.as_u32()).unwrap_or(ErrorCode::BadRVal))
+        }
+        _ => Err(ErrorCode::BadRVal),
+    }
+}
+
+// Polls `poll` unless `output` already holds its value, storing the value once
+// it completes.
+fn poll_join<T, E, F: FnMut() -> Option<Result<T, E>>>(
+    output: &mut Option<T>,
+    poll: &mut F,
+) -> Result<(), E> {
+    if output.is_none() {
+        if let Some(result) = poll() {
+            *output = Some(result?);
+        }
+    }
+    Ok(())
+}
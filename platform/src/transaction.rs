@@ -0,0 +1,107 @@
+//! `DriverTransaction` performs the subscribe, allow, command, and wait for
+//! upcall sequence that most blocking driver operations consist of.
+
+use crate::share;
+use crate::{AllowRo, AllowRw, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+/// A command to driver `DRIVER_NUM` that completes with an upcall on
+/// `SUBSCRIBE_NUM`, optionally with a buffer shared for its duration.
+///
+/// Running the transaction subscribes to the upcall, allows the buffer (if
+/// any), issues the command, then yields until the upcall arrives and returns
+/// its arguments. The command must return a plain success. The buffer and
+/// upcall are always unshared before returning, including when a step fails.
+///
+/// # Example
+/// ```ignore
+/// let (status, len, _) = DriverTransaction::<S, DRIVER_NUM, SUBSCRIBE_NUM>::new(SEND, 0, 0)
+///     .run_with_allow_ro::<ALLOW_NUM>(data)?;
+/// ```
+pub struct DriverTransaction<S: Syscalls, const DRIVER_NUM: u32, const SUBSCRIBE_NUM: u32> {
+    command_id: u32,
+    argument0: u32,
+    argument1: u32,
+    _syscalls: PhantomData<S>,
+}
+
+impl<S: Syscalls, const DRIVER_NUM: u32, const SUBSCRIBE_NUM: u32>
+    DriverTransaction<S, DRIVER_NUM, SUBSCRIBE_NUM>
+{
+    pub fn new(command_id: u32, argument0: u32, argument1: u32) -> Self {
+        DriverTransaction {
+            command_id,
+            argument0,
+            argument1,
+            _syscalls: PhantomData,
+        }
+    }
+
+    /// Runs the transaction without sharing a buffer.
+    pub fn run(self) -> Result<(u32, u32, u32), ErrorCode> {
+        let done: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope::<Subscribe<_, DRIVER_NUM, SUBSCRIBE_NUM>, _, _>(|subscribe| {
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_NUM>(subscribe, &done)?;
+            self.command_and_wait(&done)
+        })
+    }
+
+    /// Runs the transaction with `buffer` shared as read-only allow
+    /// `ALLOW_NUM`.
+    pub fn run_with_allow_ro<const ALLOW_NUM: u32>(
+        self,
+        buffer: &[u8],
+    ) -> Result<(u32, u32, u32), ErrorCode> {
+        let done: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRo<_, DRIVER_NUM, ALLOW_NUM>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_NUM>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_ro, subscribe) = handle.split();
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_NUM>(subscribe, &done)?;
+            S::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_NUM>(allow_ro, buffer)?;
+            self.command_and_wait(&done)
+        })
+    }
+
+    /// Runs the transaction with `buffer` shared as read-write allow
+    /// `ALLOW_NUM`.
+    pub fn run_with_allow_rw<const ALLOW_NUM: u32>(
+        self,
+        buffer: &mut [u8],
+    ) -> Result<(u32, u32, u32), ErrorCode> {
+        let done: Cell<Option<(u32, u32, u32)>> = Cell::new(None);
+        share::scope::<
+            (
+                AllowRw<_, DRIVER_NUM, ALLOW_NUM>,
+                Subscribe<_, DRIVER_NUM, SUBSCRIBE_NUM>,
+            ),
+            _,
+            _,
+        >(|handle| {
+            let (allow_rw, subscribe) = handle.split();
+            S::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_NUM>(subscribe, &done)?;
+            S::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_NUM>(allow_rw, buffer)?;
+            self.command_and_wait(&done)
+        })
+    }
+
+    fn command_and_wait(
+        &self,
+        done: &Cell<Option<(u32, u32, u32)>>,
+    ) -> Result<(u32, u32, u32), ErrorCode> {
+        S::command(DRIVER_NUM, self.command_id, self.argument0, self.argument1)
+            .to_result::<(), ErrorCode>()?;
+        loop {
+            match done.get() {
+                Some(args) => return Ok(args),
+                None => S::yield_wait(),
+            }
+        }
+    }
+}
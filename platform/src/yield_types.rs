@@ -13,3 +13,10 @@ pub enum YieldNoWaitReturn {
     NoUpcall = 0,
     Upcall = 1,
 }
+
+/// `true` if an upcall was run.
+impl From<YieldNoWaitReturn> for bool {
+    fn from(value: YieldNoWaitReturn) -> bool {
+        value == YieldNoWaitReturn::Upcall
+    }
+}
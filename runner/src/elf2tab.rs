@@ -40,6 +40,7 @@ pub fn convert_elf(cli: &Cli, platform: &str) -> OutFiles {
         println!("Protected region size: {}", protected_size);
     }
     let stack_size = read_stack_size(cli);
+    let heap_size = read_heap_size(cli);
     let elf = cli.elf.as_os_str();
     let mut tbf_path = cli.elf.clone();
     tbf_path.set_extension("tbf");
@@ -73,8 +74,11 @@ pub fn convert_elf(cli: &Cli, platform: &str) -> OutFiles {
         "-o".as_ref(), tab_path.as_os_str(),
         "--protected-region-size".as_ref(), protected_size.as_ref(),
         "--stack".as_ref(), stack_size.as_ref(),
-        format!("{},{}", elf.to_str().unwrap(), architecture).as_ref(),
     ]);
+    if let Some(heap_size) = &heap_size {
+        command.args(["--app-heap", heap_size.as_str()]);
+    }
+    command.arg(format!("{},{}", elf.to_str().unwrap(), architecture));
     if cli.verbose {
         command.arg("-v");
         println!("elf2tab command: {:?}", command);
@@ -136,3 +140,20 @@ fn read_stack_size(cli: &Cli) -> String {
 
     panic!("Unable to find the .stack section in {}", cli.elf.display());
 }
+
+// Reads the size of the heap reserved by `heap_size!`, and returns it as a
+// String for use on elf2tab's command line. Returns None if the process binary
+// does not reserve a heap, in which case elf2tab's default is used.
+fn read_heap_size(cli: &Cli) -> Option<String> {
+    let file = elf::File::open_path(&cli.elf).expect("Unable to open ELF");
+    // This section name comes from runtime/libtock_layout.ld.
+    let section = file
+        .sections
+        .iter()
+        .find(|section| section.shdr.name == ".heap" && section.shdr.size > 0)?;
+    let heap_size = section.shdr.size.to_string();
+    if cli.verbose {
+        println!("Found .heap section, size: {}", heap_size);
+    }
+    Some(heap_size)
+}
@@ -56,6 +56,28 @@ macro_rules! stack_size {
     }
 }
 
+/// Executables may reserve a fixed-size heap by using the `heap_size!` macro.
+/// It takes a single argument, the heap size in bytes. Example:
+/// ```
+/// heap_size!{0x1000}
+/// ```
+/// The heap is placed after `.bss`, and the process break initially covers it,
+/// so a process that does not fit in its RAM region fails to link. When using
+/// `libtock_allocator`, the global allocator only allocates from this heap, and
+/// allocations that do not fit in it fail. Without `heap_size!`, the heap starts
+/// empty and the allocator grows it on demand until the kernel refuses.
+// heap_size works like stack_size: the linker script places the .heap_buffer
+// section between the _heap_start and _heap_end symbols, which
+// libtock_allocator reads, and elf2tab is told its size.
+#[macro_export]
+macro_rules! heap_size {
+    {$size:expr} => {
+        #[no_mangle]
+        #[link_section = ".heap_buffer"]
+        pub static mut HEAP_MEMORY: [u8; $size] = [0; $size];
+    }
+}
+
 /// This is public for the sake of making `set_main!` usable in other crates.
 /// It doesn't have another function.
 pub fn handle_main_return<T: Termination>(result: T) -> ! {
@@ -6,44 +6,221 @@ extern crate libtock_debug_panic;
 pub use libtock_platform as platform;
 pub use libtock_runtime as runtime;
 
+pub mod adc {
+    use libtock_adc as adc;
+    pub type Adc = adc::Adc<super::runtime::TockSyscalls>;
+    pub use adc::AdcListener;
+}
+pub mod aes {
+    use libtock_aes as aes;
+    pub type AesCtr = aes::AesCtr<super::runtime::TockSyscalls>;
+}
 pub mod alarm {
     use libtock_alarm as alarm;
     pub type Alarm = alarm::Alarm<super::runtime::TockSyscalls>;
-    pub use alarm::{Convert, Hz, Milliseconds, Ticks};
+    pub type Instant = alarm::Instant<super::runtime::TockSyscalls>;
+    pub type PeriodicTimer = alarm::PeriodicTimer<super::runtime::TockSyscalls>;
+    pub type Stopwatch = alarm::Stopwatch<super::runtime::TockSyscalls>;
+    pub use alarm::{
+        Convert, Hz, Microseconds, Milliseconds, MissedTickPolicy, Seconds, Ticks, TimeoutError,
+    };
+}
+pub mod ambient_light {
+    use libtock_ambient_light as ambient_light;
+    pub type AmbientLight = ambient_light::AmbientLight<super::runtime::TockSyscalls>;
+}
+pub mod analog_comparator {
+    use libtock_analog_comparator as analog_comparator;
+    pub type AnalogComparator = analog_comparator::AnalogComparator<super::runtime::TockSyscalls>;
+    pub use analog_comparator::AnalogComparatorListener;
+}
+pub mod app_state {
+    use libtock_app_state as app_state;
+    pub type AppState = app_state::AppState<super::runtime::TockSyscalls>;
 }
 pub mod buttons {
     use libtock_buttons as buttons;
     pub type Buttons = buttons::Buttons<super::runtime::TockSyscalls>;
-    pub use buttons::{ButtonListener, ButtonState};
+    pub use buttons::{ButtonListener, ButtonState, PressKind};
+}
+pub mod buzzer {
+    use libtock_buzzer as buzzer;
+    pub type Buzzer = buzzer::Buzzer<super::runtime::TockSyscalls>;
+    pub use buzzer::{Note, Pitch};
+}
+pub mod can {
+    use libtock_can as can;
+    pub type Can = can::Can<super::runtime::TockSyscalls>;
+    pub use can::CanFrame;
+}
+pub mod capsules {
+    pub use capsules::{CapsuleRegistry, Probe};
+    use libtock_capsules as capsules;
 }
 pub mod console {
     use libtock_console as console;
     pub type Console = console::Console<super::runtime::TockSyscalls>;
-    pub use console::ConsoleWriter;
+    pub use console::{console_print, console_println, ConsoleWriter};
+}
+pub mod crc {
+    use libtock_crc as crc;
+    pub type Crc = crc::Crc<super::runtime::TockSyscalls>;
+    pub use crc::Algorithm;
+}
+pub mod ctap {
+    use libtock_ctap as ctap;
+    pub type Ctap<'buffers> = ctap::Ctap<'buffers, super::runtime::TockSyscalls>;
+    pub use ctap::REPORT_LEN;
 }
 pub mod gpio {
     use libtock_gpio as gpio;
     pub type Gpio = gpio::Gpio<super::runtime::TockSyscalls>;
+    pub type SoftPwm = gpio::SoftPwm<super::runtime::TockSyscalls>;
     pub use gpio::{
-        Error, GpioInterruptListener, GpioState, InputPin, OutputPin, PinInterruptEdge, Pull,
-        PullDown, PullNone, PullUp,
+        Error, GpioInterruptListener, GpioState, InputPin, OutputMode, OutputPin, PinInterruptEdge,
+        Pull, PullDown, PullNone, PullUp,
     };
 }
+pub mod hmac {
+    use libtock_hmac as hmac;
+    pub type HmacSha256<'key> = hmac::HmacSha256<'key, super::runtime::TockSyscalls>;
+}
+pub mod humidity {
+    use libtock_humidity as humidity;
+    pub type Humidity = humidity::Humidity<super::runtime::TockSyscalls>;
+}
+pub mod i2c_master {
+    use libtock_i2c_master as i2c_master;
+    pub type I2cMaster = i2c_master::I2cMaster<super::runtime::TockSyscalls>;
+}
+pub mod ieee802154 {
+    use libtock_ieee802154 as ieee802154;
+    pub type Ieee802154 = ieee802154::Ieee802154<super::runtime::TockSyscalls>;
+    pub use ieee802154::{RxFrame, BROADCAST_ADDR};
+}
+pub mod kv {
+    use libtock_kv as kv;
+    pub type KeyValue = kv::KeyValue<super::runtime::TockSyscalls>;
+    pub use kv::KvError;
+}
 pub mod leds {
     use libtock_leds as leds;
     pub type Leds = leds::Leds<super::runtime::TockSyscalls>;
+    pub type PwmLed = leds::PwmLed<super::runtime::TockSyscalls>;
+    pub use leds::{LedAnimation, FADE_INTERVAL_MS};
+    pub type TrackedLeds = leds::TrackedLeds<super::runtime::TockSyscalls>;
 }
 pub mod low_level_debug {
     use libtock_low_level_debug as lldb;
     pub type LowLevelDebug = lldb::LowLevelDebug<super::runtime::TockSyscalls>;
-    pub use lldb::AlertCode;
+    pub use lldb::{debug_check, AlertCode};
+}
+pub mod net {
+    use libtock_net as net;
+    pub type UdpSocket = net::UdpSocket<super::runtime::TockSyscalls>;
+    pub use net::Ipv6Addr;
+}
+pub mod ninedof {
+    use libtock_ninedof as ninedof;
+    pub type NineDof = ninedof::NineDof<super::runtime::TockSyscalls>;
+}
+pub mod process_info {
+    use libtock_process_info as process_info;
+    pub type ProcessInfo = process_info::ProcessInfo<super::runtime::TockSyscalls>;
 }
 pub mod proximity {
     use libtock_proximity as proximity;
     pub type Proximity = proximity::Proximity<super::runtime::TockSyscalls>;
 }
+pub mod pwm {
+    use libtock_pwm as pwm;
+    pub type Pwm = pwm::Pwm<super::runtime::TockSyscalls>;
+    pub use pwm::DEFAULT_FREQUENCY_HZ;
+}
+pub mod reset {
+    use libtock_reset as reset;
+    pub type Reset = reset::Reset<super::runtime::TockSyscalls>;
+}
+pub mod rng {
+    use libtock_rng as rng;
+    pub type Rng = rng::Rng<super::runtime::TockSyscalls>;
+    pub use rng::DeterministicRng;
+}
+pub mod screen {
+    use libtock_screen as screen;
+    pub type Screen = screen::Screen<super::runtime::TockSyscalls>;
+    pub type Backbuffer<'buf> = screen::Backbuffer<'buf, super::runtime::TockSyscalls>;
+    pub use screen::PixelFormat;
+}
+pub mod sensor {
+    pub use libtock_sensor::{Sensor, Unit};
+}
+pub mod servo {
+    use libtock_servo as servo;
+    pub type Servo = servo::Servo<super::runtime::TockSyscalls>;
+}
+pub mod sha {
+    use libtock_sha as sha;
+    pub type Sha256 = sha::Sha256<super::runtime::TockSyscalls>;
+}
+pub mod sound_pressure {
+    use libtock_sound_pressure as sound_pressure;
+    pub type SoundPressure = sound_pressure::SoundPressure<super::runtime::TockSyscalls>;
+}
+pub mod spi_controller {
+    use libtock_spi_controller as spi_controller;
+    pub type SpiController = spi_controller::SpiController<super::runtime::TockSyscalls>;
+    pub use spi_controller::{Phase, Polarity};
+}
 pub mod temperature {
     use libtock_temperature as temperature;
     pub type Temperature = temperature::Temperature<super::runtime::TockSyscalls>;
-    pub use temperature::TemperatureListener;
+    pub type CachedTemperature = temperature::CachedTemperature<super::runtime::TockSyscalls>;
+    pub type TemperatureWatch = temperature::TemperatureWatch<super::runtime::TockSyscalls>;
+    pub use temperature::{TemperatureListener, Transition};
+}
+pub mod text_screen {
+    use libtock_text_screen as text_screen;
+    pub type TextScreen = text_screen::TextScreen<super::runtime::TockSyscalls>;
+}
+pub mod watchdog {
+    use libtock_watchdog as watchdog;
+    pub type Watchdog = watchdog::Watchdog<super::runtime::TockSyscalls>;
+}
+
+/// Checks `cond` in debug builds, and prints `code` through the low-level
+/// debug capsule if it is false. Unlike `debug_assert!`, a failed check does
+/// not panic, so the app keeps running; in release builds, it does nothing.
+///
+/// # Example
+/// ```ignore
+/// libtock::tock_debug_assert!(reading < 100, 0x10);
+/// ```
+#[macro_export]
+macro_rules! tock_debug_assert {
+    ($cond:expr, $code:expr $(,)?) => {
+        $crate::low_level_debug::debug_check!($crate::low_level_debug::LowLevelDebug, $cond, $code)
+    };
+}
+
+/// Formats its arguments, like `std`'s `print!`, and writes them to the
+/// console. Output is silently dropped if the console driver is absent.
+///
+/// # Example
+/// ```ignore
+/// libtock::print!("{} samples", count);
+/// ```
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::console::console_print!($crate::console::Console, $($arg)*)
+    };
+}
+
+/// Like [`print!`], followed by a newline.
+#[macro_export]
+macro_rules! println {
+    ($($arg:tt)*) => {
+        $crate::console::console_println!($crate::console::Console, $($arg)*)
+    };
 }
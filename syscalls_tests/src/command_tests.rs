@@ -1,7 +1,7 @@
 //! Tests for the Command system call implementation in
 //! `libtock_platform::Syscalls`.
 
-use libtock_platform::Syscalls;
+use libtock_platform::{ErrorCode, Syscalls};
 use libtock_unittest::{command_return, fake, ExpectedSyscall, SyscallLogEntry};
 
 #[test]
@@ -28,3 +28,25 @@ fn command() {
         }]
     );
 }
+
+#[test]
+fn command_2_u32() {
+    let kernel = fake::Kernel::new();
+    for _ in 0..2 {
+        kernel.add_expected_syscall(ExpectedSyscall::Command {
+            driver_id: 1,
+            command_id: 2,
+            argument0: 0,
+            argument1: 0,
+            override_return: Some(command_return::success_2_u32(5, 6)),
+        });
+    }
+    assert_eq!(
+        fake::Syscalls::command(1, 2, 0, 0).get_success_2_u32(),
+        Some((5, 6))
+    );
+    assert_eq!(
+        fake::Syscalls::command(1, 2, 0, 0).to_result::<(u32, u32), ErrorCode>(),
+        Ok((5, 6))
+    );
+}
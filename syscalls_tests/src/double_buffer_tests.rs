@@ -0,0 +1,166 @@
+use core::ops::ControlFlow;
+use libtock_platform::{CommandReturn, DoubleBuffer, ErrorCode};
+use libtock_unittest::{
+    command_return, fake, DriverInfo, DriverShareRef, RwAllowBuffer, SyscallLogEntry,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+// Streams into Read-Write Allows 0 and 1. Command 1 starts the stream and
+// fills buffer 0, command 2 stops it, and the test fills the following
+// buffers with `fill`.
+#[derive(Default)]
+struct TestDriver {
+    buffers: [RefCell<RwAllowBuffer>; 2],
+    streaming: Cell<bool>,
+    share_ref: DriverShareRef,
+}
+
+impl TestDriver {
+    fn is_allowed(&self, buffer_num: u32) -> bool {
+        !self.buffers[buffer_num as usize].borrow().is_empty()
+    }
+
+    // Fills buffer `buffer_num` with `value` and reports it full.
+    fn fill(&self, buffer_num: u32, value: u8) {
+        let mut buffer = self.buffers[buffer_num as usize].borrow_mut();
+        assert!(!buffer.is_empty(), "filling a buffer that is not allowed");
+        buffer.fill(value);
+        self.share_ref
+            .schedule_upcall(0, (buffer_num, buffer.len() as u32, 0))
+            .expect("Unable to schedule upcall");
+    }
+}
+
+impl fake::SyscallDriver for TestDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(42).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_num: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_num {
+            START => {
+                if !self.is_allowed(0) || !self.is_allowed(1) {
+                    return command_return::failure(ErrorCode::NoMem);
+                }
+                self.streaming.set(true);
+                self.fill(0, 1);
+                command_return::success()
+            }
+            STOP => {
+                self.streaming.set(false);
+                command_return::success()
+            }
+            _ => command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        match self.buffers.get(buffer_num as usize) {
+            None => Err((buffer, ErrorCode::NoSupport)),
+            Some(slot) => Ok(slot.replace(buffer)),
+        }
+    }
+}
+
+const START: u32 = 1;
+const STOP: u32 = 2;
+
+type Stream = DoubleBuffer<fake::Syscalls, 42, 0, 0, 1>;
+
+#[test]
+fn alternating() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(TestDriver::default());
+    kernel.add_driver(&driver);
+
+    let mut a = [0; 4];
+    let mut b = [0; 2];
+    let mut seen = Vec::new();
+    let result = Stream::new(START, 0, 0, STOP).run(&mut a, &mut b, |buffer| {
+        // The app only gets a buffer once the kernel no longer has it, while
+        // the kernel keeps the other one to fill.
+        let (current, other) = if seen.len() % 2 == 0 { (0, 1) } else { (1, 0) };
+        assert!(!driver.is_allowed(current));
+        assert!(driver.is_allowed(other));
+        seen.push(buffer.to_vec());
+        if seen.len() == 3 {
+            return ControlFlow::Break(());
+        }
+        driver.fill(other, seen.len() as u8 + 1);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(seen, [vec![1; 4], vec![2; 2], vec![3; 4]]);
+    assert!(!driver.streaming.get());
+    assert!(!driver.is_allowed(0));
+    assert!(!driver.is_allowed(1));
+}
+
+#[test]
+fn both_filled() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(TestDriver::default());
+    kernel.add_driver(&driver);
+
+    // The kernel fills the second buffer before the app has processed the
+    // first; both are handed over in order.
+    let mut a = [0; 2];
+    let mut b = [0; 2];
+    let mut seen = Vec::new();
+    let result = Stream::new(START, 0, 0, STOP).run(&mut a, &mut b, |buffer| {
+        if seen.is_empty() {
+            driver.fill(1, 2);
+        }
+        seen.push(buffer[0]);
+        match seen.len() {
+            2 => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    });
+    assert_eq!(result, Ok(()));
+    assert_eq!(seen, [1, 2]);
+}
+
+#[test]
+fn start_failure() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(TestDriver::default());
+    kernel.add_driver(&driver);
+
+    let mut a = [0; 2];
+    let result = Stream::new(START, 0, 0, STOP).run(&mut a, &mut [], |_| {
+        panic!("no buffer should be processed");
+    });
+    assert_eq!(result, Err(ErrorCode::NoMem));
+    // Both allows are revoked, and the stream is never stopped.
+    let log = kernel.take_syscall_log();
+    assert_eq!(
+        log[log.len() - 3..],
+        [
+            SyscallLogEntry::AllowRw {
+                driver_num: 42,
+                buffer_num: 0,
+                len: 0,
+            },
+            SyscallLogEntry::AllowRw {
+                driver_num: 42,
+                buffer_num: 1,
+                len: 0,
+            },
+            SyscallLogEntry::Subscribe {
+                driver_num: 42,
+                subscribe_num: 0,
+            },
+        ]
+    );
+    assert!(!driver.is_allowed(0));
+}
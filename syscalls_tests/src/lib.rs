@@ -19,15 +19,22 @@ mod allow_rw;
 #[cfg(test)]
 mod command_tests;
 
+#[cfg(test)]
+mod double_buffer_tests;
+
 #[cfg(test)]
 mod exit_on_drop;
 
 // TODO: Add Exit.
 
-// TODO: Add Memop.
+#[cfg(test)]
+mod memop_tests;
 
 #[cfg(test)]
 mod subscribe_tests;
 
+#[cfg(test)]
+mod transaction_tests;
+
 #[cfg(test)]
 mod yield_tests;
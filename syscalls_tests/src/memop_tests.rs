@@ -0,0 +1,112 @@
+use libtock_platform::{memop_id, ErrorCode, Syscalls};
+use libtock_unittest::{fake, SyscallLogEntry};
+
+#[test]
+fn memory_layout() {
+    let kernel = fake::Kernel::new();
+    assert_eq!(
+        fake::Syscalls::memory_start(),
+        Ok(fake::MEMORY_START as *mut u8)
+    );
+    assert_eq!(
+        fake::Syscalls::memory_end(),
+        Ok(fake::MEMORY_END as *mut u8)
+    );
+    assert_eq!(
+        fake::Syscalls::app_heap_break(),
+        Ok(fake::MEMORY_START as *mut u8)
+    );
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::MEMORY_START,
+                argument0: 0,
+            },
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::MEMORY_END,
+                argument0: 0,
+            },
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::SBRK,
+                argument0: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn sbrk_returns_previous_break() {
+    let _kernel = fake::Kernel::new();
+    let start = fake::MEMORY_START as *mut u8;
+    unsafe {
+        assert_eq!(fake::Syscalls::memop_sbrk(0x400), Ok(start));
+        assert_eq!(
+            fake::Syscalls::memop_sbrk(-0x100),
+            Ok(start.wrapping_add(0x400))
+        );
+    }
+    assert_eq!(
+        fake::Syscalls::app_heap_break(),
+        Ok(start.wrapping_add(0x300))
+    );
+}
+
+#[test]
+fn brk() {
+    let _kernel = fake::Kernel::new();
+    let end = fake::MEMORY_END as *mut u8;
+    unsafe {
+        assert_eq!(fake::Syscalls::memop_brk(end), Ok(()));
+        assert_eq!(
+            fake::Syscalls::memop_brk(end.wrapping_add(1)),
+            Err(ErrorCode::NoMem)
+        );
+        assert_eq!(fake::Syscalls::memop_sbrk(1), Err(ErrorCode::NoMem));
+    }
+    assert_eq!(fake::Syscalls::app_heap_break(), Ok(end));
+}
+
+#[test]
+fn flash_and_grant_start() {
+    let kernel = fake::Kernel::new();
+    kernel.set_grant_size(0x100);
+    assert_eq!(
+        fake::Syscalls::flash_start(),
+        Ok(fake::FLASH_START as *const u8)
+    );
+    assert_eq!(
+        fake::Syscalls::grant_start(),
+        Ok((fake::MEMORY_END - 0x100) as *mut u8)
+    );
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::FLASH_START,
+                argument0: 0,
+            },
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::GRANT_START,
+                argument0: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn memory_region() {
+    let kernel = fake::Kernel::new();
+    let memory = Box::leak(vec![0u8; 64].into_boxed_slice());
+    let start = memory.as_mut_ptr();
+    kernel.set_memory_region(memory);
+    assert_eq!(fake::Syscalls::memory_start(), Ok(start));
+    assert_eq!(fake::Syscalls::memory_end(), Ok(start.wrapping_add(64)));
+    unsafe {
+        let block = fake::Syscalls::memop_sbrk(16).unwrap();
+        assert_eq!(block, start);
+        // The memory is real, so it can be written to.
+        block.write_bytes(0xff, 16);
+        assert_eq!(fake::Syscalls::memop_sbrk(64), Err(ErrorCode::NoMem));
+    }
+}
@@ -0,0 +1,132 @@
+use libtock_platform::{CommandReturn, DriverTransaction, ErrorCode};
+use libtock_unittest::{
+    command_return, fake, DriverInfo, DriverShareRef, RoAllowBuffer, SyscallLogEntry,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+// Copies the read-only buffer's length into the upcall when command 1 is
+// issued.
+#[derive(Default)]
+struct TestDriver {
+    buffer: RefCell<RoAllowBuffer>,
+    share_ref: DriverShareRef,
+    busy: Cell<bool>,
+}
+
+impl fake::SyscallDriver for TestDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(42).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_num: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        if command_num != 1 {
+            return command_return::failure(ErrorCode::NoSupport);
+        }
+        if self.busy.get() {
+            return command_return::failure(ErrorCode::Busy);
+        }
+        let len = self.buffer.borrow().len() as u32;
+        self.share_ref
+            .schedule_upcall(0, (argument0, len, 0))
+            .expect("Unable to schedule upcall");
+        command_return::success()
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num != 0 {
+            return Err((buffer, ErrorCode::NoSupport));
+        }
+        Ok(self.buffer.replace(buffer))
+    }
+}
+
+type Transaction = DriverTransaction<fake::Syscalls, 42, 0>;
+
+#[test]
+fn run_with_allow_ro() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(TestDriver::default());
+    kernel.add_driver(&driver);
+
+    let buffer = [1, 2, 3];
+    assert_eq!(
+        Transaction::new(1, 7, 0).run_with_allow_ro::<0>(&buffer),
+        Ok((7, 3, 0))
+    );
+    let log = kernel.take_syscall_log();
+    assert_eq!(
+        log[..3],
+        [
+            SyscallLogEntry::Subscribe {
+                driver_num: 42,
+                subscribe_num: 0,
+            },
+            SyscallLogEntry::AllowRo {
+                driver_num: 42,
+                buffer_num: 0,
+                len: 3,
+            },
+            SyscallLogEntry::Command {
+                driver_id: 42,
+                command_id: 1,
+                argument0: 7,
+                argument1: 0,
+            },
+        ]
+    );
+    // Both the allow and the subscribe are torn down once the upcall arrived.
+    assert!(log[3..].contains(&SyscallLogEntry::AllowRo {
+        driver_num: 42,
+        buffer_num: 0,
+        len: 0,
+    }));
+    assert_eq!(
+        log.last(),
+        Some(&SyscallLogEntry::Subscribe {
+            driver_num: 42,
+            subscribe_num: 0,
+        })
+    );
+    assert_eq!(driver.buffer.borrow().len(), 0);
+}
+
+#[test]
+fn command_failure_tears_down() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(TestDriver::default());
+    kernel.add_driver(&driver);
+    driver.busy.set(true);
+
+    let buffer = [1, 2, 3];
+    assert_eq!(
+        Transaction::new(1, 7, 0).run_with_allow_ro::<0>(&buffer),
+        Err(ErrorCode::Busy)
+    );
+    assert_eq!(driver.buffer.borrow().len(), 0);
+    assert_eq!(
+        kernel.take_syscall_log().last(),
+        Some(&SyscallLogEntry::Subscribe {
+            driver_num: 42,
+            subscribe_num: 0,
+        })
+    );
+}
+
+#[test]
+fn run_without_buffer() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(TestDriver::default());
+    kernel.add_driver(&driver);
+
+    assert_eq!(Transaction::new(1, 5, 0).run(), Ok((5, 0, 0)));
+    assert_eq!(Transaction::new(2, 5, 0).run(), Err(ErrorCode::NoSupport));
+}
@@ -1,8 +1,15 @@
 //! Tests for implementations of Yield system calls in
 //! `libtock_platform::Syscalls`.
 
-use libtock_platform::{Syscalls, YieldNoWaitReturn};
-use libtock_unittest::{fake, ExpectedSyscall, SyscallLogEntry};
+use core::cell::Cell;
+use libtock_platform::{
+    share, subscribe, DefaultConfig, ErrorCode, Select2, Select3, Subscribe, Syscalls,
+    YieldNoWaitReturn,
+};
+use libtock_unittest::{
+    command_return, fake, DriverInfo, DriverShareRef, ExpectedSyscall, SyscallLogEntry,
+};
+use std::rc::Rc;
 
 // Tests yield_no_wait with an upcall executed.
 #[test]
@@ -28,6 +35,24 @@ fn no_wait_no_upcall() {
     assert_eq!(kernel.take_syscall_log(), [SyscallLogEntry::YieldNoWait]);
 }
 
+// Tests converting the result of yield_no_wait into a bool.
+#[test]
+fn no_wait_bool() {
+    let kernel = fake::Kernel::new();
+    kernel.add_expected_syscall(ExpectedSyscall::YieldNoWait {
+        override_return: Some(YieldNoWaitReturn::Upcall),
+    });
+    kernel.add_expected_syscall(ExpectedSyscall::YieldNoWait {
+        override_return: Some(YieldNoWaitReturn::NoUpcall),
+    });
+    assert!(bool::from(fake::Syscalls::yield_no_wait()));
+    assert!(!bool::from(fake::Syscalls::yield_no_wait()));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [SyscallLogEntry::YieldNoWait, SyscallLogEntry::YieldNoWait]
+    );
+}
+
 // Tests yield_wait.
 #[test]
 fn wait() {
@@ -36,3 +61,161 @@ fn wait() {
     fake::Syscalls::yield_wait();
     assert_eq!(kernel.take_syscall_log(), [SyscallLogEntry::YieldWait]);
 }
+
+// Fake driver used to schedule upcalls for yield_until.
+#[derive(Default)]
+struct MockDriver {
+    share_ref: DriverShareRef,
+}
+
+impl fake::SyscallDriver for MockDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(1).upcall_count(3)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, _: u32, _: u32, _: u32) -> libtock_platform::CommandReturn {
+        command_return::failure(ErrorCode::NoSupport)
+    }
+}
+
+struct Counter(Cell<u32>);
+
+impl subscribe::Upcall<subscribe::AnyId> for Counter {
+    fn upcall(&self, _: u32, _: u32, _: u32) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+// Tests that yield_until yields until an upcall satisfies the predicate.
+#[test]
+fn until() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(MockDriver::default());
+    kernel.add_driver(&driver);
+    let counter = Counter(Cell::new(0));
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 0>(subscribe, &counter).unwrap();
+        driver.share_ref.schedule_upcall(0, (0, 0, 0)).unwrap();
+        driver.share_ref.schedule_upcall(0, (0, 0, 0)).unwrap();
+        kernel.take_syscall_log();
+
+        fake::Syscalls::yield_until(|| counter.0.get() == 2);
+        assert_eq!(
+            kernel.take_syscall_log(),
+            [SyscallLogEntry::YieldWait, SyscallLogEntry::YieldWait]
+        );
+
+        // Already satisfied, so no yield is needed.
+        fake::Syscalls::yield_until(|| counter.0.get() == 2);
+        assert_eq!(kernel.take_syscall_log(), []);
+    });
+}
+
+// Tests that select2 returns the first event, and that the loser's upcall is
+// unsubscribed when leaving the scope.
+#[test]
+fn select2() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(MockDriver::default());
+    kernel.add_driver(&driver);
+    let first: Cell<Option<(u32,)>> = Cell::new(None);
+    let second: Cell<Option<(u32,)>> = Cell::new(None);
+    let polled_second = Cell::new(0);
+    share::scope::<(Subscribe<_, 1, 0>, Subscribe<_, 1, 1>), _, _>(|handle| {
+        let (subscribe_first, subscribe_second) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 0>(subscribe_first, &first).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 1>(subscribe_second, &second).unwrap();
+        driver.share_ref.schedule_upcall(0, (7, 0, 0)).unwrap();
+        kernel.take_syscall_log();
+
+        let result = fake::Syscalls::select2(
+            || first.get(),
+            || {
+                polled_second.set(polled_second.get() + 1);
+                second.get()
+            },
+        );
+        assert_eq!(result, Select2::First((7,)));
+        // Polled once before the first yield, and not after A completed.
+        assert_eq!(polled_second.get(), 1);
+        assert_eq!(kernel.take_syscall_log(), [SyscallLogEntry::YieldWait]);
+    });
+    let log = kernel.take_syscall_log();
+    assert!(log.contains(&SyscallLogEntry::Subscribe {
+        driver_num: 1,
+        subscribe_num: 1,
+    }));
+
+    // B's upcall can no longer be delivered.
+    driver.share_ref.schedule_upcall(1, (0, 0, 0)).unwrap();
+    assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    assert_eq!(second.get(), None);
+}
+
+#[test]
+fn select3() {
+    let _kernel = fake::Kernel::new();
+    assert_eq!(
+        fake::Syscalls::select3(|| None::<()>, || None::<()>, || Some(3)),
+        Select3::Third(3)
+    );
+}
+
+// Tests that join3 waits for all three, whatever order they complete in.
+#[test]
+fn join3() {
+    let kernel = fake::Kernel::new();
+    let driver = Rc::new(MockDriver::default());
+    kernel.add_driver(&driver);
+    let readings: [Cell<Option<(u32,)>>; 3] = Default::default();
+    share::scope::<(Subscribe<_, 1, 0>, Subscribe<_, 1, 1>, Subscribe<_, 1, 2>), _, _>(|handle| {
+        let (subscribe_0, subscribe_1, subscribe_2) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 0>(subscribe_0, &readings[0]).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 1>(subscribe_1, &readings[1]).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 2>(subscribe_2, &readings[2]).unwrap();
+        driver.share_ref.schedule_upcall(2, (30, 0, 0)).unwrap();
+        driver.share_ref.schedule_upcall(0, (10, 0, 0)).unwrap();
+        driver.share_ref.schedule_upcall(1, (20, 0, 0)).unwrap();
+        kernel.take_syscall_log();
+
+        let poll = |reading: &Cell<Option<(u32,)>>| reading.get().map(|(value,)| Ok(value));
+        assert_eq!(
+            fake::Syscalls::join3::<_, _, _, ErrorCode, _, _, _>(
+                || poll(&readings[0]),
+                || poll(&readings[1]),
+                || poll(&readings[2]),
+            ),
+            Ok((10, 20, 30))
+        );
+        assert_eq!(
+            kernel.take_syscall_log(),
+            [
+                SyscallLogEntry::YieldWait,
+                SyscallLogEntry::YieldWait,
+                SyscallLogEntry::YieldWait
+            ]
+        );
+    });
+}
+
+// Tests that join2 returns the first error without waiting for the other.
+#[test]
+fn join2_error() {
+    let _kernel = fake::Kernel::new();
+    let polled_first = Cell::new(0);
+    assert_eq!(
+        fake::Syscalls::join2(
+            || {
+                polled_first.set(polled_first.get() + 1);
+                None::<Result<(), _>>
+            },
+            || Some(Err::<(), _>(ErrorCode::Busy)),
+        ),
+        Err(ErrorCode::Busy)
+    );
+    assert_eq!(polled_first.get(), 1);
+}
@@ -143,6 +143,15 @@ impl AllowDb {
         self.buffers.remove(&buffer.address);
         (buffer.address.into(), buffer.len.into())
     }
+
+    /// Returns the (address, len) of every non-zero-sized buffer that is still
+    /// shared.
+    pub fn active_buffers(&self) -> Vec<(*mut u8, usize)> {
+        self.buffers
+            .iter()
+            .map(|(&address, len)| (address, len.get()))
+            .collect()
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, thiserror::Error)]
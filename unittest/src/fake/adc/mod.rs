@@ -0,0 +1,224 @@
+//! Fake implementation of the ADC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/00005_adc.md
+//!
+//! Like the real API, `Adc` controls a set of fake ADC channels. It provides
+//! a function `set_value` used to immediately call an upcall with a sample
+//! taken by the ADC and a function `set_value_sync` used to call the upcall
+//! when the sample command is received. Buffered sampling is emulated by
+//! `fill_buffer` and `fill_buffer_sync`, which copy samples into the buffer
+//! shared by the process; in continuous buffered sampling, successive calls
+//! to `fill_buffer` alternate between the two shared buffers. It reports a
+//! 12-bit resolution and a 3.3 V reference voltage.
+
+use crate::{DriverInfo, DriverShareRef, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+// The `upcall_on_command` field holds the values of the upcalls (with value
+// as their sample) that should be called when the next sample commands are
+// received, in order. It is needed to test the blocking `sample`
+// library function, as there is no other way to schedule an upcall while
+// that function is waiting. `buffer_on_command` serves the same purpose for
+// buffered sampling.
+pub struct Adc<const CHANNEL_COUNT: usize> {
+    // The (mode, channel) of the sampling operation in progress, if any.
+    sampling: Cell<Option<(u32, u32)>>,
+    upcall_on_command: RefCell<VecDeque<u16>>,
+    buffer_on_command: RefCell<Option<Vec<u16>>>,
+    buffers: [RefCell<RwAllowBuffer>; 2],
+    // The buffer the next continuous buffered sample goes into.
+    next_buffer: Cell<usize>,
+    share_ref: DriverShareRef,
+}
+
+impl<const CHANNEL_COUNT: usize> Adc<CHANNEL_COUNT> {
+    pub fn new() -> std::rc::Rc<Adc<CHANNEL_COUNT>> {
+        std::rc::Rc::new(Adc {
+            sampling: Cell::new(None),
+            upcall_on_command: Default::default(),
+            buffer_on_command: RefCell::new(None),
+            buffers: Default::default(),
+            next_buffer: Cell::new(0),
+            share_ref: Default::default(),
+        })
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.sampling.get().is_some()
+    }
+
+    /// Returns the length, in bytes, of the buffer currently shared with the
+    /// driver. This is 0 when no buffer is shared.
+    pub fn buffer_len(&self) -> usize {
+        self.buffers[0].borrow().len()
+    }
+
+    /// Returns the length, in bytes, of the second buffer, used by continuous
+    /// buffered sampling. This is 0 when no buffer is shared.
+    pub fn buffer_2_len(&self) -> usize {
+        self.buffers[1].borrow().len()
+    }
+
+    /// Completes an in-progress single sample with `value`.
+    pub fn set_value(&self, value: u16) {
+        if let Some((MODE_SINGLE_SAMPLE, channel)) = self.sampling.get() {
+            self.share_ref
+                .schedule_upcall(0, (MODE_SINGLE_SAMPLE, channel, value as u32))
+                .expect("Unable to schedule upcall");
+            self.sampling.set(None);
+        }
+    }
+
+    /// Completes the next single sample with `value` as soon as it is started.
+    /// Repeated calls complete the following samples, in order.
+    pub fn set_value_sync(&self, value: u16) {
+        self.upcall_on_command.borrow_mut().push_back(value);
+    }
+
+    /// Completes an in-progress buffered sample by copying `samples` into the
+    /// shared buffer. Samples that do not fit into the buffer are discarded.
+    /// In continuous buffered sampling, this fills the next buffer and
+    /// sampling continues.
+    pub fn fill_buffer(&self, samples: &[u16]) {
+        let (mode, channel) = match self.sampling.get() {
+            Some((mode, channel)) if mode != MODE_SINGLE_SAMPLE => (mode, channel),
+            _ => return,
+        };
+        let index = match mode {
+            MODE_CONTINUOUS_BUFFER => self.next_buffer.replace(1 - self.next_buffer.get()),
+            _ => 0,
+        };
+        let mut buffer = self.buffers[index].borrow_mut();
+        let count = samples.len().min(buffer.len() / 2);
+        for (dest, sample) in buffer.chunks_exact_mut(2).zip(&samples[..count]) {
+            dest.copy_from_slice(&sample.to_ne_bytes());
+        }
+        self.share_ref
+            .schedule_upcall(0, (mode, (count as u32) << 8 | channel, 0))
+            .expect("Unable to schedule upcall");
+        if mode == MODE_SINGLE_BUFFER {
+            self.sampling.set(None);
+        }
+    }
+
+    /// Completes the next buffered sample with `samples` as soon as it is
+    /// started.
+    pub fn fill_buffer_sync(&self, samples: &[u16]) {
+        self.buffer_on_command.replace(Some(samples.into()));
+    }
+}
+
+impl<const CHANNEL_COUNT: usize> crate::fake::SyscallDriver for Adc<CHANNEL_COUNT> {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_BUFFER => Ok(self.buffers[0].replace(buffer)),
+            ALLOW_BUFFER_2 => Ok(self.buffers[1].replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success_u32(CHANNEL_COUNT as u32),
+
+            SINGLE_SAMPLE => {
+                if argument0 >= CHANNEL_COUNT as u32 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                if self.is_busy() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.sampling.set(Some((MODE_SINGLE_SAMPLE, argument0)));
+                let value = self.upcall_on_command.borrow_mut().pop_front();
+                if let Some(val) = value {
+                    self.set_value(val);
+                }
+                crate::command_return::success()
+            }
+
+            SINGLE_BUFFERED_SAMPLE | CONTINUOUS_BUFFERED_SAMPLE => {
+                if argument0 >= CHANNEL_COUNT as u32
+                    || argument1 == 0
+                    || argument1 > MAX_FREQUENCY_HZ
+                {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                let (mode, buffers) = match command_id {
+                    SINGLE_BUFFERED_SAMPLE => (MODE_SINGLE_BUFFER, &self.buffers[..1]),
+                    _ => (MODE_CONTINUOUS_BUFFER, &self.buffers[..]),
+                };
+                if buffers.iter().any(|buffer| buffer.borrow().len() < 2) {
+                    return crate::command_return::failure(ErrorCode::NoMem);
+                }
+                if self.is_busy() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.sampling.set(Some((mode, argument0)));
+                self.next_buffer.set(0);
+                if let Some(samples) = self.buffer_on_command.take() {
+                    self.fill_buffer(&samples);
+                }
+                crate::command_return::success()
+            }
+            STOP => {
+                if !self.is_busy() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+                self.sampling.set(None);
+                crate::command_return::success()
+            }
+            GET_RESOLUTION_BITS => crate::command_return::success_u32(RESOLUTION_BITS),
+            GET_REFERENCE_VOLTAGE_MV => crate::command_return::success_u32(REFERENCE_VOLTAGE_MV),
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x5;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SINGLE_SAMPLE: u32 = 1;
+const SINGLE_BUFFERED_SAMPLE: u32 = 3;
+const CONTINUOUS_BUFFERED_SAMPLE: u32 = 4;
+const STOP: u32 = 5;
+const GET_RESOLUTION_BITS: u32 = 101;
+const GET_REFERENCE_VOLTAGE_MV: u32 = 102;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 0;
+const ALLOW_BUFFER_2: u32 = 1;
+
+// Upcall modes
+
+const MODE_SINGLE_SAMPLE: u32 = 0;
+const MODE_SINGLE_BUFFER: u32 = 2;
+const MODE_CONTINUOUS_BUFFER: u32 = 3;
+
+// The highest sampling frequency accepted by the fake ADC.
+const MAX_FREQUENCY_HZ: u32 = 100_000;
+
+// The fake ADC is a 12-bit ADC with a 3.3 V reference.
+const RESOLUTION_BITS: u32 = 12;
+const REFERENCE_VOLTAGE_MV: u32 = 3300;
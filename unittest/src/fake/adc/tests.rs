@@ -0,0 +1,205 @@
+use crate::fake::{self, SyscallDriver};
+use fake::adc::*;
+use libtock_platform::{share, DefaultConfig, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let adc = Adc::<2>::new();
+
+    assert_eq!(adc.command(EXISTS, 0, 0).get_success_u32(), Some(2));
+
+    assert_eq!(
+        adc.command(SINGLE_SAMPLE, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert!(adc.command(SINGLE_SAMPLE, 1, 0).is_success());
+    assert!(adc.is_busy());
+
+    assert_eq!(
+        adc.command(SINGLE_SAMPLE, 1, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+
+    adc.set_value(100);
+    assert!(!adc.is_busy());
+    assert!(adc.command(SINGLE_SAMPLE, 0, 0).is_success());
+    adc.set_value(100);
+
+    adc.set_value_sync(100);
+    assert!(adc.command(SINGLE_SAMPLE, 0, 0).is_success());
+    assert!(adc.command(SINGLE_SAMPLE, 0, 0).is_success());
+}
+
+// Integration test that verifies Adc works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let adc = Adc::<2>::new();
+    kernel.add_driver(&adc);
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).get_success_u32(),
+        Some(2)
+    );
+    assert!(fake::Syscalls::command(DRIVER_NUM, SINGLE_SAMPLE, 1, 0).is_success());
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, SINGLE_SAMPLE, 1, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    adc.set_value(100);
+
+    let listener = Cell::<Option<(u32, u32, u32)>>::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(
+            fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener),
+            Ok(())
+        );
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SINGLE_SAMPLE, 1, 0).is_success());
+        adc.set_value(0x1234);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((MODE_SINGLE_SAMPLE, 1, 0x1234)));
+
+        adc.set_value(200);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        adc.set_value_sync(200);
+        assert!(fake::Syscalls::command(DRIVER_NUM, SINGLE_SAMPLE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((MODE_SINGLE_SAMPLE, 0, 200)));
+    });
+}
+
+#[test]
+fn buffered_sample() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let adc = Adc::<2>::new();
+    kernel.add_driver(&adc);
+
+    // Without a buffer there is nowhere to put the samples.
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, SINGLE_BUFFERED_SAMPLE, 0, 1000).get_failure(),
+        Some(ErrorCode::NoMem)
+    );
+
+    let mut buffer = [0u8; 6];
+    let listener = Cell::<Option<(u32, u32, u32)>>::new(None);
+    share::scope::<
+        (
+            libtock_platform::AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            libtock_platform::Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        assert_eq!(
+            fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(
+                allow_rw,
+                &mut buffer
+            ),
+            Ok(())
+        );
+        assert_eq!(adc.buffer_len(), 6);
+        assert_eq!(
+            fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener),
+            Ok(())
+        );
+
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, SINGLE_BUFFERED_SAMPLE, 0, 0).get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, SINGLE_BUFFERED_SAMPLE, 0, MAX_FREQUENCY_HZ + 1)
+                .get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+        assert!(fake::Syscalls::command(DRIVER_NUM, SINGLE_BUFFERED_SAMPLE, 1, 1000).is_success());
+        assert!(adc.is_busy());
+        // A single sample does not complete a buffered operation.
+        adc.set_value(5);
+        assert!(adc.is_busy());
+
+        adc.fill_buffer(&[1, 2, 3, 4]);
+        assert!(!adc.is_busy());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((MODE_SINGLE_BUFFER, 3 << 8 | 1, 0)));
+    });
+    assert_eq!(adc.buffer_len(), 0);
+    let expected: Vec<u8> = [1u16, 2, 3]
+        .iter()
+        .flat_map(|sample| sample.to_ne_bytes())
+        .collect();
+    assert_eq!(buffer[..], expected[..]);
+}
+
+#[test]
+fn continuous_buffered_sample() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let adc = Adc::<2>::new();
+    kernel.add_driver(&adc);
+
+    let mut buffer = [0u8; 4];
+    let mut buffer_2 = [0u8; 4];
+    let listener = Cell::<Option<(u32, u32, u32)>>::new(None);
+    share::scope::<
+        (
+            libtock_platform::AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            libtock_platform::AllowRw<_, DRIVER_NUM, ALLOW_BUFFER_2>,
+            libtock_platform::Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, allow_rw_2, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, &mut buffer)
+            .unwrap();
+        // Continuous sampling needs both buffers.
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, CONTINUOUS_BUFFERED_SAMPLE, 0, 1000).get_failure(),
+            Some(ErrorCode::NoMem)
+        );
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER_2>(
+            allow_rw_2,
+            &mut buffer_2,
+        )
+        .unwrap();
+        assert_eq!(adc.buffer_2_len(), 4);
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(
+            fake::Syscalls::command(DRIVER_NUM, CONTINUOUS_BUFFERED_SAMPLE, 1, 1000).is_success()
+        );
+        adc.fill_buffer(&[1, 2]);
+        adc.fill_buffer(&[3]);
+        adc.fill_buffer(&[5, 6]);
+        assert!(adc.is_busy());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(
+            listener.get(),
+            Some((MODE_CONTINUOUS_BUFFER, 2 << 8 | 1, 0))
+        );
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(
+            listener.get(),
+            Some((MODE_CONTINUOUS_BUFFER, 1 << 8 | 1, 0))
+        );
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, STOP, 0, 0).is_success());
+        assert!(!adc.is_busy());
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, STOP, 0, 0).get_failure(),
+            Some(ErrorCode::Off)
+        );
+    });
+    // The third fill went back into the first buffer.
+    assert_eq!(buffer[..], [5u16, 6].map(u16::to_ne_bytes).concat()[..]);
+    assert_eq!(buffer_2[..2], 3u16.to_ne_bytes());
+}
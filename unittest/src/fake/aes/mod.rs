@@ -0,0 +1,255 @@
+//! Fake implementation of the AES API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40006_aes.md
+//!
+//! Like the real API, `Aes` loads the shared key and IV on the setup command
+//! and encrypts the source buffer into the destination buffer on each crypt
+//! command, carrying the counter on between commands. Only AES-128-CTR is
+//! supported. Encryption is done in software and upcalls are scheduled as
+//! soon as the command is received.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+type RoundKeys = [[u8; 16]; 11];
+
+pub struct Aes {
+    algorithm: Cell<Option<u32>>,
+    // The round keys and the next counter block of the session in progress.
+    session: Cell<Option<(RoundKeys, [u8; 16])>>,
+    key_buffer: Cell<RoAllowBuffer>,
+    iv_buffer: Cell<RoAllowBuffer>,
+    source_buffer: Cell<RoAllowBuffer>,
+    dest_buffer: RefCell<RwAllowBuffer>,
+    crypt_count: Cell<usize>,
+    share_ref: DriverShareRef,
+}
+
+impl Aes {
+    pub fn new() -> std::rc::Rc<Aes> {
+        std::rc::Rc::new(Aes {
+            algorithm: Cell::new(None),
+            session: Cell::new(None),
+            key_buffer: Default::default(),
+            iv_buffer: Default::default(),
+            source_buffer: Default::default(),
+            dest_buffer: Default::default(),
+            crypt_count: Cell::new(0),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the number of crypt commands that have been completed.
+    pub fn crypt_count(&self) -> usize {
+        self.crypt_count.get()
+    }
+}
+
+impl crate::fake::SyscallDriver for Aes {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_KEY => Ok(self.key_buffer.replace(buffer)),
+            ALLOW_IV => Ok(self.iv_buffer.replace(buffer)),
+            ALLOW_SOURCE => Ok(self.source_buffer.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DEST {
+            Ok(self.dest_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+
+            SET_ALGORITHM => {
+                if argument0 != ALGORITHM_AES128_CTR {
+                    return crate::command_return::failure(ErrorCode::NoSupport);
+                }
+                self.algorithm.set(Some(argument0));
+                crate::command_return::success()
+            }
+
+            SETUP => {
+                if self.algorithm.get().is_none() {
+                    return crate::command_return::failure(ErrorCode::Reserve);
+                }
+                let key = self.key_buffer.take();
+                let iv = self.iv_buffer.take();
+                let session = match (<[u8; 16]>::try_from(&*key), <[u8; 16]>::try_from(&*iv)) {
+                    (Ok(key), Ok(iv)) => Some((expand_key(&key), iv)),
+                    _ => None,
+                };
+                self.key_buffer.set(key);
+                self.iv_buffer.set(iv);
+                if session.is_none() {
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                self.session.set(session);
+                crate::command_return::success()
+            }
+
+            CRYPT => {
+                let (round_keys, mut counter) = match self.session.get() {
+                    None => return crate::command_return::failure(ErrorCode::Reserve),
+                    Some(session) => session,
+                };
+                let source = self.source_buffer.take();
+                let mut dest = self.dest_buffer.borrow_mut();
+                if source.len() != dest.len() || source.len() % 16 != 0 {
+                    self.source_buffer.set(source);
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                for (dest, source) in dest.chunks_exact_mut(16).zip(source.chunks_exact(16)) {
+                    let keystream = encrypt_block(&round_keys, &counter);
+                    for ((d, s), k) in dest.iter_mut().zip(source).zip(keystream) {
+                        *d = s ^ k;
+                    }
+                    counter = u128::from_be_bytes(counter).wrapping_add(1).to_be_bytes();
+                }
+                self.source_buffer.set(source);
+                self.session.set(Some((round_keys, counter)));
+                self.crypt_count.set(self.crypt_count.get() + 1);
+                self.share_ref
+                    .schedule_upcall(0, (0, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// A software implementation of the AES-128 block cipher, as specified in
+// FIPS 197. It is written for clarity rather than speed or side-channel
+// resistance.
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+        b >>= 1;
+    }
+    product
+}
+
+fn sub_byte(byte: u8) -> u8 {
+    // The multiplicative inverse is byte^254; 0 maps to itself.
+    let mut inverse = 1;
+    for _ in 0..254 {
+        inverse = gf_mul(inverse, byte);
+    }
+    if byte == 0 {
+        inverse = 0;
+    }
+    inverse
+        ^ inverse.rotate_left(1)
+        ^ inverse.rotate_left(2)
+        ^ inverse.rotate_left(3)
+        ^ inverse.rotate_left(4)
+        ^ 0x63
+}
+
+fn expand_key(key: &[u8; 16]) -> RoundKeys {
+    let mut round_keys = [[0; 16]; 11];
+    round_keys[0] = *key;
+    let mut rcon = 1;
+    for round in 1..11 {
+        let previous = round_keys[round - 1];
+        let mut word = [previous[13], previous[14], previous[15], previous[12]];
+        for byte in &mut word {
+            *byte = sub_byte(*byte);
+        }
+        word[0] ^= rcon;
+        rcon = gf_mul(rcon, 2);
+        for i in 0..16 {
+            let value = previous[i] ^ word[i % 4];
+            round_keys[round][i] = value;
+            word[i % 4] = value;
+        }
+    }
+    round_keys
+}
+
+fn encrypt_block(round_keys: &RoundKeys, block: &[u8; 16]) -> [u8; 16] {
+    let mut state = *block;
+    add_round_key(&mut state, &round_keys[0]);
+    for (round, round_key) in round_keys.iter().enumerate().skip(1) {
+        for byte in &mut state {
+            *byte = sub_byte(*byte);
+        }
+        // Shift rows: row r (bytes r, r + 4, ...) rotates left by r columns.
+        let shifted = state;
+        for column in 0..4 {
+            for row in 0..4 {
+                state[row + 4 * column] = shifted[row + 4 * ((column + row) % 4)];
+            }
+        }
+        if round != 10 {
+            for column in state.chunks_exact_mut(4) {
+                let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+                column[0] = gf_mul(a0, 2) ^ gf_mul(a1, 3) ^ a2 ^ a3;
+                column[1] = a0 ^ gf_mul(a1, 2) ^ gf_mul(a2, 3) ^ a3;
+                column[2] = a0 ^ a1 ^ gf_mul(a2, 2) ^ gf_mul(a3, 3);
+                column[3] = gf_mul(a0, 3) ^ a1 ^ a2 ^ gf_mul(a3, 2);
+            }
+        }
+        add_round_key(&mut state, round_key);
+    }
+    state
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key) in state.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40006;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SET_ALGORITHM: u32 = 1;
+const SETUP: u32 = 2;
+const CRYPT: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_KEY: u32 = 0;
+const ALLOW_IV: u32 = 1;
+const ALLOW_SOURCE: u32 = 2;
+const ALLOW_DEST: u32 = 0;
+
+const ALGORITHM_AES128_CTR: u32 = 0;
@@ -0,0 +1,109 @@
+use crate::fake::{self, SyscallDriver};
+use fake::aes::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+const KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+];
+
+//Test the command implementation
+#[test]
+fn command() {
+    let aes = Aes::new();
+
+    assert!(aes.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        aes.command(SETUP, 0, 0).get_failure(),
+        Some(ErrorCode::Reserve)
+    );
+    assert_eq!(
+        aes.command(SET_ALGORITHM, 1, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert!(aes
+        .command(SET_ALGORITHM, ALGORITHM_AES128_CTR, 0)
+        .is_success());
+    // No key or IV has been shared.
+    assert_eq!(
+        aes.command(SETUP, 0, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        aes.command(CRYPT, 0, 0).get_failure(),
+        Some(ErrorCode::Reserve)
+    );
+}
+
+#[test]
+fn block_cipher() {
+    // FIPS 197 appendix B.
+    assert_eq!(
+        encrypt_block(
+            &expand_key(&KEY),
+            &[
+                0x32, 0x43, 0xf6, 0xa8, 0x88, 0x5a, 0x30, 0x8d, 0x31, 0x31, 0x98, 0xa2, 0xe0, 0x37,
+                0x07, 0x34
+            ]
+        ),
+        [
+            0x39, 0x25, 0x84, 0x1d, 0x02, 0xdc, 0x09, 0xfb, 0xdc, 0x11, 0x85, 0x97, 0x19, 0x6a,
+            0x0b, 0x32
+        ]
+    );
+}
+
+// Integration test that verifies Aes works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let aes = Aes::new();
+    kernel.add_driver(&aes);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    let source = [0; 32];
+    let mut dest = [0; 32];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+            AllowRo<_, DRIVER_NUM, ALLOW_IV>,
+            AllowRo<_, DRIVER_NUM, ALLOW_SOURCE>,
+            AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_key, allow_iv, allow_source, allow_dest, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, &KEY).unwrap();
+        // The counter wraps around after the first block.
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_IV>(allow_iv, &[0xff; 16])
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_SOURCE>(allow_source, &source)
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, &mut dest)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(
+            fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_AES128_CTR, 0)
+                .is_success()
+        );
+        assert!(fake::Syscalls::command(DRIVER_NUM, SETUP, 0, 0).is_success());
+        assert!(fake::Syscalls::command(DRIVER_NUM, CRYPT, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0,)));
+    });
+    assert_eq!(
+        dest,
+        [
+            0x8a, 0xf2, 0x86, 0x01, 0x42, 0xf7, 0x86, 0xf4, 0x09, 0x30, 0x7c, 0x1a, 0x3f, 0x7e,
+            0xaa, 0xac, 0x7d, 0xf7, 0x6b, 0x0c, 0x1a, 0xb8, 0x99, 0xb3, 0x3e, 0x42, 0xf0, 0x47,
+            0xb9, 0x1b, 0x54, 0x6f
+        ]
+    );
+    assert_eq!(aes.crypt_count(), 1);
+}
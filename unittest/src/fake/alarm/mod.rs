@@ -1,7 +1,9 @@
 //! Fake implementation of the Alarm API.
 //!
-//! Supports frequency and set_relative.
-//! Will schedule the upcall immediately.
+//! Supports frequency, time, stop, set_relative and set_absolute.
+//! Will schedule the upcall immediately, advancing the current time to the
+//! alarm's expiration. Stopping the alarm before the time is otherwise changed
+//! rewinds it to when the alarm was set, as the alarm has not expired yet.
 
 use core::cell::Cell;
 use core::num::Wrapping;
@@ -12,6 +14,8 @@ use crate::{DriverInfo, DriverShareRef};
 pub struct Alarm {
     frequency_hz: u32,
     now: Cell<Wrapping<u32>>,
+    // The time the last alarm was set at, and the time it expires at.
+    armed: Cell<Option<(Wrapping<u32>, Wrapping<u32>)>>,
     share_ref: DriverShareRef,
 }
 
@@ -20,9 +24,21 @@ impl Alarm {
         std::rc::Rc::new(Alarm {
             frequency_hz,
             now: Cell::new(Wrapping(0)),
+            armed: Cell::new(None),
             share_ref: Default::default(),
         })
     }
+
+    /// Returns the current value of the tick counter.
+    pub fn get_now(&self) -> u32 {
+        self.now.get().0
+    }
+
+    /// Sets the current value of the tick counter.
+    pub fn set_now(&self, now: u32) {
+        self.armed.set(None);
+        self.now.set(Wrapping(now));
+    }
 }
 
 impl crate::fake::SyscallDriver for Alarm {
@@ -34,9 +50,17 @@ impl crate::fake::SyscallDriver for Alarm {
         self.share_ref.replace(share_ref);
     }
 
-    fn command(&self, command_number: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+    fn command(&self, command_number: u32, argument0: u32, argument1: u32) -> CommandReturn {
         match command_number {
             command::FREQUENCY => crate::command_return::success_u32(self.frequency_hz),
+            command::TIME => crate::command_return::success_u32(self.now.get().0),
+            command::STOP => match self.armed.take() {
+                Some((set_at, wake)) if wake == self.now.get() => {
+                    self.now.set(set_at);
+                    crate::command_return::success()
+                }
+                _ => crate::command_return::failure(ErrorCode::Already),
+            },
             command::SET_RELATIVE => {
                 // We're not actually sleeping, just ticking the timer.
                 // The semantics of sleeping aren't clear,
@@ -47,9 +71,26 @@ impl crate::fake::SyscallDriver for Alarm {
                 self.share_ref
                     .schedule_upcall(subscribe::CALLBACK, (wake.0, 0, 0))
                     .expect("schedule_upcall failed");
+                self.armed.set(Some((self.now.get(), wake)));
                 self.now.set(wake);
                 crate::command_return::success_u32(wake.0)
             }
+            command::SET_ABSOLUTE => {
+                // The alarm expires `dt` ticks after `reference`. If that
+                // point has already passed, it expires immediately without
+                // moving the time backwards.
+                let (reference, dt) = (Wrapping(argument0), argument1);
+                let wake = reference + Wrapping(dt);
+                let set_at = self.now.get();
+                if (self.now.get() - reference).0 < dt {
+                    self.now.set(wake);
+                }
+                self.share_ref
+                    .schedule_upcall(subscribe::CALLBACK, (self.now.get().0, 0, 0))
+                    .expect("schedule_upcall failed");
+                self.armed.set(Some((set_at, self.now.get())));
+                crate::command_return::success_u32(wake.0)
+            }
             _ => crate::command_return::failure(ErrorCode::NoSupport),
         }
     }
@@ -12,3 +12,76 @@ fn command() {
         Some(10)
     );
 }
+
+#[test]
+fn time() {
+    use fake::SyscallDriver;
+    let alarm = Alarm::new(10);
+
+    alarm.set_now(1234);
+    assert_eq!(alarm.get_now(), 1234);
+    assert_eq!(
+        alarm.command(command::TIME, 0, 0).get_success_u32(),
+        Some(1234)
+    );
+}
+
+#[test]
+fn set_absolute() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let alarm = Alarm::new(10);
+    kernel.add_driver(&alarm);
+
+    alarm.set_now(u32::MAX - 5);
+    assert_eq!(
+        alarm
+            .command(command::SET_ABSOLUTE, u32::MAX - 10, 20)
+            .get_success_u32(),
+        Some(9)
+    );
+    assert_eq!(alarm.get_now(), 9);
+
+    // Already expired: the time does not go backwards.
+    assert_eq!(
+        alarm.command(command::SET_ABSOLUTE, 0, 5).get_success_u32(),
+        Some(5)
+    );
+    assert_eq!(alarm.get_now(), 9);
+}
+
+#[test]
+fn stop() {
+    use fake::SyscallDriver;
+    let kernel = fake::Kernel::new();
+    let alarm = Alarm::new(10);
+    kernel.add_driver(&alarm);
+
+    alarm.set_now(100);
+    assert_eq!(
+        alarm.command(command::STOP, 0, 0).get_failure(),
+        Some(ErrorCode::Already)
+    );
+    assert_eq!(
+        alarm
+            .command(command::SET_RELATIVE, 50, 0)
+            .get_success_u32(),
+        Some(150)
+    );
+    assert!(alarm.command(command::STOP, 0, 0).is_success());
+    assert_eq!(alarm.get_now(), 100);
+
+    // Once the time has moved on, the alarm is considered expired.
+    assert_eq!(
+        alarm
+            .command(command::SET_RELATIVE, 50, 0)
+            .get_success_u32(),
+        Some(150)
+    );
+    alarm.set_now(200);
+    assert_eq!(
+        alarm.command(command::STOP, 0, 0).get_failure(),
+        Some(ErrorCode::Already)
+    );
+    assert_eq!(alarm.get_now(), 200);
+}
@@ -0,0 +1,109 @@
+//! Fake implementation of the analog comparator API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/00007_analog_comparator.md
+//!
+//! Like the real API, `AnalogComparator` controls a set of fake comparator
+//! channels. It provides a function `set_value` to set the result of an
+//! immediate comparison, a function `crossing` used to immediately call the
+//! upcall for a channel whose interrupt is enabled, and a function
+//! `crossing_sync` used to call it when the interrupt is enabled.
+
+use crate::{DriverInfo, DriverShareRef};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::Cell;
+
+pub struct AnalogComparator<const CHANNEL_COUNT: usize> {
+    values: [Cell<bool>; CHANNEL_COUNT],
+    // Bit n is set while the interrupt of channel n is enabled.
+    comparing: Cell<u32>,
+    crossing_on_start: Cell<bool>,
+    share_ref: DriverShareRef,
+}
+
+impl<const CHANNEL_COUNT: usize> AnalogComparator<CHANNEL_COUNT> {
+    pub fn new() -> std::rc::Rc<AnalogComparator<CHANNEL_COUNT>> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const FALSE: Cell<bool> = Cell::new(false);
+        std::rc::Rc::new(AnalogComparator {
+            values: [FALSE; CHANNEL_COUNT],
+            comparing: Cell::new(0),
+            crossing_on_start: Cell::new(false),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Sets the result of an immediate comparison on `channel`.
+    pub fn set_value(&self, channel: u32, value: bool) {
+        self.values[channel as usize].set(value);
+    }
+
+    pub fn is_comparing(&self, channel: u32) -> bool {
+        self.comparing.get() & 1 << channel != 0
+    }
+
+    /// Signals that the output of `channel` rose, if its interrupt is enabled.
+    pub fn crossing(&self, channel: u32) {
+        if self.is_comparing(channel) {
+            self.share_ref
+                .schedule_upcall(0, (channel, 0, 0))
+                .expect("Unable to schedule upcall");
+        }
+    }
+
+    /// Signals a crossing on the next channel whose interrupt is enabled, as
+    /// soon as it is enabled.
+    pub fn crossing_sync(&self) {
+        self.crossing_on_start.set(true);
+    }
+}
+
+impl<const CHANNEL_COUNT: usize> crate::fake::SyscallDriver for AnalogComparator<CHANNEL_COUNT> {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        if command_id == EXISTS {
+            return crate::command_return::success_u32(CHANNEL_COUNT as u32);
+        }
+        if argument0 >= CHANNEL_COUNT as u32 {
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+        match command_id {
+            COMPARISON => {
+                crate::command_return::success_u32(self.values[argument0 as usize].get() as u32)
+            }
+            START_COMPARING => {
+                self.comparing.set(self.comparing.get() | 1 << argument0);
+                if self.crossing_on_start.take() {
+                    self.crossing(argument0);
+                }
+                crate::command_return::success()
+            }
+            STOP_COMPARING => {
+                self.comparing.set(self.comparing.get() & !(1 << argument0));
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x7;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const COMPARISON: u32 = 1;
+const START_COMPARING: u32 = 2;
+const STOP_COMPARING: u32 = 3;
@@ -0,0 +1,67 @@
+use crate::fake::{self, SyscallDriver};
+use fake::analog_comparator::*;
+use libtock_platform::{share, DefaultConfig, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let comparator = AnalogComparator::<2>::new();
+
+    assert_eq!(comparator.command(EXISTS, 0, 0).get_success_u32(), Some(2));
+    assert_eq!(
+        comparator.command(COMPARISON, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert_eq!(
+        comparator.command(COMPARISON, 1, 0).get_success_u32(),
+        Some(0)
+    );
+    comparator.set_value(1, true);
+    assert_eq!(
+        comparator.command(COMPARISON, 1, 0).get_success_u32(),
+        Some(1)
+    );
+
+    assert!(comparator.command(START_COMPARING, 1, 0).is_success());
+    assert!(comparator.is_comparing(1));
+    assert!(!comparator.is_comparing(0));
+    assert!(comparator.command(STOP_COMPARING, 1, 0).is_success());
+    assert!(!comparator.is_comparing(1));
+}
+
+// Integration test that verifies AnalogComparator works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let comparator = AnalogComparator::<2>::new();
+    kernel.add_driver(&comparator);
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).get_success_u32(),
+        Some(2)
+    );
+
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(
+            fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener),
+            Ok(())
+        );
+
+        // No upcall while the interrupt is disabled.
+        comparator.crossing(1);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_COMPARING, 1, 0).is_success());
+        comparator.crossing(1);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((1,)));
+
+        comparator.crossing_sync();
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_COMPARING, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0,)));
+    });
+}
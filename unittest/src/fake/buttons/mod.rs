@@ -5,9 +5,11 @@
 //! a function `get_button_state` used to retrieve the state and interrupt
 //! status of a button.
 //!
-//! It also provides the function `set_pressed` that set the button's state.
+//! It also provides the function `set_pressed` that set the button's state,
+//! and `set_pressed_sync` that sets the button's state once interrupts are
+//! enabled for it.
 
-use core::cell::Cell;
+use core::cell::{Cell, RefCell};
 use libtock_platform::{CommandReturn, ErrorCode};
 
 use crate::{DriverInfo, DriverShareRef};
@@ -18,8 +20,13 @@ pub struct ButtonState {
     pub interrupt_enabled: bool,
 }
 
+// `upcall_on_interrupt_enable` holds button state changes that are applied when
+// interrupts are enabled for that button. It is needed to test the library's
+// blocking functions, which enable interrupts and then wait for an upcall that
+// could not be scheduled in any other way.
 pub struct Buttons<const NUM_BUTTONS: usize> {
     buttons: [Cell<ButtonState>; NUM_BUTTONS],
+    upcall_on_interrupt_enable: RefCell<Vec<(u32, bool)>>,
     share_ref: DriverShareRef,
 }
 
@@ -32,6 +39,7 @@ impl<const NUM_BUTTONS: usize> Buttons<NUM_BUTTONS> {
         });
         std::rc::Rc::new(Buttons {
             buttons: [OFF; NUM_BUTTONS],
+            upcall_on_interrupt_enable: Default::default(),
             share_ref: Default::default(),
         })
     }
@@ -56,6 +64,14 @@ impl<const NUM_BUTTONS: usize> Buttons<NUM_BUTTONS> {
             .ok_or(ErrorCode::Invalid)
     }
 
+    /// Queues a change of the button's state, which is applied (in the order
+    /// queued) the next time interrupts are enabled for the button.
+    pub fn set_pressed_sync(&self, button: u32, pressed: bool) {
+        self.upcall_on_interrupt_enable
+            .borrow_mut()
+            .push((button, pressed));
+    }
+
     pub fn get_button_state(&self, button: u32) -> Option<ButtonState> {
         self.buttons.get(button as usize).map(|button| button.get())
     }
@@ -80,6 +96,14 @@ impl<const NUM_BUTTONS: usize> crate::fake::SyscallDriver for Buttons<NUM_BUTTON
                         interrupt_enabled: true,
                         ..button
                     });
+                    let pending = self.upcall_on_interrupt_enable.take();
+                    let (now, later) = pending
+                        .into_iter()
+                        .partition(|&(button, _)| button == argument0);
+                    self.upcall_on_interrupt_enable.replace(later);
+                    for (button, pressed) in now {
+                        let _ = self.set_pressed(button, pressed);
+                    }
                     crate::command_return::success()
                 } else {
                     crate::command_return::failure(ErrorCode::Invalid)
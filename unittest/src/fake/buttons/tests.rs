@@ -110,3 +110,26 @@ fn kernel_integration() {
     );
     assert!(fake::Syscalls::command(DRIVER_NUM, BUTTONS_DISABLE_INTERRUPTS, 0, 0).is_success());
 }
+
+#[test]
+fn set_pressed_sync() {
+    use fake::SyscallDriver;
+    let buttons = Buttons::<10>::new();
+
+    buttons.set_pressed_sync(0, true);
+    buttons.set_pressed_sync(0, false);
+    buttons.set_pressed_sync(1, true);
+    assert!(!buttons.get_button_state(0).unwrap().pressed);
+
+    assert!(buttons
+        .command(BUTTONS_ENABLE_INTERRUPTS, 1, 0)
+        .is_success());
+    assert!(buttons.get_button_state(1).unwrap().pressed);
+    assert!(!buttons.get_button_state(0).unwrap().pressed);
+
+    // Both queued changes for button 0 are applied, in order.
+    assert!(buttons
+        .command(BUTTONS_ENABLE_INTERRUPTS, 0, 0)
+        .is_success());
+    assert!(!buttons.get_button_state(0).unwrap().pressed);
+}
@@ -0,0 +1,104 @@
+//! Fake implementation of the Buzzer API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90000_buzzer.md
+//!
+//! Like the real API, `Buzzer` plays one tone at a time. Tones keep playing
+//! until `finish` is called or they are stopped, unless
+//! `set_finish_immediately` is used to make every tone finish as soon as it
+//! starts. Played tones are recorded and can be retrieved via `take_tones`.
+
+use crate::{DriverInfo, DriverShareRef};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct Buzzer {
+    playing: Cell<Option<(u32, u32)>>,
+    finish_immediately: Cell<bool>,
+    tones: RefCell<Vec<(u32, u32)>>,
+    share_ref: DriverShareRef,
+}
+
+impl Buzzer {
+    pub fn new() -> std::rc::Rc<Buzzer> {
+        std::rc::Rc::new(Buzzer {
+            playing: Cell::new(None),
+            finish_immediately: Cell::new(false),
+            tones: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the (frequency_hz, duration_ms) of the tone being played.
+    pub fn playing(&self) -> Option<(u32, u32)> {
+        self.playing.get()
+    }
+
+    /// Finishes the tone being played, if any.
+    pub fn finish(&self) {
+        if self.playing.take().is_some() {
+            self.share_ref
+                .schedule_upcall(0, (0, 0, 0))
+                .expect("Unable to schedule upcall");
+        }
+    }
+
+    /// If true, tones finish as soon as they are started.
+    pub fn set_finish_immediately(&self, finish_immediately: bool) {
+        self.finish_immediately.set(finish_immediately);
+    }
+
+    /// Returns the (frequency_hz, duration_ms) of the tones started so far,
+    /// and clears them.
+    pub fn take_tones(&self) -> Vec<(u32, u32)> {
+        self.tones.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Buzzer {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+
+            TONE => {
+                if self.playing.get().is_some() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.playing.set(Some((argument0, argument1)));
+                self.tones.borrow_mut().push((argument0, argument1));
+                if self.finish_immediately.get() {
+                    self.finish();
+                }
+                crate::command_return::success()
+            }
+            STOP => {
+                if self.playing.get().is_none() {
+                    return crate::command_return::failure(ErrorCode::Already);
+                }
+                self.finish();
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90000;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const TONE: u32 = 1;
+const STOP: u32 = 2;
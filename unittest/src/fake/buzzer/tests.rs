@@ -0,0 +1,60 @@
+use crate::fake::{self, SyscallDriver};
+use fake::buzzer::*;
+use libtock_platform::{share, DefaultConfig, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let buzzer = Buzzer::new();
+
+    assert!(buzzer.command(EXISTS, 1, 2).is_success());
+
+    assert_eq!(
+        buzzer.command(STOP, 0, 0).get_failure(),
+        Some(ErrorCode::Already)
+    );
+    assert!(buzzer.command(TONE, 440, 100).is_success());
+    assert_eq!(buzzer.playing(), Some((440, 100)));
+    assert_eq!(
+        buzzer.command(TONE, 880, 100).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert!(buzzer.command(STOP, 0, 0).is_success());
+    assert_eq!(buzzer.playing(), None);
+
+    buzzer.set_finish_immediately(true);
+    assert!(buzzer.command(TONE, 880, 50).is_success());
+    assert_eq!(buzzer.playing(), None);
+    assert_eq!(buzzer.take_tones(), [(440, 100), (880, 50)]);
+}
+
+// Integration test that verifies Buzzer works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let buzzer = Buzzer::new();
+    kernel.add_driver(&buzzer);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 1, 2).is_success());
+
+    let listener = Cell::<Option<()>>::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(
+            fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener),
+            Ok(())
+        );
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, TONE, 440, 100).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        buzzer.finish();
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some(()));
+
+        listener.set(None);
+        assert!(fake::Syscalls::command(DRIVER_NUM, TONE, 440, 100).is_success());
+        assert!(fake::Syscalls::command(DRIVER_NUM, STOP, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some(()));
+    });
+}
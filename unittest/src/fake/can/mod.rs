@@ -0,0 +1,260 @@
+//! Fake implementation of the CAN API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20007_can.md
+//!
+//! Like the real API, `Can` only sends and receives frames while enabled,
+//! and records the frames sent by the process. `receive_frame` delivers a
+//! frame while the process is receiving, and `receive_frame_sync` delivers
+//! one as soon as receiving starts.
+//!
+//! The fake also implements `ADD_FILTER` and `CLEAR_FILTERS`, with
+//! `MAX_FILTERS` acceptance filters. The upstream capsule has no filter
+//! support; these commands exist so that `libtock_can`'s support for them can
+//! be tested. Once a filter is added, frames that match no filter are not
+//! received.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+/// A frame sent or received through the fake.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanMessage {
+    pub id: u32,
+    pub extended: bool,
+    pub data: Vec<u8>,
+}
+
+/// An acceptance filter: frames whose ID matches `id` in the bits set in
+/// `mask` are received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CanFilter {
+    pub id: u32,
+    pub mask: u32,
+    pub extended: bool,
+}
+
+impl CanFilter {
+    fn matches(&self, frame: &CanMessage) -> bool {
+        frame.extended == self.extended && frame.id & self.mask == self.id & self.mask
+    }
+}
+
+pub struct Can {
+    bitrate: Cell<Option<u32>>,
+    enabled: Cell<bool>,
+    receiving: Cell<bool>,
+    filters: RefCell<Vec<CanFilter>>,
+    sent: RefCell<Vec<CanMessage>>,
+    frame_on_receive: RefCell<Option<CanMessage>>,
+    tx_buffer: Cell<RoAllowBuffer>,
+    rx_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Can {
+    pub fn new() -> std::rc::Rc<Can> {
+        std::rc::Rc::new(Can {
+            bitrate: Cell::new(None),
+            enabled: Cell::new(false),
+            receiving: Cell::new(false),
+            filters: Default::default(),
+            sent: Default::default(),
+            frame_on_receive: Default::default(),
+            tx_buffer: Default::default(),
+            rx_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the bitrate set by the process, if any.
+    pub fn bitrate(&self) -> Option<u32> {
+        self.bitrate.get()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    pub fn is_receiving(&self) -> bool {
+        self.receiving.get()
+    }
+
+    /// Returns the acceptance filters added by the process.
+    pub fn filters(&self) -> Vec<CanFilter> {
+        self.filters.borrow().clone()
+    }
+
+    /// Returns every frame sent since the last call.
+    pub fn take_sent(&self) -> Vec<CanMessage> {
+        self.sent.take()
+    }
+
+    /// Delivers `frame` into the shared receive buffer if the process is
+    /// receiving and the frame passes the acceptance filters. Data that does
+    /// not fit into the buffer is discarded.
+    pub fn receive_frame(&self, frame: CanMessage) {
+        let filters = self.filters.borrow();
+        if !self.receiving.get()
+            || !(filters.is_empty() || filters.iter().any(|f| f.matches(&frame)))
+        {
+            return;
+        }
+        let mut buffer = self.rx_buffer.borrow_mut();
+        let len = frame.data.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&frame.data[..len]);
+        let flags = (frame.extended as u32) << 8 | len as u32;
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_RX, (0, frame.id, flags))
+            .expect("Unable to schedule upcall");
+    }
+
+    /// Delivers `frame` as soon as the process starts receiving.
+    pub fn receive_frame_sync(&self, frame: CanMessage) {
+        self.frame_on_receive.replace(Some(frame));
+    }
+
+    fn send(&self, id: u32, flags: u32) -> CommandReturn {
+        if !self.enabled.get() {
+            return crate::command_return::failure(ErrorCode::Off);
+        }
+        let len = (flags & 0xff) as usize;
+        let buffer = self.tx_buffer.take();
+        if len > buffer.len() || len > MAX_DATA_LEN {
+            self.tx_buffer.set(buffer);
+            return crate::command_return::failure(ErrorCode::Size);
+        }
+        let data = buffer[..len].to_vec();
+        self.tx_buffer.set(buffer);
+        self.sent.borrow_mut().push(CanMessage {
+            id,
+            extended: flags & (1 << 8) != 0,
+            data,
+        });
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_TX, (0, 0, 0))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for Can {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_TX {
+            Ok(self.tx_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_RX {
+            Ok(self.rx_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            SET_BITRATE => {
+                if self.enabled.get() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.bitrate.set(Some(argument0));
+                crate::command_return::success()
+            }
+            ENABLE => {
+                if self.bitrate.get().is_none() {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.enabled.set(true);
+                crate::command_return::success()
+            }
+            SEND => self.send(argument0, argument1),
+            START_RECEIVING => {
+                if !self.enabled.get() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+                self.receiving.set(true);
+                if let Some(frame) = self.frame_on_receive.take() {
+                    self.receive_frame(frame);
+                }
+                crate::command_return::success()
+            }
+            STOP_RECEIVING => {
+                self.receiving.set(false);
+                crate::command_return::success()
+            }
+            ADD_FILTER => {
+                let mut filters = self.filters.borrow_mut();
+                if filters.len() == MAX_FILTERS {
+                    return crate::command_return::failure(ErrorCode::NoMem);
+                }
+                filters.push(CanFilter {
+                    id: argument0 & !EXTENDED_FLAG,
+                    mask: argument1,
+                    extended: argument0 & EXTENDED_FLAG != 0,
+                });
+                crate::command_return::success()
+            }
+            CLEAR_FILTERS => {
+                self.filters.borrow_mut().clear();
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20007;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SET_BITRATE: u32 = 1;
+const ENABLE: u32 = 3;
+const SEND: u32 = 5;
+const START_RECEIVING: u32 = 6;
+const STOP_RECEIVING: u32 = 7;
+const ADD_FILTER: u32 = 16;
+const CLEAR_FILTERS: u32 = 17;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+
+// Subscribe IDs
+
+const SUBSCRIBE_TX: u32 = 0;
+const SUBSCRIBE_RX: u32 = 1;
+
+const MAX_DATA_LEN: usize = 8;
+// The number of acceptance filters the fake supports.
+const MAX_FILTERS: usize = 4;
+// Set in the ID passed to ADD_FILTER for extended frames.
+const EXTENDED_FLAG: u32 = 1 << 31;
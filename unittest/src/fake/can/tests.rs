@@ -0,0 +1,183 @@
+use crate::fake::{self, SyscallDriver};
+use fake::can::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let can = Can::new();
+
+    assert!(can.command(EXISTS, 0, 0).is_success());
+    // The bitrate must be set before enabling.
+    assert_eq!(
+        can.command(ENABLE, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        can.command(SEND, 0x123, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert_eq!(
+        can.command(START_RECEIVING, 0, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+
+    assert!(can.command(SET_BITRATE, 500_000, 0).is_success());
+    assert!(can.command(ENABLE, 0, 0).is_success());
+    assert_eq!(can.bitrate(), Some(500_000));
+    assert!(can.is_enabled());
+    assert_eq!(
+        can.command(SET_BITRATE, 250_000, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+
+    assert!(can.command(START_RECEIVING, 0, 0).is_success());
+    assert!(can.is_receiving());
+    assert!(can.command(STOP_RECEIVING, 0, 0).is_success());
+    assert!(!can.is_receiving());
+
+    assert!(can.command(ADD_FILTER, 0x7e8, 0x7f8).is_success());
+    assert!(can
+        .command(ADD_FILTER, EXTENDED_FLAG | 0x18da_f110, 0x1fff_ffff)
+        .is_success());
+    assert_eq!(
+        can.filters(),
+        [
+            CanFilter {
+                id: 0x7e8,
+                mask: 0x7f8,
+                extended: false
+            },
+            CanFilter {
+                id: 0x18da_f110,
+                mask: 0x1fff_ffff,
+                extended: true
+            },
+        ]
+    );
+    for _ in 2..MAX_FILTERS {
+        assert!(can.command(ADD_FILTER, 0, 0).is_success());
+    }
+    assert_eq!(
+        can.command(ADD_FILTER, 0, 0).get_failure(),
+        Some(ErrorCode::NoMem)
+    );
+    assert!(can.command(CLEAR_FILTERS, 0, 0).is_success());
+    assert_eq!(can.filters(), []);
+}
+
+// Integration test that verifies Can works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let can = Can::new();
+    kernel.add_driver(&can);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_BITRATE, 500_000, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, ENABLE, 0, 0).is_success());
+
+    let sent = Cell::<Option<(u32,)>>::new(None);
+    let received = Cell::<Option<(u32, u32, u32)>>::new(None);
+    let mut rx = [0; 8];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_tx, allow_rx, subscribe_tx, subscribe_rx) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, &[1, 2, 3])
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(
+            subscribe_tx,
+            &sent,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(
+            subscribe_rx,
+            &received,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, SEND, 0x123, 4).get_failure(),
+            Some(ErrorCode::Size)
+        );
+        assert!(fake::Syscalls::command(DRIVER_NUM, SEND, 0x123, 1 << 8 | 3).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sent.get(), Some((0,)));
+
+        // Frames are only delivered while receiving.
+        let frame = CanMessage {
+            id: 0x7e8,
+            extended: false,
+            data: vec![4, 5],
+        };
+        can.receive_frame(frame.clone());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_RECEIVING, 0, 0).is_success());
+        can.receive_frame(frame);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((0, 0x7e8, 2)));
+    });
+    assert_eq!(rx[..2], [4, 5]);
+    assert_eq!(
+        can.take_sent(),
+        [CanMessage {
+            id: 0x123,
+            extended: true,
+            data: vec![1, 2, 3],
+        }]
+    );
+}
+
+#[test]
+fn filters() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let can = Can::new();
+    kernel.add_driver(&can);
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_BITRATE, 500_000, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, ENABLE, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, ADD_FILTER, 0x7e8, 0x7f8).is_success());
+
+    let received = Cell::<Option<(u32, u32, u32)>>::new(None);
+    let mut rx = [0; 8];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rx, subscribe_rx) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(
+            subscribe_rx,
+            &received,
+        )
+        .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, START_RECEIVING, 0, 0).is_success());
+
+        let frame = |id, extended| CanMessage {
+            id,
+            extended,
+            data: vec![],
+        };
+        // Neither the ID nor the frame format match.
+        can.receive_frame(frame(0x123, false));
+        can.receive_frame(frame(0x7e8, true));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        can.receive_frame(frame(0x7ef, false));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((0, 0x7ef, 0)));
+    });
+}
@@ -0,0 +1,200 @@
+//! A configurable fake driver, for testing code that uses a capsule that has no
+//! dedicated fake.
+//!
+//! A `Capsule` is created with a `CapsuleBuilder`, which sets its driver number
+//! and the commands, allow buffers, and upcalls it supports. Each command is
+//! implemented by a closure, which receives the `Capsule` so it can inspect the
+//! shared buffers and schedule upcalls. Commands without a closure return
+//! `ErrorCode::NoSupport`, except for command 0 (the driver existence check),
+//! which succeeds by default.
+//!
+//! # Example
+//! ```
+//! use libtock_platform::ErrorCode;
+//! use libtock_unittest::{command_return, fake};
+//!
+//! // A capsule whose command 1 echoes its first argument in an upcall, and
+//! // whose command 2 always fails.
+//! let capsule = fake::Capsule::builder(0x90100)
+//!     .upcall_count(1)
+//!     .allow_ro(0)
+//!     .command(1, |capsule, argument0, _| {
+//!         capsule.schedule_upcall(0, (argument0, 0, 0));
+//!         command_return::success()
+//!     })
+//!     .command(2, |_, _, _| command_return::failure(ErrorCode::Busy))
+//!     .build();
+//! let kernel = fake::Kernel::new();
+//! kernel.add_driver(&capsule);
+//! ```
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+type CommandFn = Box<dyn Fn(&Capsule, u32, u32) -> CommandReturn>;
+
+pub struct Capsule {
+    driver_num: u32,
+    upcall_count: u32,
+    commands: HashMap<u32, CommandFn>,
+    ro_buffers: HashMap<u32, RefCell<RoAllowBuffer>>,
+    rw_buffers: HashMap<u32, RefCell<RwAllowBuffer>>,
+    share_ref: DriverShareRef,
+}
+
+impl Capsule {
+    /// Starts building a `Capsule` with driver number `driver_num`.
+    pub fn builder(driver_num: u32) -> CapsuleBuilder {
+        CapsuleBuilder {
+            driver_num,
+            upcall_count: 0,
+            commands: HashMap::new(),
+            ro_buffers: Vec::new(),
+            rw_buffers: Vec::new(),
+        }
+    }
+
+    /// Schedules upcall `subscribe_num` with `args`. Like the real kernel, this
+    /// does nothing if the process has not subscribed to it. Panics if
+    /// `subscribe_num` is not below the capsule's upcall count.
+    pub fn schedule_upcall(&self, subscribe_num: u32, args: (u32, u32, u32)) {
+        self.share_ref
+            .schedule_upcall(subscribe_num, args)
+            .expect("schedule_upcall failed");
+    }
+
+    /// Returns a copy of the contents of read-only allow buffer `buffer_num`,
+    /// which is empty if the process has not shared a buffer. Panics if the
+    /// capsule was not built with `buffer_num`.
+    pub fn ro_buffer(&self, buffer_num: u32) -> Vec<u8> {
+        self.ro_buffers[&buffer_num].borrow().to_vec()
+    }
+
+    /// Returns a copy of the contents of read-write allow buffer
+    /// `buffer_num`, which is empty if the process has not shared a buffer.
+    /// Panics if the capsule was not built with `buffer_num`.
+    pub fn rw_buffer(&self, buffer_num: u32) -> Vec<u8> {
+        self.rw_buffers[&buffer_num].borrow().to_vec()
+    }
+
+    /// Copies `data` into the start of read-write allow buffer `buffer_num`,
+    /// truncated to the length of the buffer. Returns the number of bytes
+    /// copied. Panics if the capsule was not built with `buffer_num`.
+    pub fn write_rw_buffer(&self, buffer_num: u32, data: &[u8]) -> usize {
+        let mut buffer = self.rw_buffers[&buffer_num].borrow_mut();
+        let len = data.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&data[..len]);
+        len
+    }
+}
+
+/// Configures a `Capsule`. Created by `Capsule::builder`.
+pub struct CapsuleBuilder {
+    driver_num: u32,
+    upcall_count: u32,
+    commands: HashMap<u32, CommandFn>,
+    ro_buffers: Vec<u32>,
+    rw_buffers: Vec<u32>,
+}
+
+impl CapsuleBuilder {
+    /// Sets the number of upcalls the capsule supports, numbered from 0.
+    pub fn upcall_count(mut self, upcall_count: u32) -> Self {
+        self.upcall_count = upcall_count;
+        self
+    }
+
+    /// Implements command `command_id` with `f`, which is called with the
+    /// capsule and the command's two arguments. Replaces any previous
+    /// implementation of `command_id`.
+    pub fn command<F: Fn(&Capsule, u32, u32) -> CommandReturn + 'static>(
+        mut self,
+        command_id: u32,
+        f: F,
+    ) -> Self {
+        self.commands.insert(command_id, Box::new(f));
+        self
+    }
+
+    /// Accepts read-only allow buffer number `buffer_num`.
+    pub fn allow_ro(mut self, buffer_num: u32) -> Self {
+        self.ro_buffers.push(buffer_num);
+        self
+    }
+
+    /// Accepts read-write allow buffer number `buffer_num`.
+    pub fn allow_rw(mut self, buffer_num: u32) -> Self {
+        self.rw_buffers.push(buffer_num);
+        self
+    }
+
+    pub fn build(self) -> std::rc::Rc<Capsule> {
+        std::rc::Rc::new(Capsule {
+            driver_num: self.driver_num,
+            upcall_count: self.upcall_count,
+            commands: self.commands,
+            ro_buffers: self
+                .ro_buffers
+                .into_iter()
+                .map(|buffer_num| (buffer_num, Default::default()))
+                .collect(),
+            rw_buffers: self
+                .rw_buffers
+                .into_iter()
+                .map(|buffer_num| (buffer_num, Default::default()))
+                .collect(),
+            share_ref: Default::default(),
+        })
+    }
+}
+
+impl crate::fake::SyscallDriver for Capsule {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(self.driver_num).upcall_count(self.upcall_count)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match self.commands.get(&command_id) {
+            Some(f) => f(self, argument0, argument1),
+            None if command_id == DRIVER_CHECK => crate::command_return::success(),
+            None => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match self.ro_buffers.get(&buffer_num) {
+            Some(shared) => Ok(shared.replace(buffer)),
+            None => Err((buffer, ErrorCode::NoSupport)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        match self.rw_buffers.get(&buffer_num) {
+            Some(shared) => Ok(shared.replace(buffer)),
+            None => Err((buffer, ErrorCode::NoSupport)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Implementation details below
+// -----------------------------------------------------------------------------
+
+const DRIVER_CHECK: u32 = 0;
@@ -0,0 +1,91 @@
+use crate::{command_return, fake};
+use core::cell::Cell;
+use libtock_platform::{share, DefaultConfig, ErrorCode, Syscalls, YieldNoWaitReturn};
+
+const DRIVER_NUM: u32 = 0x90100;
+
+// Tests the command implementation.
+#[test]
+fn command() {
+    use fake::SyscallDriver;
+    let capsule = fake::Capsule::builder(DRIVER_NUM)
+        .command(1, |_, argument0, argument1| {
+            command_return::success_u32(argument0 + argument1)
+        })
+        .command(2, |_, _, _| command_return::failure(ErrorCode::Busy))
+        .build();
+    assert!(capsule.command(0, 0, 0).is_success());
+    assert_eq!(capsule.command(1, 2, 3).get_success_u32(), Some(5));
+    assert_eq!(
+        capsule.command(2, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert_eq!(
+        capsule.command(3, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+
+    // The existence check can be overridden.
+    let capsule = fake::Capsule::builder(DRIVER_NUM)
+        .command(0, |_, _, _| command_return::failure(ErrorCode::NoDevice))
+        .build();
+    assert_eq!(
+        capsule.command(0, 0, 0).get_failure(),
+        Some(ErrorCode::NoDevice)
+    );
+}
+
+// Integration test that verifies Capsule works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    let kernel = fake::Kernel::new();
+    // Command 1 replies with the sum of the shared read-only buffer, and
+    // command 2 fills the read-write buffer with its argument.
+    let capsule = fake::Capsule::builder(DRIVER_NUM)
+        .upcall_count(1)
+        .allow_ro(0)
+        .allow_rw(1)
+        .command(1, |capsule, _, _| {
+            let sum = capsule.ro_buffer(0).iter().map(|&b| b as u32).sum();
+            capsule.schedule_upcall(0, (sum, 0, 0));
+            command_return::success()
+        })
+        .command(2, |capsule, argument0, _| {
+            let len = capsule.rw_buffer(1).len();
+            capsule.write_rw_buffer(1, &vec![argument0 as u8; len]);
+            command_return::success()
+        })
+        .build();
+    kernel.add_driver(&capsule);
+    assert!(fake::Syscalls::command(DRIVER_NUM, 0, 0, 0).is_success());
+
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    share::scope(|subscribe| {
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+        share::scope(|allow_ro| {
+            fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, 0>(allow_ro, &[1, 2, 3]).unwrap();
+            assert_eq!(capsule.ro_buffer(0), [1, 2, 3]);
+            assert!(fake::Syscalls::command(DRIVER_NUM, 1, 0, 0).is_success());
+        });
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((6,)));
+    });
+    assert!(capsule.ro_buffer(0).is_empty());
+
+    let mut buffer = [0; 4];
+    share::scope(|allow_rw| {
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, 1>(allow_rw, &mut buffer).unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, 2, 7, 0).is_success());
+    });
+    assert_eq!(buffer, [7; 4]);
+
+    // Buffers the capsule was not built with are rejected.
+    share::scope(|allow_rw| {
+        assert_eq!(
+            fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, 0>(allow_rw, &mut buffer),
+            Err(ErrorCode::NoSupport)
+        );
+    });
+}
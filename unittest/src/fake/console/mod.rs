@@ -4,6 +4,9 @@
 //! Like the real API, `Console` stores each message written to it.
 //! The resulting byte stream can be retrieved via `take_bytes`
 //! for use in unit tests.
+//!
+//! Reads complete immediately with the available input. If there is none, the
+//! read stays pending until `add_input` is called or the read is aborted.
 
 use core::cell::{Cell, RefCell};
 use core::cmp;
@@ -18,6 +21,10 @@ pub struct Console {
     read_buffer: RefCell<RwAllowBuffer>,
     /// To be returned on read
     input: Cell<Vec<u8>>,
+    /// The length of the read waiting for input, if any.
+    pending_read: Cell<Option<usize>>,
+    /// The most bytes accepted by a single write, if limited.
+    max_write_len: Cell<Option<usize>>,
 
     share_ref: DriverShareRef,
 }
@@ -33,6 +40,8 @@ impl Console {
             buffer: Default::default(),
             read_buffer: Default::default(),
             input: Cell::new(Vec::from(inputs)),
+            pending_read: Cell::new(None),
+            max_write_len: Cell::new(None),
             share_ref: Default::default(),
         })
     }
@@ -42,6 +51,27 @@ impl Console {
     pub fn take_bytes(&self) -> Vec<u8> {
         self.messages.take()
     }
+
+    /// Appends `inputs` to the input, completing the pending read, if any.
+    pub fn add_input(&self, inputs: &[u8]) {
+        let mut input = self.input.take();
+        input.extend_from_slice(inputs);
+        self.input.set(input);
+        if let Some(count_wanted) = self.pending_read.take() {
+            self.complete_read(count_wanted);
+        }
+    }
+
+    /// Returns true if a read is waiting for input.
+    pub fn is_reading(&self) -> bool {
+        self.pending_read.get().is_some()
+    }
+
+    /// Limits the number of bytes accepted by each write command, emulating a
+    /// kernel with a small internal buffer.
+    pub fn set_max_write_len(&self, len: usize) {
+        self.max_write_len.set(Some(len));
+    }
 }
 
 impl crate::fake::SyscallDriver for Console {
@@ -83,7 +113,10 @@ impl crate::fake::SyscallDriver for Console {
             WRITE => {
                 let mut bytes = self.messages.take();
                 let buffer = self.buffer.take();
-                let size = cmp::min(buffer.len(), argument0 as usize);
+                let mut size = cmp::min(buffer.len(), argument0 as usize);
+                if let Some(max_write_len) = self.max_write_len.get() {
+                    size = cmp::min(size, max_write_len);
+                }
                 bytes.extend_from_slice(&(*buffer)[..size]);
                 self.buffer.set(buffer);
                 self.messages.set(bytes);
@@ -92,18 +125,24 @@ impl crate::fake::SyscallDriver for Console {
                     .expect("Unable to schedule upcall {}");
             }
             READ => {
-                let count_wanted = argument0 as usize;
-                let bytes = self.input.take();
-                let count_wanted = cmp::min(count_wanted, bytes.len());
-                let to_send = &bytes[..count_wanted];
-                let to_keep = &bytes[count_wanted..];
-                self.input.set(Vec::from(to_keep));
-
-                let count_available = to_send.len();
-                self.read_buffer.borrow_mut()[..count_wanted].copy_from_slice(to_send);
-                self.share_ref
-                    .schedule_upcall(SUBSCRIBE_READ, (0, count_available as u32, 0))
-                    .expect("Unable to schedule upcall {}");
+                if self.is_reading() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                let input = self.input.take();
+                let empty = input.is_empty();
+                self.input.set(input);
+                if empty {
+                    self.pending_read.set(Some(argument0 as usize));
+                } else {
+                    self.complete_read(argument0 as usize);
+                }
+            }
+            ABORT => {
+                if self.pending_read.take().is_some() {
+                    self.share_ref
+                        .schedule_upcall(SUBSCRIBE_READ, (ErrorCode::Cancel as u32, 0, 0))
+                        .expect("Unable to schedule upcall {}");
+                }
             }
             _ => return crate::command_return::failure(ErrorCode::NoSupport),
         }
@@ -115,6 +154,23 @@ impl crate::fake::SyscallDriver for Console {
 // Implementation details below
 // -----------------------------------------------------------------------------
 
+impl Console {
+    fn complete_read(&self, count_wanted: usize) {
+        let bytes = self.input.take();
+        let count_wanted = cmp::min(count_wanted, bytes.len());
+        let count_wanted = cmp::min(count_wanted, self.read_buffer.borrow().len());
+        let to_send = &bytes[..count_wanted];
+        let to_keep = &bytes[count_wanted..];
+        self.input.set(Vec::from(to_keep));
+
+        let count_available = to_send.len();
+        self.read_buffer.borrow_mut()[..count_wanted].copy_from_slice(to_send);
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_READ, (0, count_available as u32, 0))
+            .expect("Unable to schedule upcall {}");
+    }
+}
+
 #[cfg(test)]
 mod tests;
 
@@ -124,7 +180,7 @@ const DRIVER_NUM: u32 = 1;
 const DRIVER_CHECK: u32 = 0;
 const WRITE: u32 = 1;
 const READ: u32 = 2;
-//const ABORT: u32 = 3;
+const ABORT: u32 = 3;
 const SUBSCRIBE_WRITE: u32 = 1;
 const SUBSCRIBE_READ: u32 = 2;
 const ALLOW_WRITE: u32 = 1;
@@ -1,5 +1,6 @@
 use crate::fake;
 use crate::{RoAllowBuffer, RwAllowBuffer};
+use core::cell::Cell;
 use libtock_platform::share;
 use libtock_platform::DefaultConfig;
 
@@ -62,3 +63,99 @@ fn kernel_integration() {
         );
     });
 }
+
+#[test]
+fn max_write_len() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let console = fake::Console::new();
+    console.set_max_write_len(2);
+    kernel.add_driver(&console);
+    share::scope(|allow_ro| {
+        fake::Syscalls::allow_ro::<
+            DefaultConfig,
+            { fake::console::DRIVER_NUM },
+            { fake::console::ALLOW_WRITE },
+        >(allow_ro, b"abcd")
+        .unwrap();
+        assert!(
+            fake::Syscalls::command(fake::console::DRIVER_NUM, fake::console::WRITE, 4, 0)
+                .is_success()
+        );
+    });
+    assert_eq!(console.take_bytes(), b"ab");
+}
+
+#[test]
+fn pending_read() {
+    use libtock_platform::{ErrorCode, Syscalls, YieldNoWaitReturn};
+    let kernel = fake::Kernel::new();
+    let console = fake::Console::new();
+    kernel.add_driver(&console);
+
+    let mut buf = [0; 4];
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    share::scope::<
+        (
+            libtock_platform::AllowRw<
+                fake::Syscalls,
+                { fake::console::DRIVER_NUM },
+                { fake::console::ALLOW_READ },
+            >,
+            libtock_platform::Subscribe<
+                fake::Syscalls,
+                { fake::console::DRIVER_NUM },
+                { fake::console::SUBSCRIBE_READ },
+            >,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<
+            DefaultConfig,
+            { fake::console::DRIVER_NUM },
+            { fake::console::ALLOW_READ },
+        >(allow_rw, &mut buf)
+        .unwrap();
+        fake::Syscalls::subscribe::<
+            _,
+            _,
+            DefaultConfig,
+            { fake::console::DRIVER_NUM },
+            { fake::console::SUBSCRIBE_READ },
+        >(subscribe, &listener)
+        .unwrap();
+
+        // Without input, the read waits until some arrives.
+        assert!(
+            fake::Syscalls::command(fake::console::DRIVER_NUM, fake::console::READ, 3, 0)
+                .is_success()
+        );
+        assert!(console.is_reading());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        assert_eq!(
+            fake::Syscalls::command(fake::console::DRIVER_NUM, fake::console::READ, 3, 0)
+                .get_failure(),
+            Some(ErrorCode::Busy)
+        );
+        console.add_input(b"ab");
+        assert!(!console.is_reading());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 2)));
+
+        // Aborting a waiting read completes it with no bytes.
+        assert!(
+            fake::Syscalls::command(fake::console::DRIVER_NUM, fake::console::READ, 3, 0)
+                .is_success()
+        );
+        assert!(
+            fake::Syscalls::command(fake::console::DRIVER_NUM, fake::console::ABORT, 0, 0)
+                .is_success()
+        );
+        assert!(!console.is_reading());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((ErrorCode::Cancel as u32, 0)));
+    });
+    assert_eq!(&buf[..2], b"ab");
+}
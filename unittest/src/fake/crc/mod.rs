@@ -0,0 +1,129 @@
+//! Fake implementation of the CRC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40002_crc.md
+//!
+//! Like the real API, `Crc` computes the CRC of the buffer shared by the
+//! process, using a software implementation of each algorithm. Results are
+//! delivered as soon as the compute command is received.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::Cell;
+
+pub struct Crc {
+    buffer: Cell<RoAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Crc {
+    pub fn new() -> std::rc::Rc<Crc> {
+        std::rc::Rc::new(Crc {
+            buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+}
+
+impl crate::fake::SyscallDriver for Crc {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+
+            COMPUTE => {
+                let buffer = self.buffer.take();
+                if argument1 as usize > buffer.len() {
+                    self.buffer.set(buffer);
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                let data = &buffer[..argument1 as usize];
+                let crc = match argument0 {
+                    ALGORITHM_CRC32 => !crc32_reflected(0xEDB88320, data),
+                    ALGORITHM_CRC32C => !crc32_reflected(0x82F63B78, data),
+                    ALGORITHM_CRC16_CCITT => crc16_ccitt(data) as u32,
+                    _ => {
+                        self.buffer.set(buffer);
+                        return crate::command_return::failure(ErrorCode::NoSupport);
+                    }
+                };
+                self.buffer.set(buffer);
+                self.share_ref
+                    .schedule_upcall(0, (0, crc, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+// Computes a reflected CRC-32 with an initial value of 0xFFFFFFFF. The caller
+// applies the final XOR.
+fn crc32_reflected(polynomial: u32, data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFF;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ polynomial
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40002;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const COMPUTE: u32 = 1;
+
+const ALLOW_BUFFER: u32 = 0;
+
+// Algorithms
+
+const ALGORITHM_CRC32: u32 = 0;
+const ALGORITHM_CRC32C: u32 = 1;
+const ALGORITHM_CRC16_CCITT: u32 = 2;
@@ -0,0 +1,59 @@
+use crate::fake::{self, SyscallDriver};
+use fake::crc::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let crc = Crc::new();
+
+    assert!(crc.command(EXISTS, 1, 2).is_success());
+    // Longer than the (empty) shared buffer.
+    assert_eq!(
+        crc.command(COMPUTE, ALGORITHM_CRC32, 1).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+}
+
+#[test]
+fn algorithms() {
+    assert_eq!(!crc32_reflected(0xEDB88320, b"123456789"), 0xCBF43926);
+    assert_eq!(!crc32_reflected(0x82F63B78, b"123456789"), 0xE3069283);
+    assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+}
+
+// Integration test that verifies Crc works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let crc = Crc::new();
+    kernel.add_driver(&crc);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 1, 2).is_success());
+
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_ro, b"123456789")
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, COMPUTE, ALGORITHM_CRC32, 9).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 0xCBF43926)));
+
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, COMPUTE, 3, 9).get_failure(),
+            Some(ErrorCode::NoSupport)
+        );
+    });
+}
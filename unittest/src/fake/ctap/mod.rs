@@ -0,0 +1,155 @@
+//! Fake implementation of the CTAP USB HID API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20009_ctap.md
+//!
+//! Like the real API, `Ctap` exchanges 64-byte reports with the host. Reports
+//! sent by the host are queued by `push_report` and delivered one at a time
+//! as the process asks to receive them. Reports sent by the process are
+//! recorded, and in loopback mode are also queued to be received back.
+
+use crate::{DriverInfo, DriverShareRef, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+pub struct Ctap {
+    loopback: Cell<bool>,
+    receiving: Cell<bool>,
+    incoming: RefCell<VecDeque<Vec<u8>>>,
+    sent: RefCell<Vec<Vec<u8>>>,
+    recv_buffer: RefCell<RwAllowBuffer>,
+    send_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Ctap {
+    pub fn new() -> std::rc::Rc<Ctap> {
+        std::rc::Rc::new(Ctap {
+            loopback: Cell::new(false),
+            receiving: Cell::new(false),
+            incoming: Default::default(),
+            sent: Default::default(),
+            recv_buffer: Default::default(),
+            send_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// When enabled, reports sent by the process are received back.
+    pub fn set_loopback(&self, loopback: bool) {
+        self.loopback.set(loopback);
+    }
+
+    pub fn is_receiving(&self) -> bool {
+        self.receiving.get()
+    }
+
+    /// Returns every report sent by the process since the last call.
+    pub fn take_sent(&self) -> Vec<Vec<u8>> {
+        self.sent.take()
+    }
+
+    /// Queues a report sent by the host.
+    pub fn push_report(&self, report: &[u8]) {
+        self.incoming.borrow_mut().push_back(report.into());
+        self.deliver();
+    }
+
+    // Delivers the oldest queued report, if the process is receiving.
+    fn deliver(&self) {
+        if !self.receiving.get() {
+            return;
+        }
+        let report = match self.incoming.borrow_mut().pop_front() {
+            Some(report) => report,
+            None => return,
+        };
+        let mut buffer = self.recv_buffer.borrow_mut();
+        let len = report.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&report[..len]);
+        self.receiving.set(false);
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_RECV, (0, len as u32, 0))
+            .expect("Unable to schedule upcall");
+    }
+}
+
+impl crate::fake::SyscallDriver for Ctap {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_RECV => Ok(self.recv_buffer.replace(buffer)),
+            ALLOW_SEND => Ok(self.send_buffer.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn command(&self, command_id: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            SEND => {
+                let report = self.send_buffer.borrow().to_vec();
+                if report.len() != REPORT_LEN {
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                if self.loopback.get() {
+                    self.incoming.borrow_mut().push_back(report.clone());
+                }
+                self.sent.borrow_mut().push(report);
+                self.share_ref
+                    .schedule_upcall(SUBSCRIBE_SEND, (0, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+            RECEIVE => {
+                if self.recv_buffer.borrow().len() != REPORT_LEN {
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                if self.receiving.get() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.receiving.set(true);
+                self.deliver();
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20009;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SEND: u32 = 1;
+const RECEIVE: u32 = 2;
+
+// Allow IDs
+
+const ALLOW_RECV: u32 = 0;
+const ALLOW_SEND: u32 = 1;
+
+// Subscribe IDs
+
+const SUBSCRIBE_SEND: u32 = 0;
+const SUBSCRIBE_RECV: u32 = 1;
+
+const REPORT_LEN: usize = 64;
@@ -0,0 +1,83 @@
+use crate::fake::{self, SyscallDriver};
+use fake::ctap::*;
+use libtock_platform::{share, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let ctap = Ctap::new();
+
+    assert!(ctap.command(EXISTS, 0, 0).is_success());
+    // No buffers have been shared.
+    assert_eq!(
+        ctap.command(SEND, 0, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert_eq!(
+        ctap.command(RECEIVE, 0, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+    assert!(!ctap.is_receiving());
+}
+
+// Integration test that verifies Ctap works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let ctap = Ctap::new();
+    kernel.add_driver(&ctap);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+    ctap.set_loopback(true);
+
+    let mut recv = [0; REPORT_LEN];
+    let mut send = [7; REPORT_LEN];
+    let sent = Cell::<Option<(u32,)>>::new(None);
+    let received = Cell::<Option<(u32, u32)>>::new(None);
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_RECV>,
+            AllowRw<_, DRIVER_NUM, ALLOW_SEND>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_SEND>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RECV>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_recv, allow_send, subscribe_send, subscribe_recv) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RECV>(allow_recv, &mut recv)
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_SEND>(allow_send, &mut send)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_SEND>(
+            subscribe_send,
+            &sent,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RECV>(
+            subscribe_recv,
+            &received,
+        )
+        .unwrap();
+
+        // Nothing has been sent by the host yet.
+        assert!(fake::Syscalls::command(DRIVER_NUM, RECEIVE, 0, 0).is_success());
+        assert!(ctap.is_receiving());
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, RECEIVE, 0, 0).get_failure(),
+            Some(ErrorCode::Busy)
+        );
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        ctap.push_report(&[1; REPORT_LEN]);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((0, REPORT_LEN as u32)));
+        assert!(!ctap.is_receiving());
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SEND, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sent.get(), Some((0,)));
+    });
+    assert_eq!(recv, [1; REPORT_LEN]);
+    assert_eq!(ctap.take_sent(), [[7; REPORT_LEN].to_vec()]);
+}
@@ -2,12 +2,14 @@
 //! https://github.com/tock/tock/blob/master/doc/syscalls/00004_gpio.md
 //!
 //! Like the real API, `Gpio` controls a set of fake gpios. It provides
-//! a function `get_button_state` used to retrieve the state and interrupt
-//! status of a button.
+//! a function `get_gpio_state` used to retrieve the state and interrupt
+//! status of a gpio.
 //!
-//! It also provides the function `set_pressed` that set the button's state.
+//! It also provides the function `set_value` that sets the gpio's value, and
+//! `set_value_sync` that sets the gpio's value once interrupts are enabled for
+//! it.
 
-use core::cell::Cell;
+use core::cell::{Cell, RefCell};
 use libtock_platform::{CommandReturn, ErrorCode};
 use std::convert::TryFrom;
 
@@ -16,6 +18,9 @@ use crate::{DriverInfo, DriverShareRef};
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum GpioMode {
     Output,
+    /// An open-drain output. A `value` of `true` means the line is released,
+    /// and `false` that the pin pulls it low.
+    OutputOpenDrain,
     Input(PullMode),
     Disable,
 }
@@ -67,8 +72,13 @@ pub struct GpioState {
     pub interrupt_enabled: Option<InterruptEdge>,
 }
 
+// `upcall_on_interrupt_enable` holds value changes that are applied when
+// interrupts are enabled for that gpio. It is needed to test the library's
+// blocking functions, which enable interrupts and then wait for an upcall that
+// could not be scheduled in any other way.
 pub struct Gpio<const NUM_GPIOS: usize> {
     gpios: [Cell<Option<GpioState>>; NUM_GPIOS],
+    upcall_on_interrupt_enable: RefCell<Vec<(u32, bool)>>,
     share_ref: DriverShareRef,
 }
 
@@ -82,6 +92,7 @@ impl<const NUM_GPIOS: usize> Gpio<NUM_GPIOS> {
         }));
         std::rc::Rc::new(Gpio {
             gpios: [OFF; NUM_GPIOS],
+            upcall_on_interrupt_enable: Default::default(),
             share_ref: Default::default(),
         })
     }
@@ -128,6 +139,14 @@ impl<const NUM_GPIOS: usize> Gpio<NUM_GPIOS> {
             .and_then(|value| value)
     }
 
+    /// Queues a change of the gpio's value, which is applied (in the order
+    /// queued) the next time interrupts are enabled for the gpio.
+    pub fn set_value_sync(&self, pin: u32, value: bool) {
+        self.upcall_on_interrupt_enable
+            .borrow_mut()
+            .push((pin, value));
+    }
+
     pub fn get_gpio_state(&self, button: u32) -> Option<GpioState> {
         self.gpios
             .get(button as usize)
@@ -160,7 +179,7 @@ impl<const NUM_GPIOS: usize> crate::fake::SyscallDriver for Gpio<NUM_GPIOS> {
                         crate::command_return::success()
                     }
                     GPIO_SET => {
-                        if let GpioMode::Output = gpio.mode {
+                        if let GpioMode::Output | GpioMode::OutputOpenDrain = gpio.mode {
                             self.gpios[argument0 as usize].set(Some(GpioState {
                                 value: true,
                                 ..gpio
@@ -169,7 +188,7 @@ impl<const NUM_GPIOS: usize> crate::fake::SyscallDriver for Gpio<NUM_GPIOS> {
                         crate::command_return::success()
                     }
                     GPIO_CLEAR => {
-                        if let GpioMode::Output = gpio.mode {
+                        if let GpioMode::Output | GpioMode::OutputOpenDrain = gpio.mode {
                             self.gpios[argument0 as usize].set(Some(GpioState {
                                 value: false,
                                 ..gpio
@@ -178,7 +197,7 @@ impl<const NUM_GPIOS: usize> crate::fake::SyscallDriver for Gpio<NUM_GPIOS> {
                         crate::command_return::success()
                     }
                     GPIO_TOGGLE => {
-                        if let GpioMode::Output = gpio.mode {
+                        if let GpioMode::Output | GpioMode::OutputOpenDrain = gpio.mode {
                             self.gpios[argument0 as usize].set(Some(GpioState {
                                 value: !gpio.value,
                                 ..gpio
@@ -186,6 +205,13 @@ impl<const NUM_GPIOS: usize> crate::fake::SyscallDriver for Gpio<NUM_GPIOS> {
                         }
                         crate::command_return::success()
                     }
+                    GPIO_ENABLE_OUTPUT_OPEN_DRAIN => {
+                        self.gpios[argument0 as usize].set(Some(GpioState {
+                            mode: GpioMode::OutputOpenDrain,
+                            ..gpio
+                        }));
+                        crate::command_return::success()
+                    }
                     GPIO_ENABLE_INPUT => {
                         let pull_mode = PullMode::try_from(argument1);
                         match pull_mode {
@@ -214,6 +240,13 @@ impl<const NUM_GPIOS: usize> crate::fake::SyscallDriver for Gpio<NUM_GPIOS> {
                                     interrupt_enabled: Some(interrupt_edge),
                                     ..gpio
                                 }));
+                                let pending = self.upcall_on_interrupt_enable.take();
+                                let (now, later) =
+                                    pending.into_iter().partition(|&(pin, _)| pin == argument0);
+                                self.upcall_on_interrupt_enable.replace(later);
+                                for (pin, value) in now {
+                                    let _ = self.set_value(pin, value);
+                                }
                                 crate::command_return::success()
                             }
                             Err(error) => crate::command_return::failure(error),
@@ -268,3 +301,5 @@ const GPIO_ENABLE_INTERRUPTS: u32 = 7;
 const GPIO_DISABLE_INTERRUPTS: u32 = 8;
 
 const GPIO_DISABLE: u32 = 9;
+
+const GPIO_ENABLE_OUTPUT_OPEN_DRAIN: u32 = 16;
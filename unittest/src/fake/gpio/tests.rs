@@ -174,6 +174,29 @@ fn command() {
     assert_eq!(gpio.get_gpio_state(0).unwrap().mode, GpioMode::Disable);
 }
 
+#[test]
+fn open_drain() {
+    use fake::SyscallDriver;
+    let gpio = Gpio::<2>::new();
+
+    assert!(gpio
+        .command(GPIO_ENABLE_OUTPUT_OPEN_DRAIN, 0, 0)
+        .is_success());
+    assert_eq!(
+        gpio.get_gpio_state(0).unwrap().mode,
+        GpioMode::OutputOpenDrain
+    );
+    assert!(gpio.command(GPIO_SET, 0, 0).is_success());
+    assert!(gpio.get_gpio_state(0).unwrap().value);
+    assert!(gpio.command(GPIO_CLEAR, 0, 0).is_success());
+    assert!(!gpio.get_gpio_state(0).unwrap().value);
+    assert_eq!(
+        gpio.command(GPIO_ENABLE_OUTPUT_OPEN_DRAIN, 2, 0)
+            .get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+}
+
 // Integration test that verifies Gpio works with fake::Kernel and
 // libtock_platform::Syscalls.
 #[test]
@@ -401,3 +424,20 @@ fn kernel_integration() {
     assert!(fake::Syscalls::command(DRIVER_NUM, GPIO_DISABLE, 0, 0).is_success());
     assert_eq!(gpio.get_gpio_state(0).unwrap().mode, GpioMode::Disable);
 }
+
+#[test]
+fn set_value_sync() {
+    use fake::SyscallDriver;
+    let gpio = Gpio::<10>::new();
+
+    gpio.set_value_sync(0, true);
+    gpio.set_value_sync(1, true);
+    assert!(!gpio.get_gpio_state(0).unwrap().value);
+
+    assert!(gpio.command(GPIO_ENABLE_INTERRUPTS, 1, 0).is_success());
+    assert!(gpio.get_gpio_state(1).unwrap().value);
+    assert!(!gpio.get_gpio_state(0).unwrap().value);
+
+    assert!(gpio.command(GPIO_ENABLE_INTERRUPTS, 0, 0).is_success());
+    assert!(gpio.get_gpio_state(0).unwrap().value);
+}
@@ -0,0 +1,166 @@
+//! Fake implementation of the HMAC API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40003_hmac.md
+//!
+//! Like the real API, `Hmac` reads the shared key when a computation starts,
+//! accumulates the data shared by the process on each update command and
+//! writes the MAC into the destination buffer when the finish command is
+//! received. MACs are computed in software and upcalls are scheduled as soon
+//! as the command is received.
+
+use crate::fake::sha::sha256;
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct Hmac {
+    algorithm: Cell<Option<u32>>,
+    // The key read when the computation in progress started, if any.
+    key: RefCell<Option<Vec<u8>>>,
+    data: RefCell<Vec<u8>>,
+    key_buffer: Cell<RoAllowBuffer>,
+    data_buffer: Cell<RoAllowBuffer>,
+    dest_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Hmac {
+    pub fn new() -> std::rc::Rc<Hmac> {
+        std::rc::Rc::new(Hmac {
+            algorithm: Cell::new(None),
+            key: Default::default(),
+            data: Default::default(),
+            key_buffer: Default::default(),
+            data_buffer: Default::default(),
+            dest_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    // Reads the key from the key buffer if the computation has not started.
+    fn start(&self) {
+        if self.key.borrow().is_none() {
+            let buffer = self.key_buffer.take();
+            self.key.replace(Some(buffer.to_vec()));
+            self.key_buffer.set(buffer);
+        }
+    }
+}
+
+impl crate::fake::SyscallDriver for Hmac {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_KEY => Ok(self.key_buffer.replace(buffer)),
+            ALLOW_DATA => Ok(self.data_buffer.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_DEST {
+            Ok(self.dest_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            SET_ALGORITHM => {
+                if argument0 != ALGORITHM_SHA256 {
+                    return crate::command_return::failure(ErrorCode::NoSupport);
+                }
+                self.algorithm.set(Some(argument0));
+                crate::command_return::success()
+            }
+
+            UPDATE => {
+                if self.algorithm.get().is_none() {
+                    return crate::command_return::failure(ErrorCode::Reserve);
+                }
+                self.start();
+                let buffer = self.data_buffer.take();
+                self.data.borrow_mut().extend_from_slice(&buffer);
+                self.data_buffer.set(buffer);
+                self.share_ref
+                    .schedule_upcall(0, (0, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+
+            FINISH => {
+                if self.algorithm.get().is_none() {
+                    return crate::command_return::failure(ErrorCode::Reserve);
+                }
+                let mut dest = self.dest_buffer.borrow_mut();
+                if dest.len() < MAC_LEN {
+                    return crate::command_return::failure(ErrorCode::Size);
+                }
+                self.start();
+                let key = self.key.take().unwrap_or_default();
+                dest[..MAC_LEN].copy_from_slice(&hmac_sha256(&key, &self.data.take()));
+                self.algorithm.set(None);
+                self.share_ref
+                    .schedule_upcall(0, (0, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+/// Computes the HMAC-SHA256 of `data` under `key`, as specified in RFC 2104.
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut block_key = [0; BLOCK_LEN];
+    if key.len() > BLOCK_LEN {
+        block_key[..32].copy_from_slice(&sha256(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner: Vec<u8> = block_key.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(data);
+    let mut outer: Vec<u8> = block_key.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40003;
+
+// Command IDs
+
+const SET_ALGORITHM: u32 = 0;
+const UPDATE: u32 = 2;
+const FINISH: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_KEY: u32 = 0;
+const ALLOW_DATA: u32 = 1;
+const ALLOW_DEST: u32 = 0;
+
+const ALGORITHM_SHA256: u32 = 0;
+const BLOCK_LEN: usize = 64;
+const MAC_LEN: usize = 32;
@@ -0,0 +1,96 @@
+use crate::fake::{self, SyscallDriver};
+use fake::hmac::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let hmac = Hmac::new();
+
+    assert_eq!(
+        hmac.command(UPDATE, 0, 0).get_failure(),
+        Some(ErrorCode::Reserve)
+    );
+    assert_eq!(
+        hmac.command(SET_ALGORITHM, 1, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert!(hmac
+        .command(SET_ALGORITHM, ALGORITHM_SHA256, 0)
+        .is_success());
+    // No destination buffer has been shared.
+    assert_eq!(
+        hmac.command(FINISH, 0, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+}
+
+#[test]
+fn hmac_sha256_vectors() {
+    // RFC 4231 test case 2.
+    assert_eq!(
+        hmac_sha256(b"Jefe", b"what do ya want for nothing?"),
+        [
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+            0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+            0x64, 0xec, 0x38, 0x43
+        ]
+    );
+    // RFC 4231 test case 6, whose key is longer than a block.
+    assert_eq!(
+        hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        ),
+        [
+            0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5,
+            0xb7, 0x7f, 0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46, 0x04, 0x0f,
+            0x0e, 0xe3, 0x7f, 0x54
+        ]
+    );
+}
+
+// Integration test that verifies Hmac works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let hmac = Hmac::new();
+    kernel.add_driver(&hmac);
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_SHA256, 0).is_success());
+
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    let mut mac = [0; 32];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+            AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+            AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_key, allow_data, allow_dest, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, b"Jefe")
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(
+            allow_data,
+            b"what do ya want for nothing?",
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_dest, &mut mac)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, UPDATE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0,)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, FINISH, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+    });
+    assert_eq!(mac, hmac_sha256(b"Jefe", b"what do ya want for nothing?"));
+}
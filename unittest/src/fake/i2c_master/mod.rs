@@ -0,0 +1,202 @@
+//! Fake implementation of the I2C master API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20003_i2c_master.md
+//!
+//! `I2cMaster` emulates a bus on which every device added with `add_device`
+//! echoes back the bytes last written to it, and every device added with
+//! `add_register_device` behaves like a typical sensor: the first byte written
+//! selects a register, and the following bytes written or read access
+//! consecutive registers from there. Transfers addressed to any other device
+//! complete with `ErrorCode::NoAck`. Transfers complete as soon as
+//! they are started, and are recorded so they can be retrieved via
+//! `take_transfers`.
+
+use crate::{DriverInfo, DriverShareRef, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// A transfer performed on the fake bus.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum I2cTransfer {
+    Write {
+        address: u8,
+        data: Vec<u8>,
+    },
+    Read {
+        address: u8,
+        len: usize,
+    },
+    // A write followed by a read, joined by a repeated start.
+    WriteRead {
+        address: u8,
+        data: Vec<u8>,
+        read_len: usize,
+    },
+}
+
+// A device on the fake bus.
+enum Device {
+    // Holds the bytes last written to it.
+    Echo(Vec<u8>),
+    Registers { map: Vec<u8>, selected: usize },
+}
+
+pub struct I2cMaster {
+    devices: RefCell<HashMap<u8, Device>>,
+    transfers: RefCell<Vec<I2cTransfer>>,
+    buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl I2cMaster {
+    pub fn new() -> std::rc::Rc<I2cMaster> {
+        std::rc::Rc::new(I2cMaster {
+            devices: Default::default(),
+            transfers: Default::default(),
+            buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Adds a device that acknowledges transfers addressed to `address`.
+    pub fn add_device(&self, address: u8) {
+        self.devices
+            .borrow_mut()
+            .entry(address)
+            .or_insert(Device::Echo(Vec::new()));
+    }
+
+    /// Adds a device at `address` with the given registers, starting at
+    /// register 0. Accesses beyond the last register wrap around to the first.
+    pub fn add_register_device(&self, address: u8, registers: &[u8]) {
+        assert!(!registers.is_empty(), "A register device needs registers");
+        self.devices.borrow_mut().insert(
+            address,
+            Device::Registers {
+                map: registers.to_vec(),
+                selected: 0,
+            },
+        );
+    }
+
+    /// Returns the registers of the register device at `address`, or None if
+    /// there is no register device there.
+    pub fn get_registers(&self, address: u8) -> Option<Vec<u8>> {
+        match self.devices.borrow().get(&address) {
+            Some(Device::Registers { map, .. }) => Some(map.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the transfers performed so far, and clears them.
+    pub fn take_transfers(&self) -> Vec<I2cTransfer> {
+        self.transfers.take()
+    }
+
+    // Performs a transfer, returning the upcall status.
+    fn run(&self, address: u8, write_len: usize, read_len: usize) -> u32 {
+        let mut devices = self.devices.borrow_mut();
+        let mut buffer = self.buffer.borrow_mut();
+        match devices.get_mut(&address) {
+            None => return ErrorCode::NoAck as u32,
+            Some(Device::Echo(echo)) => {
+                if write_len > 0 {
+                    *echo = buffer[..write_len].to_vec();
+                }
+                for (i, byte) in buffer[..read_len].iter_mut().enumerate() {
+                    *byte = echo.get(i).copied().unwrap_or(0);
+                }
+            }
+            Some(Device::Registers { map, selected }) => {
+                if let Some((&register, data)) = buffer[..write_len].split_first() {
+                    *selected = register as usize % map.len();
+                    for &byte in data {
+                        map[*selected] = byte;
+                        *selected = (*selected + 1) % map.len();
+                    }
+                }
+                for byte in &mut buffer[..read_len] {
+                    *byte = map[*selected];
+                    *selected = (*selected + 1) % map.len();
+                }
+            }
+        }
+        0
+    }
+}
+
+impl crate::fake::SyscallDriver for I2cMaster {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        if command_id == EXISTS {
+            return crate::command_return::success();
+        }
+        let address = argument0 as u8;
+        let (write_len, read_len) = match command_id {
+            WRITE => (argument1 as usize, 0),
+            READ => (0, argument1 as usize),
+            WRITE_READ => ((argument1 & 0xffff) as usize, (argument1 >> 16) as usize),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        if argument0 > 0x7f || write_len.max(read_len) > self.buffer.borrow().len() {
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+
+        let data = self.buffer.borrow()[..write_len].to_vec();
+        self.transfers.borrow_mut().push(match command_id {
+            WRITE => I2cTransfer::Write { address, data },
+            READ => I2cTransfer::Read {
+                address,
+                len: read_len,
+            },
+            _ => I2cTransfer::WriteRead {
+                address,
+                data,
+                read_len,
+            },
+        });
+        let status = self.run(address, write_len, read_len);
+        self.share_ref
+            .schedule_upcall(0, (command_id, status, 0))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20003;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const WRITE: u32 = 1;
+const READ: u32 = 2;
+const WRITE_READ: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 1;
@@ -0,0 +1,138 @@
+use crate::fake::{self, SyscallDriver};
+use fake::i2c_master::*;
+use libtock_platform::{share, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+use std::cell::Cell;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let i2c = I2cMaster::new();
+
+    assert!(i2c.command(EXISTS, 0, 0).is_success());
+    // No buffer has been shared.
+    assert_eq!(
+        i2c.command(WRITE, 0x10, 1).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        i2c.command(4, 0x10, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert_eq!(i2c.take_transfers(), []);
+}
+
+// Integration test that verifies I2cMaster works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let i2c = I2cMaster::new();
+    kernel.add_driver(&i2c);
+    i2c.add_device(0x10);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    let mut buffer = [1, 2, 3];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, &mut buffer)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 0x10, 2).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((WRITE, 0)));
+
+        // No device is present at 0x11.
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ, 0x11, 3).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((READ, ErrorCode::NoAck as u32)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE_READ, 0x10, 3 << 16 | 1).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((WRITE_READ, 0)));
+    });
+    assert_eq!(buffer, [1, 0, 0]);
+    assert_eq!(
+        i2c.take_transfers(),
+        [
+            I2cTransfer::Write {
+                address: 0x10,
+                data: vec![1, 2]
+            },
+            I2cTransfer::Read {
+                address: 0x11,
+                len: 3
+            },
+            I2cTransfer::WriteRead {
+                address: 0x10,
+                data: vec![1],
+                read_len: 3
+            },
+        ]
+    );
+}
+
+#[test]
+fn register_device() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let i2c = I2cMaster::new();
+    kernel.add_driver(&i2c);
+    i2c.add_register_device(0x76, &[10, 11, 12, 13]);
+    assert_eq!(i2c.get_registers(0x10), None);
+
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    let mut buffer = [2, 0x20, 0x21];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, &mut buffer)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        // Writes registers 2 and 3.
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 0x76, 3).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((WRITE, 0)));
+    });
+    assert_eq!(i2c.get_registers(0x76), Some(vec![10, 11, 0x20, 0x21]));
+
+    // Reads registers 3, 0, and 1, wrapping around.
+    let mut buffer = [3, 0, 0];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, &mut buffer)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE_READ, 0x76, 3 << 16 | 1).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((WRITE_READ, 0)));
+    });
+    assert_eq!(buffer, [0x21, 10, 11]);
+}
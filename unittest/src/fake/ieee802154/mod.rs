@@ -0,0 +1,253 @@
+//! Fake implementation of the IEEE 802.15.4 radio API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/30001_ieee802154.md
+//!
+//! Like the real API, `Ieee802154` applies address, PAN, and channel changes
+//! when they are committed, and completes transmissions as soon as they are
+//! requested. Frames are delivered into the shared receive buffer by
+//! `receive_frame`, by `receive_frame_sync` the next time a receive buffer is
+//! shared, or, in loopback mode, by transmitting to the radio's own address.
+//!
+//! Frames addressed to other radios are only received in promiscuous mode, in
+//! which the buffer receives the raw frame, starting with its MAC header. The
+//! upstream API has no promiscuous mode; the fake adds `SET_PROMISCUOUS` so
+//! that `libtock_ieee802154`'s support for it can be tested. The receive upcall
+//! reports the frame's RSSI and LQI, as set by `set_link_quality`.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+#[derive(Clone, Copy, Default)]
+struct Config {
+    address: u16,
+    pan: u16,
+    channel: u8,
+    promiscuous: bool,
+}
+
+pub struct Ieee802154 {
+    pending: Cell<Config>,
+    config: Cell<Config>,
+    loopback: Cell<bool>,
+    link_quality: Cell<(i8, u8)>,
+    transmitted: RefCell<Vec<(u16, Vec<u8>)>>,
+    frame_on_allow: RefCell<Option<(u16, Vec<u8>)>>,
+    tx_buffer: Cell<RoAllowBuffer>,
+    rx_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Ieee802154 {
+    pub fn new() -> std::rc::Rc<Ieee802154> {
+        std::rc::Rc::new(Ieee802154 {
+            pending: Default::default(),
+            config: Default::default(),
+            loopback: Cell::new(false),
+            link_quality: Cell::new((0, 0)),
+            transmitted: Default::default(),
+            frame_on_allow: Default::default(),
+            tx_buffer: Default::default(),
+            rx_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the committed short address.
+    pub fn address(&self) -> u16 {
+        self.config.get().address
+    }
+
+    /// Returns the committed PAN identifier.
+    pub fn pan(&self) -> u16 {
+        self.config.get().pan
+    }
+
+    /// Returns the committed channel, or 0 if none was committed.
+    pub fn channel(&self) -> u8 {
+        self.config.get().channel
+    }
+
+    /// Returns whether promiscuous mode is committed.
+    pub fn is_promiscuous(&self) -> bool {
+        self.config.get().promiscuous
+    }
+
+    /// Sets the RSSI, in dBm, and LQI reported for frames received from now
+    /// on.
+    pub fn set_link_quality(&self, rssi: i8, lqi: u8) {
+        self.link_quality.set((rssi, lqi));
+    }
+
+    /// When enabled, frames transmitted to the radio's own address (or to the
+    /// broadcast address) are received back.
+    pub fn set_loopback(&self, loopback: bool) {
+        self.loopback.set(loopback);
+    }
+
+    /// Returns the (destination, payload) of every frame transmitted since the
+    /// last call.
+    pub fn take_transmitted(&self) -> Vec<(u16, Vec<u8>)> {
+        self.transmitted.take()
+    }
+
+    /// Receives a frame from `src` into the shared receive buffer, truncating
+    /// it to the buffer's length. The frame is dropped if no buffer is shared.
+    pub fn receive_frame(&self, src: u16, payload: &[u8]) {
+        self.receive_frame_to(self.address(), src, payload);
+    }
+
+    /// Like `receive_frame`, but for a frame addressed to `dest`. Unless
+    /// promiscuous mode is enabled, the frame is dropped if `dest` is neither
+    /// the radio's address nor the broadcast address.
+    pub fn receive_frame_to(&self, dest: u16, src: u16, payload: &[u8]) {
+        let config = self.config.get();
+        let mut frame = Vec::new();
+        if config.promiscuous {
+            // A data frame with PAN ID compression and short addresses.
+            frame.extend_from_slice(&[0x41, 0x88, 0]);
+            frame.extend_from_slice(&config.pan.to_le_bytes());
+            frame.extend_from_slice(&dest.to_le_bytes());
+            frame.extend_from_slice(&src.to_le_bytes());
+        } else if dest != config.address && dest != BROADCAST_ADDR {
+            return;
+        }
+        frame.extend_from_slice(payload);
+
+        let mut buffer = self.rx_buffer.borrow_mut();
+        if buffer.is_empty() {
+            return;
+        }
+        let len = frame.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&frame[..len]);
+        let (rssi, lqi) = self.link_quality.get();
+        let link_quality = rssi as u8 as u32 | (lqi as u32) << 8;
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_RX, (len as u32, src as u32, link_quality))
+            .expect("Unable to schedule upcall");
+    }
+
+    /// Receives a frame from `src` as soon as a receive buffer is shared.
+    pub fn receive_frame_sync(&self, src: u16, payload: &[u8]) {
+        self.frame_on_allow.replace(Some((src, payload.into())));
+    }
+
+    fn transmit(&self, dest: u32, len: u32) -> CommandReturn {
+        let buffer = self.tx_buffer.take();
+        if len as usize > buffer.len() {
+            self.tx_buffer.set(buffer);
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+        if len as usize > MAX_PAYLOAD_LEN {
+            self.tx_buffer.set(buffer);
+            return crate::command_return::failure(ErrorCode::Size);
+        }
+        let payload = buffer[..len as usize].to_vec();
+        self.tx_buffer.set(buffer);
+
+        let dest = dest as u16;
+        let address = self.address();
+        if self.loopback.get() && (dest == address || dest == BROADCAST_ADDR) {
+            self.receive_frame(address, &payload);
+        }
+        self.transmitted.borrow_mut().push((dest, payload));
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_TX, (0, (dest != BROADCAST_ADDR) as u32, 0))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for Ieee802154 {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_TX {
+            Ok(self.tx_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num != ALLOW_RX {
+            return Err((buffer, ErrorCode::Invalid));
+        }
+        let previous = self.rx_buffer.replace(buffer);
+        if !self.rx_buffer.borrow().is_empty() {
+            if let Some((src, payload)) = self.frame_on_allow.take() {
+                self.receive_frame(src, &payload);
+            }
+        }
+        Ok(previous)
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        let mut pending = self.pending.get();
+        match command_id {
+            EXISTS => return crate::command_return::success(),
+            SET_SHORT_ADDR => pending.address = argument0 as u16,
+            SET_PAN => pending.pan = argument0 as u16,
+            SET_CHANNEL => {
+                if !(11..=26).contains(&argument0) {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                pending.channel = argument0 as u8;
+            }
+            SET_PROMISCUOUS => pending.promiscuous = argument0 != 0,
+            COMMIT_CONFIG => self.config.set(pending),
+            TRANSMIT => return self.transmit(argument0, argument1),
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        }
+        self.pending.set(pending);
+        crate::command_return::success()
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SET_SHORT_ADDR: u32 = 3;
+const SET_PAN: u32 = 5;
+const SET_CHANNEL: u32 = 6;
+const COMMIT_CONFIG: u32 = 8;
+const TRANSMIT: u32 = 27;
+const SET_PROMISCUOUS: u32 = 32;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+
+// Subscribe IDs
+
+const SUBSCRIBE_RX: u32 = 0;
+const SUBSCRIBE_TX: u32 = 1;
+
+const BROADCAST_ADDR: u16 = 0xffff;
+
+// The largest MAC payload that fits into a 127-byte frame with short
+// addressing and no security header.
+const MAX_PAYLOAD_LEN: usize = 116;
@@ -0,0 +1,145 @@
+use crate::fake::{self, SyscallDriver};
+use fake::ieee802154::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let radio = Ieee802154::new();
+
+    assert!(radio.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        radio.command(SET_CHANNEL, 27, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert!(radio.command(SET_SHORT_ADDR, 0x1540, 0).is_success());
+    assert!(radio.command(SET_PAN, 0xabcd, 0).is_success());
+    assert!(radio.command(SET_CHANNEL, 26, 0).is_success());
+    // Nothing changes until the configuration is committed.
+    assert_eq!(radio.address(), 0);
+    assert_eq!(radio.channel(), 0);
+    assert!(radio.command(COMMIT_CONFIG, 0, 0).is_success());
+    assert_eq!(radio.address(), 0x1540);
+    assert_eq!(radio.pan(), 0xabcd);
+    assert_eq!(radio.channel(), 26);
+    assert!(radio.command(SET_PROMISCUOUS, 1, 0).is_success());
+    assert!(!radio.is_promiscuous());
+    assert!(radio.command(COMMIT_CONFIG, 0, 0).is_success());
+    assert!(radio.is_promiscuous());
+
+    // No payload has been shared.
+    assert_eq!(
+        radio.command(TRANSMIT, 0x0802, 1).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+}
+
+// Integration test that verifies Ieee802154 works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let radio = Ieee802154::new();
+    kernel.add_driver(&radio);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_SHORT_ADDR, 0x1540, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, COMMIT_CONFIG, 0, 0).is_success());
+    radio.set_loopback(true);
+
+    let sent = Cell::<Option<(u32, u32)>>::new(None);
+    let received = Cell::<Option<(u32, u32)>>::new(None);
+    let mut rx = [0; 4];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_tx, allow_rx, subscribe_rx, subscribe_tx) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, b"hello")
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(
+            subscribe_rx,
+            &received,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(
+            subscribe_tx,
+            &sent,
+        )
+        .unwrap();
+
+        // A frame for another radio is not looped back.
+        assert!(fake::Syscalls::command(DRIVER_NUM, TRANSMIT, 0x0802, 5).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sent.get(), Some((0, 1)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, TRANSMIT, 0x1540, 5).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((4, 0x1540)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+
+        received.set(None);
+        radio.receive_frame(0x0802, b"ab");
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((2, 0x0802)));
+    });
+    assert_eq!(rx, *b"abll");
+    assert_eq!(
+        radio.take_transmitted(),
+        [(0x0802, b"hello".to_vec()), (0x1540, b"hello".to_vec())]
+    );
+}
+
+#[test]
+fn promiscuous() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let radio = Ieee802154::new();
+    kernel.add_driver(&radio);
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_SHORT_ADDR, 0x1540, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_PAN, 0xabcd, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, COMMIT_CONFIG, 0, 0).is_success());
+    radio.set_link_quality(-40, 200);
+
+    let received = Cell::<Option<(u32, u32, u32)>>::new(None);
+    let mut rx = [0; 12];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rx, subscribe_rx) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(
+            subscribe_rx,
+            &received,
+        )
+        .unwrap();
+
+        // Frames for other radios are dropped.
+        radio.receive_frame_to(0x0802, 0x0803, b"hi");
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_PROMISCUOUS, 1, 0).is_success());
+        assert!(fake::Syscalls::command(DRIVER_NUM, COMMIT_CONFIG, 0, 0).is_success());
+        radio.receive_frame_to(0x0802, 0x0803, b"hi");
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((11, 0x0803, 200 << 8 | 0xd8)));
+    });
+    assert_eq!(
+        rx,
+        [0x41, 0x88, 0, 0xcd, 0xab, 0x02, 0x08, 0x03, 0x08, b'h', b'i', 0]
+    );
+}
@@ -1,4 +1,5 @@
 use crate::kernel_data::{with_kernel_data, DriverData, KernelData, KERNEL_DATA};
+use crate::upcall::UpcallId;
 use crate::{DriverShareRef, ExpectedSyscall, SyscallLogEntry};
 use std::cell::Cell;
 
@@ -35,6 +36,8 @@ impl Kernel {
                 create_location: std::panic::Location::caller(),
                 drivers: Default::default(),
                 expected_syscalls: Default::default(),
+                injected_upcalls: Default::default(),
+                memory: Default::default(),
                 syscall_log: Vec::new(),
                 upcall_queue: Default::default(),
             }))
@@ -104,14 +107,85 @@ impl Kernel {
         });
     }
 
+    /// Injects an upcall for subscribe number `subscribe_num` of driver
+    /// `driver_num`, with arguments `args`, to be delivered by the next Yield.
+    /// This lets a test decide when an upcall fires, independently of the
+    /// driver, for instance while the code under test is waiting in a
+    /// blocking call.
+    ///
+    /// At the next Yield, all injected upcalls are added to the upcall queue,
+    /// in the order they were injected, after any upcalls already queued.
+    /// Yield delivers one upcall at a time, so upcalls (including those of a
+    /// single driver) are delivered in the order they were injected. Like a
+    /// driver scheduling an upcall, an injected upcall is discarded if the
+    /// process has not subscribed to it when it is added to the queue.
+    ///
+    /// Panics if no driver with number `driver_num` was added, or if
+    /// `subscribe_num` is not below its upcall count.
+    pub fn inject_upcall(&self, driver_num: u32, subscribe_num: u32, args: (u32, u32, u32)) {
+        with_kernel_data(|kernel_data| {
+            let kernel_data = kernel_data.unwrap();
+            let driver_data = kernel_data
+                .drivers
+                .get(&driver_num)
+                .unwrap_or_else(|| panic!("inject_upcall: no driver with number {}", driver_num));
+            assert!(
+                subscribe_num < driver_data.num_upcalls,
+                "inject_upcall: upcall number {} too large, expected < {}",
+                subscribe_num,
+                driver_data.num_upcalls
+            );
+            let id = UpcallId {
+                driver_num,
+                subscribe_num,
+            };
+            kernel_data.injected_upcalls.push_back((id, args));
+        });
+    }
+
     /// Returns the system call log and empties it.
     pub fn take_syscall_log(&self) -> Vec<SyscallLogEntry> {
         with_kernel_data(|kernel_data| std::mem::take(&mut kernel_data.unwrap().syscall_log))
     }
+
+    /// Uses `memory` as the process' RAM region reported by Memop, and moves
+    /// the break to its start. By default, the region spans
+    /// [`MEMORY_START`](crate::fake::MEMORY_START) to
+    /// [`MEMORY_END`](crate::fake::MEMORY_END), which is not backed by real
+    /// memory; tests that write to memory obtained through Memop (such as
+    /// allocator tests) should provide a buffer instead.
+    pub fn set_memory_region(&self, memory: &'static mut [u8]) {
+        with_kernel_data(|kernel_data| {
+            kernel_data.unwrap().memory = crate::fake::Memory::backed_by(memory)
+        });
+    }
+
+    /// Reserves the top `size` bytes of the process' RAM region for grants,
+    /// which the break cannot be moved into. By default, the grant region is
+    /// empty.
+    pub fn set_grant_size(&self, size: usize) {
+        with_kernel_data(|kernel_data| kernel_data.unwrap().memory.set_grant_size(size));
+    }
 }
 
 impl Drop for Kernel {
     fn drop(&mut self) {
-        KERNEL_DATA.with(|kernel_data| kernel_data.replace(None));
+        let kernel_data = KERNEL_DATA.with(|kernel_data| kernel_data.replace(None));
+        // A buffer the kernel still has access to may already have been freed,
+        // which on a real system lets the capsule access reused memory. Safe
+        // code always unallows buffers at the end of their `share::scope`, so
+        // this only catches incorrect unsafe code. Skipped while unwinding, to
+        // avoid turning a test failure into an abort.
+        if std::thread::panicking() {
+            return;
+        }
+        if let Some(kernel_data) = kernel_data {
+            let active_buffers = kernel_data.allow_db.active_buffers();
+            assert!(
+                active_buffers.is_empty(),
+                "fake::Kernel dropped while buffers are still allowed: {:?}",
+                active_buffers
+            );
+        }
     }
 }
@@ -1,5 +1,8 @@
 use crate::kernel_data::with_kernel_data;
-use crate::{fake, ExpectedSyscall, SyscallLogEntry};
+use crate::{command_return, fake, DriverInfo, ExpectedSyscall, RoAllowBuffer, SyscallLogEntry};
+use libtock_platform::{share, CommandReturn, DefaultConfig, ErrorCode, Subscribe, Syscalls};
+use std::cell::Cell;
+use std::rc::Rc;
 
 #[test]
 fn expected_syscall_queue() {
@@ -45,3 +48,104 @@ fn syscall_log() {
     assert_eq!(kernel.take_syscall_log(), [YieldNoWait, YieldWait]);
     assert_eq!(kernel.take_syscall_log(), []);
 }
+
+// A driver that keeps the buffer passed to Read-Only Allow 0.
+#[derive(Default)]
+struct AllowDriver {
+    buffer: Cell<RoAllowBuffer>,
+}
+
+impl fake::SyscallDriver for AllowDriver {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(1)
+    }
+
+    fn command(&self, _: u32, _: u32, _: u32) -> CommandReturn {
+        command_return::failure(ErrorCode::NoSupport)
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num != 0 {
+            return Err((buffer, ErrorCode::NoSupport));
+        }
+        Ok(self.buffer.replace(buffer))
+    }
+}
+
+#[test]
+fn drop_after_unallow() {
+    let buffer = [1, 2, 3];
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&Rc::new(AllowDriver::default()));
+    share::scope(|allow_ro| {
+        fake::Syscalls::allow_ro::<DefaultConfig, 1, 0>(allow_ro, &buffer).unwrap();
+    });
+    drop(kernel);
+}
+
+#[test]
+#[should_panic(expected = "fake::Kernel dropped while buffers are still allowed")]
+fn drop_while_allowed() {
+    use libtock_platform::{syscall_class, RawSyscalls};
+    let buffer = [1, 2, 3];
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&Rc::new(AllowDriver::default()));
+    // Safety: buffer outlives the kernel, so the driver's reference to it
+    // stays valid.
+    unsafe {
+        fake::Syscalls::syscall4::<{ syscall_class::ALLOW_RO }>([
+            1u32.into(),
+            0u32.into(),
+            buffer.as_ptr().into(),
+            buffer.len().into(),
+        ]);
+    }
+    drop(kernel);
+}
+
+#[test]
+fn inject_upcall() {
+    use libtock_platform::YieldNoWaitReturn::{NoUpcall, Upcall};
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Capsule::builder(1).upcall_count(2).build());
+    kernel.add_driver(&fake::Capsule::builder(2).upcall_count(1).build());
+    let listener_1 = Cell::<Option<(u32,)>>::new(None);
+    let listener_2 = Cell::<Option<(u32,)>>::new(None);
+    share::scope::<(Subscribe<_, 1, 0>, Subscribe<_, 2, 0>), _, _>(|handle| {
+        let (subscribe_1, subscribe_2) = handle.split();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 1, 0>(subscribe_1, &listener_1).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, 2, 0>(subscribe_2, &listener_2).unwrap();
+
+        // Injected upcalls are delivered one per Yield, in injection order.
+        kernel.inject_upcall(1, 0, (10, 0, 0));
+        kernel.inject_upcall(2, 0, (20, 0, 0));
+        kernel.inject_upcall(1, 0, (11, 0, 0));
+        assert_eq!(fake::Syscalls::yield_no_wait(), Upcall);
+        assert_eq!((listener_1.get(), listener_2.get()), (Some((10,)), None));
+        assert_eq!(fake::Syscalls::yield_no_wait(), Upcall);
+        assert_eq!(listener_2.get(), Some((20,)));
+        fake::Syscalls::yield_wait();
+        assert_eq!(listener_1.get(), Some((11,)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), NoUpcall);
+
+        // An upcall with no subscription is discarded.
+        kernel.inject_upcall(1, 1, (12, 0, 0));
+        assert_eq!(fake::Syscalls::yield_no_wait(), NoUpcall);
+    });
+    // As are upcalls that were unsubscribed before the next Yield.
+    kernel.inject_upcall(1, 0, (13, 0, 0));
+    assert_eq!(fake::Syscalls::yield_no_wait(), NoUpcall);
+    assert_eq!(listener_1.get(), Some((11,)));
+}
+
+#[test]
+#[should_panic(expected = "inject_upcall: upcall number 1 too large, expected < 1")]
+fn inject_upcall_invalid() {
+    let kernel = fake::Kernel::new();
+    kernel.add_driver(&fake::Capsule::builder(1).upcall_count(1).build());
+    kernel.inject_upcall(1, 1, (0, 0, 0));
+}
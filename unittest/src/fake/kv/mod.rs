@@ -0,0 +1,157 @@
+//! Fake implementation of the key-value API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/50003_key_value.md
+//!
+//! Like the real API, `KeyValue` stores values under keys shared by the
+//! process, and reports a missing key as `ErrorCode::NoSupport` in the
+//! upcall. Every command completes as soon as it is received.
+//!
+//! The fake also implements `GET_KEY`, which copies the key at an index into
+//! the output buffer. The upstream capsule has no way to enumerate keys; this
+//! command exists so that `libtock_kv`'s support for it can be tested.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+pub struct KeyValue {
+    // Ordered, so that keys are enumerated in a stable order.
+    store: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+    key_buffer: Cell<RoAllowBuffer>,
+    input_buffer: Cell<RoAllowBuffer>,
+    output_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl KeyValue {
+    pub fn new() -> std::rc::Rc<KeyValue> {
+        std::rc::Rc::new(KeyValue {
+            store: Default::default(),
+            key_buffer: Default::default(),
+            input_buffer: Default::default(),
+            output_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the value stored under `key`, if any.
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.store.borrow().get(key).cloned()
+    }
+
+    fn complete(&self, status: Option<ErrorCode>, len: usize) -> CommandReturn {
+        let status = status.map_or(0, |error| error as u32);
+        self.share_ref
+            .schedule_upcall(0, (status, len as u32, 0))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for KeyValue {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_KEY => Ok(self.key_buffer.replace(buffer)),
+            ALLOW_INPUT => Ok(self.input_buffer.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_OUTPUT {
+            Ok(self.output_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        if command_id == EXISTS {
+            return crate::command_return::success();
+        }
+        if command_id == GET_KEY {
+            let store = self.store.borrow();
+            return match store.keys().nth(argument0 as usize) {
+                None => self.complete(Some(ErrorCode::NoSupport), 0),
+                Some(key) => {
+                    let mut output = self.output_buffer.borrow_mut();
+                    let copied = key.len().min(output.len());
+                    output[..copied].copy_from_slice(&key[..copied]);
+                    let status = (copied < key.len()).then(|| ErrorCode::Size);
+                    self.complete(status, key.len())
+                }
+            };
+        }
+        let key_buffer = self.key_buffer.take();
+        let key = key_buffer.to_vec();
+        self.key_buffer.set(key_buffer);
+        if key.is_empty() {
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+
+        match command_id {
+            GET => match self.store.borrow().get(&key) {
+                None => self.complete(Some(ErrorCode::NoSupport), 0),
+                Some(value) => {
+                    let mut output = self.output_buffer.borrow_mut();
+                    let copied = value.len().min(output.len());
+                    output[..copied].copy_from_slice(&value[..copied]);
+                    let status = (copied < value.len()).then(|| ErrorCode::Size);
+                    self.complete(status, value.len())
+                }
+            },
+
+            SET => {
+                let input = self.input_buffer.take();
+                let value = input.to_vec();
+                self.input_buffer.set(input);
+                self.store.borrow_mut().insert(key, value);
+                self.complete(None, 0)
+            }
+
+            DELETE => match self.store.borrow_mut().remove(&key) {
+                None => self.complete(Some(ErrorCode::NoSupport), 0),
+                Some(_) => self.complete(None, 0),
+            },
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50003;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET: u32 = 1;
+const SET: u32 = 2;
+const DELETE: u32 = 3;
+const GET_KEY: u32 = 16;
+
+// Allow IDs
+
+const ALLOW_KEY: u32 = 0;
+const ALLOW_INPUT: u32 = 1;
+const ALLOW_OUTPUT: u32 = 0;
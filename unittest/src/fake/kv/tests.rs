@@ -0,0 +1,85 @@
+use crate::fake::{self, SyscallDriver};
+use fake::kv::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let kv = KeyValue::new();
+
+    assert!(kv.command(EXISTS, 0, 0).is_success());
+    // No key has been shared.
+    assert_eq!(
+        kv.command(GET, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        kv.command(SET, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+}
+
+// Integration test that verifies KeyValue works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let kv = KeyValue::new();
+    kernel.add_driver(&kv);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    let mut output = [0; 2];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_KEY>,
+            AllowRo<_, DRIVER_NUM, ALLOW_INPUT>,
+            AllowRw<_, DRIVER_NUM, ALLOW_OUTPUT>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_key, allow_input, allow_output, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_KEY>(allow_key, b"key")
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_INPUT>(allow_input, b"abc")
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_OUTPUT>(
+            allow_output,
+            &mut output,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((ErrorCode::NoSupport as u32, 0)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 0)));
+        assert_eq!(kv.get(b"key"), Some(b"abc".to_vec()));
+
+        // Enumerates the keys, with the same output buffer handling.
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_KEY, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((ErrorCode::Size as u32, 3)));
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_KEY, 1, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((ErrorCode::NoSupport as u32, 0)));
+
+        // The output buffer is too small for the value.
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((ErrorCode::Size as u32, 3)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, DELETE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 0)));
+        assert_eq!(kv.get(b"key"), None);
+    });
+    assert_eq!(output, *b"ab");
+}
@@ -9,29 +9,82 @@
 //! `use libtock_unittest::fake` and refer to the type with the `fake::` prefix
 //! (e.g. `fake::Console`).
 
+mod adc;
+mod aes;
 mod alarm;
+mod ambient_light;
+mod analog_comparator;
 mod buttons;
+mod buzzer;
+mod can;
+mod capsule;
 mod console;
+mod crc;
+mod ctap;
 mod gpio;
+mod hmac;
+mod humidity;
+mod i2c_master;
+mod ieee802154;
 mod kernel;
+mod kv;
 mod leds;
 mod low_level_debug;
+mod ninedof;
+mod nonvolatile_storage;
 mod proximity;
+mod pwm;
+mod rng;
+mod screen;
+mod servo;
+mod sha;
+mod sound_pressure;
+mod spi_controller;
 mod syscall_driver;
 mod syscalls;
 mod temperature;
+mod text_screen;
+mod udp;
+mod watchdog;
 
+pub use adc::Adc;
+pub use aes::Aes;
 pub use alarm::Alarm;
+pub use ambient_light::AmbientLight;
+pub use analog_comparator::AnalogComparator;
 pub use buttons::Buttons;
+pub use buzzer::Buzzer;
+pub use can::{Can, CanFilter, CanMessage};
+pub use capsule::{Capsule, CapsuleBuilder};
 pub use console::Console;
+pub use crc::Crc;
+pub use ctap::Ctap;
 pub use gpio::{Gpio, GpioMode, InterruptEdge, PullMode};
+pub use hmac::Hmac;
+pub use humidity::Humidity;
+pub use i2c_master::{I2cMaster, I2cTransfer};
+pub use ieee802154::Ieee802154;
 pub use kernel::Kernel;
+pub use kv::KeyValue;
 pub use leds::Leds;
 pub use low_level_debug::{LowLevelDebug, Message};
+pub use ninedof::{NineDof, NineDofSensor};
+pub use nonvolatile_storage::NonvolatileStorage;
 pub use proximity::Proximity;
+pub use pwm::{Pwm, PwmOutput};
+pub use rng::Rng;
+pub use screen::Screen;
+pub use servo::Servo;
+pub use sha::Sha;
+pub use sound_pressure::SoundPressure;
+pub use spi_controller::SpiController;
 pub use syscall_driver::SyscallDriver;
-pub use syscalls::Syscalls;
+pub(crate) use syscalls::Memory;
+pub use syscalls::{Syscalls, FLASH_START, MEMORY_END, MEMORY_START};
 pub use temperature::Temperature;
+pub use text_screen::TextScreen;
+pub use udp::{Udp, UdpDatagram};
+pub use watchdog::Watchdog;
 
 #[cfg(test)]
 mod kernel_tests;
@@ -0,0 +1,104 @@
+//! Fake implementation of the NineDof API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/60004_ninedof.md
+//!
+//! Like the real API, `NineDof` controls a fake accelerometer, magnetometer
+//! and gyroscope that share a single upcall. It provides a function
+//! `set_value` used to immediately call an upcall with the (x, y, z) values
+//! read by the active sensor and a function `set_value_sync` used to call the
+//! upcall when the next read command is received.
+
+use crate::{DriverInfo, DriverShareRef};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::Cell;
+
+/// The sensor a reading was requested from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NineDofSensor {
+    Accelerometer,
+    Magnetometer,
+    Gyroscope,
+}
+
+// The `upcall_on_command` field is set to Some(value) if an upcall(with value as its argument) should be called when read command is received,
+// or None otherwise. It was needed for testing the `read_*_sync` library functions which simulate a synchronous read,
+// because it was impossible to schedule an upcall during the `synchronous` read in other ways.
+pub struct NineDof {
+    reading: Cell<Option<NineDofSensor>>,
+    upcall_on_command: Cell<Option<(i32, i32, i32)>>,
+    share_ref: DriverShareRef,
+}
+
+impl NineDof {
+    pub fn new() -> std::rc::Rc<NineDof> {
+        std::rc::Rc::new(NineDof {
+            reading: Cell::new(None),
+            upcall_on_command: Cell::new(None),
+            share_ref: Default::default(),
+        })
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.reading.get().is_some()
+    }
+
+    /// Returns the sensor the in-progress reading was requested from, if any.
+    pub fn reading(&self) -> Option<NineDofSensor> {
+        self.reading.get()
+    }
+
+    pub fn set_value(&self, value: (i32, i32, i32)) {
+        if self.is_busy() {
+            self.share_ref
+                .schedule_upcall(0, (value.0 as u32, value.1 as u32, value.2 as u32))
+                .expect("Unable to schedule upcall");
+            self.reading.set(None);
+        }
+    }
+
+    pub fn set_value_sync(&self, value: (i32, i32, i32)) {
+        self.upcall_on_command.set(Some(value));
+    }
+}
+
+impl crate::fake::SyscallDriver for NineDof {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn command(&self, command_id: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        let sensor = match command_id {
+            EXISTS => return crate::command_return::success(),
+            READ_ACCELEROMETER => NineDofSensor::Accelerometer,
+            READ_MAGNETOMETER => NineDofSensor::Magnetometer,
+            READ_GYROSCOPE => NineDofSensor::Gyroscope,
+            _ => return crate::command_return::failure(ErrorCode::NoSupport),
+        };
+        if self.is_busy() {
+            return crate::command_return::failure(ErrorCode::Busy);
+        }
+        self.reading.set(Some(sensor));
+        if let Some(val) = self.upcall_on_command.take() {
+            self.set_value(val);
+        }
+        crate::command_return::success()
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x60004;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ_ACCELEROMETER: u32 = 1;
+const READ_MAGNETOMETER: u32 = 100;
+const READ_GYROSCOPE: u32 = 200;
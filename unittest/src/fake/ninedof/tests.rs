@@ -0,0 +1,73 @@
+use crate::fake::{self, SyscallDriver};
+use fake::ninedof::*;
+use libtock_platform::{share, DefaultConfig, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let ninedof = NineDof::new();
+
+    assert!(ninedof.command(EXISTS, 1, 2).is_success());
+
+    assert!(ninedof.command(READ_ACCELEROMETER, 0, 0).is_success());
+    assert_eq!(ninedof.reading(), Some(NineDofSensor::Accelerometer));
+
+    assert_eq!(
+        ninedof.command(READ_GYROSCOPE, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    assert_eq!(ninedof.reading(), Some(NineDofSensor::Accelerometer));
+
+    ninedof.set_value((1, 2, 3));
+    assert!(!ninedof.is_busy());
+    assert!(ninedof.command(READ_MAGNETOMETER, 0, 0).is_success());
+    assert_eq!(ninedof.reading(), Some(NineDofSensor::Magnetometer));
+    ninedof.set_value((1, 2, 3));
+
+    ninedof.set_value_sync((1, 2, 3));
+    assert!(ninedof.command(READ_GYROSCOPE, 0, 0).is_success());
+    assert!(!ninedof.is_busy());
+
+    assert_eq!(
+        ninedof.command(2, 0, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+}
+
+// Integration test that verifies NineDof works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let ninedof = NineDof::new();
+    kernel.add_driver(&ninedof);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 1, 2).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, READ_ACCELEROMETER, 0, 0).is_success());
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, READ_MAGNETOMETER, 0, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    ninedof.set_value((1, 2, 3));
+
+    let listener = Cell::<Option<(u32, u32, u32)>>::new(None);
+    share::scope(|subscribe| {
+        assert_eq!(
+            fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener),
+            Ok(())
+        );
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ_MAGNETOMETER, 0, 0).is_success());
+        ninedof.set_value((-1, 0, 1));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((-1i32 as u32, 0, 1)));
+
+        ninedof.set_value((4, 5, 6));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        ninedof.set_value_sync((4, 5, 6));
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ_GYROSCOPE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((4, 5, 6)));
+    });
+}
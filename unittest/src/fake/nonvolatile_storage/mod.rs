@@ -0,0 +1,157 @@
+//! Fake implementation of the nonvolatile storage API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/50001_nonvolatile_storage.md
+//!
+//! Like the real API, `NonvolatileStorage` gives the process a fixed-size
+//! region it can read from and write to at any offset. The region is 1024
+//! bytes long and initially erased (filled with 0xff). Reads and writes
+//! complete as soon as they are started. A power loss can be emulated with
+//! `fail_writes_after`.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct NonvolatileStorage {
+    region: RefCell<Vec<u8>>,
+    read_buffer: RefCell<RwAllowBuffer>,
+    write_buffer: Cell<RoAllowBuffer>,
+    // The number of writes that succeed before writes start failing.
+    writes_left: Cell<Option<usize>>,
+    share_ref: DriverShareRef,
+}
+
+impl NonvolatileStorage {
+    pub fn new() -> std::rc::Rc<NonvolatileStorage> {
+        std::rc::Rc::new(NonvolatileStorage {
+            region: RefCell::new(vec![0xff; REGION_SIZE]),
+            read_buffer: Default::default(),
+            write_buffer: Default::default(),
+            writes_left: Cell::new(None),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the contents of the region.
+    pub fn contents(&self) -> Vec<u8> {
+        self.region.borrow().clone()
+    }
+
+    /// Makes write commands fail with `ErrorCode::Fail`, without changing the
+    /// region, once `writes` more writes have completed. This emulates a power
+    /// loss for code that must survive one. `None` lets writes succeed again.
+    pub fn fail_writes_after(&self, writes: Option<usize>) {
+        self.writes_left.set(writes);
+    }
+
+    // Returns the range of the region accessed by an operation, if it fits.
+    fn range(offset: u32, len: u32, buffer_len: usize) -> Option<std::ops::Range<usize>> {
+        let (offset, len) = (offset as usize, len as usize);
+        if len > buffer_len || offset.checked_add(len)? > REGION_SIZE {
+            return None;
+        }
+        Some(offset..offset + len)
+    }
+}
+
+impl crate::fake::SyscallDriver for NonvolatileStorage {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_WRITE {
+            Ok(self.write_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_READ {
+            Ok(self.read_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            GET_SIZE => crate::command_return::success_u32(REGION_SIZE as u32),
+
+            READ => {
+                let mut buffer = self.read_buffer.borrow_mut();
+                let range = match Self::range(argument0, argument1, buffer.len()) {
+                    None => return crate::command_return::failure(ErrorCode::Invalid),
+                    Some(range) => range,
+                };
+                buffer[..range.len()].copy_from_slice(&self.region.borrow()[range]);
+                self.share_ref
+                    .schedule_upcall(SUBSCRIBE_READ, (argument1, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+
+            WRITE => {
+                match self.writes_left.get() {
+                    Some(0) => return crate::command_return::failure(ErrorCode::Fail),
+                    Some(left) => self.writes_left.set(Some(left - 1)),
+                    None => {}
+                }
+                let buffer = self.write_buffer.take();
+                let range = Self::range(argument0, argument1, buffer.len());
+                if let Some(range) = range.clone() {
+                    self.region.borrow_mut()[range.clone()].copy_from_slice(&buffer[..range.len()]);
+                }
+                self.write_buffer.set(buffer);
+                if range.is_none() {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.share_ref
+                    .schedule_upcall(SUBSCRIBE_WRITE, (argument1, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x50001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_SIZE: u32 = 1;
+const READ: u32 = 2;
+const WRITE: u32 = 3;
+
+// Allow IDs
+
+const ALLOW_READ: u32 = 0;
+const ALLOW_WRITE: u32 = 0;
+
+// Subscribe IDs
+
+const SUBSCRIBE_READ: u32 = 0;
+const SUBSCRIBE_WRITE: u32 = 1;
+
+const REGION_SIZE: usize = 1024;
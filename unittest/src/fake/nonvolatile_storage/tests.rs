@@ -0,0 +1,90 @@
+use crate::fake::{self, SyscallDriver};
+use fake::nonvolatile_storage::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+use std::cell::Cell;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let storage = NonvolatileStorage::new();
+
+    assert!(storage.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        storage.command(GET_SIZE, 0, 0).get_success_u32(),
+        Some(REGION_SIZE as u32)
+    );
+    // No buffers have been shared.
+    assert_eq!(
+        storage.command(READ, 0, 1).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        storage.command(WRITE, 0, 1).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    storage.fail_writes_after(Some(0));
+    assert_eq!(
+        storage.command(WRITE, 0, 1).get_failure(),
+        Some(ErrorCode::Fail)
+    );
+    assert_eq!(storage.contents(), vec![0xff; REGION_SIZE]);
+}
+
+// Integration test that verifies NonvolatileStorage works with fake::Kernel
+// and libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let storage = NonvolatileStorage::new();
+    kernel.add_driver(&storage);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let read_listener = Cell::<Option<(u32,)>>::new(None);
+    let write_listener = Cell::<Option<(u32,)>>::new(None);
+    let mut read_buffer = [0; 4];
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_READ>,
+            AllowRo<_, DRIVER_NUM, ALLOW_WRITE>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_READ>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_WRITE>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, allow_ro, subscribe_read, subscribe_write) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_READ>(
+            allow_rw,
+            &mut read_buffer,
+        )
+        .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_WRITE>(allow_ro, &[1, 2])
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_READ>(
+            subscribe_read,
+            &read_listener,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_WRITE>(
+            subscribe_write,
+            &write_listener,
+        )
+        .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 1, 2).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(write_listener.get(), Some((2,)));
+
+        // The region ends 2 bytes after offset 1022.
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, READ, 1022, 4).get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ, 0, 4).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(read_listener.get(), Some((4,)));
+    });
+    assert_eq!(read_buffer, [0xff, 1, 2, 0xff]);
+}
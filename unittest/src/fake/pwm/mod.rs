@@ -0,0 +1,107 @@
+//! Fake implementation of the PWM API, documented here:
+//! https://github.com/tock/tock/blob/master/capsules/extra/src/pwm.rs
+//!
+//! Like the real API, `Pwm` controls a set of fake PWM pins, and remembers the
+//! frequency and duty cycle each one was last started with. It records every
+//! start, so tests can check how an output changed over time.
+
+use crate::DriverInfo;
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct Pwm<const PINS_COUNT: usize> {
+    outputs: [Cell<Option<PwmOutput>>; PINS_COUNT],
+    starts: RefCell<Vec<(u32, PwmOutput)>>,
+}
+
+/// The output of a running PWM pin.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PwmOutput {
+    pub frequency_hz: u32,
+    /// The duty cycle, in hundredths of a percent.
+    pub duty_cycle: u16,
+}
+
+impl<const PINS_COUNT: usize> Pwm<PINS_COUNT> {
+    pub fn new() -> std::rc::Rc<Pwm<PINS_COUNT>> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const STOPPED: Cell<Option<PwmOutput>> = Cell::new(None);
+        std::rc::Rc::new(Pwm {
+            outputs: [STOPPED; PINS_COUNT],
+            starts: Default::default(),
+        })
+    }
+
+    /// Returns the output of `pin`, or None if it is stopped.
+    pub fn get_output(&self, pin: u32) -> Option<PwmOutput> {
+        self.outputs[pin as usize].get()
+    }
+
+    /// Returns the (pin, output) of every start since the last call.
+    pub fn take_starts(&self) -> Vec<(u32, PwmOutput)> {
+        self.starts.take()
+    }
+}
+
+impl<const PINS_COUNT: usize> crate::fake::SyscallDriver for Pwm<PINS_COUNT> {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM)
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            START => {
+                let pin = argument0 & 0xffff;
+                let duty_cycle = (argument0 >> 16) as u16;
+                let output = match self.outputs.get(pin as usize) {
+                    Some(output) => output,
+                    None => return crate::command_return::failure(ErrorCode::Invalid),
+                };
+                if argument1 == 0 || argument1 > MAX_FREQUENCY_HZ || duty_cycle > MAX_DUTY_CYCLE {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                let started = PwmOutput {
+                    frequency_hz: argument1,
+                    duty_cycle,
+                };
+                output.set(Some(started));
+                self.starts.borrow_mut().push((pin, started));
+                crate::command_return::success()
+            }
+            STOP => match self.outputs.get(argument0 as usize) {
+                None => crate::command_return::failure(ErrorCode::Invalid),
+                Some(output) => {
+                    output.set(None);
+                    crate::command_return::success()
+                }
+            },
+            GET_MAX_FREQUENCY => {
+                if argument0 as usize >= PINS_COUNT {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                crate::command_return::success_u32(MAX_FREQUENCY_HZ)
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x10000;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const START: u32 = 1;
+const STOP: u32 = 2;
+const GET_MAX_FREQUENCY: u32 = 3;
+
+const MAX_FREQUENCY_HZ: u32 = 1_000_000;
+const MAX_DUTY_CYCLE: u16 = 10_000;
@@ -0,0 +1,59 @@
+use crate::fake::{self, SyscallDriver};
+use fake::pwm::*;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let pwm = Pwm::<2>::new();
+
+    assert!(pwm.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        pwm.command(GET_MAX_FREQUENCY, 1, 0).get_success_u32(),
+        Some(MAX_FREQUENCY_HZ)
+    );
+
+    assert!(pwm.command(START, 5000 << 16 | 1, 1000).is_success());
+    assert_eq!(
+        pwm.get_output(1),
+        Some(PwmOutput {
+            frequency_hz: 1000,
+            duty_cycle: 5000
+        })
+    );
+    assert_eq!(pwm.get_output(0), None);
+
+    assert_eq!(
+        pwm.command(START, 10_001 << 16 | 1, 1000).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        pwm.command(START, 2, 1000).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert!(pwm.command(STOP, 1, 0).is_success());
+    assert_eq!(pwm.get_output(1), None);
+    assert_eq!(
+        pwm.command(STOP, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+}
+
+// Integration test that verifies Pwm works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let pwm = Pwm::<2>::new();
+    kernel.add_driver(&pwm);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, START, 2500 << 16, 500).is_success());
+    assert_eq!(
+        pwm.get_output(0),
+        Some(PwmOutput {
+            frequency_hz: 500,
+            duty_cycle: 2500
+        })
+    );
+}
@@ -0,0 +1,114 @@
+//! Fake implementation of the RNG API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/40001_rng.md
+//!
+//! Like the real API, `Rng` fills a buffer shared by the process with random
+//! bytes. The "random" bytes are provided by the test through `add_bytes`.
+//! A request is completed as soon as bytes are available; if fewer bytes are
+//! available than were requested, the request is completed with only those
+//! bytes, which allows testing partial fills.
+
+use crate::{DriverInfo, DriverShareRef, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct Rng {
+    // The number of bytes requested by the in-progress command, if any.
+    requested: Cell<Option<usize>>,
+    bytes: RefCell<Vec<u8>>,
+    buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Rng {
+    pub fn new() -> std::rc::Rc<Rng> {
+        std::rc::Rc::new(Rng {
+            requested: Cell::new(None),
+            bytes: Default::default(),
+            buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.requested.get().is_some()
+    }
+
+    /// Adds bytes to be returned by future requests. If a request is in
+    /// progress, it is completed immediately.
+    pub fn add_bytes(&self, bytes: &[u8]) {
+        self.bytes.borrow_mut().extend_from_slice(bytes);
+        self.complete_request();
+    }
+
+    fn complete_request(&self) {
+        let requested = match self.requested.get() {
+            Some(requested) => requested,
+            None => return,
+        };
+        let mut bytes = self.bytes.borrow_mut();
+        if bytes.is_empty() {
+            return;
+        }
+        let mut buffer = self.buffer.borrow_mut();
+        let count = requested.min(buffer.len()).min(bytes.len());
+        buffer[..count].copy_from_slice(&bytes[..count]);
+        bytes.drain(..count);
+        self.requested.set(None);
+        self.share_ref
+            .schedule_upcall(0, (0, count as u32, 0))
+            .expect("Unable to schedule upcall");
+    }
+}
+
+impl crate::fake::SyscallDriver for Rng {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+
+            GET_BYTES => {
+                if self.is_busy() {
+                    return crate::command_return::failure(ErrorCode::Busy);
+                }
+                self.requested.set(Some(argument0 as usize));
+                self.complete_request();
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x40001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_BYTES: u32 = 1;
+
+const ALLOW_BUFFER: u32 = 0;
@@ -0,0 +1,66 @@
+use crate::fake::{self, SyscallDriver};
+use fake::rng::*;
+use libtock_platform::{share, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let rng = Rng::new();
+
+    assert!(rng.command(EXISTS, 1, 2).is_success());
+
+    assert!(rng.command(GET_BYTES, 4, 0).is_success());
+    assert!(rng.is_busy());
+    assert_eq!(
+        rng.command(GET_BYTES, 4, 0).get_failure(),
+        Some(ErrorCode::Busy)
+    );
+    rng.add_bytes(&[1, 2]);
+    assert!(!rng.is_busy());
+}
+
+// Integration test that verifies Rng works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let rng = Rng::new();
+    kernel.add_driver(&rng);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 1, 2).is_success());
+
+    let mut buf = [0; 4];
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_rw, &mut buf)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        rng.add_bytes(&[1, 2, 3, 4, 5, 6]);
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_BYTES, 3, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 3)));
+
+        // Only 3 bytes are left, so the request is partially filled.
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_BYTES, 4, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 3)));
+
+        // No bytes left: the request is only completed once bytes are added.
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_BYTES, 1, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+        rng.add_bytes(&[7]);
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 1)));
+    });
+    assert_eq!(buf, [7, 5, 6, 0]);
+}
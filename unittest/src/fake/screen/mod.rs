@@ -0,0 +1,133 @@
+//! Fake implementation of the screen API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90001_screen.md
+//!
+//! `Screen` emulates a 128x64 RGB565 display. Every command completes as
+//! soon as it is received. Written pixel data is recorded along with the
+//! write frame it was written to, and can be retrieved via `take_writes`.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+/// A write frame, as (x, y, width, height).
+pub type Frame = (u32, u32, u32, u32);
+
+pub struct Screen {
+    frame: Cell<Frame>,
+    max_write_len: Cell<Option<usize>>,
+    writes: RefCell<Vec<(Frame, Vec<u8>)>>,
+    buffer: Cell<RoAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Screen {
+    pub fn new() -> std::rc::Rc<Screen> {
+        std::rc::Rc::new(Screen {
+            frame: Cell::new((0, 0, WIDTH, HEIGHT)),
+            max_write_len: Cell::new(None),
+            writes: Default::default(),
+            buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    pub fn frame(&self) -> Frame {
+        self.frame.get()
+    }
+
+    /// Limits the number of bytes accepted by each write command, emulating a
+    /// kernel with a small internal buffer.
+    pub fn set_max_write_len(&self, len: usize) {
+        self.max_write_len.set(Some(len));
+    }
+
+    /// Returns the pixel data written by each write command, together with
+    /// the write frame it was written to, and clears them.
+    pub fn take_writes(&self) -> Vec<(Frame, Vec<u8>)> {
+        self.writes.take()
+    }
+
+    fn complete(&self, data: u32) -> CommandReturn {
+        self.share_ref
+            .schedule_upcall(0, (0, data, 0))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for Screen {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            GET_RESOLUTION => crate::command_return::success_2_u32(WIDTH, HEIGHT),
+            GET_PIXEL_FORMAT => crate::command_return::success_u32(PIXEL_FORMAT_RGB565),
+
+            SET_WRITE_FRAME => {
+                let (x, y) = (argument0 & 0xffff, argument0 >> 16);
+                let (width, height) = (argument1 & 0xffff, argument1 >> 16);
+                if x + width > WIDTH || y + height > HEIGHT {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.frame.set((x, y, width, height));
+                self.complete(0)
+            }
+
+            WRITE => {
+                let buffer = self.buffer.take();
+                let mut len = (argument0 as usize).min(buffer.len());
+                if let Some(max_write_len) = self.max_write_len.get() {
+                    len = len.min(max_write_len);
+                }
+                let pixels = buffer[..len].to_vec();
+                self.buffer.set(buffer);
+                self.writes.borrow_mut().push((self.frame.get(), pixels));
+                self.complete(len as u32)
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_RESOLUTION: u32 = 23;
+const GET_PIXEL_FORMAT: u32 = 25;
+const SET_WRITE_FRAME: u32 = 100;
+const WRITE: u32 = 200;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 0;
+
+const WIDTH: u32 = 128;
+const HEIGHT: u32 = 64;
+const PIXEL_FORMAT_RGB565: u32 = 2;
@@ -0,0 +1,71 @@
+use crate::fake::{self, SyscallDriver};
+use fake::screen::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, Subscribe, YieldNoWaitReturn};
+use std::cell::Cell;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let screen = Screen::new();
+
+    assert!(screen.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        screen.command(GET_RESOLUTION, 0, 0).get_success_2_u32(),
+        Some((WIDTH, HEIGHT))
+    );
+    assert_eq!(
+        screen.command(GET_PIXEL_FORMAT, 0, 0).get_success_u32(),
+        Some(PIXEL_FORMAT_RGB565)
+    );
+    // The frame would extend past the right edge.
+    assert_eq!(
+        screen
+            .command(SET_WRITE_FRAME, 120, 16 | 1 << 16)
+            .get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(screen.frame(), (0, 0, WIDTH, HEIGHT));
+}
+
+// Integration test that verifies Screen works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let screen = Screen::new();
+    kernel.add_driver(&screen);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let listener = Cell::<Option<(u32, u32)>>::new(None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(
+            allow_ro,
+            &[1, 2, 3, 4],
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(
+            fake::Syscalls::command(DRIVER_NUM, SET_WRITE_FRAME, 8 | 4 << 16, 2 | 1 << 16)
+                .is_success()
+        );
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(screen.frame(), (8, 4, 2, 1));
+
+        screen.set_max_write_len(3);
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 4, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 3)));
+    });
+    assert_eq!(screen.take_writes(), [((8, 4, 2, 1), vec![1, 2, 3])]);
+}
@@ -0,0 +1,83 @@
+//! Fake implementation of the servo API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90009_servo.md
+//!
+//! Like the real API, `Servo` controls a set of fake servos and remembers the
+//! angle each one was last moved to. It records every angle it is asked to
+//! move to, so tests can check the path a servo took.
+
+use crate::DriverInfo;
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct Servo<const SERVOS_COUNT: usize> {
+    angles: [Cell<Option<u16>>; SERVOS_COUNT],
+    moves: RefCell<Vec<(u32, u16)>>,
+}
+
+impl<const SERVOS_COUNT: usize> Servo<SERVOS_COUNT> {
+    pub fn new() -> std::rc::Rc<Servo<SERVOS_COUNT>> {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const UNSET: Cell<Option<u16>> = Cell::new(None);
+        std::rc::Rc::new(Servo {
+            angles: [UNSET; SERVOS_COUNT],
+            moves: Default::default(),
+        })
+    }
+
+    /// Returns the angle `servo` was last moved to, if any.
+    pub fn get_angle(&self, servo: u32) -> Option<u16> {
+        self.angles[servo as usize].get()
+    }
+
+    /// Returns the (servo, angle) of every move since the last call.
+    pub fn take_moves(&self) -> Vec<(u32, u16)> {
+        self.moves.take()
+    }
+}
+
+impl<const SERVOS_COUNT: usize> crate::fake::SyscallDriver for Servo<SERVOS_COUNT> {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM)
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            SERVO_COUNT => crate::command_return::success_u32(SERVOS_COUNT as u32),
+            SET_ANGLE => {
+                if argument0 >= SERVOS_COUNT as u32 || argument1 > MAX_ANGLE {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.angles[argument0 as usize].set(Some(argument1 as u16));
+                self.moves.borrow_mut().push((argument0, argument1 as u16));
+                crate::command_return::success()
+            }
+            GET_ANGLE => match self.angles.get(argument0 as usize).map(Cell::get) {
+                None => crate::command_return::failure(ErrorCode::Invalid),
+                // The capsule cannot know where the servo is until it is
+                // first moved.
+                Some(None) => crate::command_return::failure(ErrorCode::Fail),
+                Some(Some(angle)) => crate::command_return::success_u32(angle as u32),
+            },
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90009;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SERVO_COUNT: u32 = 1;
+const SET_ANGLE: u32 = 2;
+const GET_ANGLE: u32 = 3;
+
+const MAX_ANGLE: u32 = 180;
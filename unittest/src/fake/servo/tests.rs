@@ -0,0 +1,50 @@
+use crate::fake::{self, SyscallDriver};
+use fake::servo::*;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let servo = Servo::<2>::new();
+
+    assert!(servo.command(EXISTS, 0, 0).is_success());
+    assert_eq!(servo.command(SERVO_COUNT, 0, 0).get_success_u32(), Some(2));
+
+    assert_eq!(
+        servo.command(GET_ANGLE, 1, 0).get_failure(),
+        Some(ErrorCode::Fail)
+    );
+    assert!(servo.command(SET_ANGLE, 1, 90).is_success());
+    assert_eq!(servo.get_angle(1), Some(90));
+    assert_eq!(servo.command(GET_ANGLE, 1, 0).get_success_u32(), Some(90));
+
+    assert_eq!(
+        servo.command(SET_ANGLE, 1, 181).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        servo.command(SET_ANGLE, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        servo.command(GET_ANGLE, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(servo.take_moves(), [(1, 90)]);
+}
+
+// Integration test that verifies Servo works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let servo = Servo::<2>::new();
+    kernel.add_driver(&servo);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_ANGLE, 0, 45).is_success());
+    assert_eq!(
+        fake::Syscalls::command(DRIVER_NUM, GET_ANGLE, 0, 0).get_success_u32(),
+        Some(45)
+    );
+    assert_eq!(servo.get_angle(0), Some(45));
+}
@@ -0,0 +1,85 @@
+use crate::fake::{self, SyscallDriver};
+use fake::sha::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let sha = Sha::new();
+
+    assert_eq!(
+        sha.command(UPDATE, 0, 0).get_failure(),
+        Some(ErrorCode::Reserve)
+    );
+    assert_eq!(
+        sha.command(SET_ALGORITHM, 1, 0).get_failure(),
+        Some(ErrorCode::NoSupport)
+    );
+    assert!(sha.command(SET_ALGORITHM, ALGORITHM_SHA256, 0).is_success());
+    // No destination buffer has been shared.
+    assert_eq!(
+        sha.command(FINISH, 0, 0).get_failure(),
+        Some(ErrorCode::Size)
+    );
+}
+
+#[test]
+fn sha256_vectors() {
+    assert_eq!(
+        sha256(b""),
+        [
+            0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f,
+            0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b,
+            0x78, 0x52, 0xb8, 0x55
+        ]
+    );
+    // Two-block message from FIPS 180-4 appendix B.2.
+    assert_eq!(
+        sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        [
+            0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e,
+            0x60, 0x39, 0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4,
+            0x19, 0xdb, 0x06, 0xc1
+        ]
+    );
+}
+
+// Integration test that verifies Sha works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let sha = Sha::new();
+    kernel.add_driver(&sha);
+    assert!(fake::Syscalls::command(DRIVER_NUM, SET_ALGORITHM, ALGORITHM_SHA256, 0).is_success());
+
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    let mut digest = [0; 32];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_DATA>,
+            AllowRw<_, DRIVER_NUM, ALLOW_DEST>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, allow_rw, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_DATA>(allow_ro, b"ab").unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_DEST>(allow_rw, &mut digest)
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, UPDATE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0,)));
+        assert_eq!(sha.data_len(), 2);
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, FINISH, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sha.data_len(), 0);
+    });
+    assert_eq!(digest, sha256(b"ab"));
+}
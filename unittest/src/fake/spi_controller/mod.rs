@@ -0,0 +1,205 @@
+//! Fake implementation of the SPI controller API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/20001_spi_controller.md
+//!
+//! `SpiController` emulates a bus with a single device, which shifts out the
+//! bytes given to `set_response` while the controller shifts data in. Bytes
+//! written to the device are recorded and can be retrieved via `take_written`.
+//! Transfers complete as soon as they are started. The controller has
+//! `CHIP_SELECT_COUNT` chip select lines; the selected line and whether it is
+//! held low between transfers are exposed to tests.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+pub struct SpiController {
+    rate: Cell<u32>,
+    polarity: Cell<u32>,
+    phase: Cell<u32>,
+    chip_select: Cell<u32>,
+    held_low: Cell<bool>,
+    response: RefCell<Vec<u8>>,
+    written: RefCell<Vec<u8>>,
+    tx_buffer: Cell<RoAllowBuffer>,
+    rx_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl SpiController {
+    pub fn new() -> std::rc::Rc<SpiController> {
+        std::rc::Rc::new(SpiController {
+            rate: Cell::new(DEFAULT_RATE),
+            polarity: Cell::new(0),
+            phase: Cell::new(0),
+            chip_select: Cell::new(0),
+            held_low: Cell::new(false),
+            response: Default::default(),
+            written: Default::default(),
+            tx_buffer: Default::default(),
+            rx_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    pub fn rate(&self) -> u32 {
+        self.rate.get()
+    }
+
+    pub fn polarity(&self) -> u32 {
+        self.polarity.get()
+    }
+
+    pub fn phase(&self) -> u32 {
+        self.phase.get()
+    }
+
+    pub fn chip_select(&self) -> u32 {
+        self.chip_select.get()
+    }
+
+    /// Returns true if the chip select line is held low between transfers.
+    pub fn is_held_low(&self) -> bool {
+        self.held_low.get()
+    }
+
+    /// Sets the bytes the device shifts out during the next transfers. Once
+    /// they have all been shifted out, the device shifts out zeroes.
+    pub fn set_response(&self, response: &[u8]) {
+        self.response.replace(response.into());
+    }
+
+    /// Returns the bytes written to the device so far, and clears them.
+    pub fn take_written(&self) -> Vec<u8> {
+        self.written.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for SpiController {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_TX {
+            Ok(self.tx_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_RX {
+            Ok(self.rx_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+
+            READ_WRITE => {
+                let len = argument0 as usize;
+                let tx = self.tx_buffer.take();
+                let mut rx = self.rx_buffer.borrow_mut();
+                if len == 0 || len > tx.len() || len > rx.len() {
+                    self.tx_buffer.set(tx);
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.written.borrow_mut().extend_from_slice(&tx[..len]);
+                self.tx_buffer.set(tx);
+                let mut response = self.response.borrow_mut();
+                let shifted = len.min(response.len());
+                rx[..shifted].copy_from_slice(&response[..shifted]);
+                rx[shifted..len].fill(0);
+                response.drain(..shifted);
+                self.share_ref
+                    .schedule_upcall(0, (argument0, 0, 0))
+                    .expect("Unable to schedule upcall");
+                crate::command_return::success()
+            }
+
+            SET_CHIP_SELECT => {
+                if argument0 >= CHIP_SELECT_COUNT {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.chip_select.set(argument0);
+                crate::command_return::success()
+            }
+
+            SET_RATE => {
+                if argument0 == 0 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.rate.set(argument0.min(MAX_RATE));
+                crate::command_return::success()
+            }
+            GET_RATE => crate::command_return::success_u32(self.rate.get()),
+
+            SET_PHASE | SET_POLARITY => {
+                if argument0 > 1 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                match command_id {
+                    SET_PHASE => self.phase.set(argument0),
+                    _ => self.polarity.set(argument0),
+                }
+                crate::command_return::success()
+            }
+
+            HOLD_LOW => {
+                self.held_low.set(true);
+                crate::command_return::success()
+            }
+            RELEASE_LOW => {
+                self.held_low.set(false);
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x20001;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const READ_WRITE: u32 = 2;
+const SET_CHIP_SELECT: u32 = 3;
+const SET_RATE: u32 = 5;
+const GET_RATE: u32 = 6;
+const SET_PHASE: u32 = 7;
+const SET_POLARITY: u32 = 9;
+const HOLD_LOW: u32 = 11;
+const RELEASE_LOW: u32 = 12;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+
+const CHIP_SELECT_COUNT: u32 = 4;
+const DEFAULT_RATE: u32 = 1_000_000;
+// The fastest rate supported by the fake controller; faster requests are
+// rounded down to it.
+const MAX_RATE: u32 = 8_000_000;
@@ -0,0 +1,86 @@
+use crate::fake::{self, SyscallDriver};
+use fake::spi_controller::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+use std::cell::Cell;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let spi = SpiController::new();
+
+    assert!(spi.command(EXISTS, 0, 0).is_success());
+    // No buffers have been shared.
+    assert_eq!(
+        spi.command(READ_WRITE, 1, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert_eq!(
+        spi.command(GET_RATE, 0, 0).get_success_u32(),
+        Some(DEFAULT_RATE)
+    );
+    assert_eq!(
+        spi.command(SET_RATE, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(spi.command(SET_RATE, 100_000_000, 0).is_success());
+    assert_eq!(spi.rate(), MAX_RATE);
+
+    assert!(spi.command(SET_PHASE, 1, 0).is_success());
+    assert_eq!(spi.phase(), 1);
+    assert!(spi.command(SET_POLARITY, 1, 0).is_success());
+    assert_eq!(spi.polarity(), 1);
+    assert_eq!(
+        spi.command(SET_POLARITY, 2, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert!(spi.command(SET_CHIP_SELECT, 2, 0).is_success());
+    assert_eq!(spi.chip_select(), 2);
+    assert_eq!(
+        spi.command(SET_CHIP_SELECT, CHIP_SELECT_COUNT, 0)
+            .get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert!(spi.command(HOLD_LOW, 0, 0).is_success());
+    assert!(spi.is_held_low());
+    assert!(spi.command(RELEASE_LOW, 0, 0).is_success());
+    assert!(!spi.is_held_low());
+}
+
+// Integration test that verifies SpiController works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let spi = SpiController::new();
+    kernel.add_driver(&spi);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    spi.set_response(&[0xa5, 0x5a]);
+    let listener = Cell::<Option<(u32,)>>::new(None);
+    let mut rx = [0xff; 3];
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_tx, allow_rx, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, &[1, 2, 3])
+            .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, READ_WRITE, 3, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((3,)));
+    });
+    assert_eq!(rx, [0xa5, 0x5a, 0]);
+    assert_eq!(spi.take_written(), [1, 2, 3]);
+}
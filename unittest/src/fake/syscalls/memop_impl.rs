@@ -0,0 +1,141 @@
+//! `fake::Kernel`'s implementation of the Memop system call.
+//!
+//! By default, the process' memory is not real: the addresses returned
+//! describe a fake RAM region, and only the position of the break within it is
+//! tracked. `fake::Kernel::set_memory_region` replaces that region with a real
+//! buffer. The grant region is empty unless `fake::Kernel::set_grant_size`
+//! reserves some of the top of the RAM region for it.
+
+use crate::kernel_data::with_kernel_data;
+use crate::SyscallLogEntry;
+use libtock_platform::{memop_id, return_variant, ErrorCode, Register};
+use std::convert::TryInto;
+use std::ptr::null_mut;
+
+/// The start of the fake RAM region.
+pub const MEMORY_START: usize = 0x2000_0000;
+
+/// The end (exclusive) of the fake RAM region.
+pub const MEMORY_END: usize = 0x2001_0000;
+
+/// The start of the fake flash region.
+pub const FLASH_START: usize = 0x0004_0000;
+
+// The fake process' memory layout, stored in KernelData.
+pub struct Memory {
+    // The buffer backing the region, or null if the region is not real.
+    // Addresses returned for a real region are derived from it, so they can be
+    // dereferenced (including under Miri's strict provenance checks).
+    buffer: *mut u8,
+    start: usize,
+    end: usize,
+    grant_start: usize,
+    app_break: usize,
+}
+
+impl Memory {
+    pub fn new(start: usize, end: usize) -> Memory {
+        Memory {
+            buffer: null_mut(),
+            start,
+            end,
+            grant_start: end,
+            app_break: start,
+        }
+    }
+
+    /// A region backed by `buffer`.
+    pub fn backed_by(buffer: &'static mut [u8]) -> Memory {
+        let start = buffer.as_mut_ptr() as usize;
+        Memory {
+            buffer: buffer.as_mut_ptr(),
+            ..Memory::new(start, start + buffer.len())
+        }
+    }
+
+    // Returns `address`, which must be in the region, as a Register.
+    fn register(&self, address: usize) -> Register {
+        if self.buffer.is_null() {
+            return address.into();
+        }
+        self.buffer.wrapping_add(address - self.start).into()
+    }
+
+    /// Reserves the top `size` bytes of the region for grants. Panics if the
+    /// break is already above the new grant region.
+    pub fn set_grant_size(&mut self, size: usize) {
+        let grant_start = self.end.checked_sub(size).expect("Grant region too large");
+        assert!(
+            grant_start >= self.start && grant_start >= self.app_break,
+            "Grant region overlaps the process' memory"
+        );
+        self.grant_start = grant_start;
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Memory {
+        Memory::new(MEMORY_START, MEMORY_END)
+    }
+}
+
+pub(super) fn memop(memop_num: Register, argument0: Register) -> [Register; 2] {
+    let memop_num = memop_num.try_into().expect("Too large memop number");
+    let argument0: usize = argument0.into();
+    with_kernel_data(|option_kernel_data| {
+        let kernel_data = option_kernel_data.expect("Memop called but no fake::Kernel exists");
+
+        kernel_data.syscall_log.push(SyscallLogEntry::Memop {
+            memop_num,
+            argument0,
+        });
+
+        // Memop cannot be expected yet, so any expected system call means the
+        // wrong one was made.
+        if let Some(expected_syscall) = kernel_data.expected_syscalls.pop_front() {
+            expected_syscall.panic_wrong_call("Memop");
+        }
+
+        let memory = &mut kernel_data.memory;
+        match memop_num {
+            memop_id::BRK => match set_break(memory, argument0) {
+                Ok(()) => [return_variant::SUCCESS.into(), 0u32.into()],
+                Err(error) => [return_variant::FAILURE.into(), error.into()],
+            },
+            memop_id::SBRK => {
+                let previous = memory.app_break;
+                // The increment is a sign-extended i32.
+                let new_break = previous.wrapping_add(argument0 as i32 as isize as usize);
+                match set_break(memory, new_break) {
+                    Ok(()) => [
+                        return_variant::SUCCESS_U32.into(),
+                        memory.register(previous),
+                    ],
+                    Err(error) => [return_variant::FAILURE.into(), error.into()],
+                }
+            }
+            memop_id::MEMORY_START => [
+                return_variant::SUCCESS_U32.into(),
+                memory.register(memory.start),
+            ],
+            memop_id::MEMORY_END => [
+                return_variant::SUCCESS_U32.into(),
+                memory.register(memory.end),
+            ],
+            memop_id::FLASH_START => [return_variant::SUCCESS_U32.into(), FLASH_START.into()],
+            memop_id::GRANT_START => [
+                return_variant::SUCCESS_U32.into(),
+                memory.register(memory.grant_start),
+            ],
+            _ => [return_variant::FAILURE.into(), ErrorCode::NoSupport.into()],
+        }
+    })
+}
+
+fn set_break(memory: &mut Memory, address: usize) -> Result<(), ErrorCode> {
+    if !(memory.start..=memory.grant_start).contains(&address) {
+        return Err(ErrorCode::NoMem);
+    }
+    memory.app_break = address;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use super::memop_impl::*;
+use crate::{fake, SyscallLogEntry};
+use libtock_platform::{memop_id, return_variant, ErrorCode, Register};
+use std::convert::TryInto;
+
+// Makes a memop call, returning its return variant and value.
+fn call(memop_num: u32, argument0: usize) -> (u32, usize) {
+    let [r0, r1] = memop(memop_num.into(), argument0.into());
+    (r0.try_into().unwrap(), r1.into())
+}
+
+#[test]
+fn layout() {
+    let kernel = fake::Kernel::new();
+    let success_u32 = return_variant::SUCCESS_U32.into();
+    assert_eq!(call(memop_id::MEMORY_START, 0), (success_u32, MEMORY_START));
+    assert_eq!(call(memop_id::MEMORY_END, 0), (success_u32, MEMORY_END));
+    assert_eq!(
+        kernel.take_syscall_log(),
+        [
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::MEMORY_START,
+                argument0: 0,
+            },
+            SyscallLogEntry::Memop {
+                memop_num: memop_id::MEMORY_END,
+                argument0: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn brk_sbrk() {
+    let _kernel = fake::Kernel::new();
+    let success_u32 = return_variant::SUCCESS_U32.into();
+    let failure = return_variant::FAILURE.into();
+    assert_eq!(call(memop_id::SBRK, 0x100), (success_u32, MEMORY_START));
+    assert_eq!(call(memop_id::SBRK, 0), (success_u32, MEMORY_START + 0x100));
+    assert_eq!(
+        call(memop_id::SBRK, -0x80i32 as isize as usize),
+        (success_u32, MEMORY_START + 0x100)
+    );
+    assert_eq!(call(memop_id::SBRK, 0), (success_u32, MEMORY_START + 0x80));
+
+    assert_eq!(
+        call(memop_id::BRK, MEMORY_END),
+        (return_variant::SUCCESS.into(), 0)
+    );
+    assert_eq!(
+        call(memop_id::BRK, MEMORY_END + 1),
+        (failure, ErrorCode::NoMem as usize)
+    );
+    assert_eq!(
+        call(memop_id::SBRK, 1),
+        (failure, ErrorCode::NoMem as usize)
+    );
+    assert_eq!(call(memop_id::SBRK, 0), (success_u32, MEMORY_END));
+}
+
+#[test]
+fn memory_region() {
+    let kernel = fake::Kernel::new();
+    let buffer = Box::leak(Box::new([0u8; 0x100]));
+    let start = buffer.as_ptr() as usize;
+    kernel.set_memory_region(buffer);
+    let [r0, r1] = memop(memop_id::SBRK.into(), 0x10usize.into());
+    assert_eq!(r0.as_u32(), return_variant::SUCCESS_U32.into());
+    let memory: *mut u8 = r1.into();
+    assert_eq!(memory as usize, start);
+    // The returned pointer can be used to access the buffer.
+    unsafe { memory.write_bytes(0xff, 0x10) };
+    assert_eq!(
+        call(memop_id::SBRK, 0),
+        (return_variant::SUCCESS_U32.into(), start + 0x10)
+    );
+    assert_eq!(call(memop_id::MEMORY_END, 0).1, start + 0x100);
+}
+
+#[test]
+fn flash_and_grant() {
+    let kernel = fake::Kernel::new();
+    let success_u32 = return_variant::SUCCESS_U32.into();
+    assert_eq!(call(memop_id::FLASH_START, 0), (success_u32, FLASH_START));
+    // The grant region is empty by default.
+    assert_eq!(call(memop_id::GRANT_START, 0), (success_u32, MEMORY_END));
+
+    kernel.set_grant_size(0x1000);
+    assert_eq!(
+        call(memop_id::GRANT_START, 0),
+        (success_u32, MEMORY_END - 0x1000)
+    );
+    // The break cannot be moved into the grant region.
+    assert_eq!(
+        call(memop_id::BRK, MEMORY_END - 0x1000 + 1),
+        (return_variant::FAILURE.into(), ErrorCode::NoMem as usize)
+    );
+    assert_eq!(
+        call(memop_id::BRK, MEMORY_END - 0x1000),
+        (return_variant::SUCCESS.into(), 0)
+    );
+}
+
+#[test]
+fn unsupported() {
+    let _kernel = fake::Kernel::new();
+    let [r0, r1]: [Register; 2] = memop(100u32.into(), 0u32.into());
+    assert_eq!(r0.as_u32(), return_variant::FAILURE.into());
+    assert_eq!(r1.as_u32(), ErrorCode::NoSupport as u32);
+}
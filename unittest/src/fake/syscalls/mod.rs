@@ -2,6 +2,7 @@ mod allow_ro_impl;
 mod allow_rw_impl;
 mod command_impl;
 mod exit_impl;
+mod memop_impl;
 mod raw_syscalls_impl;
 mod subscribe_impl;
 mod yield_impl;
@@ -11,6 +12,8 @@ mod yield_impl;
 /// tests to provide the code under test access to Tock's system calls.
 pub struct Syscalls;
 
+pub use memop_impl::{Memory, FLASH_START, MEMORY_END, MEMORY_START};
+
 #[cfg(test)]
 mod allow_ro_impl_tests;
 #[cfg(test)]
@@ -20,6 +23,8 @@ mod command_impl_tests;
 #[cfg(all(not(miri), test))]
 mod exit_impl_tests;
 #[cfg(test)]
+mod memop_impl_tests;
+#[cfg(test)]
 mod raw_syscalls_impl_tests;
 #[cfg(test)]
 mod subscribe_impl_tests;
@@ -52,8 +52,6 @@ fn allow_rw() {
 
 // TODO: Implement Exit.
 
-// TODO: Implement Memop.
-
 // TODO: Implement Subscribe.
 
 // TODO: Move the yield1 and yield2 tests here.
@@ -1,6 +1,7 @@
 //! Implementations of Yield system calls.
 
 use crate::kernel_data::{with_kernel_data, KERNEL_DATA};
+use crate::upcall::UpcallQueueEntry;
 use crate::{ExpectedSyscall, SyscallLogEntry};
 
 /// # Safety
@@ -23,6 +24,7 @@ pub(super) unsafe fn yield_no_wait(return_ptr: *mut libtock_platform::YieldNoWai
         }
     });
 
+    queue_injected_upcalls();
     let upcall_ran = match invoke_next_upcall() {
         true => libtock_platform::YieldNoWaitReturn::Upcall,
         false => libtock_platform::YieldNoWaitReturn::NoUpcall,
@@ -49,6 +51,7 @@ pub(super) fn yield_wait() {
         }
     });
 
+    queue_injected_upcalls();
     if skip_upcall {
         return;
     }
@@ -64,6 +67,27 @@ pub(super) fn yield_wait() {
     );
 }
 
+// Moves the upcalls injected by `fake::Kernel::inject_upcall` to the back of
+// the upcall queue, discarding those the process has not subscribed to (or has
+// subscribed the null upcall to). Panics if no kernel data is present.
+fn queue_injected_upcalls() {
+    with_kernel_data(|option_kernel_data| {
+        let kernel_data = option_kernel_data.unwrap();
+        while let Some((id, args)) = kernel_data.injected_upcalls.pop_front() {
+            let upcall = match kernel_data.drivers[&id.driver_num]
+                .upcalls
+                .get(&id.subscribe_num)
+            {
+                Some(&upcall) if !upcall.is_null() => upcall,
+                _ => continue,
+            };
+            kernel_data
+                .upcall_queue
+                .push_back(UpcallQueueEntry { args, id, upcall });
+        }
+    });
+}
+
 // Pops the next upcall off the kernel data's upcall queue and invokes it, or
 // does nothing if the upcall queue was entry. The return value indicates
 // whether an upcall was run. Panics if no kernel data is present.
@@ -4,17 +4,19 @@
 //! Like the real API, `Temperature` controls a fake temperature sensor. It provides
 //! a function `set_value` used to immediately call an upcall with a temperature value read by the sensor
 //! and a function 'set_value_sync' used to call the upcall when the read command is received.
+//! Values passed to successive `set_value_sync` calls are used by successive read commands, in order.
 
 use crate::{DriverInfo, DriverShareRef};
 use libtock_platform::{CommandReturn, ErrorCode};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
 
-// The `upcall_on_command` field is set to Some(value) if an upcall(with value as its argument) should be called when read command is received,
-// or None otherwise. It was needed for testing `read_sync` library function which simulates a synchronous temperature read,
+// The `upcall_on_command` field holds the values of the upcalls (with value as their argument) that should be called when the next read
+// commands are received, in order. It was needed for testing `read_sync` library function which simulates a synchronous temperature read,
 // because it was impossible to schedule an upcall during the `synchronous` read in other ways.
 pub struct Temperature {
     busy: Cell<bool>,
-    upcall_on_command: Cell<Option<i32>>,
+    upcall_on_command: RefCell<VecDeque<i32>>,
     share_ref: DriverShareRef,
 }
 
@@ -22,7 +24,7 @@ impl Temperature {
     pub fn new() -> std::rc::Rc<Temperature> {
         std::rc::Rc::new(Temperature {
             busy: Cell::new(false),
-            upcall_on_command: Cell::new(None),
+            upcall_on_command: Default::default(),
             share_ref: Default::default(),
         })
     }
@@ -39,7 +41,7 @@ impl Temperature {
         }
     }
     pub fn set_value_sync(&self, value: i32) {
-        self.upcall_on_command.set(Some(value));
+        self.upcall_on_command.borrow_mut().push_back(value);
     }
 }
 
@@ -61,7 +63,8 @@ impl crate::fake::SyscallDriver for Temperature {
                     return crate::command_return::failure(ErrorCode::Busy);
                 }
                 self.busy.set(true);
-                if let Some(val) = self.upcall_on_command.take() {
+                let value = self.upcall_on_command.borrow_mut().pop_front();
+                if let Some(val) = value {
                     self.set_value(val);
                 }
                 crate::command_return::success()
@@ -0,0 +1,150 @@
+//! Fake implementation of the text screen API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/90003_text_screen.md
+//!
+//! `TextScreen` emulates a 16x2 character display. Every command completes
+//! as soon as it is received. Written text is recorded along with the cursor
+//! position it was written at, and can be retrieved via `take_writes`.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+
+// The cursor position, as (column, row), and the text written there.
+type Write = ((u32, u32), Vec<u8>);
+
+pub struct TextScreen {
+    on: Cell<bool>,
+    cursor: Cell<(u32, u32)>,
+    max_write_len: Cell<Option<usize>>,
+    writes: RefCell<Vec<Write>>,
+    buffer: Cell<RoAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl TextScreen {
+    pub fn new() -> std::rc::Rc<TextScreen> {
+        std::rc::Rc::new(TextScreen {
+            on: Cell::new(false),
+            cursor: Cell::new((0, 0)),
+            max_write_len: Cell::new(None),
+            writes: Default::default(),
+            buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on.get()
+    }
+
+    /// Returns the cursor position as (column, row).
+    pub fn cursor(&self) -> (u32, u32) {
+        self.cursor.get()
+    }
+
+    /// Limits the number of bytes accepted by each write command, emulating a
+    /// kernel with a small internal buffer.
+    pub fn set_max_write_len(&self, len: usize) {
+        self.max_write_len.set(Some(len));
+    }
+
+    /// Returns the text written by each write command, together with the
+    /// cursor position it was written at, and clears them.
+    pub fn take_writes(&self) -> Vec<Write> {
+        self.writes.take()
+    }
+
+    fn complete(&self, data0: u32, data1: u32) -> CommandReturn {
+        self.share_ref
+            .schedule_upcall(0, (0, data0, data1))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for TextScreen {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(1)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_BUFFER {
+            Ok(self.buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            GET_SIZE => self.complete(COLUMNS, ROWS),
+            ON | OFF => {
+                self.on.set(command_id == ON);
+                self.complete(0, 0)
+            }
+
+            WRITE => {
+                let buffer = self.buffer.take();
+                let mut len = (argument0 as usize).min(buffer.len());
+                if let Some(max_write_len) = self.max_write_len.get() {
+                    len = len.min(max_write_len);
+                }
+                let text = buffer[..len].to_vec();
+                self.buffer.set(buffer);
+                let (column, row) = self.cursor.get();
+                self.writes.borrow_mut().push(((column, row), text));
+                self.cursor
+                    .set(((column + len as u32).min(COLUMNS - 1), row));
+                self.complete(len as u32, 0)
+            }
+
+            CLEAR => {
+                self.cursor.set((0, 0));
+                self.complete(0, 0)
+            }
+
+            SET_CURSOR => {
+                if argument0 >= COLUMNS || argument1 >= ROWS {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.cursor.set((argument0, argument1));
+                self.complete(0, 0)
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x90003;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const GET_SIZE: u32 = 1;
+const ON: u32 = 2;
+const OFF: u32 = 3;
+const WRITE: u32 = 8;
+const CLEAR: u32 = 9;
+const SET_CURSOR: u32 = 11;
+
+// Allow IDs
+
+const ALLOW_BUFFER: u32 = 0;
+
+const COLUMNS: u32 = 16;
+const ROWS: u32 = 2;
@@ -0,0 +1,70 @@
+use crate::fake::{self, SyscallDriver};
+use fake::text_screen::*;
+use libtock_platform::{share, AllowRo, DefaultConfig, Subscribe, YieldNoWaitReturn};
+use std::cell::Cell;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let screen = TextScreen::new();
+
+    assert!(screen.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        screen.command(SET_CURSOR, COLUMNS, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        screen.command(SET_CURSOR, 0, ROWS).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(screen.cursor(), (0, 0));
+    assert!(!screen.is_on());
+}
+
+// Integration test that verifies TextScreen works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let screen = TextScreen::new();
+    kernel.add_driver(&screen);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let listener = Cell::<Option<(u32, u32, u32)>>::new(None);
+    share::scope::<
+        (
+            AllowRo<_, DRIVER_NUM, ALLOW_BUFFER>,
+            Subscribe<_, DRIVER_NUM, 0>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_ro, subscribe) = handle.split();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_BUFFER>(allow_ro, b"Hello")
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, 0>(subscribe, &listener)
+            .unwrap();
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, GET_SIZE, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, COLUMNS, ROWS)));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, ON, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert!(screen.is_on());
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, SET_CURSOR, 3, 1).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        screen.set_max_write_len(4);
+        assert!(fake::Syscalls::command(DRIVER_NUM, WRITE, 5, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(listener.get(), Some((0, 4, 0)));
+        assert_eq!(screen.cursor(), (7, 1));
+
+        assert!(fake::Syscalls::command(DRIVER_NUM, CLEAR, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(screen.cursor(), (0, 0));
+    });
+    assert_eq!(screen.take_writes(), [((3, 1), b"Hell".to_vec())]);
+}
@@ -0,0 +1,281 @@
+//! Fake implementation of the UDP API, documented here:
+//! https://github.com/tock/tock/blob/master/doc/syscalls/30002_udp.md
+//!
+//! Like the real API, `Udp` only accepts datagrams for the port the process
+//! has bound, and completes sends as soon as they are requested. Incoming
+//! datagrams are queued by `receive_datagram` and delivered one at a time
+//! once a receive buffer is shared. Datagrams sent to the loopback address
+//! and the bound port are received back.
+//!
+//! The fake also implements `JOIN_MULTICAST` and `LEAVE_MULTICAST`, which take
+//! a multicast group from the first address in the configuration buffer.
+//! The upstream capsule has no multicast support; these commands exist so that
+//! `libtock_net`'s support for them can be tested. Datagrams sent to a group
+//! with `receive_multicast` are only received if the process joined it.
+
+use crate::{DriverInfo, DriverShareRef, RoAllowBuffer, RwAllowBuffer};
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+/// A datagram sent by the process.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UdpDatagram {
+    pub src_port: u16,
+    pub dst_addr: [u8; 16],
+    pub dst_port: u16,
+    pub payload: Vec<u8>,
+}
+
+// An incoming datagram: source address, source port, and payload.
+type Incoming = ([u8; 16], u16, Vec<u8>);
+
+pub struct Udp {
+    bound_port: Cell<Option<u16>>,
+    groups: RefCell<Vec<[u8; 16]>>,
+    sent: RefCell<Vec<UdpDatagram>>,
+    incoming: RefCell<VecDeque<Incoming>>,
+    tx_buffer: Cell<RoAllowBuffer>,
+    rx_buffer: RefCell<RwAllowBuffer>,
+    cfg_buffer: RefCell<RwAllowBuffer>,
+    rx_cfg_buffer: RefCell<RwAllowBuffer>,
+    share_ref: DriverShareRef,
+}
+
+impl Udp {
+    pub fn new() -> std::rc::Rc<Udp> {
+        std::rc::Rc::new(Udp {
+            bound_port: Cell::new(None),
+            groups: Default::default(),
+            sent: Default::default(),
+            incoming: Default::default(),
+            tx_buffer: Default::default(),
+            rx_buffer: Default::default(),
+            cfg_buffer: Default::default(),
+            rx_cfg_buffer: Default::default(),
+            share_ref: Default::default(),
+        })
+    }
+
+    /// Returns the port bound by the process, if any.
+    pub fn bound_port(&self) -> Option<u16> {
+        self.bound_port.get()
+    }
+
+    /// Returns the multicast groups the process has joined, in the order it
+    /// joined them.
+    pub fn multicast_groups(&self) -> Vec<[u8; 16]> {
+        self.groups.borrow().clone()
+    }
+
+    /// Returns every datagram sent since the last call.
+    pub fn take_sent(&self) -> Vec<UdpDatagram> {
+        self.sent.take()
+    }
+
+    /// Queues a datagram from `src_port` on `src_addr` for `dst_port`. It is
+    /// dropped if `dst_port` is not the bound port.
+    pub fn receive_datagram(
+        &self,
+        src_addr: [u8; 16],
+        src_port: u16,
+        dst_port: u16,
+        payload: &[u8],
+    ) {
+        if self.bound_port.get() != Some(dst_port) {
+            return;
+        }
+        self.incoming
+            .borrow_mut()
+            .push_back((src_addr, src_port, payload.into()));
+        self.deliver();
+    }
+
+    /// Like `receive_datagram`, but for a datagram sent to the multicast group
+    /// `group`. It is dropped if the process has not joined `group`.
+    pub fn receive_multicast(
+        &self,
+        group: [u8; 16],
+        src_addr: [u8; 16],
+        src_port: u16,
+        dst_port: u16,
+        payload: &[u8],
+    ) {
+        if self.groups.borrow().contains(&group) {
+            self.receive_datagram(src_addr, src_port, dst_port, payload);
+        }
+    }
+
+    // Delivers the oldest queued datagram, if a receive buffer is shared.
+    fn deliver(&self) {
+        let mut buffer = self.rx_buffer.borrow_mut();
+        if buffer.is_empty() {
+            return;
+        }
+        let (src_addr, src_port, payload) = match self.incoming.borrow_mut().pop_front() {
+            Some(datagram) => datagram,
+            None => return,
+        };
+        let len = payload.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&payload[..len]);
+        let mut rx_cfg = self.rx_cfg_buffer.borrow_mut();
+        if rx_cfg.len() >= SOCK_ADDR_LEN {
+            rx_cfg[..16].copy_from_slice(&src_addr);
+            rx_cfg[16..SOCK_ADDR_LEN].copy_from_slice(&src_port.to_ne_bytes());
+        }
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_RX, (len as u32, 0, 0))
+            .expect("Unable to schedule upcall");
+    }
+
+    // Reads the (address, port) pair at `index` in the configuration buffer.
+    fn cfg_sock_addr(&self, index: usize) -> Option<([u8; 16], u16)> {
+        let cfg = self.cfg_buffer.borrow();
+        let sock_addr = cfg.get(index * SOCK_ADDR_LEN..(index + 1) * SOCK_ADDR_LEN)?;
+        let mut addr = [0; 16];
+        addr.copy_from_slice(&sock_addr[..16]);
+        Some((addr, u16::from_ne_bytes([sock_addr[16], sock_addr[17]])))
+    }
+
+    fn send(&self) -> CommandReturn {
+        let (src, dst) = match (self.cfg_sock_addr(0), self.cfg_sock_addr(1)) {
+            (Some(src), Some(dst)) => (src, dst),
+            _ => return crate::command_return::failure(ErrorCode::Invalid),
+        };
+        if self.bound_port.get() != Some(src.1) {
+            return crate::command_return::failure(ErrorCode::Invalid);
+        }
+        let buffer = self.tx_buffer.take();
+        let payload = buffer.to_vec();
+        self.tx_buffer.set(buffer);
+        if payload.len() > MAX_TX_LEN {
+            return crate::command_return::failure(ErrorCode::Size);
+        }
+
+        if dst.0 == LOOPBACK {
+            self.receive_datagram(LOOPBACK, src.1, dst.1, &payload);
+        }
+        self.sent.borrow_mut().push(UdpDatagram {
+            src_port: src.1,
+            dst_addr: dst.0,
+            dst_port: dst.1,
+            payload,
+        });
+        self.share_ref
+            .schedule_upcall(SUBSCRIBE_TX, (0, 0, 0))
+            .expect("Unable to schedule upcall");
+        crate::command_return::success()
+    }
+}
+
+impl crate::fake::SyscallDriver for Udp {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM).upcall_count(2)
+    }
+
+    fn register(&self, share_ref: DriverShareRef) {
+        self.share_ref.replace(share_ref);
+    }
+
+    fn allow_readonly(
+        &self,
+        buffer_num: u32,
+        buffer: RoAllowBuffer,
+    ) -> Result<RoAllowBuffer, (RoAllowBuffer, ErrorCode)> {
+        if buffer_num == ALLOW_TX {
+            Ok(self.tx_buffer.replace(buffer))
+        } else {
+            Err((buffer, ErrorCode::Invalid))
+        }
+    }
+
+    fn allow_readwrite(
+        &self,
+        buffer_num: u32,
+        buffer: RwAllowBuffer,
+    ) -> Result<RwAllowBuffer, (RwAllowBuffer, ErrorCode)> {
+        match buffer_num {
+            ALLOW_RX => {
+                let previous = self.rx_buffer.replace(buffer);
+                self.deliver();
+                Ok(previous)
+            }
+            ALLOW_CFG => Ok(self.cfg_buffer.replace(buffer)),
+            ALLOW_RX_CFG => Ok(self.rx_cfg_buffer.replace(buffer)),
+            _ => Err((buffer, ErrorCode::Invalid)),
+        }
+    }
+
+    fn command(&self, command_id: u32, _argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            SEND => self.send(),
+            BIND => match self.cfg_sock_addr(0) {
+                Some((_, 0)) | None => crate::command_return::failure(ErrorCode::Invalid),
+                Some((_, port)) => {
+                    self.bound_port.set(Some(port));
+                    crate::command_return::success()
+                }
+            },
+            GET_MAX_TX_LEN => crate::command_return::success_u32(MAX_TX_LEN as u32),
+            JOIN_MULTICAST | LEAVE_MULTICAST => {
+                let group = match self.cfg_sock_addr(0) {
+                    Some((group, _)) if group[0] == 0xff => group,
+                    _ => return crate::command_return::failure(ErrorCode::Invalid),
+                };
+                let mut groups = self.groups.borrow_mut();
+                let index = groups.iter().position(|&joined| joined == group);
+                match (command_id, index) {
+                    (JOIN_MULTICAST, None) => groups.push(group),
+                    (JOIN_MULTICAST, Some(_)) => {
+                        return crate::command_return::failure(ErrorCode::Already)
+                    }
+                    (_, Some(index)) => {
+                        groups.remove(index);
+                    }
+                    (_, None) => return crate::command_return::failure(ErrorCode::Invalid),
+                }
+                crate::command_return::success()
+            }
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x30002;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const SEND: u32 = 2;
+const BIND: u32 = 3;
+const GET_MAX_TX_LEN: u32 = 4;
+const JOIN_MULTICAST: u32 = 16;
+const LEAVE_MULTICAST: u32 = 17;
+
+// Allow IDs
+
+const ALLOW_TX: u32 = 0;
+const ALLOW_RX: u32 = 0;
+const ALLOW_CFG: u32 = 1;
+const ALLOW_RX_CFG: u32 = 2;
+
+// Subscribe IDs
+
+const SUBSCRIBE_RX: u32 = 0;
+const SUBSCRIBE_TX: u32 = 1;
+
+// A 16-byte IPv6 address followed by a native-endian port.
+const SOCK_ADDR_LEN: usize = 18;
+
+const LOOPBACK: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+// The largest payload the fake sends in one datagram.
+const MAX_TX_LEN: usize = 200;
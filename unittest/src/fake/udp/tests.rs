@@ -0,0 +1,154 @@
+use crate::fake::{self, SyscallDriver};
+use fake::udp::*;
+use libtock_platform::{share, AllowRo, AllowRw, DefaultConfig, Subscribe, YieldNoWaitReturn};
+
+//Test the command implementation
+#[test]
+fn command() {
+    let udp = Udp::new();
+
+    assert!(udp.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        udp.command(GET_MAX_TX_LEN, 0, 0).get_success_u32(),
+        Some(MAX_TX_LEN as u32)
+    );
+    // No configuration has been shared.
+    assert_eq!(
+        udp.command(BIND, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        udp.command(SEND, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(
+        udp.command(JOIN_MULTICAST, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+    assert_eq!(udp.bound_port(), None);
+}
+
+// Integration test that verifies Udp works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let udp = Udp::new();
+    kernel.add_driver(&udp);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+
+    let mut cfg = [0; 2 * SOCK_ADDR_LEN];
+    cfg[16..18].copy_from_slice(&2000u16.to_ne_bytes());
+    cfg[18..34].copy_from_slice(&LOOPBACK);
+    cfg[34..36].copy_from_slice(&2000u16.to_ne_bytes());
+    let mut rx = [0; 4];
+    let mut rx_cfg = [0; 2 * SOCK_ADDR_LEN];
+    let sent = Cell::<Option<(u32,)>>::new(None);
+    let received = Cell::<Option<(u32,)>>::new(None);
+    share::scope::<
+        (
+            AllowRw<_, DRIVER_NUM, ALLOW_CFG>,
+            AllowRo<_, DRIVER_NUM, ALLOW_TX>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX_CFG>,
+            AllowRw<_, DRIVER_NUM, ALLOW_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_RX>,
+            Subscribe<_, DRIVER_NUM, SUBSCRIBE_TX>,
+        ),
+        _,
+        _,
+    >(|handle| {
+        let (allow_cfg, allow_tx, allow_rx_cfg, allow_rx, subscribe_rx, subscribe_tx) =
+            handle.split();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CFG>(allow_cfg, &mut cfg)
+            .unwrap();
+        fake::Syscalls::allow_ro::<DefaultConfig, DRIVER_NUM, ALLOW_TX>(allow_tx, b"hello")
+            .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_RX>(
+            subscribe_rx,
+            &received,
+        )
+        .unwrap();
+        fake::Syscalls::subscribe::<_, _, DefaultConfig, DRIVER_NUM, SUBSCRIBE_TX>(
+            subscribe_tx,
+            &sent,
+        )
+        .unwrap();
+
+        // Sending requires the source port to be bound.
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, SEND, 0, 0).get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+        assert!(fake::Syscalls::command(DRIVER_NUM, BIND, 0, 0).is_success());
+        assert_eq!(udp.bound_port(), Some(2000));
+
+        // The looped-back datagram waits for a receive buffer.
+        assert!(fake::Syscalls::command(DRIVER_NUM, SEND, 0, 0).is_success());
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(sent.get(), Some((0,)));
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX_CFG>(
+            allow_rx_cfg,
+            &mut rx_cfg,
+        )
+        .unwrap();
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_RX>(allow_rx, &mut rx).unwrap();
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::Upcall);
+        assert_eq!(received.get(), Some((4,)));
+
+        // Datagrams for other ports are dropped.
+        udp.receive_datagram(LOOPBACK, 7, 2001, b"no");
+        assert_eq!(fake::Syscalls::yield_no_wait(), YieldNoWaitReturn::NoUpcall);
+    });
+    assert_eq!(rx, *b"hell");
+    assert_eq!(rx_cfg[..16], LOOPBACK);
+    assert_eq!(rx_cfg[16..18], 2000u16.to_ne_bytes());
+    assert_eq!(
+        udp.take_sent(),
+        [UdpDatagram {
+            src_port: 2000,
+            dst_addr: LOOPBACK,
+            dst_port: 2000,
+            payload: b"hello".to_vec(),
+        }]
+    );
+}
+
+#[test]
+fn multicast() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let udp = Udp::new();
+    kernel.add_driver(&udp);
+
+    let group = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xfb];
+    let mut cfg = [0; 2 * SOCK_ADDR_LEN];
+    share::scope::<AllowRw<_, DRIVER_NUM, ALLOW_CFG>, _, _>(|allow_cfg| {
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CFG>(allow_cfg, &mut cfg)
+            .unwrap();
+        // The unspecified address is not a multicast group.
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, JOIN_MULTICAST, 0, 0).get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+    });
+    cfg[..16].copy_from_slice(&group);
+    share::scope::<AllowRw<_, DRIVER_NUM, ALLOW_CFG>, _, _>(|allow_cfg| {
+        fake::Syscalls::allow_rw::<DefaultConfig, DRIVER_NUM, ALLOW_CFG>(allow_cfg, &mut cfg)
+            .unwrap();
+        assert!(fake::Syscalls::command(DRIVER_NUM, JOIN_MULTICAST, 0, 0).is_success());
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, JOIN_MULTICAST, 0, 0).get_failure(),
+            Some(ErrorCode::Already)
+        );
+        assert_eq!(udp.multicast_groups(), [group]);
+        assert!(fake::Syscalls::command(DRIVER_NUM, LEAVE_MULTICAST, 0, 0).is_success());
+        assert_eq!(
+            fake::Syscalls::command(DRIVER_NUM, LEAVE_MULTICAST, 0, 0).get_failure(),
+            Some(ErrorCode::Invalid)
+        );
+    });
+    assert!(udp.multicast_groups().is_empty());
+}
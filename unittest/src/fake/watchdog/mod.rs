@@ -0,0 +1,81 @@
+//! Fake implementation of the watchdog API.
+//!
+//! Like the real API, `Watchdog` can be started with a timeout, tickled, and
+//! stopped. It never resets anything; instead, tests can check the current
+//! timeout and how many times the watchdog was tickled.
+
+use crate::DriverInfo;
+use libtock_platform::{CommandReturn, ErrorCode};
+use std::cell::Cell;
+
+pub struct Watchdog {
+    timeout_ms: Cell<Option<u32>>,
+    tickles: Cell<u32>,
+}
+
+impl Watchdog {
+    pub fn new() -> std::rc::Rc<Watchdog> {
+        std::rc::Rc::new(Watchdog {
+            timeout_ms: Cell::new(None),
+            tickles: Cell::new(0),
+        })
+    }
+
+    /// Returns the timeout the watchdog is running with, or None if it is
+    /// stopped.
+    pub fn get_timeout_ms(&self) -> Option<u32> {
+        self.timeout_ms.get()
+    }
+
+    /// Returns the number of tickles since the last call.
+    pub fn take_tickle_count(&self) -> u32 {
+        self.tickles.take()
+    }
+}
+
+impl crate::fake::SyscallDriver for Watchdog {
+    fn info(&self) -> DriverInfo {
+        DriverInfo::new(DRIVER_NUM)
+    }
+
+    fn command(&self, command_id: u32, argument0: u32, _argument1: u32) -> CommandReturn {
+        match command_id {
+            EXISTS => crate::command_return::success(),
+            START => {
+                if argument0 == 0 {
+                    return crate::command_return::failure(ErrorCode::Invalid);
+                }
+                self.timeout_ms.set(Some(argument0));
+                crate::command_return::success()
+            }
+            TICKLE => {
+                if self.timeout_ms.get().is_none() {
+                    return crate::command_return::failure(ErrorCode::Off);
+                }
+                self.tickles.set(self.tickles.get() + 1);
+                crate::command_return::success()
+            }
+            STOP => match self.timeout_ms.take() {
+                Some(_) => crate::command_return::success(),
+                None => crate::command_return::failure(ErrorCode::Already),
+            },
+            _ => crate::command_return::failure(ErrorCode::NoSupport),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+// -----------------------------------------------------------------------------
+// Driver number and command IDs
+// -----------------------------------------------------------------------------
+
+const DRIVER_NUM: u32 = 0x9000A;
+
+// Command IDs
+
+const EXISTS: u32 = 0;
+const START: u32 = 1;
+const TICKLE: u32 = 2;
+const STOP: u32 = 3;
@@ -0,0 +1,47 @@
+use crate::fake::{self, SyscallDriver};
+use fake::watchdog::*;
+
+//Test the command implementation
+#[test]
+fn command() {
+    let watchdog = Watchdog::new();
+
+    assert!(watchdog.command(EXISTS, 0, 0).is_success());
+    assert_eq!(
+        watchdog.command(TICKLE, 0, 0).get_failure(),
+        Some(ErrorCode::Off)
+    );
+    assert_eq!(
+        watchdog.command(START, 0, 0).get_failure(),
+        Some(ErrorCode::Invalid)
+    );
+
+    assert!(watchdog.command(START, 1000, 0).is_success());
+    assert_eq!(watchdog.get_timeout_ms(), Some(1000));
+    assert!(watchdog.command(TICKLE, 0, 0).is_success());
+    assert_eq!(watchdog.take_tickle_count(), 1);
+    assert_eq!(watchdog.take_tickle_count(), 0);
+
+    assert!(watchdog.command(STOP, 0, 0).is_success());
+    assert_eq!(watchdog.get_timeout_ms(), None);
+    assert_eq!(
+        watchdog.command(STOP, 0, 0).get_failure(),
+        Some(ErrorCode::Already)
+    );
+}
+
+// Integration test that verifies Watchdog works with fake::Kernel and
+// libtock_platform::Syscalls.
+#[test]
+fn kernel_integration() {
+    use libtock_platform::Syscalls;
+    let kernel = fake::Kernel::new();
+    let watchdog = Watchdog::new();
+    kernel.add_driver(&watchdog);
+    assert!(fake::Syscalls::command(DRIVER_NUM, EXISTS, 0, 0).is_success());
+    assert!(fake::Syscalls::command(DRIVER_NUM, START, 500, 0).is_success());
+    assert_eq!(watchdog.get_timeout_ms(), Some(500));
+    assert!(fake::Syscalls::command(DRIVER_NUM, TICKLE, 0, 0).is_success());
+    assert_eq!(watchdog.take_tickle_count(), 1);
+    assert!(fake::Syscalls::command(DRIVER_NUM, STOP, 0, 0).is_success());
+}
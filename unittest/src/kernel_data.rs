@@ -21,6 +21,12 @@ pub(crate) struct KernelData {
 
     pub drivers: std::collections::HashMap<u32, DriverData>,
     pub expected_syscalls: std::collections::VecDeque<crate::ExpectedSyscall>,
+
+    // Upcalls injected by `fake::Kernel::inject_upcall`, with their arguments,
+    // in the order they were injected. They are moved to the upcall queue by
+    // the next Yield.
+    pub injected_upcalls: std::collections::VecDeque<(crate::upcall::UpcallId, (u32, u32, u32))>,
+    pub memory: crate::fake::Memory,
     pub syscall_log: Vec<crate::SyscallLogEntry>,
     pub upcall_queue: crate::upcall::UpcallQueue,
 }
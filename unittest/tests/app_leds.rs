@@ -0,0 +1,70 @@
+//! An example of testing an app's logic on the host, with `fake::Kernel` in
+//! place of a Tock kernel.
+//!
+//! Code under test is written against a generic `S: Syscalls`. On Tock, it is
+//! used with `libtock_runtime::TockSyscalls`, which `libtock`'s API aliases
+//! do; in tests, it is used with `fake::Syscalls`, and the syscalls it makes
+//! are routed to the fake drivers added to the `fake::Kernel`.
+
+use libtock_leds::Leds;
+use libtock_platform::{ErrorCode, Syscalls};
+use libtock_unittest::{command_return, fake, SyscallLogEntry};
+
+// The app logic under test: shows the low bits of `value` on the LEDs, with
+// LED 0 as the least significant bit.
+fn show_binary<S: Syscalls>(value: u32) -> Result<(), ErrorCode> {
+    for led in 0..Leds::<S>::count()? {
+        if value >> led & 1 == 1 {
+            Leds::<S>::on(led)?;
+        } else {
+            Leds::<S>::off(led)?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn shows_value() {
+    let kernel = fake::Kernel::new();
+    let leds = fake::Leds::<4>::new();
+    kernel.add_driver(&leds);
+
+    assert_eq!(show_binary::<fake::Syscalls>(0b1010), Ok(()));
+    let states: Vec<_> = (0..4).map(|led| leds.get_led(led).unwrap()).collect();
+    assert_eq!(states, [false, true, false, true]);
+
+    // Bits beyond the number of LEDs are not shown.
+    assert_eq!(show_binary::<fake::Syscalls>(0b10001), Ok(()));
+    let states: Vec<_> = (0..4).map(|led| leds.get_led(led).unwrap()).collect();
+    assert_eq!(states, [true, false, false, false]);
+}
+
+// Drivers can also be emulated with `fake::Capsule`, for instance to inject
+// failures the dedicated fakes do not support.
+#[test]
+fn stops_on_failure() {
+    let kernel = fake::Kernel::new();
+    // An LED driver with two LEDs, which fails to turn any of them on.
+    let leds = fake::Capsule::builder(2)
+        .command(0, |_, _, _| command_return::success_u32(2))
+        .command(1, |_, _, _| command_return::failure(ErrorCode::Fail))
+        .command(2, |_, _, _| command_return::success())
+        .build();
+    kernel.add_driver(&leds);
+
+    assert_eq!(show_binary::<fake::Syscalls>(0b10), Err(ErrorCode::Fail));
+    // LED 0 was turned off, and the app gave up after failing to turn on LED 1.
+    let commands: Vec<_> = kernel
+        .take_syscall_log()
+        .into_iter()
+        .filter_map(|entry| match entry {
+            SyscallLogEntry::Command {
+                command_id,
+                argument0,
+                ..
+            } => Some((command_id, argument0)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(commands, [(0, 0), (2, 0), (1, 1)]);
+}